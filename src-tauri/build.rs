@@ -2,16 +2,44 @@ fn main() {
     #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
     build_apple_intelligence_bridge();
 
-    generate_tray_translations();
+    #[cfg(target_os = "macos")]
+    build_scripting_bridge();
+
+    export_app_identifier();
+    generate_translations("tray", "TrayStrings", "tray_translations.rs");
+    generate_translations(
+        "notifications",
+        "NotificationStrings",
+        "notification_translations.rs",
+    );
+
+    #[cfg(feature = "grpc")]
+    tonic_prost_build::configure()
+        .build_client(false)
+        .compile_protos(&["proto/handy/v1/handy.proto"], &["proto"])
+        .expect("Failed to compile the gRPC protocol");
 
     tauri_build::build()
 }
 
-/// Generate tray menu translations from frontend locale files.
+/// Passes the bundle identifier from tauri.conf.json on as
+/// `HANDY_APP_IDENTIFIER`, for code that runs without a tauri context
+fn export_app_identifier() {
+    println!("cargo:rerun-if-changed=tauri.conf.json");
+    let config: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string("tauri.conf.json").unwrap()).unwrap();
+    let identifier = config["identifier"]
+        .as_str()
+        .expect("tauri.conf.json has no identifier");
+    println!("cargo:rustc-env=HANDY_APP_IDENTIFIER={}", identifier);
+}
+
+/// Generate translations for strings shown by the backend (the tray menu,
+/// notifications) from the frontend locale files.
 ///
 /// Source of truth: src/i18n/locales/*/translation.json
-/// The English "tray" section defines the struct fields.
-fn generate_tray_translations() {
+/// The English `section` defines the fields of `struct_name`.
+fn generate_translations(section: &str, struct_name: &str, file_name: &str) {
     use std::collections::BTreeMap;
     use std::fs;
     use std::path::Path;
@@ -38,8 +66,8 @@ fn generate_tray_translations() {
         let content = fs::read_to_string(&json_path).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&content).unwrap();
 
-        if let Some(tray) = parsed.get("tray").cloned() {
-            translations.insert(lang, tray);
+        if let Some(strings) = parsed.get(section).cloned() {
+            translations.insert(lang, strings);
         }
     }
 
@@ -56,22 +84,24 @@ fn generate_tray_translations() {
     );
 
     // Struct
-    out.push_str("#[derive(Debug, Clone)]\npub struct TrayStrings {\n");
+    out.push_str(&format!(
+        "#[derive(Debug, Clone)]\npub struct {struct_name} {{\n"
+    ));
     for (rust_field, _) in &fields {
         out.push_str(&format!("    pub {rust_field}: String,\n"));
     }
     out.push_str("}\n\n");
 
     // Static map
-    out.push_str(
-        "pub static TRANSLATIONS: Lazy<HashMap<&'static str, TrayStrings>> = Lazy::new(|| {\n",
-    );
+    out.push_str(&format!(
+        "pub static TRANSLATIONS: Lazy<HashMap<&'static str, {struct_name}>> = Lazy::new(|| {{\n"
+    ));
     out.push_str("    let mut m = HashMap::new();\n");
 
-    for (lang, tray) in &translations {
-        out.push_str(&format!("    m.insert(\"{lang}\", TrayStrings {{\n"));
+    for (lang, strings) in &translations {
+        out.push_str(&format!("    m.insert(\"{lang}\", {struct_name} {{\n"));
         for (rust_field, json_key) in &fields {
-            let val = tray.get(json_key).and_then(|v| v.as_str()).unwrap_or("");
+            let val = strings.get(json_key).and_then(|v| v.as_str()).unwrap_or("");
             out.push_str(&format!(
                 "        {rust_field}: \"{}\".to_string(),\n",
                 escape_string(val)
@@ -82,10 +112,10 @@ fn generate_tray_translations() {
 
     out.push_str("    m\n});\n");
 
-    fs::write(Path::new(&out_dir).join("tray_translations.rs"), out).unwrap();
+    fs::write(Path::new(&out_dir).join(file_name), out).unwrap();
 
     println!(
-        "cargo:warning=Generated tray translations: {} languages, {} fields",
+        "cargo:warning=Generated {section} translations: {} languages, {} fields",
         translations.len(),
         fields.len()
     );
@@ -237,3 +267,98 @@ fn build_apple_intelligence_bridge() {
 
     println!("cargo:rustc-link-arg=-Wl,-rpath,/usr/lib/swift");
 }
+
+/// Compiles the AppleScript command classes (see Handy.sdef) and the
+/// EventKit calendar reader used for meeting detection
+#[cfg(target_os = "macos")]
+fn build_scripting_bridge() {
+    use std::env;
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    const SWIFT_FILES: &[&str] = &["swift/scripting.swift", "swift/calendar.swift"];
+    const BRIDGE_HEADER: &str = "swift/scripting_bridge.h";
+
+    for file in SWIFT_FILES {
+        println!("cargo:rerun-if-changed={file}");
+    }
+    println!("cargo:rerun-if-changed={BRIDGE_HEADER}");
+    println!("cargo:rerun-if-changed=Handy.sdef");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR not set"));
+    let static_lib_path = out_dir.join("libscripting.a");
+
+    let sdk_path = String::from_utf8(
+        Command::new("xcrun")
+            .args(["--sdk", "macosx", "--show-sdk-path"])
+            .output()
+            .expect("Failed to locate macOS SDK")
+            .stdout,
+    )
+    .expect("SDK path is not valid UTF-8")
+    .trim()
+    .to_string();
+
+    // Keep in step with bundle.macOS.minimumSystemVersion in tauri.conf.json
+    let target = match env::var("CARGO_CFG_TARGET_ARCH").as_deref() {
+        Ok("aarch64") => "arm64-apple-macosx10.15",
+        _ => "x86_64-apple-macosx10.15",
+    };
+
+    let mut object_paths = Vec::new();
+    for file in SWIFT_FILES {
+        let object_path = out_dir.join(
+            PathBuf::from(file)
+                .with_extension("o")
+                .file_name()
+                .expect("Swift file has a name"),
+        );
+        let status = Command::new("xcrun")
+            .args([
+                "swiftc",
+                "-target",
+                target,
+                "-sdk",
+                &sdk_path,
+                "-O",
+                "-parse-as-library",
+                "-import-objc-header",
+                BRIDGE_HEADER,
+                "-c",
+                file,
+                "-o",
+                object_path
+                    .to_str()
+                    .expect("Failed to convert object path to string"),
+            ])
+            .status()
+            .expect("Failed to invoke swiftc for the scripting bridge");
+
+        if !status.success() {
+            panic!("swiftc failed to compile {file}");
+        }
+        object_paths.push(object_path);
+    }
+
+    let status = Command::new("libtool")
+        .arg("-static")
+        .arg("-o")
+        .arg(&static_lib_path)
+        .args(&object_paths)
+        .status()
+        .expect("Failed to create static library for the scripting bridge");
+
+    if !status.success() {
+        panic!("libtool failed for the scripting bridge");
+    }
+
+    println!("cargo:rustc-link-search=native={}", out_dir.display());
+    println!("cargo:rustc-link-lib=static=scripting");
+    println!(
+        "cargo:rustc-link-search=native={}",
+        PathBuf::from(&sdk_path).join("usr/lib/swift").display()
+    );
+    println!("cargo:rustc-link-lib=framework=Cocoa");
+    println!("cargo:rustc-link-lib=framework=EventKit");
+    println!("cargo:rustc-link-arg=-Wl,-rpath,/usr/lib/swift");
+}
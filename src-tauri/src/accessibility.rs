@@ -0,0 +1,783 @@
+//! Text insertion through platform accessibility APIs.
+//!
+//! Commits text straight into the focused text field without touching the
+//! clipboard or synthesizing keystrokes. Not every toolkit exposes editable
+//! text this way, so callers fall back to a regular paste method when
+//! `insert_text` returns `Ok(false)`. The caret's screen position is exposed
+//! the same way for placing the overlay, and the kind of focused control for
+//! choosing how to output text.
+
+use crate::active_app::ActiveApp;
+use log::debug;
+
+/// Inserts text at the caret of the focused element.
+/// Returns `Ok(false)` when the focused element doesn't support insertion.
+pub fn insert_text(text: &str) -> Result<bool, String> {
+    platform_insert_text(text)
+}
+
+/// Returns up to `max_chars` characters before the caret in the focused text
+/// field, or `None` when the platform or app doesn't expose it.
+pub fn text_before_caret(max_chars: usize) -> Option<String> {
+    platform_text_before_caret(max_chars)
+}
+
+/// Title of the focused app's focused window
+#[cfg(target_os = "macos")]
+pub fn focused_window_title() -> Option<String> {
+    ax::focused_window_title()
+}
+
+/// Kind of control that has keyboard focus
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FocusedControl {
+    /// An editable text field
+    Text,
+    /// A terminal, where clipboard shortcuts differ between apps
+    Terminal,
+    /// A password field, which dictation never goes into
+    Secure,
+    /// A control that doesn't take text, like a button or a file list
+    NotEditable,
+}
+
+/// Apps whose windows are terminals, matched like app paste rules
+const TERMINAL_APPS: &[&str] = &[
+    "terminal",
+    "iterm2",
+    "dev.warp",
+    "kitty",
+    "alacritty",
+    "wezterm",
+    "ghostty",
+    "konsole",
+    "xterm",
+    "tilix",
+    "cmd.exe",
+    "powershell",
+    "pwsh.exe",
+];
+
+/// What kind of control has focus in `app`, or `None` when the platform or
+/// app doesn't say. Unknown controls are `None` rather than `NotEditable`,
+/// since many apps expose little.
+pub fn focused_control(app: Option<&ActiveApp>) -> Option<FocusedControl> {
+    let control = platform_focused_control();
+    // A password prompt inside a terminal is still a password prompt
+    if control != Some(FocusedControl::Secure) && app.is_some_and(is_terminal_app) {
+        return Some(FocusedControl::Terminal);
+    }
+    control
+}
+
+/// Whether `app` is a terminal emulator
+pub fn is_terminal_app(app: &ActiveApp) -> bool {
+    TERMINAL_APPS.iter().any(|name| app.matches(name))
+}
+
+#[cfg(target_os = "linux")]
+fn platform_focused_control() -> Option<FocusedControl> {
+    atspi::focused_control()
+        .map_err(|e| debug!("AT-SPI focused control unavailable: {}", e))
+        .ok()
+        .flatten()
+}
+
+#[cfg(target_os = "macos")]
+fn platform_focused_control() -> Option<FocusedControl> {
+    ax::focused_control()
+}
+
+#[cfg(target_os = "windows")]
+fn platform_focused_control() -> Option<FocusedControl> {
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
+    };
+    use windows::Win32::UI::Accessibility::{
+        CUIAutomation, IUIAutomation, UIA_ButtonControlTypeId, UIA_CheckBoxControlTypeId,
+        UIA_DocumentControlTypeId, UIA_EditControlTypeId, UIA_ListControlTypeId,
+        UIA_ListItemControlTypeId, UIA_MenuItemControlTypeId, UIA_RadioButtonControlTypeId,
+        UIA_TabItemControlTypeId, UIA_TreeControlTypeId, UIA_TreeItemControlTypeId,
+    };
+
+    unsafe {
+        // Does nothing if COM is already set up on this thread
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        let automation: IUIAutomation =
+            CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok()?;
+        let element = automation.GetFocusedElement().ok()?;
+
+        if element
+            .CurrentIsPassword()
+            .is_ok_and(|password| password.as_bool())
+        {
+            return Some(FocusedControl::Secure);
+        }
+        // Windows Terminal and the classic console
+        let class = element.CurrentClassName().map(|c| c.to_string());
+        if matches!(class.as_deref(), Ok("TermControl" | "ConsoleWindowClass")) {
+            return Some(FocusedControl::Terminal);
+        }
+        let control_type = element.CurrentControlType().ok()?;
+        if control_type == UIA_EditControlTypeId || control_type == UIA_DocumentControlTypeId {
+            Some(FocusedControl::Text)
+        } else if [
+            UIA_ButtonControlTypeId,
+            UIA_CheckBoxControlTypeId,
+            UIA_RadioButtonControlTypeId,
+            UIA_ListControlTypeId,
+            UIA_ListItemControlTypeId,
+            UIA_TreeControlTypeId,
+            UIA_TreeItemControlTypeId,
+            UIA_MenuItemControlTypeId,
+            UIA_TabItemControlTypeId,
+        ]
+        .contains(&control_type)
+        {
+            Some(FocusedControl::NotEditable)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn platform_focused_control() -> Option<FocusedControl> {
+    None
+}
+
+/// Screen rectangle of the caret in the focused text field. Measured in
+/// points on macOS and in physical pixels on Windows.
+#[derive(Clone, Copy, Debug)]
+pub struct CaretBounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Returns where the caret is on screen, or `None` when the platform or app
+/// doesn't expose it.
+pub fn caret_bounds() -> Option<CaretBounds> {
+    platform_caret_bounds()
+}
+
+#[cfg(target_os = "macos")]
+fn platform_caret_bounds() -> Option<CaretBounds> {
+    ax::caret_bounds()
+}
+
+/// Only apps drawing the system caret report it here; most browsers and
+/// Electron apps don't.
+#[cfg(target_os = "windows")]
+fn platform_caret_bounds() -> Option<CaretBounds> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::Graphics::Gdi::ClientToScreen;
+    use windows::Win32::UI::WindowsAndMessaging::{GetGUIThreadInfo, GUITHREADINFO};
+
+    let mut info = GUITHREADINFO {
+        cbSize: std::mem::size_of::<GUITHREADINFO>() as u32,
+        ..Default::default()
+    };
+    unsafe {
+        // Thread 0 is the foreground thread
+        GetGUIThreadInfo(0, &mut info).ok()?;
+        if info.hwndCaret.is_invalid() {
+            return None;
+        }
+        let mut origin = POINT {
+            x: info.rcCaret.left,
+            y: info.rcCaret.top,
+        };
+        if !ClientToScreen(info.hwndCaret, &mut origin).as_bool() {
+            return None;
+        }
+        Some(CaretBounds {
+            x: origin.x as f64,
+            y: origin.y as f64,
+            width: (info.rcCaret.right - info.rcCaret.left) as f64,
+            height: (info.rcCaret.bottom - info.rcCaret.top) as f64,
+        })
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn platform_caret_bounds() -> Option<CaretBounds> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn platform_text_before_caret(max_chars: usize) -> Option<String> {
+    atspi::text_before_caret(max_chars)
+        .map_err(|e| debug!("AT-SPI caret context unavailable: {}", e))
+        .ok()
+        .flatten()
+}
+
+#[cfg(target_os = "macos")]
+fn platform_text_before_caret(max_chars: usize) -> Option<String> {
+    ax::text_before_caret(max_chars)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn platform_text_before_caret(_max_chars: usize) -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn platform_insert_text(text: &str) -> Result<bool, String> {
+    match atspi::insert_text(text) {
+        Ok(inserted) => Ok(inserted),
+        Err(e) => {
+            // A missing or broken accessibility bus is treated as unsupported
+            debug!("AT-SPI insertion unavailable: {}", e);
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn platform_insert_text(text: &str) -> Result<bool, String> {
+    let inserted = ax::insert_text(text);
+    if !inserted {
+        debug!("Focused element doesn't accept AXSelectedText");
+    }
+    Ok(inserted)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn platform_insert_text(_text: &str) -> Result<bool, String> {
+    debug!("Accessibility insertion is not supported on this platform");
+    Ok(false)
+}
+
+/// Minimal AT-SPI client over D-Bus. Finds the focused editable element in
+/// the active window and calls `EditableText.InsertText` at the caret.
+#[cfg(target_os = "linux")]
+mod atspi {
+    use super::FocusedControl;
+    use log::debug;
+    use std::sync::{Mutex, PoisonError};
+    use std::time::{Duration, Instant};
+    use zbus::blocking::Connection;
+    use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+    const ROOT_PATH: &str = "/org/a11y/atspi/accessible/root";
+    const REGISTRY: &str = "org.a11y.atspi.Registry";
+    const ACCESSIBLE_IFACE: &str = "org.a11y.atspi.Accessible";
+    const TEXT_IFACE: &str = "org.a11y.atspi.Text";
+    const EDITABLE_TEXT_IFACE: &str = "org.a11y.atspi.EditableText";
+
+    // AtspiStateType values
+    const STATE_ACTIVE: u32 = 1;
+    const STATE_EDITABLE: u32 = 7;
+    const STATE_FOCUSED: u32 = 12;
+
+    // AtspiRole values
+    const ROLE_CHECK_BOX: u32 = 7;
+    const ROLE_LIST: u32 = 31;
+    const ROLE_LIST_ITEM: u32 = 32;
+    const ROLE_MENU_ITEM: u32 = 35;
+    const ROLE_PAGE_TAB: u32 = 37;
+    const ROLE_PASSWORD_TEXT: u32 = 40;
+    const ROLE_BUTTON: u32 = 43;
+    const ROLE_RADIO_BUTTON: u32 = 44;
+    const ROLE_TABLE: u32 = 55;
+    const ROLE_TABLE_CELL: u32 = 56;
+    const ROLE_TERMINAL: u32 = 60;
+    const ROLE_TOGGLE_BUTTON: u32 = 62;
+    const ROLE_TREE: u32 = 65;
+    const ROLE_TREE_TABLE: u32 = 66;
+
+    /// Limits on the search for the focused element, which makes a blocking
+    /// D-Bus call per node, so huge trees (browsers) can't stall a paste
+    const MAX_VISITED_NODES: usize = 500;
+    const MAX_DEPTH: usize = 25;
+    const SEARCH_BUDGET: Duration = Duration::from_millis(100);
+
+    type ObjectRef = (String, OwnedObjectPath);
+
+    /// The focused element last found, with the window it's in. Checked
+    /// first, since a dictation looks up focus several times.
+    static LAST_FOCUSED: Mutex<Option<(ObjectRef, ObjectRef)>> = Mutex::new(None);
+
+    fn connect() -> zbus::Result<Connection> {
+        let session = Connection::session()?;
+        let reply = session.call_method(
+            Some("org.a11y.Bus"),
+            "/org/a11y/bus",
+            Some("org.a11y.Bus"),
+            "GetAddress",
+            &(),
+        )?;
+        let address: String = reply.body().deserialize()?;
+        zbus::blocking::connection::Builder::address(address.as_str())?.build()
+    }
+
+    fn children(conn: &Connection, object: &ObjectRef) -> zbus::Result<Vec<ObjectRef>> {
+        let reply = conn.call_method(
+            Some(object.0.as_str()),
+            object.1.as_str(),
+            Some(ACCESSIBLE_IFACE),
+            "GetChildren",
+            &(),
+        )?;
+        reply.body().deserialize()
+    }
+
+    fn states(conn: &Connection, object: &ObjectRef) -> zbus::Result<Vec<u32>> {
+        let reply = conn.call_method(
+            Some(object.0.as_str()),
+            object.1.as_str(),
+            Some(ACCESSIBLE_IFACE),
+            "GetState",
+            &(),
+        )?;
+        reply.body().deserialize()
+    }
+
+    fn interfaces(conn: &Connection, object: &ObjectRef) -> zbus::Result<Vec<String>> {
+        let reply = conn.call_method(
+            Some(object.0.as_str()),
+            object.1.as_str(),
+            Some(ACCESSIBLE_IFACE),
+            "GetInterfaces",
+            &(),
+        )?;
+        reply.body().deserialize()
+    }
+
+    fn role(conn: &Connection, object: &ObjectRef) -> zbus::Result<u32> {
+        let reply = conn.call_method(
+            Some(object.0.as_str()),
+            object.1.as_str(),
+            Some(ACCESSIBLE_IFACE),
+            "GetRole",
+            &(),
+        )?;
+        reply.body().deserialize()
+    }
+
+    /// States are a bitfield split across 32-bit words
+    fn has_state(states: &[u32], state: u32) -> bool {
+        states
+            .get((state / 32) as usize)
+            .is_some_and(|bits| bits & (1 << (state % 32)) != 0)
+    }
+
+    /// Finds the focused element whose states pass `accept`
+    fn find_focused(
+        conn: &Connection,
+        accept: impl Fn(&[u32]) -> bool,
+    ) -> zbus::Result<Option<(ObjectRef, Vec<u32>)>> {
+        let root: ObjectRef = (REGISTRY.to_string(), OwnedObjectPath::try_from(ROOT_PATH)?);
+
+        // Only search the active window of each application
+        let mut windows = Vec::new();
+        for app in children(conn, &root)? {
+            for window in children(conn, &app).unwrap_or_default() {
+                if states(conn, &window).is_ok_and(|s| has_state(&s, STATE_ACTIVE)) {
+                    windows.push(window);
+                }
+            }
+        }
+
+        let cached = LAST_FOCUSED
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        if let Some((_, object)) = cached.filter(|(window, _)| windows.contains(window)) {
+            if let Ok(object_states) = states(conn, &object) {
+                if has_state(&object_states, STATE_FOCUSED) && accept(&object_states) {
+                    return Ok(Some((object, object_states)));
+                }
+            }
+        }
+
+        let started = Instant::now();
+        let mut pending: Vec<(ObjectRef, usize, usize)> = windows
+            .iter()
+            .enumerate()
+            .map(|(index, window)| (window.clone(), 0, index))
+            .collect();
+        let mut visited = 0;
+        while let Some((object, depth, window)) = pending.pop() {
+            visited += 1;
+            if visited > MAX_VISITED_NODES || started.elapsed() > SEARCH_BUDGET {
+                debug!(
+                    "Gave up looking for the focused element after {} nodes",
+                    visited
+                );
+                break;
+            }
+
+            let Ok(object_states) = states(conn, &object) else {
+                continue;
+            };
+            if has_state(&object_states, STATE_FOCUSED) && accept(&object_states) {
+                *LAST_FOCUSED.lock().unwrap_or_else(PoisonError::into_inner) =
+                    Some((windows[window].clone(), object.clone()));
+                return Ok(Some((object, object_states)));
+            }
+            if depth < MAX_DEPTH {
+                pending.extend(
+                    children(conn, &object)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|child| (child, depth + 1, window)),
+                );
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn find_focused_editable(conn: &Connection) -> zbus::Result<Option<ObjectRef>> {
+        let focused = find_focused(conn, |states| has_state(states, STATE_EDITABLE))?;
+        Ok(focused.map(|(object, _)| object))
+    }
+
+    pub fn focused_control() -> zbus::Result<Option<FocusedControl>> {
+        let conn = connect()?;
+
+        let Some((object, object_states)) = find_focused(&conn, |_| true)? else {
+            return Ok(None);
+        };
+        Ok(match role(&conn, &object)? {
+            ROLE_PASSWORD_TEXT => Some(FocusedControl::Secure),
+            ROLE_TERMINAL => Some(FocusedControl::Terminal),
+            _ if has_state(&object_states, STATE_EDITABLE) => Some(FocusedControl::Text),
+            ROLE_BUTTON | ROLE_TOGGLE_BUTTON | ROLE_CHECK_BOX | ROLE_RADIO_BUTTON | ROLE_LIST
+            | ROLE_LIST_ITEM | ROLE_TABLE | ROLE_TABLE_CELL | ROLE_TREE | ROLE_TREE_TABLE
+            | ROLE_MENU_ITEM | ROLE_PAGE_TAB => Some(FocusedControl::NotEditable),
+            _ => None,
+        })
+    }
+
+    fn caret_offset(conn: &Connection, object: &ObjectRef) -> zbus::Result<i32> {
+        let reply = conn.call_method(
+            Some(object.0.as_str()),
+            object.1.as_str(),
+            Some("org.freedesktop.DBus.Properties"),
+            "Get",
+            &(TEXT_IFACE, "CaretOffset"),
+        )?;
+        let caret: OwnedValue = reply.body().deserialize()?;
+        Ok(i32::try_from(caret)?)
+    }
+
+    pub fn text_before_caret(max_chars: usize) -> zbus::Result<Option<String>> {
+        let conn = connect()?;
+
+        let Some(target) = find_focused_editable(&conn)? else {
+            return Ok(None);
+        };
+        let caret = caret_offset(&conn, &target)?;
+        let start = caret.saturating_sub(max_chars as i32).max(0);
+
+        let reply = conn.call_method(
+            Some(target.0.as_str()),
+            target.1.as_str(),
+            Some(TEXT_IFACE),
+            "GetText",
+            &(start, caret),
+        )?;
+        Ok(Some(reply.body().deserialize()?))
+    }
+
+    /// `InsertText` takes the length of the string in bytes, not characters
+    pub(super) fn insert_length(text: &str) -> i32 {
+        text.len() as i32
+    }
+
+    pub fn insert_text(text: &str) -> zbus::Result<bool> {
+        let conn = connect()?;
+
+        let Some(target) = find_focused_editable(&conn)? else {
+            return Ok(false);
+        };
+        if !interfaces(&conn, &target)?
+            .iter()
+            .any(|iface| iface == EDITABLE_TEXT_IFACE)
+        {
+            return Ok(false);
+        }
+
+        let caret = caret_offset(&conn, &target)?;
+
+        let reply = conn.call_method(
+            Some(target.0.as_str()),
+            target.1.as_str(),
+            Some(EDITABLE_TEXT_IFACE),
+            "InsertText",
+            &(caret, text, insert_length(text)),
+        )?;
+        reply.body().deserialize()
+    }
+}
+
+/// Sets `AXSelectedText` on the focused element, which replaces the selection
+/// (or inserts at the caret) the same way typing would.
+#[cfg(target_os = "macos")]
+mod ax {
+    use core_foundation::base::{CFGetTypeID, CFRelease, CFTypeRef, TCFType};
+    use core_foundation::string::{CFString, CFStringRef};
+    use std::ffi::c_void;
+    use std::ptr;
+
+    use super::{CaretBounds, FocusedControl};
+
+    type AXUIElementRef = *const c_void;
+    type AXValueRef = *const c_void;
+    type AXError = i32;
+
+    const AX_ERROR_SUCCESS: AXError = 0;
+    const AX_VALUE_CG_RECT_TYPE: u32 = 3;
+    const AX_VALUE_CF_RANGE_TYPE: u32 = 4;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct CFRange {
+        location: isize,
+        length: isize,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct CGRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    }
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+        fn AXUIElementCreateSystemWide() -> AXUIElementRef;
+        fn AXUIElementCopyAttributeValue(
+            element: AXUIElementRef,
+            attribute: CFStringRef,
+            value: *mut CFTypeRef,
+        ) -> AXError;
+        fn AXUIElementCopyParameterizedAttributeValue(
+            element: AXUIElementRef,
+            attribute: CFStringRef,
+            parameter: CFTypeRef,
+            value: *mut CFTypeRef,
+        ) -> AXError;
+        fn AXUIElementIsAttributeSettable(
+            element: AXUIElementRef,
+            attribute: CFStringRef,
+            settable: *mut u8,
+        ) -> AXError;
+        fn AXUIElementSetAttributeValue(
+            element: AXUIElementRef,
+            attribute: CFStringRef,
+            value: CFTypeRef,
+        ) -> AXError;
+        fn AXValueGetValue(value: AXValueRef, value_type: u32, out: *mut c_void) -> bool;
+    }
+
+    /// Copies an attribute value. The caller owns the returned reference.
+    unsafe fn copy_attribute(element: AXUIElementRef, name: &'static str) -> Option<CFTypeRef> {
+        let attribute = CFString::from_static_string(name);
+        let mut value: CFTypeRef = ptr::null();
+        let err =
+            AXUIElementCopyAttributeValue(element, attribute.as_concrete_TypeRef(), &mut value);
+        (err == AX_ERROR_SUCCESS && !value.is_null()).then_some(value)
+    }
+
+    /// Returns the focused element. The caller owns the returned reference.
+    unsafe fn focused_element() -> Option<AXUIElementRef> {
+        if !AXIsProcessTrusted() {
+            return None;
+        }
+        let system = AXUIElementCreateSystemWide();
+        let focused = copy_attribute(system, "AXFocusedUIElement");
+        CFRelease(system);
+        focused
+    }
+
+    /// Copies a string attribute, `None` if it's missing or not a string
+    unsafe fn string_attribute(element: AXUIElementRef, name: &'static str) -> Option<String> {
+        let value = copy_attribute(element, name)?;
+        if CFGetTypeID(value) != CFString::type_id() {
+            CFRelease(value);
+            return None;
+        }
+        Some(CFString::wrap_under_create_rule(value as CFStringRef).to_string())
+    }
+
+    pub fn focused_window_title() -> Option<String> {
+        unsafe {
+            if !AXIsProcessTrusted() {
+                return None;
+            }
+            let system = AXUIElementCreateSystemWide();
+            let app = copy_attribute(system, "AXFocusedApplication");
+            CFRelease(system);
+            let app = app?;
+            let window = copy_attribute(app, "AXFocusedWindow");
+            CFRelease(app);
+            let window = window?;
+            let title = string_attribute(window, "AXTitle");
+            CFRelease(window);
+            title.filter(|title| !title.is_empty())
+        }
+    }
+
+    pub fn focused_control() -> Option<FocusedControl> {
+        unsafe {
+            let focused = focused_element()?;
+            let role = string_attribute(focused, "AXRole");
+            let subrole = string_attribute(focused, "AXSubrole");
+            CFRelease(focused);
+
+            if subrole.as_deref() == Some("AXSecureTextField") {
+                return Some(FocusedControl::Secure);
+            }
+            match role?.as_str() {
+                "AXTextField" | "AXTextArea" | "AXComboBox" => Some(FocusedControl::Text),
+                "AXButton" | "AXCheckBox" | "AXRadioButton" | "AXPopUpButton" | "AXMenuButton"
+                | "AXList" | "AXOutline" | "AXTable" | "AXRow" | "AXCell" | "AXMenuItem" => {
+                    Some(FocusedControl::NotEditable)
+                }
+                _ => None,
+            }
+        }
+    }
+
+    pub fn text_before_caret(max_chars: usize) -> Option<String> {
+        unsafe {
+            let focused = focused_element()?;
+            let range = copy_attribute(focused, "AXSelectedTextRange");
+            let value = copy_attribute(focused, "AXValue");
+            CFRelease(focused);
+
+            let mut caret = CFRange::default();
+            let has_range = range.is_some_and(|range| {
+                let ok = AXValueGetValue(
+                    range,
+                    AX_VALUE_CF_RANGE_TYPE,
+                    &mut caret as *mut CFRange as *mut c_void,
+                );
+                CFRelease(range);
+                ok
+            });
+            let value = value?;
+            if !has_range || CFGetTypeID(value) != CFString::type_id() {
+                CFRelease(value);
+                return None;
+            }
+
+            // AX ranges are in UTF-16 code units
+            let text = CFString::wrap_under_create_rule(value as CFStringRef).to_string();
+            let units: Vec<u16> = text.encode_utf16().collect();
+            let end = (caret.location.max(0) as usize).min(units.len());
+            let before = String::from_utf16_lossy(&units[..end]);
+            let skip = before.chars().count().saturating_sub(max_chars);
+            Some(before.chars().skip(skip).collect())
+        }
+    }
+
+    /// Bounds of the selected range, which is the caret when nothing is
+    /// selected. In global points with the origin at the top left.
+    pub fn caret_bounds() -> Option<CaretBounds> {
+        unsafe {
+            let focused = focused_element()?;
+            let Some(range) = copy_attribute(focused, "AXSelectedTextRange") else {
+                CFRelease(focused);
+                return None;
+            };
+
+            let attribute = CFString::from_static_string("AXBoundsForRange");
+            let mut bounds: CFTypeRef = ptr::null();
+            let err = AXUIElementCopyParameterizedAttributeValue(
+                focused,
+                attribute.as_concrete_TypeRef(),
+                range,
+                &mut bounds,
+            );
+            CFRelease(range);
+            CFRelease(focused);
+            if err != AX_ERROR_SUCCESS || bounds.is_null() {
+                return None;
+            }
+
+            let mut rect = CGRect::default();
+            let ok = AXValueGetValue(
+                bounds,
+                AX_VALUE_CG_RECT_TYPE,
+                &mut rect as *mut CGRect as *mut c_void,
+            );
+            CFRelease(bounds);
+
+            // Some apps answer with an empty rect at the origin instead of
+            // an error
+            (ok && (rect.x != 0.0 || rect.y != 0.0 || rect.height != 0.0)).then_some(CaretBounds {
+                x: rect.x,
+                y: rect.y,
+                width: rect.width,
+                height: rect.height,
+            })
+        }
+    }
+
+    pub fn insert_text(text: &str) -> bool {
+        unsafe {
+            let Some(focused) = focused_element() else {
+                return false;
+            };
+
+            let attribute = CFString::from_static_string("AXSelectedText");
+            let mut settable = 0u8;
+            let err = AXUIElementIsAttributeSettable(
+                focused,
+                attribute.as_concrete_TypeRef(),
+                &mut settable,
+            );
+            if err != AX_ERROR_SUCCESS || settable == 0 {
+                CFRelease(focused);
+                return false;
+            }
+
+            let before = string_attribute(focused, "AXValue");
+            // Replacing a selection with the same text leaves nothing to compare
+            let same_as_selection =
+                string_attribute(focused, "AXSelectedText").is_some_and(|sel| sel == text);
+            let value = CFString::new(text);
+            let err = AXUIElementSetAttributeValue(
+                focused,
+                attribute.as_concrete_TypeRef(),
+                value.as_CFTypeRef(),
+            );
+            let after = string_attribute(focused, "AXValue");
+            CFRelease(focused);
+
+            if err != AX_ERROR_SUCCESS {
+                return false;
+            }
+
+            // Some apps (notably Electron) report success without changing
+            // anything; an unchanged value means the text never landed. The
+            // value is compared rather than its length, since replacing a
+            // selection with text of the same length keeps the length.
+            same_as_selection
+                || !matches!((before, after), (Some(b), Some(a)) if a == b && !text.is_empty())
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::atspi::insert_length;
+
+    #[test]
+    fn insert_length_counts_bytes() {
+        assert_eq!(insert_length("hello"), 5);
+        assert_eq!(insert_length("café"), 5);
+        assert_eq!(insert_length("你好"), 6);
+        assert_eq!(insert_length("👍"), 4);
+    }
+}
@@ -1,15 +1,32 @@
+use crate::active_app;
+use crate::app_overrides;
 #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
 use crate::apple_intelligence;
 use crate::audio_feedback::{play_feedback_sound, play_feedback_sound_blocking, SoundType};
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::commands::permissions::{self, MicrophoneAccess};
+use crate::dictation;
+use crate::email;
+use crate::events::{self, AppEvent, DictationState, ErrorSource};
+use crate::http_actions;
+use crate::incognito;
+use crate::llm_client::{CompletionError, CompletionParams};
+use crate::managers::analytics::{self, UsageCategory};
 use crate::managers::audio::AudioRecordingManager;
-use crate::managers::history::HistoryManager;
-use crate::managers::transcription::TranscriptionManager;
-use crate::settings::{get_settings, AppSettings, APPLE_INTELLIGENCE_PROVIDER_ID};
+use crate::managers::history::{HistoryManager, TranscriptionMetadata};
+use crate::managers::transcription::{TranscriptionManager, TranscriptionOutput};
+use crate::mqtt;
+use crate::note_targets;
+use crate::notifications::{notify, NotificationKind};
+use crate::obs_captions;
+use crate::policy;
+use crate::post_process_queue::{self, QueuedPostProcess};
+use crate::preview;
+use crate::settings::{get_settings, AppSettings, PasteMethod, APPLE_INTELLIGENCE_PROVIDER_ID};
 use crate::shortcut;
-use crate::tray::{change_tray_icon, TrayIconState};
-use crate::utils::{
-    self, show_processing_overlay, show_recording_overlay, show_transcribing_overlay,
-};
+use crate::transcript_output;
+use crate::utils;
+use crate::webhooks::{self, TranscriptionPayload};
 use crate::TranscriptionCoordinator;
 use ferrous_opencc::{config::BuiltinConfig, OpenCC};
 use log::{debug, error, warn};
@@ -40,6 +57,10 @@ pub trait ShortcutAction: Send + Sync {
 // Transcribe Action
 struct TranscribeAction {
     post_process: bool,
+    /// Append to the binding's output file instead of pasting
+    to_file: bool,
+    /// Keep this dictation out of history even when session incognito is off
+    incognito: bool,
 }
 
 /// Field name for structured output JSON schema
@@ -50,18 +71,78 @@ fn strip_invisible_chars(s: &str) -> String {
     s.replace(['\u{200B}', '\u{200C}', '\u{200D}', '\u{FEFF}'], "")
 }
 
-/// Build a system prompt from the user's prompt template.
+/// Build a system prompt from the configured system prompt and the user's prompt template.
 /// Removes `${output}` placeholder since the transcription is sent as the user message.
-fn build_system_prompt(prompt_template: &str) -> String {
-    prompt_template.replace("${output}", "").trim().to_string()
+fn build_system_prompt(system_prompt: Option<&str>, prompt_template: &str) -> String {
+    let instructions = prompt_template.replace("${output}", "").trim().to_string();
+    match system_prompt {
+        Some(system) if !instructions.is_empty() => format!("{}\n\n{}", system, instructions),
+        Some(system) => system.to_string(),
+        None => instructions,
+    }
+}
+
+/// Build the user message for legacy (non-structured) mode.
+/// Replaces `${output}` with the transcription, or appends the transcription
+/// when the template does not reference it.
+fn build_user_prompt(prompt_template: &str, transcription: &str) -> String {
+    if prompt_template.contains("${output}") {
+        prompt_template.replace("${output}", transcription)
+    } else if prompt_template.trim().is_empty() {
+        transcription.to_string()
+    } else {
+        format!("{}\n\n{}", prompt_template.trim_end(), transcription)
+    }
+}
+
+/// A cloud provider couldn't be reached, so post-processing is worth trying
+/// again once the network is back
+#[derive(Debug)]
+pub(crate) struct ProviderUnreachable;
+
+/// Text of the prompt selected for post-processing
+pub(crate) fn selected_prompt_text(settings: &AppSettings) -> Option<String> {
+    let prompt_id = settings.post_process_selected_prompt_id.as_ref()?;
+    settings
+        .post_process_prompts
+        .iter()
+        .find(|prompt| &prompt.id == prompt_id)
+        .map(|prompt| prompt.prompt.clone())
+}
+
+/// Result of post-processing a transcript
+pub(crate) struct PostProcessed {
+    /// `None` if it was skipped or failed, so the raw text should be used
+    pub text: Option<String>,
+    /// Requests retried after transient provider errors
+    pub retries: u32,
+}
+
+pub(crate) async fn post_process_transcription(
+    app: &AppHandle,
+    settings: &AppSettings,
+    transcription: &str,
+) -> Result<PostProcessed, ProviderUnreachable> {
+    let mut retries = 0;
+    let text = process_with_provider(app, settings, transcription, &mut retries).await?;
+    if retries > 0 {
+        debug!("Post-processing needed {} retries", retries);
+    }
+    Ok(PostProcessed { text, retries })
 }
 
-async fn post_process_transcription(settings: &AppSettings, transcription: &str) -> Option<String> {
+/// Post-processes `transcription`, adding every retried request to `retries`
+async fn process_with_provider(
+    app: &AppHandle,
+    settings: &AppSettings,
+    transcription: &str,
+    retries: &mut u32,
+) -> Result<Option<String>, ProviderUnreachable> {
     let provider = match settings.active_post_process_provider().cloned() {
         Some(provider) => provider,
         None => {
             debug!("Post-processing enabled but no provider is selected");
-            return None;
+            return Ok(None);
         }
     };
 
@@ -76,35 +157,42 @@ async fn post_process_transcription(settings: &AppSettings, transcription: &str)
             "Post-processing skipped because provider '{}' has no model configured",
             provider.id
         );
-        return None;
+        return Ok(None);
     }
 
     let selected_prompt_id = match &settings.post_process_selected_prompt_id {
         Some(id) => id.clone(),
         None => {
             debug!("Post-processing skipped because no prompt is selected");
-            return None;
+            return Ok(None);
         }
     };
 
-    let prompt = match settings
+    let selected_prompt = match settings
         .post_process_prompts
         .iter()
         .find(|prompt| prompt.id == selected_prompt_id)
     {
-        Some(prompt) => prompt.prompt.clone(),
+        Some(prompt) => prompt,
         None => {
             debug!(
                 "Post-processing skipped because prompt '{}' was not found",
                 selected_prompt_id
             );
-            return None;
+            return Ok(None);
         }
     };
 
-    if prompt.trim().is_empty() {
+    let prompt = selected_prompt.prompt.clone();
+    let configured_system_prompt = settings.resolve_system_prompt(selected_prompt);
+    let mut params = CompletionParams::from(selected_prompt);
+    if provider.id == "openrouter" {
+        params.routing = Some(settings.openrouter_routing.clone());
+    }
+
+    if prompt.trim().is_empty() && configured_system_prompt.is_none() {
         debug!("Post-processing skipped because the selected prompt is empty");
-        return None;
+        return Ok(None);
     }
 
     debug!(
@@ -121,7 +209,7 @@ async fn post_process_transcription(settings: &AppSettings, transcription: &str)
     if provider.supports_structured_output {
         debug!("Using structured outputs for provider '{}'", provider.id);
 
-        let system_prompt = build_system_prompt(&prompt);
+        let system_prompt = build_system_prompt(configured_system_prompt.as_deref(), &prompt);
         let user_content = transcription.to_string();
 
         // Handle Apple Intelligence separately since it uses native Swift APIs
@@ -132,10 +220,13 @@ async fn post_process_transcription(settings: &AppSettings, transcription: &str)
                     debug!(
                         "Apple Intelligence selected but not currently available on this device"
                     );
-                    return None;
+                    return Ok(None);
                 }
 
-                let token_limit = model.trim().parse::<i32>().unwrap_or(0);
+                let token_limit = params
+                    .max_tokens
+                    .map(|limit| limit.min(i32::MAX as u32) as i32)
+                    .unwrap_or_else(|| model.trim().parse::<i32>().unwrap_or(0));
                 return match apple_intelligence::process_text_with_system_prompt(
                     &system_prompt,
                     &user_content,
@@ -144,19 +235,25 @@ async fn post_process_transcription(settings: &AppSettings, transcription: &str)
                     Ok(result) => {
                         if result.trim().is_empty() {
                             debug!("Apple Intelligence returned an empty response");
-                            None
+                            Ok(None)
                         } else {
                             let result = strip_invisible_chars(&result);
                             debug!(
                                 "Apple Intelligence post-processing succeeded. Output length: {} chars",
                                 result.len()
                             );
-                            Some(result)
+                            Ok(Some(result))
                         }
                     }
                     Err(err) => {
                         error!("Apple Intelligence post-processing failed: {}", err);
-                        None
+                        notify(
+                            app,
+                            NotificationKind::PostProcessError,
+                            &format!("Apple Intelligence: {}", err),
+                            false,
+                        );
+                        Ok(None)
                     }
                 };
             }
@@ -164,7 +261,7 @@ async fn post_process_transcription(settings: &AppSettings, transcription: &str)
             #[cfg(not(all(target_os = "macos", target_arch = "aarch64")))]
             {
                 debug!("Apple Intelligence provider selected on unsupported platform");
-                return None;
+                return Ok(None);
             }
         }
 
@@ -181,16 +278,18 @@ async fn post_process_transcription(settings: &AppSettings, transcription: &str)
             "additionalProperties": false
         });
 
-        match crate::llm_client::send_chat_completion_with_schema(
+        let (result, attempts) = crate::llm_client::send_chat_completion_with_retry(
             &provider,
             api_key.clone(),
             &model,
             user_content,
             Some(system_prompt),
             Some(json_schema),
+            params.clone(),
         )
-        .await
-        {
+        .await;
+        *retries += attempts;
+        match result {
             Ok(Some(content)) => {
                 // Parse the JSON response to extract the transcription field
                 match serde_json::from_str::<serde_json::Value>(&content) {
@@ -204,10 +303,10 @@ async fn post_process_transcription(settings: &AppSettings, transcription: &str)
                                 provider.id,
                                 result.len()
                             );
-                            return Some(result);
+                            return Ok(Some(result));
                         } else {
                             error!("Structured output response missing 'transcription' field");
-                            return Some(strip_invisible_chars(&content));
+                            return Ok(Some(strip_invisible_chars(&content)));
                         }
                     }
                     Err(e) => {
@@ -215,13 +314,40 @@ async fn post_process_transcription(settings: &AppSettings, transcription: &str)
                             "Failed to parse structured output JSON: {}. Returning raw content.",
                             e
                         );
-                        return Some(strip_invisible_chars(&content));
+                        return Ok(Some(strip_invisible_chars(&content)));
                     }
                 }
             }
             Ok(None) => {
                 error!("LLM API response has no content");
-                return None;
+                notify(
+                    app,
+                    NotificationKind::PostProcessError,
+                    &format!("{} returned no text", provider.label),
+                    false,
+                );
+                return Ok(None);
+            }
+            Err(CompletionError::Unreachable(e)) if !incognito::is_local_provider(&provider) => {
+                warn!(
+                    "Post-processing provider '{}' is unreachable: {}",
+                    provider.id, e
+                );
+                return Err(ProviderUnreachable);
+            }
+            // Legacy mode would only run into the same limits
+            Err(CompletionError::Transient(e)) => {
+                error!(
+                    "LLM post-processing failed for provider '{}' after {} retries: {}. Falling back to original transcription.",
+                    provider.id, attempts, e
+                );
+                notify(
+                    app,
+                    NotificationKind::PostProcessError,
+                    &format!("{}: {}", provider.label, e),
+                    false,
+                );
+                return Ok(None);
             }
             Err(e) => {
                 warn!(
@@ -233,13 +359,23 @@ async fn post_process_transcription(settings: &AppSettings, transcription: &str)
         }
     }
 
-    // Legacy mode: Replace ${output} variable in the prompt with the actual text
-    let processed_prompt = prompt.replace("${output}", transcription);
+    // Legacy mode: Replace ${output} variable in the prompt with the actual text.
+    // A configured system prompt is sent as a separate system message.
+    let processed_prompt = build_user_prompt(&prompt, transcription);
     debug!("Processed prompt length: {} chars", processed_prompt.len());
 
-    match crate::llm_client::send_chat_completion(&provider, api_key, &model, processed_prompt)
-        .await
-    {
+    let (result, attempts) = crate::llm_client::send_chat_completion_with_retry(
+        &provider,
+        api_key,
+        &model,
+        processed_prompt,
+        configured_system_prompt,
+        None,
+        params,
+    )
+    .await;
+    *retries += attempts;
+    match result {
         Ok(Some(content)) => {
             let content = strip_invisible_chars(&content);
             debug!(
@@ -247,11 +383,24 @@ async fn post_process_transcription(settings: &AppSettings, transcription: &str)
                 provider.id,
                 content.len()
             );
-            Some(content)
+            Ok(Some(content))
         }
         Ok(None) => {
             error!("LLM API response has no content");
-            None
+            notify(
+                app,
+                NotificationKind::PostProcessError,
+                &format!("{} returned no text", provider.label),
+                false,
+            );
+            Ok(None)
+        }
+        Err(CompletionError::Unreachable(e)) if !incognito::is_local_provider(&provider) => {
+            warn!(
+                "Post-processing provider '{}' is unreachable: {}",
+                provider.id, e
+            );
+            Err(ProviderUnreachable)
         }
         Err(e) => {
             error!(
@@ -259,7 +408,13 @@ async fn post_process_transcription(settings: &AppSettings, transcription: &str)
                 provider.id,
                 e
             );
-            None
+            notify(
+                app,
+                NotificationKind::PostProcessError,
+                &format!("{}: {}", provider.label, e),
+                false,
+            );
+            Ok(None)
         }
     }
 }
@@ -308,18 +463,53 @@ async fn maybe_convert_chinese_variant(
     }
 }
 
+/// Tells where the raw transcript went while post-processing waits for the
+/// provider to come back
+fn queued_notice(
+    note_target: Option<&str>,
+    to_file: bool,
+    preview: bool,
+    copy_only: bool,
+) -> String {
+    let delivered = match note_target {
+        Some(name) => format!("Sent the raw transcript to {}", name),
+        None if to_file => "Saved the raw transcript to the file".to_string(),
+        None if preview => "Showing the raw transcript".to_string(),
+        None if copy_only => "Copied the raw transcript".to_string(),
+        None => "Pasted the raw transcript".to_string(),
+    };
+    format!("{}. It will be refined once you're back online.", delivered)
+}
+
 impl ShortcutAction for TranscribeAction {
     fn start(&self, app: &AppHandle, binding_id: &str, _shortcut_str: &str) {
         let start_time = Instant::now();
         debug!("TranscribeAction::start called for binding: {}", binding_id);
 
+        match permissions::microphone_access() {
+            MicrophoneAccess::Granted => {}
+            MicrophoneAccess::Prompting => {
+                debug!("Asking for microphone access, not recording until it's answered");
+                return;
+            }
+            MicrophoneAccess::Denied => {
+                notify(
+                    app,
+                    NotificationKind::MicrophoneDenied,
+                    permissions::MICROPHONE_DENIED,
+                    false,
+                );
+                return;
+            }
+        }
+
         // Load model in the background
         let tm = app.state::<Arc<TranscriptionManager>>();
         tm.initiate_model_load();
 
         let binding_id = binding_id.to_string();
-        change_tray_icon(app, TrayIconState::Recording);
-        show_recording_overlay(app);
+        dictation::transition(app, DictationState::Recording);
+        incognito::show_overlay_indicator(app, self.incognito || incognito::is_session_enabled());
 
         let rm = app.state::<Arc<AudioRecordingManager>>();
 
@@ -370,6 +560,8 @@ impl ShortcutAction for TranscribeAction {
         if recording_started {
             // Dynamically register the cancel shortcut in a separate task to avoid deadlock
             shortcut::register_cancel_shortcut(app);
+        } else {
+            dictation::transition(app, DictationState::Idle);
         }
 
         debug!(
@@ -390,8 +582,7 @@ impl ShortcutAction for TranscribeAction {
         let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
         let hm = Arc::clone(&app.state::<Arc<HistoryManager>>());
 
-        change_tray_icon(app, TrayIconState::Transcribing);
-        show_transcribing_overlay(app);
+        dictation::transition(app, DictationState::Transcribing);
 
         // Unmute before playing audio feedback so the stop sound is audible
         rm.remove_mute();
@@ -401,6 +592,8 @@ impl ShortcutAction for TranscribeAction {
 
         let binding_id = binding_id.to_string(); // Clone binding_id for the async task
         let post_process = self.post_process;
+        let to_file = self.to_file;
+        let incognito = self.incognito || incognito::is_session_enabled();
 
         tauri::async_runtime::spawn(async move {
             let _guard = FinishGuard(ah.clone());
@@ -418,17 +611,57 @@ impl ShortcutAction for TranscribeAction {
                     samples.len()
                 );
 
+                // Per-app overrides follow the app being dictated into
+                let target = active_app::locked_target()
+                    .map(|target| target.app)
+                    .or_else(active_app::frontmost_app);
+                let resolved = app_overrides::resolve(&get_settings(&ah), target.as_ref());
+                // Read with the app, so both describe the same window
+                let window_title = resolved
+                    .settings
+                    .capture_window_titles
+                    .then(active_app::target_window_title)
+                    .flatten();
+
                 let transcription_time = Instant::now();
-                let samples_clone = samples.clone(); // Clone for history saving
-                match tm.transcribe(samples) {
-                    Ok(transcription) => {
+                // Shared with history saving
+                let samples = Arc::new(samples);
+                match tm.transcribe(Arc::clone(&samples), &resolved.settings) {
+                    Ok(output) => {
+                        let transcription_latency = transcription_time.elapsed();
+                        if let Some(model) = tm.get_current_model() {
+                            analytics::track(&ah, UsageCategory::Model, &model);
+                        }
+                        let transcription = output.full_text();
                         debug!(
-                            "Transcription completed in {:?}: '{}'",
+                            "Transcription completed in {:?}, {} characters",
                             transcription_time.elapsed(),
-                            transcription
+                            transcription.chars().count()
                         );
+                        if output.partial {
+                            notify(
+                                &ah,
+                                NotificationKind::TranscriptionPartial,
+                                &format!(
+                                    "Transcription took over {} seconds, so only the start of the recording was kept.",
+                                    resolved.settings.transcription_timeout_secs
+                                ),
+                                false,
+                            );
+                        }
                         if !transcription.is_empty() {
-                            let settings = get_settings(&ah);
+                            // Events reach API clients and logs, so incognito text stays out
+                            if !incognito {
+                                events::emit(
+                                    &ah,
+                                    AppEvent::Transcribed {
+                                        text: transcription.clone(),
+                                    },
+                                );
+                                obs_captions::caption(&ah, &transcription);
+                            }
+                            let post_process = resolved.post_process(post_process);
+                            let settings = resolved.settings;
                             let mut final_text = transcription.clone();
                             let mut post_processed_text: Option<String> = None;
                             let mut post_process_prompt: Option<String> = None;
@@ -440,87 +673,296 @@ impl ShortcutAction for TranscribeAction {
                                 final_text = converted_text;
                             }
 
+                            // Incognito transcripts never leave this machine, and
+                            // a policy can keep all of them on it
+                            let post_process = post_process
+                                && settings
+                                    .active_post_process_provider()
+                                    .is_none_or(|provider| {
+                                        policy::allows_provider(provider)
+                                            && (!incognito
+                                                || incognito::is_local_provider(provider))
+                                    });
+
                             // Then apply LLM post-processing if this is the post-process hotkey
                             // Uses final_text which may already have Chinese conversion applied
                             if post_process {
-                                show_processing_overlay(&ah);
+                                dictation::transition(&ah, DictationState::PostProcessing);
                             }
+                            // Like history, note targets keep incognito text out
+                            let note_target = settings
+                                .bindings
+                                .get(&binding_id)
+                                .and_then(|b| b.note_target_id.as_ref())
+                                .filter(|_| !incognito)
+                                .and_then(|id| settings.note_targets.iter().find(|t| &t.id == id));
+
+                            // Offline, the raw text goes out now and is refined later
+                            let mut queued: Option<QueuedPostProcess> = None;
+                            let mut post_process_retries = 0;
                             let processed = if post_process {
-                                post_process_transcription(&settings, &final_text).await
+                                // Only the spoken part goes to the LLM so verbatim text
+                                // survives, with the same Chinese conversion as the rest
+                                let (text, literal) = match &output.literal {
+                                    Some(literal) => (
+                                        maybe_convert_chinese_variant(&settings, &output.text)
+                                            .await
+                                            .unwrap_or_else(|| output.text.clone()),
+                                        Some(literal.clone()),
+                                    ),
+                                    None => (final_text.clone(), None),
+                                };
+                                match post_process_transcription(&ah, &settings, &text).await {
+                                    Ok(processed) => {
+                                        post_process_retries = processed.retries;
+                                        processed.text.map(|processed| {
+                                            TranscriptionOutput::join(
+                                                &processed,
+                                                literal.as_deref(),
+                                            )
+                                        })
+                                    }
+                                    Err(ProviderUnreachable) => {
+                                        notify(
+                                            &ah,
+                                            NotificationKind::PostProcessQueued,
+                                            &queued_notice(
+                                                note_target.map(|t| t.name.as_str()),
+                                                to_file,
+                                                settings.preview_before_paste,
+                                                settings.paste_method == PasteMethod::CopyOnly,
+                                            ),
+                                            false,
+                                        );
+                                        queued = Some(QueuedPostProcess {
+                                            text,
+                                            literal,
+                                            settings: settings.clone(),
+                                        });
+                                        None
+                                    }
+                                }
                             } else {
                                 None
                             };
                             if let Some(processed_text) = processed {
+                                analytics::track(
+                                    &ah,
+                                    UsageCategory::Provider,
+                                    &settings.post_process_provider_id,
+                                );
                                 post_processed_text = Some(processed_text.clone());
                                 final_text = processed_text;
+                                post_process_prompt = selected_prompt_text(&settings);
+                            } else if final_text != transcription {
+                                // Chinese conversion was applied but no LLM post-processing
+                                post_processed_text = Some(final_text.clone());
+                            }
+
+                            if !incognito {
+                                events::emit(
+                                    &ah,
+                                    AppEvent::TranscriptionCompleted {
+                                        text: final_text.clone(),
+                                    },
+                                );
+                                if final_text != transcription {
+                                    obs_captions::caption(&ah, &final_text);
+                                }
+                            }
+                            let target_app = target.map(|app| app.name);
+
+                            if incognito {
+                                debug!("Incognito dictation, not saving to history");
+                            } else {
+                                if let Some(journal_dir) = settings
+                                    .daily_journal_dir
+                                    .as_deref()
+                                    .filter(|d| !d.trim().is_empty())
+                                {
+                                    if let Err(e) = transcript_output::append_to_journal(
+                                        journal_dir,
+                                        &settings.daily_journal_template,
+                                        &final_text,
+                                        target_app.as_deref(),
+                                    ) {
+                                        warn!("Failed to append transcription to journal: {}", e);
+                                    }
+                                }
 
-                                // Get the prompt that was used
-                                if let Some(prompt_id) = &settings.post_process_selected_prompt_id {
-                                    if let Some(prompt) = settings
-                                        .post_process_prompts
-                                        .iter()
-                                        .find(|p| &p.id == prompt_id)
+                                // Save to history with post-processed text and prompt
+                                let hm_clone = Arc::clone(&hm);
+                                let transcription_for_history = transcription.clone();
+                                let metadata = TranscriptionMetadata {
+                                    model: tm.get_current_model(),
+                                    target_app,
+                                    window_title,
+                                    latency_ms: Some(transcription_latency.as_millis() as i64),
+                                };
+                                let payload = TranscriptionPayload {
+                                    event: TranscriptionPayload::EVENT,
+                                    text: final_text.clone(),
+                                    transcription_text: transcription.clone(),
+                                    post_processed_text: post_processed_text.clone(),
+                                    post_process_prompt: post_process_prompt.clone(),
+                                    timestamp: chrono::Utc::now().timestamp(),
+                                    duration_ms: (samples.len() as u64 * 1000
+                                        / WHISPER_SAMPLE_RATE as u64)
+                                        as i64,
+                                    model: metadata.model.clone(),
+                                    app_name: metadata.target_app.clone(),
+                                    window_title: metadata.window_title.clone(),
+                                    post_process_retries,
+                                };
+                                webhooks::send_transcription(&settings.webhooks, &payload);
+                                http_actions::run_all(&settings.http_actions, &payload);
+                                mqtt::publish_transcription(&payload);
+                                email::send_transcription(&ah, &settings.email, &payload);
+                                let ah_history = ah.clone();
+                                tauri::async_runtime::spawn(async move {
+                                    match hm_clone
+                                        .save_transcription(
+                                            samples,
+                                            transcription_for_history,
+                                            post_processed_text,
+                                            post_process_prompt,
+                                            metadata,
+                                        )
+                                        .await
                                     {
-                                        post_process_prompt = Some(prompt.prompt.clone());
+                                        Ok(entry_id) => {
+                                            if let Some(job) = queued {
+                                                post_process_queue::enqueue(entry_id, job);
+                                            }
+                                        }
+                                        Err(e) => {
+                                            error!(
+                                                "Failed to save transcription to history: {}",
+                                                e
+                                            );
+                                            analytics::track(
+                                                &ah_history,
+                                                UsageCategory::Error,
+                                                "history",
+                                            );
+                                        }
                                     }
+                                });
+                            }
+
+                            // Like history, pipes keep incognito text out
+                            if let Some(pipe_path) = settings
+                                .transcript_pipe_path
+                                .as_deref()
+                                .filter(|p| !incognito && !p.trim().is_empty())
+                            {
+                                if let Err(e) =
+                                    transcript_output::write_to_pipe(pipe_path, &final_text)
+                                {
+                                    warn!("Failed to send transcription to pipe: {}", e);
                                 }
-                            } else if final_text != transcription {
-                                // Chinese conversion was applied but no LLM post-processing
-                                post_processed_text = Some(final_text.clone());
                             }
 
-                            // Save to history with post-processed text and prompt
-                            let hm_clone = Arc::clone(&hm);
-                            let transcription_for_history = transcription.clone();
-                            tauri::async_runtime::spawn(async move {
-                                if let Err(e) = hm_clone
-                                    .save_transcription(
-                                        samples_clone,
-                                        transcription_for_history,
-                                        post_processed_text,
-                                        post_process_prompt,
-                                    )
-                                    .await
+                            if let Some(target) = note_target {
+                                if let Err(e) = note_targets::send(&ah, target, &final_text).await {
+                                    error!(
+                                        "Failed to send transcription to {}: {}",
+                                        target.name, e
+                                    );
+                                    // Don't lose the transcript, since it went nowhere else
+                                    let message = match utils::copy_text(&ah, &final_text) {
+                                        Ok(()) => format!(
+                                            "Couldn't send to {}, so the transcription was copied instead: {}",
+                                            target.name, e
+                                        ),
+                                        Err(copy_error) => format!(
+                                            "Couldn't send to {}: {}. Copying it failed too: {}",
+                                            target.name, e, copy_error
+                                        ),
+                                    };
+                                    events::emit_error(&ah, ErrorSource::NoteTarget, message);
+                                }
+                                dictation::transition(&ah, DictationState::Idle);
+                                return;
+                            }
+
+                            if to_file {
+                                let output_path = settings
+                                    .bindings
+                                    .get(&binding_id)
+                                    .and_then(|b| b.output_file_path.clone())
+                                    .unwrap_or_default();
+                                if let Err(e) =
+                                    transcript_output::append_to_file(&output_path, &final_text)
                                 {
-                                    error!("Failed to save transcription to history: {}", e);
+                                    error!("Failed to append transcription to file: {}", e);
+                                    events::emit_error(&ah, ErrorSource::Paste, e);
                                 }
-                            });
+                                dictation::transition(&ah, DictationState::Idle);
+                                return;
+                            }
+
+                            if settings.preview_before_paste {
+                                preview::show_preview(&ah, final_text);
+                                dictation::transition(&ah, DictationState::Idle);
+                                return;
+                            }
+
+                            // Copy-only pastes announce themselves already
+                            let notify_completion = settings.paste_method != PasteMethod::CopyOnly;
 
                             // Paste the final text (either processed or original)
                             let ah_clone = ah.clone();
                             let paste_time = Instant::now();
                             ah.run_on_main_thread(move || {
-                                match utils::paste(final_text, ah_clone.clone()) {
-                                    Ok(()) => debug!(
-                                        "Text pasted successfully in {:?}",
-                                        paste_time.elapsed()
-                                    ),
-                                    Err(e) => error!("Failed to paste transcription: {}", e),
+                                match utils::paste(final_text.clone(), ah_clone.clone(), incognito)
+                                {
+                                    Ok(()) => {
+                                        debug!(
+                                            "Text pasted successfully in {:?}",
+                                            paste_time.elapsed()
+                                        );
+                                        if notify_completion {
+                                            notify(
+                                                &ah_clone,
+                                                NotificationKind::Completed,
+                                                &final_text,
+                                                incognito,
+                                            );
+                                        }
+                                    }
+                                    Err(e) => {
+                                        error!("Failed to paste transcription: {}", e);
+                                        analytics::track(&ah_clone, UsageCategory::Error, "paste");
+                                        events::emit_error(&ah_clone, ErrorSource::Paste, e);
+                                    }
                                 }
                                 // Hide the overlay after transcription is complete
-                                utils::hide_recording_overlay(&ah_clone);
-                                change_tray_icon(&ah_clone, TrayIconState::Idle);
+                                dictation::transition(&ah_clone, DictationState::Idle);
                             })
                             .unwrap_or_else(|e| {
                                 error!("Failed to run paste on main thread: {:?}", e);
-                                utils::hide_recording_overlay(&ah);
-                                change_tray_icon(&ah, TrayIconState::Idle);
+                                dictation::transition(&ah, DictationState::Idle);
                             });
                         } else {
-                            utils::hide_recording_overlay(&ah);
-                            change_tray_icon(&ah, TrayIconState::Idle);
+                            dictation::transition(&ah, DictationState::Idle);
                         }
                     }
                     Err(err) => {
                         debug!("Global Shortcut Transcription error: {}", err);
-                        utils::hide_recording_overlay(&ah);
-                        change_tray_icon(&ah, TrayIconState::Idle);
+                        analytics::track(&ah, UsageCategory::Error, "transcription");
+                        notify(
+                            &ah,
+                            NotificationKind::TranscriptionError,
+                            &err.to_string(),
+                            false,
+                        );
+                        dictation::transition(&ah, DictationState::Idle);
                     }
                 }
             } else {
                 debug!("No samples retrieved from recording stop");
-                utils::hide_recording_overlay(&ah);
-                change_tray_icon(&ah, TrayIconState::Idle);
+                dictation::transition(&ah, DictationState::Idle);
             }
         });
 
@@ -544,6 +986,55 @@ impl ShortcutAction for CancelAction {
     }
 }
 
+// Undo Last Dictation Action
+struct UndoDictationAction;
+
+impl ShortcutAction for UndoDictationAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let ah = app.clone();
+        app.run_on_main_thread(move || {
+            if let Err(e) = utils::undo_last_paste(&ah) {
+                warn!("Failed to undo last dictation: {}", e);
+                events::emit_error(&ah, ErrorSource::Undo, e);
+            }
+        })
+        .unwrap_or_else(|e| error!("Failed to run undo on main thread: {:?}", e));
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Nothing to do on stop for undo
+    }
+}
+
+// Paste Last Transcript Action
+struct PasteLastTranscriptAction;
+
+impl ShortcutAction for PasteLastTranscriptAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        if let Err(e) = utils::paste_last_transcript(app) {
+            warn!("Failed to paste last transcript: {}", e);
+            events::emit_error(app, ErrorSource::Paste, e);
+        }
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Nothing to do on stop for re-paste
+    }
+}
+
+// Toggle OBS Captions Action
+struct ToggleObsCaptionsAction;
+
+impl ShortcutAction for ToggleObsCaptionsAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        obs_captions::toggle(app);
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Nothing to do on stop for toggle
+    }
+}
+
 // Test Action
 struct TestAction;
 
@@ -574,16 +1065,50 @@ pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::ne
         "transcribe".to_string(),
         Arc::new(TranscribeAction {
             post_process: false,
+            to_file: false,
+            incognito: false,
         }) as Arc<dyn ShortcutAction>,
     );
     map.insert(
         "transcribe_with_post_process".to_string(),
-        Arc::new(TranscribeAction { post_process: true }) as Arc<dyn ShortcutAction>,
+        Arc::new(TranscribeAction {
+            post_process: true,
+            to_file: false,
+            incognito: false,
+        }) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "transcribe_to_file".to_string(),
+        Arc::new(TranscribeAction {
+            post_process: false,
+            to_file: true,
+            incognito: false,
+        }) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "transcribe_incognito".to_string(),
+        Arc::new(TranscribeAction {
+            post_process: false,
+            to_file: false,
+            incognito: true,
+        }) as Arc<dyn ShortcutAction>,
     );
     map.insert(
         "cancel".to_string(),
         Arc::new(CancelAction) as Arc<dyn ShortcutAction>,
     );
+    map.insert(
+        "undo_last_dictation".to_string(),
+        Arc::new(UndoDictationAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "paste_last_transcript".to_string(),
+        Arc::new(PasteLastTranscriptAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "toggle_obs_captions".to_string(),
+        Arc::new(ToggleObsCaptionsAction) as Arc<dyn ShortcutAction>,
+    );
     map.insert(
         "test".to_string(),
         Arc::new(TestAction) as Arc<dyn ShortcutAction>,
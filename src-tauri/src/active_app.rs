@@ -0,0 +1,507 @@
+//! Detection of the frontmost application.
+//!
+//! Used at paste time to apply per-application output rules and to record
+//! where each transcript went. Detection is best effort: when the platform or
+//! display server doesn't expose the focused application, `frontmost_app`
+//! returns `None`.
+
+use log::{debug, info};
+use once_cell::sync::Lazy;
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use std::process::Command;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveApp {
+    /// Human readable application name (e.g. "Terminal")
+    pub name: String,
+    /// Bundle id on macOS, executable path on Windows, WM_CLASS on Linux
+    pub identifier: Option<String>,
+}
+
+impl ActiveApp {
+    /// Case-insensitive substring match against the name and identifier
+    pub fn matches(&self, pattern: &str) -> bool {
+        let pattern = pattern.trim().to_lowercase();
+        if pattern.is_empty() {
+            return false;
+        }
+        self.name.to_lowercase().contains(&pattern)
+            || self
+                .identifier
+                .as_ref()
+                .is_some_and(|id| id.to_lowercase().contains(&pattern))
+    }
+}
+
+/// A window that dictation is delivered to regardless of focus
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetWindow {
+    pub app: ActiveApp,
+    /// Window title when the target was locked
+    pub title: Option<String>,
+    /// Bundle id on macOS, HWND on Windows, X11 window id on Linux
+    handle: String,
+}
+
+/// Window locked as the dictation target, if any
+static LOCKED_TARGET: Lazy<Mutex<Option<TargetWindow>>> = Lazy::new(|| Mutex::new(None));
+
+pub fn frontmost_app() -> Option<ActiveApp> {
+    let app = platform_frontmost_app();
+    debug!("Frontmost application: {:?}", app);
+    app
+}
+
+/// Locks dictation to the currently focused window
+pub fn lock_target() -> Result<TargetWindow, String> {
+    let mut target = platform_focused_window().ok_or("Could not determine the focused window")?;
+    if target.title.is_none() {
+        target.title = platform_window_title();
+    }
+    info!("Locked dictation target to {}", target.app.name);
+    *LOCKED_TARGET.lock().unwrap_or_else(|e| e.into_inner()) = Some(target.clone());
+    Ok(target)
+}
+
+pub fn unlock_target() {
+    if let Some(target) = LOCKED_TARGET
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+    {
+        info!("Unlocked dictation target {}", target.app.name);
+    }
+}
+
+pub fn locked_target() -> Option<TargetWindow> {
+    LOCKED_TARGET
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Title of the window dictation goes to: the locked target's title when a
+/// target is locked, otherwise the focused window's
+pub fn target_window_title() -> Option<String> {
+    match locked_target() {
+        Some(target) => target.title,
+        None => platform_window_title(),
+    }
+}
+
+/// Brings the target window to the front so input reaches it
+pub fn activate_target(target: &TargetWindow) -> Result<(), String> {
+    debug!("Activating dictation target {}", target.app.name);
+    platform_activate(target)
+}
+
+/// On macOS the target is the app rather than one of its windows.
+#[cfg(target_os = "macos")]
+fn platform_focused_window() -> Option<TargetWindow> {
+    let app = platform_frontmost_app()?;
+    let handle = app.identifier.clone()?;
+    Some(TargetWindow {
+        app,
+        title: None,
+        handle,
+    })
+}
+
+/// `open -b` activates an app by bundle id without needing automation permissions.
+#[cfg(target_os = "macos")]
+fn platform_activate(target: &TargetWindow) -> Result<(), String> {
+    let status = Command::new("open")
+        .args(["-b", &target.handle])
+        .status()
+        .map_err(|e| format!("Failed to run open: {}", e))?;
+    if !status.success() {
+        return Err(format!("Could not activate {}", target.app.name));
+    }
+    Ok(())
+}
+
+/// Asks NSWorkspace, which doesn't require automation permissions.
+#[cfg(target_os = "macos")]
+fn platform_frontmost_app() -> Option<ActiveApp> {
+    use objc2_app_kit::NSWorkspace;
+
+    let app = NSWorkspace::sharedWorkspace().frontmostApplication()?;
+    let identifier = app.bundleIdentifier().map(|id| id.to_string());
+    Some(ActiveApp {
+        name: app
+            .localizedName()
+            .map(|name| name.to_string())
+            .or_else(|| identifier.clone())?,
+        identifier,
+    })
+}
+
+/// Reads the title through the accessibility API, which needs the
+/// Accessibility permission Handy already has for pasting.
+#[cfg(target_os = "macos")]
+fn platform_window_title() -> Option<String> {
+    crate::accessibility::focused_window_title()
+}
+
+#[cfg(target_os = "windows")]
+fn platform_frontmost_app() -> Option<ActiveApp> {
+    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+    app_for_window(unsafe { GetForegroundWindow() })
+}
+
+#[cfg(target_os = "windows")]
+fn platform_focused_window() -> Option<TargetWindow> {
+    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+    let hwnd = lockable_window(unsafe { GetForegroundWindow() })?;
+    let app = app_for_window(hwnd)?;
+    Some(TargetWindow {
+        app,
+        title: window_title(hwnd),
+        handle: (hwnd.0 as isize).to_string(),
+    })
+}
+
+/// Window classes of the taskbar, its tray overflow and the desktop
+#[cfg(any(target_os = "windows", test))]
+fn is_shell_window_class(class: &str) -> bool {
+    matches!(
+        class,
+        "Shell_TrayWnd"
+            | "Shell_SecondaryTrayWnd"
+            | "NotifyIconOverflowWindow"
+            | "TopLevelWindowForOverflowXamlIsland"
+            | "Progman"
+            | "WorkerW"
+    )
+}
+
+/// Locking from the tray menu leaves the taskbar (or Handy's menu window) in
+/// the foreground. The window the user was working in is then the first app
+/// window below it in the z-order.
+#[cfg(target_os = "windows")]
+fn lockable_window(
+    foreground: windows::Win32::Foundation::HWND,
+) -> Option<windows::Win32::Foundation::HWND> {
+    use windows::Win32::UI::WindowsAndMessaging::{GetWindow, GW_HWNDNEXT};
+
+    if !is_shell_or_own_window(foreground) {
+        return Some(foreground);
+    }
+    let mut hwnd = foreground;
+    loop {
+        hwnd = unsafe { GetWindow(hwnd, GW_HWNDNEXT) }.ok()?;
+        if hwnd.is_invalid() {
+            return None;
+        }
+        if is_app_window(hwnd) && !is_shell_or_own_window(hwnd) {
+            return Some(hwnd);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn is_shell_or_own_window(hwnd: windows::Win32::Foundation::HWND) -> bool {
+    use windows::Win32::UI::WindowsAndMessaging::{GetClassNameW, GetWindowThreadProcessId};
+
+    let mut pid = 0u32;
+    let mut class = [0u16; 256];
+    let len = unsafe {
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        GetClassNameW(hwnd, &mut class)
+    };
+    pid == std::process::id()
+        || is_shell_window_class(&String::from_utf16_lossy(&class[..len.max(0) as usize]))
+}
+
+/// A visible top-level window that shows in the taskbar
+#[cfg(target_os = "windows")]
+fn is_app_window(hwnd: windows::Win32::Foundation::HWND) -> bool {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindow, GetWindowLongW, IsWindowVisible, GWL_EXSTYLE, GW_OWNER, WS_EX_TOOLWINDOW,
+    };
+
+    unsafe {
+        IsWindowVisible(hwnd).as_bool()
+            && GetWindow(hwnd, GW_OWNER).map_or(true, |owner| owner.is_invalid())
+            && (GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW.0) == 0
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn window_title(hwnd: windows::Win32::Foundation::HWND) -> Option<String> {
+    use windows::Win32::UI::WindowsAndMessaging::GetWindowTextW;
+
+    let mut buffer = [0u16; 512];
+    let len = unsafe { GetWindowTextW(hwnd, &mut buffer) };
+    let title = String::from_utf16_lossy(&buffer[..len.max(0) as usize]);
+    (!title.is_empty()).then_some(title)
+}
+
+#[cfg(target_os = "windows")]
+fn platform_window_title() -> Option<String> {
+    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+    window_title(unsafe { GetForegroundWindow() })
+}
+
+#[cfg(target_os = "windows")]
+fn platform_activate(target: &TargetWindow) -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        IsIconic, IsWindow, SetForegroundWindow, ShowWindow, SW_RESTORE,
+    };
+
+    let handle: isize = target
+        .handle
+        .parse()
+        .map_err(|_| "Invalid window handle".to_string())?;
+    let hwnd = HWND(handle as *mut _);
+    unsafe {
+        if !IsWindow(Some(hwnd)).as_bool() {
+            return Err(format!("The {} window no longer exists", target.app.name));
+        }
+        if IsIconic(hwnd).as_bool() {
+            let _ = ShowWindow(hwnd, SW_RESTORE);
+        }
+        if !SetForegroundWindow(hwnd).as_bool() {
+            return Err(format!("Could not activate {}", target.app.name));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn app_for_window(hwnd: windows::Win32::Foundation::HWND) -> Option<ActiveApp> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+
+    unsafe {
+        if hwnd.is_invalid() {
+            return None;
+        }
+
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == 0 {
+            return None;
+        }
+
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut len = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(process);
+        result.ok()?;
+
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        let name = std::path::Path::new(&path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone());
+
+        Some(ActiveApp {
+            name,
+            identifier: Some(path),
+        })
+    }
+}
+
+/// The focused window, read from the X server directly rather than through a
+/// process per dictation. Only X11 exposes it to other clients; on Wayland
+/// this still works for XWayland windows.
+#[cfg(target_os = "linux")]
+struct X11 {
+    conn: x11rb::rust_connection::RustConnection,
+    root: x11rb::protocol::xproto::Window,
+}
+
+#[cfg(target_os = "linux")]
+impl X11 {
+    fn connect() -> Option<Self> {
+        use x11rb::connection::Connection;
+
+        let (conn, screen) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots.get(screen)?.root;
+        Some(Self { conn, root })
+    }
+
+    fn property(
+        &self,
+        window: x11rb::protocol::xproto::Window,
+        name: &str,
+    ) -> Option<x11rb::protocol::xproto::GetPropertyReply> {
+        use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+        let atom = self
+            .conn
+            .intern_atom(true, name.as_bytes())
+            .ok()?
+            .reply()
+            .ok()?
+            .atom;
+        if atom == u32::from(AtomEnum::NONE) {
+            return None;
+        }
+        self.conn
+            .get_property(false, window, atom, AtomEnum::ANY, 0, 1024)
+            .ok()?
+            .reply()
+            .ok()
+    }
+
+    fn active_window(&self) -> Option<x11rb::protocol::xproto::Window> {
+        let window = self
+            .property(self.root, "_NET_ACTIVE_WINDOW")?
+            .value32()?
+            .next()?;
+        (window != 0).then_some(window)
+    }
+
+    fn class(&self, window: x11rb::protocol::xproto::Window) -> Option<String> {
+        wm_class_name(&self.property(window, "WM_CLASS")?.value)
+    }
+
+    fn title(&self, window: x11rb::protocol::xproto::Window) -> Option<String> {
+        let value = self
+            .property(window, "_NET_WM_NAME")
+            .filter(|reply| !reply.value.is_empty())
+            .or_else(|| self.property(window, "WM_NAME"))?
+            .value;
+        let title = String::from_utf8_lossy(&value).trim().to_string();
+        (!title.is_empty()).then_some(title)
+    }
+}
+
+/// The class half of a WM_CLASS value ("instance\0Class\0"), which is what
+/// `xdotool getwindowclassname` printed
+#[cfg(any(target_os = "linux", test))]
+fn wm_class_name(value: &[u8]) -> Option<String> {
+    let class = value
+        .split(|&b| b == 0)
+        .filter(|part| !part.is_empty())
+        .nth(1)?;
+    Some(String::from_utf8_lossy(class).into_owned())
+}
+
+/// The window class stands in for the app name since the window name is its
+/// title.
+#[cfg(target_os = "linux")]
+fn platform_frontmost_app() -> Option<ActiveApp> {
+    let x11 = X11::connect()?;
+    let class = x11.class(x11.active_window()?)?;
+    Some(ActiveApp {
+        name: class.clone(),
+        identifier: Some(class),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn platform_window_title() -> Option<String> {
+    let x11 = X11::connect()?;
+    x11.title(x11.active_window()?)
+}
+
+#[cfg(target_os = "linux")]
+fn platform_focused_window() -> Option<TargetWindow> {
+    let x11 = X11::connect()?;
+    let window = x11.active_window()?;
+    let class = x11.class(window)?;
+    Some(TargetWindow {
+        app: ActiveApp {
+            name: class.clone(),
+            identifier: Some(class),
+        },
+        title: x11.title(window),
+        handle: window.to_string(),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn platform_activate(target: &TargetWindow) -> Result<(), String> {
+    let output = Command::new("xdotool")
+        .args(["windowactivate", "--sync", &target.handle])
+        .output()
+        .map_err(|e| format!("Failed to execute xdotool: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Could not activate {}: {}",
+            target.app.name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn platform_frontmost_app() -> Option<ActiveApp> {
+    None
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn platform_focused_window() -> Option<TargetWindow> {
+    None
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn platform_window_title() -> Option<String> {
+    None
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn platform_activate(_target: &TargetWindow) -> Result<(), String> {
+    Err("Activating windows is not supported on this platform".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn taskbar_and_desktop_are_shell_windows() {
+        assert!(is_shell_window_class("Shell_TrayWnd"));
+        assert!(is_shell_window_class("Shell_SecondaryTrayWnd"));
+        assert!(is_shell_window_class("NotifyIconOverflowWindow"));
+        assert!(is_shell_window_class("Progman"));
+        assert!(!is_shell_window_class("Notepad"));
+        assert!(!is_shell_window_class("CabinetWClass"));
+    }
+
+    #[test]
+    fn reads_the_class_from_wm_class() {
+        assert_eq!(
+            wm_class_name(b"navigator\0firefox\0").as_deref(),
+            Some("firefox")
+        );
+        assert_eq!(
+            wm_class_name(b"gnome-terminal-server\0Gnome-terminal\0").as_deref(),
+            Some("Gnome-terminal")
+        );
+        assert_eq!(wm_class_name(b"only-instance\0"), None);
+        assert_eq!(wm_class_name(b""), None);
+    }
+
+    #[test]
+    fn matches_name_or_identifier() {
+        let app = ActiveApp {
+            name: "Terminal".to_string(),
+            identifier: Some("com.apple.Terminal".to_string()),
+        };
+        assert!(app.matches("terminal"));
+        assert!(app.matches("com.apple"));
+        assert!(!app.matches("  "));
+        assert!(!app.matches("iterm"));
+    }
+}
@@ -0,0 +1,565 @@
+//! Local HTTP API for scripts and other apps.
+//!
+//! When enabled, a small server listens on `127.0.0.1` at the configured
+//! port. Every request must carry `Authorization: Bearer <token>`, where the
+//! token is kept in the credential store and generated the first time the
+//! server starts. Endpoints:
+//!
+//! - `POST /v1/recording/start`, with `?post_process=true` to post-process
+//! - `POST /v1/recording/stop`
+//! - `POST /v1/recording/cancel`
+//! - `POST /v1/transcribe` with an audio file as the body, answered with the
+//!   text. `?format=mp3` names the format if it isn't detected.
+//! - `GET /v1/result` for the most recent transcript in history
+//! - `POST /v1/result/copy` copies that transcript to the clipboard, if
+//!   `api_server.allow_clipboard` is set
+//! - `GET /v1/stream`, a WebSocket that sends every app event as JSON
+//! - `POST /mcp`, the MCP server (see `mcp`)
+//!
+//! WebSocket clients that can't set headers, like browsers, may pass the
+//! token as `?token=` instead.
+//!
+//! Requests beyond `MAX_ACTIVE_REQUESTS` get a 503, and only one uploaded
+//! file is transcribed at a time; others get a 429.
+
+use crate::audio_toolkit::decode_audio_samples;
+use crate::managers::history::{HistoryEntry, HistoryManager};
+use crate::managers::transcription::TranscriptionManager;
+use crate::mcp;
+use crate::secrets::{self, SecretKind};
+use crate::settings::get_settings;
+use crate::signal_handle;
+use crate::utils;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use log::{debug, error, info, warn};
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, TryLockError};
+use std::thread;
+use tauri::{AppHandle, Listener, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tiny_http::{Header, Method, Request, Response, Server};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+/// Largest audio file accepted by `/v1/transcribe`
+const MAX_UPLOAD_BYTES: usize = 200 * 1024 * 1024;
+
+/// Requests handled at once, each on its own thread. WebSocket streams count
+/// for as long as they're open.
+const MAX_ACTIVE_REQUESTS: usize = 16;
+
+/// Largest JSON-RPC message accepted by `/mcp`
+const MAX_MESSAGE_BYTES: u64 = 1024 * 1024;
+
+/// Name the trigger is logged under, like the signal names for SIGUSR1/2
+const SOURCE: &str = "HTTP API";
+
+static SERVER: Lazy<Mutex<Option<Arc<Server>>>> = Lazy::new(|| Mutex::new(None));
+
+static ACTIVE_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Open `/v1/stream` sockets by id. `None` tells one to close.
+static STREAMS: Lazy<Mutex<HashMap<u64, mpsc::Sender<Option<String>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_STREAM: AtomicU64 = AtomicU64::new(0);
+
+/// Held while an upload is read and transcribed, so only one is in memory
+static UPLOAD: Mutex<()> = Mutex::new(());
+
+/// A request counted in `ACTIVE_REQUESTS` until dropped
+struct ActiveRequest;
+
+impl ActiveRequest {
+    fn start() -> Option<Self> {
+        if ACTIVE_REQUESTS.fetch_add(1, Ordering::SeqCst) < MAX_ACTIVE_REQUESTS {
+            Some(ActiveRequest)
+        } else {
+            ACTIVE_REQUESTS.fetch_sub(1, Ordering::SeqCst);
+            None
+        }
+    }
+}
+
+impl Drop for ActiveRequest {
+    fn drop(&mut self) {
+        ACTIVE_REQUESTS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Route {
+    StartRecording { post_process: bool },
+    StopRecording,
+    CancelRecording,
+    Transcribe { format: Option<String> },
+    LastResult,
+    CopyLastResult,
+    Stream,
+    Mcp,
+}
+
+fn query_param<'a>(url: &'a str, name: &str) -> Option<&'a str> {
+    let (_, query) = url.split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Matches a request line to an endpoint. `Err` is the status to answer with.
+fn route(method: &Method, url: &str) -> Result<Route, u16> {
+    let path = url.split_once('?').map_or(url, |(path, _)| path);
+    let route = match path.trim_end_matches('/') {
+        "/v1/recording/start" => Route::StartRecording {
+            post_process: matches!(query_param(url, "post_process"), Some("true" | "1")),
+        },
+        "/v1/recording/stop" => Route::StopRecording,
+        "/v1/recording/cancel" => Route::CancelRecording,
+        "/v1/transcribe" => Route::Transcribe {
+            format: query_param(url, "format").map(str::to_string),
+        },
+        "/v1/result" => Route::LastResult,
+        "/v1/result/copy" => Route::CopyLastResult,
+        "/v1/stream" => Route::Stream,
+        "/mcp" => Route::Mcp,
+        _ => return Err(404),
+    };
+    let expected = match route {
+        Route::LastResult | Route::Stream => Method::Get,
+        _ => Method::Post,
+    };
+    if *method == expected {
+        Ok(route)
+    } else {
+        Err(405)
+    }
+}
+
+/// Checks a bearer token without leaking how much of it matched
+pub(crate) fn authorized(header: Option<&str>, token: &str) -> bool {
+    let Some(given) = header.and_then(|value| value.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn token_name() -> String {
+    secrets::secret_name(SecretKind::ApiServerToken, "default")
+}
+
+/// Returns the API token, generating one if there is none yet
+pub fn token() -> Result<String, String> {
+    match stored_token() {
+        Some(token) => Ok(token),
+        None => regenerate_token(),
+    }
+}
+
+pub fn stored_token() -> Option<String> {
+    secrets::get_secret(&token_name())
+}
+
+/// Replaces the API token, so clients using the old one are refused
+pub fn regenerate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    secrets::set_secret(&token_name(), &token)?;
+    close_streams();
+    Ok(token)
+}
+
+/// Closes every open event stream, which were authorized with settings or a
+/// token that may no longer hold
+fn close_streams() {
+    let streams: Vec<_> = STREAMS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .drain()
+        .collect();
+    for (_, stream) in streams {
+        let _ = stream.send(None);
+    }
+}
+
+/// Stops the server if it's running and starts it again if it's enabled, e.g.
+/// after the port changed
+pub fn restart(app: &AppHandle) -> Result<(), String> {
+    let mut server = SERVER.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(running) = server.take() {
+        running.unblock();
+        info!("Stopped HTTP API");
+    }
+    close_streams();
+
+    let settings = get_settings(app).api_server;
+    if !settings.enabled {
+        return Ok(());
+    }
+    token()?;
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, settings.port));
+    let started = Arc::new(
+        Server::http(addr).map_err(|e| format!("Failed to start HTTP API on {}: {}", addr, e))?,
+    );
+    info!("HTTP API listening on {}", addr);
+
+    let listener = Arc::clone(&started);
+    let app = app.clone();
+    thread::spawn(move || {
+        for request in listener.incoming_requests() {
+            let Some(active) = ActiveRequest::start() else {
+                respond(request, 503, json!({ "error": "Too many requests" }));
+                continue;
+            };
+            let app = app.clone();
+            // Transcribing a file can take a while, so don't hold up others
+            thread::spawn(move || {
+                handle(&app, request);
+                drop(active);
+            });
+        }
+        debug!("HTTP API listener exited");
+    });
+    *server = Some(started);
+    Ok(())
+}
+
+fn header(request: &Request, name: &str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str().to_string())
+}
+
+/// The `Authorization` header, or for the WebSocket stream only, `?token=`
+fn credentials(header: Option<String>, method: &Method, url: &str) -> Option<String> {
+    header.or_else(|| {
+        (route(method, url) == Ok(Route::Stream))
+            .then(|| query_param(url, "token"))
+            .flatten()
+            .map(|token| format!("Bearer {}", token))
+    })
+}
+
+fn handle(app: &AppHandle, mut request: Request) {
+    let authorization = credentials(
+        header(&request, "Authorization"),
+        request.method(),
+        request.url(),
+    );
+    let token = stored_token().unwrap_or_default();
+
+    let (status, body) = if token.is_empty() || !authorized(authorization.as_deref(), &token) {
+        (401, json!({ "error": "Missing or invalid API token" }))
+    } else {
+        match route(request.method(), request.url()) {
+            Ok(Route::Stream) => return stream_events(app, request),
+            Ok(route) => {
+                debug!("HTTP API request: {:?}", route);
+                respond_to(app, route, &mut request)
+            }
+            Err(404) => (404, json!({ "error": "Not found" })),
+            Err(status) => (status, json!({ "error": "Method not allowed" })),
+        }
+    };
+    respond(request, status, body);
+}
+
+fn respond(request: Request, status: u16, body: Value) {
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("static header is valid");
+    let body = if body.is_null() {
+        String::new()
+    } else {
+        body.to_string()
+    };
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(content_type);
+    if let Err(e) = request.respond(response) {
+        warn!("Failed to answer HTTP API request: {}", e);
+    }
+}
+
+fn respond_to(app: &AppHandle, route: Route, request: &mut Request) -> (u16, Value) {
+    match route {
+        Route::Transcribe { format } => match transcribe_body(app, request, format.as_deref()) {
+            Ok(text) => (200, json!({ "text": text })),
+            Err((status, message)) => (status, json!({ "error": message })),
+        },
+        Route::Mcp => mcp_message(app, request),
+        route => run_command(app, route, SOURCE),
+    }
+}
+
+/// Runs an endpoint that needs no request body. `named_pipe` shares these.
+pub(crate) fn run_command(app: &AppHandle, route: Route, source: &str) -> (u16, Value) {
+    match route {
+        Route::StartRecording { post_process } => {
+            match signal_handle::start_recording(app, post_process, source) {
+                Ok(()) => (202, json!({ "status": "starting" })),
+                Err(e) => (409, json!({ "error": e })),
+            }
+        }
+        Route::StopRecording => match signal_handle::stop_recording(app, source) {
+            Ok(()) => (202, json!({ "status": "stopping" })),
+            Err(e) => (409, json!({ "error": e })),
+        },
+        Route::CancelRecording => {
+            utils::cancel_current_operation(app);
+            (200, json!({ "status": "cancelled" }))
+        }
+        Route::Transcribe { .. } | Route::Stream | Route::Mcp => {
+            (400, json!({ "error": "Not available here" }))
+        }
+        Route::LastResult => match latest_entry(app) {
+            Ok(entry) => (200, result_json(&entry)),
+            Err(response) => response,
+        },
+        Route::CopyLastResult if !get_settings(app).api_server.allow_clipboard => (
+            403,
+            json!({ "error": "Copying to the clipboard is turned off (api_server.allow_clipboard)" }),
+        ),
+        Route::CopyLastResult => match latest_entry(app) {
+            Ok(entry) => {
+                let text = entry
+                    .post_processed_text
+                    .as_deref()
+                    .unwrap_or(&entry.transcription_text);
+                match app.clipboard().write_text(text) {
+                    Ok(()) => (200, result_json(&entry)),
+                    Err(e) => {
+                        error!("Failed to copy last transcription for HTTP API: {}", e);
+                        (500, json!({ "error": "Failed to copy to the clipboard" }))
+                    }
+                }
+            }
+            Err(response) => response,
+        },
+    }
+}
+
+fn latest_entry(app: &AppHandle) -> Result<HistoryEntry, (u16, Value)> {
+    match app.state::<Arc<HistoryManager>>().get_latest_entry() {
+        Ok(Some(entry)) => Ok(entry),
+        Ok(None) => Err((404, json!({ "error": "No transcriptions yet" }))),
+        Err(e) => {
+            error!("Failed to read last transcription for HTTP API: {}", e);
+            Err((500, json!({ "error": "Failed to read history" })))
+        }
+    }
+}
+
+fn result_json(entry: &HistoryEntry) -> Value {
+    json!({
+        "text": entry.post_processed_text.as_deref().unwrap_or(&entry.transcription_text),
+        "transcription_text": entry.transcription_text,
+        "post_processed_text": entry.post_processed_text,
+        "timestamp": entry.timestamp,
+    })
+}
+
+/// Upgrades the request to a WebSocket and forwards every `app-event` to it
+/// until the client goes away or the server restarts
+fn stream_events(app: &AppHandle, request: Request) {
+    let Some(key) = header(&request, "Sec-WebSocket-Key") else {
+        let response = Response::from_string("Expected a WebSocket upgrade").with_status_code(426);
+        if let Err(e) = request.respond(response) {
+            warn!("Failed to answer HTTP API request: {}", e);
+        }
+        return;
+    };
+
+    let response = Response::empty(101)
+        .with_header(Header::from_bytes(&b"Upgrade"[..], &b"websocket"[..]).unwrap())
+        .with_header(Header::from_bytes(&b"Connection"[..], &b"Upgrade"[..]).unwrap())
+        .with_header(
+            Header::from_bytes(
+                &b"Sec-WebSocket-Accept"[..],
+                derive_accept_key(key.as_bytes()),
+            )
+            .unwrap(),
+        );
+    let stream = request.upgrade("websocket", response);
+    let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+
+    let (tx, rx) = mpsc::channel::<Option<String>>();
+    let id = NEXT_STREAM.fetch_add(1, Ordering::SeqCst);
+    STREAMS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(id, tx.clone());
+    let listener = app.listen_any("app-event", move |event| {
+        let _ = tx.send(Some(event.payload().to_string()));
+    });
+    debug!("WebSocket client connected to the HTTP API");
+
+    // A closed connection shows up as a failed send
+    for payload in rx {
+        let Some(payload) = payload else {
+            let _ = socket.close(None);
+            let _ = socket.flush();
+            debug!("Closed WebSocket stream of the HTTP API");
+            break;
+        };
+        if let Err(e) = socket.send(Message::text(payload)) {
+            debug!("WebSocket client disconnected: {}", e);
+            break;
+        }
+    }
+    app.unlisten(listener);
+    STREAMS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&id);
+}
+
+/// Answers a JSON-RPC message. Notifications get an empty 202.
+fn mcp_message(app: &AppHandle, request: &mut Request) -> (u16, Value) {
+    let mut body = Vec::new();
+    if let Err(e) = request
+        .as_reader()
+        .take(MAX_MESSAGE_BYTES)
+        .read_to_end(&mut body)
+    {
+        return (
+            400,
+            json!({ "error": format!("Failed to read request body: {}", e) }),
+        );
+    }
+    match serde_json::from_slice::<Value>(&body) {
+        Ok(message) => match mcp::handle_message(app, &message) {
+            Some(response) => (200, response),
+            None => (202, Value::Null),
+        },
+        Err(e) => (
+            400,
+            json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": -32700, "message": e.to_string() }
+            }),
+        ),
+    }
+}
+
+/// Transcribes the audio file in the request body with the selected model
+fn transcribe_body(
+    app: &AppHandle,
+    request: &mut Request,
+    format: Option<&str>,
+) -> Result<String, (u16, String)> {
+    let _upload = match UPLOAD.try_lock() {
+        Ok(upload) => upload,
+        Err(TryLockError::Poisoned(e)) => e.into_inner(),
+        Err(TryLockError::WouldBlock) => {
+            return Err((429, "Already transcribing a file".to_string()))
+        }
+    };
+    if request
+        .body_length()
+        .is_some_and(|length| length > MAX_UPLOAD_BYTES)
+    {
+        return Err((413, "Audio file is too large".to_string()));
+    }
+    let mut audio = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_UPLOAD_BYTES as u64 + 1)
+        .read_to_end(&mut audio)
+        .map_err(|e| (400, format!("Failed to read request body: {}", e)))?;
+    if audio.len() > MAX_UPLOAD_BYTES {
+        return Err((413, "Audio file is too large".to_string()));
+    }
+    let samples = decode_audio_samples(audio, format)
+        .map_err(|e| (400, format!("Unsupported audio file: {}", e)))?;
+
+    let tm = app.state::<Arc<TranscriptionManager>>();
+    tm.initiate_model_load();
+    tm.transcribe(Arc::new(samples), &get_settings(app))
+        .map(|output| output.full_text())
+        .map_err(|e| (500, format!("Transcription failed: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_requests_by_method_and_path() {
+        assert_eq!(
+            route(&Method::Post, "/v1/recording/start?post_process=true"),
+            Ok(Route::StartRecording { post_process: true })
+        );
+        assert_eq!(
+            route(&Method::Post, "/v1/recording/start"),
+            Ok(Route::StartRecording {
+                post_process: false
+            })
+        );
+        assert_eq!(route(&Method::Get, "/v1/result/"), Ok(Route::LastResult));
+        assert_eq!(route(&Method::Post, "/mcp"), Ok(Route::Mcp));
+        assert_eq!(
+            route(&Method::Post, "/v1/transcribe?format=mp3"),
+            Ok(Route::Transcribe {
+                format: Some("mp3".to_string())
+            })
+        );
+        assert_eq!(route(&Method::Get, "/v1/result/copy"), Err(405));
+        assert_eq!(
+            route(&Method::Get, "/v1/stream?token=abc"),
+            Ok(Route::Stream)
+        );
+        assert_eq!(route(&Method::Get, "/v1/recording/stop"), Err(405));
+        assert_eq!(route(&Method::Post, "/v2/transcribe"), Err(404));
+    }
+
+    #[test]
+    fn requires_the_exact_bearer_token() {
+        assert!(authorized(Some("Bearer abc123"), "abc123"));
+        assert!(!authorized(Some("Bearer abc124"), "abc123"));
+        assert!(!authorized(Some("Bearer abc"), "abc123"));
+        assert!(!authorized(Some("abc123"), "abc123"));
+        assert!(!authorized(None, "abc123"));
+    }
+
+    #[test]
+    fn accepts_the_query_token_only_for_the_stream() {
+        assert_eq!(
+            credentials(None, &Method::Get, "/v1/stream?token=abc123"),
+            Some("Bearer abc123".to_string())
+        );
+        let start = credentials(None, &Method::Post, "/v1/recording/start?token=abc123");
+        assert_eq!(start, None);
+        assert!(!authorized(start.as_deref(), "abc123"));
+        assert_eq!(
+            credentials(None, &Method::Post, "/v1/result/copy?token=abc123"),
+            None
+        );
+        assert_eq!(
+            credentials(
+                Some("Bearer xyz".to_string()),
+                &Method::Get,
+                "/v1/stream?token=abc123"
+            ),
+            Some("Bearer xyz".to_string())
+        );
+    }
+}
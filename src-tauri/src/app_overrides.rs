@@ -0,0 +1,118 @@
+//! Per-application setting overrides.
+//!
+//! An override applies to dictation into apps whose name or identifier
+//! matches it, and can change the language, prompt, paste method and whether
+//! post-processing runs. The first matching override wins. Every step of the
+//! pipeline gets its settings from [`resolve`], so they all agree on which
+//! override applies.
+
+use crate::active_app::ActiveApp;
+use crate::settings::{AppOverride, AppSettings};
+
+/// Settings with the matching override applied
+pub struct ResolvedSettings {
+    pub settings: AppSettings,
+    /// Set when the override turns post-processing on or off
+    pub post_process: Option<bool>,
+}
+
+impl ResolvedSettings {
+    /// Whether to post-process, given what the shortcut asked for
+    pub fn post_process(&self, requested: bool) -> bool {
+        self.post_process.unwrap_or(requested)
+    }
+}
+
+fn find_override<'a>(
+    overrides: &'a [AppOverride],
+    app: Option<&ActiveApp>,
+) -> Option<&'a AppOverride> {
+    app.and_then(|app| overrides.iter().find(|o| app.matches(&o.app_match)))
+}
+
+pub fn resolve(settings: &AppSettings, app: Option<&ActiveApp>) -> ResolvedSettings {
+    let mut resolved = settings.clone();
+    let Some(app_override) = find_override(&settings.app_overrides, app) else {
+        return ResolvedSettings {
+            settings: resolved,
+            post_process: None,
+        };
+    };
+
+    if let Some(language) = &app_override.language {
+        resolved.selected_language = language.clone();
+    }
+    // A prompt that has since been deleted leaves the selected one in use
+    if let Some(prompt_id) = app_override
+        .prompt_id
+        .as_ref()
+        .filter(|id| settings.post_process_prompts.iter().any(|p| &p.id == *id))
+    {
+        resolved.post_process_selected_prompt_id = Some(prompt_id.clone());
+    }
+    if let Some(paste_method) = app_override.paste_method {
+        resolved.paste_method = paste_method;
+    }
+
+    ResolvedSettings {
+        settings: resolved,
+        post_process: app_override.post_process,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{get_default_settings, PasteMethod};
+
+    fn app(name: &str, identifier: &str) -> ActiveApp {
+        ActiveApp {
+            name: name.to_string(),
+            identifier: Some(identifier.to_string()),
+        }
+    }
+
+    #[test]
+    fn first_matching_override_applies() {
+        let mut settings = get_default_settings();
+        let prompt_id = settings.post_process_prompts[0].id.clone();
+        settings.app_overrides = vec![
+            AppOverride {
+                app_match: "com.apple.Terminal".to_string(),
+                language: Some("en".to_string()),
+                prompt_id: Some("deleted".to_string()),
+                paste_method: Some(PasteMethod::Direct),
+                post_process: Some(false),
+            },
+            AppOverride {
+                app_match: "slack".to_string(),
+                language: None,
+                prompt_id: Some(prompt_id.clone()),
+                paste_method: None,
+                post_process: Some(true),
+            },
+        ];
+
+        let terminal = resolve(&settings, Some(&app("Terminal", "com.apple.Terminal")));
+        assert_eq!(terminal.settings.selected_language, "en");
+        assert_eq!(terminal.settings.paste_method, PasteMethod::Direct);
+        assert_eq!(
+            terminal.settings.post_process_selected_prompt_id,
+            settings.post_process_selected_prompt_id
+        );
+        assert!(!terminal.post_process(true));
+
+        let slack = resolve(&settings, Some(&app("Slack", "com.tinyspeck.slackmacgap")));
+        assert_eq!(slack.settings.selected_language, "auto");
+        assert_eq!(slack.settings.paste_method, settings.paste_method);
+        assert_eq!(
+            slack.settings.post_process_selected_prompt_id,
+            Some(prompt_id)
+        );
+        assert!(slack.post_process(false));
+
+        let other = resolve(&settings, Some(&app("Notes", "com.apple.Notes")));
+        assert!(other.post_process.is_none());
+        assert!(!resolve(&settings, None).post_process(false));
+    }
+}
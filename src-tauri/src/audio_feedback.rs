@@ -4,7 +4,7 @@ use cpal::traits::{DeviceTrait, HostTrait};
 use log::{debug, error, warn};
 use rodio::OutputStreamBuilder;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, Cursor, Read, Seek};
 use std::path::{Path, PathBuf};
 use std::thread;
 use tauri::{AppHandle, Manager};
@@ -67,6 +67,16 @@ pub fn play_test_sound(app: &AppHandle, sound_type: SoundType) {
     }
 }
 
+/// Plays a history recording on the selected output device at full volume
+pub fn play_recording(app: &AppHandle, audio: Vec<u8>) {
+    let selected_device = settings::get_settings(app).selected_output_device;
+    thread::spawn(move || {
+        if let Err(e) = play_audio(Cursor::new(audio), selected_device, 1.0) {
+            error!("Failed to play recording: {}", e);
+        }
+    });
+}
+
 fn play_sound_async(app: &AppHandle, path: PathBuf) {
     let app_handle = app.clone();
     thread::spawn(move || {
@@ -93,6 +103,14 @@ fn play_audio_file(
     path: &std::path::Path,
     selected_device: Option<String>,
     volume: f32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    play_audio(BufReader::new(File::open(path)?), selected_device, volume)
+}
+
+fn play_audio<R: Read + Seek + Send + Sync + 'static>(
+    audio: R,
+    selected_device: Option<String>,
+    volume: f32,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let stream_builder = if let Some(device_name) = selected_device {
         if device_name == "Default" {
@@ -126,10 +144,7 @@ fn play_audio_file(
     let stream_handle = stream_builder.open_stream()?;
     let mixer = stream_handle.mixer();
 
-    let file = File::open(path)?;
-    let buf_reader = BufReader::new(file);
-
-    let sink = rodio::play(mixer, buf_reader)?;
+    let sink = rodio::play(mixer, audio)?;
     sink.set_volume(volume);
     sink.sleep_until_end();
 
@@ -10,7 +10,7 @@ use cpal::{
 };
 
 use crate::audio_toolkit::{
-    audio::{AudioVisualiser, FrameResampler},
+    audio::{AudioLevels, AudioVisualiser, FrameResampler},
     constants,
     vad::{self, VadFrame},
     VoiceActivityDetector,
@@ -27,7 +27,7 @@ pub struct AudioRecorder {
     cmd_tx: Option<mpsc::Sender<Cmd>>,
     worker_handle: Option<std::thread::JoinHandle<()>>,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
-    level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    level_cb: Option<Arc<dyn Fn(AudioLevels) + Send + Sync + 'static>>,
 }
 
 impl AudioRecorder {
@@ -48,7 +48,7 @@ impl AudioRecorder {
 
     pub fn with_level_callback<F>(mut self, cb: F) -> Self
     where
-        F: Fn(Vec<f32>) + Send + Sync + 'static,
+        F: Fn(AudioLevels) + Send + Sync + 'static,
     {
         self.level_cb = Some(Arc::new(cb));
         self
@@ -60,6 +60,9 @@ impl AudioRecorder {
         }
 
         let (sample_tx, sample_rx) = mpsc::channel::<Vec<f32>>();
+        // Buffers the consumer is done with go back to the stream to be
+        // filled again, so capturing doesn't allocate for every callback
+        let (recycle_tx, recycle_rx) = mpsc::channel::<Vec<f32>>();
         let (cmd_tx, cmd_rx) = mpsc::channel::<Cmd>();
 
         let host = crate::audio_toolkit::get_cpal_host();
@@ -91,33 +94,53 @@ impl AudioRecorder {
             );
 
             let stream = match config.sample_format() {
-                cpal::SampleFormat::U8 => {
-                    AudioRecorder::build_stream::<u8>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I8 => {
-                    AudioRecorder::build_stream::<i8>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I16 => {
-                    AudioRecorder::build_stream::<i16>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I32 => {
-                    AudioRecorder::build_stream::<i32>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::F32 => {
-                    AudioRecorder::build_stream::<f32>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
+                cpal::SampleFormat::U8 => AudioRecorder::build_stream::<u8>(
+                    &thread_device,
+                    &config,
+                    sample_tx,
+                    recycle_rx,
+                    channels,
+                )
+                .unwrap(),
+                cpal::SampleFormat::I8 => AudioRecorder::build_stream::<i8>(
+                    &thread_device,
+                    &config,
+                    sample_tx,
+                    recycle_rx,
+                    channels,
+                )
+                .unwrap(),
+                cpal::SampleFormat::I16 => AudioRecorder::build_stream::<i16>(
+                    &thread_device,
+                    &config,
+                    sample_tx,
+                    recycle_rx,
+                    channels,
+                )
+                .unwrap(),
+                cpal::SampleFormat::I32 => AudioRecorder::build_stream::<i32>(
+                    &thread_device,
+                    &config,
+                    sample_tx,
+                    recycle_rx,
+                    channels,
+                )
+                .unwrap(),
+                cpal::SampleFormat::F32 => AudioRecorder::build_stream::<f32>(
+                    &thread_device,
+                    &config,
+                    sample_tx,
+                    recycle_rx,
+                    channels,
+                )
+                .unwrap(),
                 _ => panic!("unsupported sample format"),
             };
 
             stream.play().expect("failed to start stream");
 
             // keep the stream alive while we process samples
-            run_consumer(sample_rate, vad, sample_rx, cmd_rx, level_cb);
+            run_consumer(sample_rate, vad, sample_rx, recycle_tx, cmd_rx, level_cb);
             // stream is dropped here, after run_consumer returns
         });
 
@@ -158,15 +181,15 @@ impl AudioRecorder {
         device: &cpal::Device,
         config: &cpal::SupportedStreamConfig,
         sample_tx: mpsc::Sender<Vec<f32>>,
+        recycle_rx: mpsc::Receiver<Vec<f32>>,
         channels: usize,
     ) -> Result<cpal::Stream, cpal::BuildStreamError>
     where
         T: Sample + SizedSample + Send + 'static,
         f32: cpal::FromSample<T>,
     {
-        let mut output_buffer = Vec::new();
-
         let stream_cb = move |data: &[T], _: &cpal::InputCallbackInfo| {
+            let mut output_buffer = recycle_rx.try_recv().unwrap_or_default();
             output_buffer.clear();
 
             if channels == 1 {
@@ -187,7 +210,7 @@ impl AudioRecorder {
                 }
             }
 
-            if sample_tx.send(output_buffer.clone()).is_err() {
+            if sample_tx.send(output_buffer).is_err() {
                 log::error!("Failed to send samples");
             }
         };
@@ -243,8 +266,9 @@ fn run_consumer(
     in_sample_rate: u32,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
     sample_rx: mpsc::Receiver<Vec<f32>>,
+    recycle_tx: mpsc::Sender<Vec<f32>>,
     cmd_rx: mpsc::Receiver<Cmd>,
-    level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    level_cb: Option<Arc<dyn Fn(AudioLevels) + Send + Sync + 'static>>,
 ) {
     let mut frame_resampler = FrameResampler::new(
         in_sample_rate as usize,
@@ -294,9 +318,9 @@ fn run_consumer(
         };
 
         // ---------- spectrum processing ---------------------------------- //
-        if let Some(buckets) = visualizer.feed(&raw) {
+        if let Some(levels) = visualizer.feed(&raw) {
             if let Some(cb) = &level_cb {
-                cb(buckets);
+                cb(levels);
             }
         }
 
@@ -304,6 +328,7 @@ fn run_consumer(
         frame_resampler.push(&raw, &mut |frame: &[f32]| {
             handle_frame(frame, recording, &vad, &mut processed_samples)
         });
+        let _ = recycle_tx.send(raw);
 
         // non-blocking check for a command
         while let Ok(cmd) = cmd_rx.try_recv() {
@@ -324,6 +349,7 @@ fn run_consumer(
                         frame_resampler.push(&remaining, &mut |frame: &[f32]| {
                             handle_frame(frame, true, &vad, &mut processed_samples)
                         });
+                        let _ = recycle_tx.send(remaining);
                     }
 
                     frame_resampler.finish(&mut |frame: &[f32]| {
@@ -8,6 +8,8 @@ pub struct FrameResampler {
     resampler: Option<FftFixedIn<f32>>,
     chunk_in: usize,
     in_buf: Vec<f32>,
+    /// Resampled output, reused for every chunk
+    out_buf: Vec<Vec<f32>>,
     frame_samples: usize,
     pending: Vec<f32>,
 }
@@ -25,10 +27,16 @@ impl FrameResampler {
                 .expect("Failed to create resampler")
         });
 
+        let out_buf = resampler
+            .as_ref()
+            .map(|resampler| resampler.output_buffer_allocate(true))
+            .unwrap_or_default();
+
         Self {
             resampler,
             chunk_in,
             in_buf: Vec::with_capacity(chunk_in),
+            out_buf,
             frame_samples,
             pending: Vec::with_capacity(frame_samples),
         }
@@ -47,17 +55,7 @@ impl FrameResampler {
             src = &src[take..];
 
             if self.in_buf.len() == self.chunk_in {
-                // let start = std::time::Instant::now();
-                if let Ok(out) = self
-                    .resampler
-                    .as_mut()
-                    .unwrap()
-                    .process(&[&self.in_buf[..]], None)
-                {
-                    // let duration = start.elapsed();
-                    // log::debug!("Resampler took: {:?}", duration);
-                    self.emit_frames(&out[0], &mut emit);
-                }
+                self.resample_in_buf(&mut emit);
                 self.in_buf.clear();
             }
         }
@@ -65,14 +63,11 @@ impl FrameResampler {
 
     pub fn finish(&mut self, mut emit: impl FnMut(&[f32])) {
         // Process any remaining input samples
-        if let Some(ref mut resampler) = self.resampler {
-            if !self.in_buf.is_empty() {
-                // Pad with zeros to reach chunk size
-                self.in_buf.resize(self.chunk_in, 0.0);
-                if let Ok(out) = resampler.process(&[&self.in_buf[..]], None) {
-                    self.emit_frames(&out[0], &mut emit);
-                }
-            }
+        if self.resampler.is_some() && !self.in_buf.is_empty() {
+            // Pad with zeros to reach chunk size
+            self.in_buf.resize(self.chunk_in, 0.0);
+            self.resample_in_buf(&mut emit);
+            self.in_buf.clear();
         }
 
         // Emit any remaining pending frame (padded with zeros)
@@ -83,6 +78,19 @@ impl FrameResampler {
         }
     }
 
+    /// Resamples a full `in_buf` into `out_buf` and emits the result
+    fn resample_in_buf(&mut self, emit: &mut impl FnMut(&[f32])) {
+        let Some(resampler) = self.resampler.as_mut() else {
+            return;
+        };
+        let mut out = std::mem::take(&mut self.out_buf);
+        if let Ok((_, written)) = resampler.process_into_buffer(&[&self.in_buf[..]], &mut out, None)
+        {
+            self.emit_frames(&out[0][..written], emit);
+        }
+        self.out_buf = out;
+    }
+
     fn emit_frames(&mut self, mut data: &[f32], emit: &mut impl FnMut(&[f32])) {
         while !data.is_empty() {
             let space = self.frame_samples - self.pending.len();
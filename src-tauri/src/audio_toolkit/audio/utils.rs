@@ -1,7 +1,18 @@
-use anyhow::Result;
+use super::FrameResampler;
+use crate::audio_toolkit::constants;
+use anyhow::{anyhow, Result};
 use hound::{WavSpec, WavWriter};
 use log::debug;
+use std::io::{Cursor, ErrorKind};
 use std::path::Path;
+use std::time::Duration;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
 
 /// Save audio samples as a WAV file
 pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Result<()> {
@@ -24,3 +35,103 @@ pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Res
     debug!("Saved WAV file: {:?}", file_path.as_ref());
     Ok(())
 }
+
+/// Encode audio samples as a WAV file in memory, for writing it somewhere
+/// other than straight to disk (e.g. encrypted)
+pub fn encode_wav(samples: &[f32]) -> Result<Vec<u8>> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut buffer = Cursor::new(Vec::new());
+    let mut writer = WavWriter::new(&mut buffer, spec)?;
+    for sample in samples {
+        writer.write_sample((sample * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(buffer.into_inner())
+}
+
+/// Decode an audio file (WAV, MP3, M4A, FLAC or Ogg) into mono samples at the
+/// rate models expect. `extension` helps pick the format but isn't needed.
+pub fn decode_audio_samples(data: Vec<u8>, extension: Option<&str>) -> Result<Vec<f32>> {
+    let mut hint = Hint::new();
+    if let Some(extension) = extension {
+        hint.with_extension(extension);
+    }
+    let stream = MediaSourceStream::new(Box::new(Cursor::new(data)), Default::default());
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )?
+        .format;
+    let track = format
+        .default_track()
+        .ok_or_else(|| anyhow!("No audio track found"))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| anyhow!("Unknown sample rate"))?;
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut mono = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet is skipped rather than failing the whole file
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let channels = decoded.spec().channels.count().max(1);
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+        buffer.copy_interleaved_ref(decoded);
+        mono.extend(
+            buffer
+                .samples()
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32),
+        );
+    }
+
+    let mut samples = Vec::with_capacity(mono.len());
+    let mut resampler = FrameResampler::new(
+        sample_rate as usize,
+        constants::WHISPER_SAMPLE_RATE as usize,
+        Duration::from_millis(30),
+    );
+    resampler.push(&mono, |frame| samples.extend_from_slice(frame));
+    resampler.finish(|frame| samples.extend_from_slice(frame));
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_wav_at_the_model_rate() {
+        let samples: Vec<f32> = (0..16000).map(|i| (i as f32 * 0.05).sin() * 0.5).collect();
+        let decoded = decode_audio_samples(encode_wav(&samples).unwrap(), Some("wav")).unwrap();
+        assert!(decoded.len() >= samples.len());
+        for (sample, decoded) in samples.iter().zip(&decoded) {
+            assert!((sample - decoded).abs() < 1e-3);
+        }
+        assert!(decode_audio_samples(b"not audio".to_vec(), None).is_err());
+    }
+}
@@ -6,6 +6,17 @@ const DB_MAX: f32 = -8.0;
 const GAIN: f32 = 1.3;
 const CURVE_POWER: f32 = 0.7;
 
+/// Levels for one analysis window of input audio
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioLevels {
+    /// Root mean square amplitude, 0.0 to 1.0
+    pub rms: f32,
+    /// Largest absolute sample, 0.0 to 1.0
+    pub peak: f32,
+    /// Loudness per frequency band from low to high, 0.0 to 1.0
+    pub bands: Vec<f32>,
+}
+
 pub struct AudioVisualiser {
     fft: Arc<dyn Fft<f32>>,
     window: Vec<f32>,
@@ -77,7 +88,7 @@ impl AudioVisualiser {
         }
     }
 
-    pub fn feed(&mut self, samples: &[f32]) -> Option<Vec<f32>> {
+    pub fn feed(&mut self, samples: &[f32]) -> Option<AudioLevels> {
         // Add new samples to buffer
         self.buffer.extend_from_slice(samples);
 
@@ -92,6 +103,15 @@ impl AudioVisualiser {
         // Remove DC component
         let mean = window_samples.iter().sum::<f32>() / self.window_size as f32;
 
+        let mut square_sum = 0.0;
+        let mut peak: f32 = 0.0;
+        for &sample in window_samples {
+            let centered = sample - mean;
+            square_sum += centered * centered;
+            peak = peak.max(centered.abs());
+        }
+        let rms = (square_sum / self.window_size as f32).sqrt();
+
         // Apply window function and prepare FFT input
         for (i, &sample) in window_samples.iter().enumerate() {
             let windowed_sample = (sample - mean) * self.window[i];
@@ -145,7 +165,11 @@ impl AudioVisualiser {
         // Clear processed samples from buffer
         self.buffer.clear();
 
-        Some(buckets)
+        Some(AudioLevels {
+            rms: rms.min(1.0),
+            peak: peak.min(1.0),
+            bands: buckets,
+        })
     }
 
     pub fn reset(&mut self) {
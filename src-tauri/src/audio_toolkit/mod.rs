@@ -5,8 +5,12 @@ pub mod utils;
 pub mod vad;
 
 pub use audio::{
-    list_input_devices, list_output_devices, save_wav_file, AudioRecorder, CpalDeviceInfo,
+    decode_audio_samples, encode_wav, list_input_devices, list_output_devices, save_wav_file,
+    AudioLevels, AudioRecorder, CpalDeviceInfo,
+};
+pub use text::{
+    apply_custom_words, apply_spoken_commands, filter_transcription_output, format_literal,
+    split_literal_segment, stitch_overlapping,
 };
-pub use text::{apply_custom_words, filter_transcription_output};
 pub use utils::get_cpal_host;
 pub use vad::{SileroVad, VoiceActivityDetector};
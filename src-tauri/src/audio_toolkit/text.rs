@@ -1,6 +1,7 @@
 use natural::phonetics::soundex;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::collections::HashMap;
 use strsim::levenshtein;
 
 /// Builds an n-gram string by cleaning and concatenating words
@@ -283,6 +284,303 @@ pub fn filter_transcription_output(text: &str) -> String {
     filtered.trim().to_string()
 }
 
+/// Punctuation that attaches to the preceding word (no space before it)
+const CLOSING_PUNCTUATION: &[char] = &[
+    '.', ',', ';', ':', '!', '?', ')', ']', '}', '\u{201D}', '\u{2019}', '\u{2026}',
+];
+
+/// Punctuation that attaches to the following word (no space after it)
+const OPENING_PUNCTUATION: &[char] = &[
+    '(', '[', '{', '\u{201C}', '\u{2018}', '\u{00BF}', '\u{00A1}',
+];
+
+/// Punctuation the speech model may insert on its own, which a spoken
+/// punctuation command should replace rather than add to
+const AUTO_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?'];
+
+/// Normalizes a word for command matching: lowercase with surrounding punctuation removed
+fn normalize_command_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// How a replacement joins the surrounding text
+#[derive(Debug, PartialEq)]
+enum Attachment {
+    /// Line breaks: no space on either side
+    Break,
+    /// Closing punctuation: joins the previous word
+    Closing,
+    /// Opening punctuation: joins the next word
+    Opening,
+    /// Anything else is spaced like a regular word
+    Word,
+}
+
+fn classify_replacement(replacement: &str, output: &str) -> Attachment {
+    if replacement.contains('\n') && replacement.trim().is_empty() {
+        return Attachment::Break;
+    }
+    // A straight double quote opens or closes depending on how many are already open
+    if replacement == "\"" {
+        return if output.matches('"').count().is_multiple_of(2) {
+            Attachment::Opening
+        } else {
+            Attachment::Closing
+        };
+    }
+    if !replacement.is_empty()
+        && replacement
+            .chars()
+            .all(|c| CLOSING_PUNCTUATION.contains(&c))
+    {
+        return Attachment::Closing;
+    }
+    if !replacement.is_empty()
+        && replacement
+            .chars()
+            .all(|c| OPENING_PUNCTUATION.contains(&c))
+    {
+        return Attachment::Opening;
+    }
+    Attachment::Word
+}
+
+/// Converts spoken formatting commands ("new line", "comma", "open quote", ...)
+/// into their literal text.
+///
+/// Matching is case-insensitive, ignores punctuation the speech model attached
+/// to the command words, and prefers the longest phrase at each position.
+/// Punctuation replacements are attached to the neighbouring words so that
+/// "hello comma world" becomes "hello, world".
+///
+/// # Arguments
+/// * `text` - The transcription text
+/// * `commands` - Mapping from spoken phrase to replacement text
+///
+/// # Returns
+/// The text with all recognised commands replaced
+pub fn apply_spoken_commands(text: &str, commands: &HashMap<String, String>) -> String {
+    let mut phrases: Vec<(Vec<String>, &str)> = commands
+        .iter()
+        .map(|(phrase, replacement)| {
+            let words = phrase
+                .split_whitespace()
+                .map(normalize_command_word)
+                .filter(|w| !w.is_empty())
+                .collect::<Vec<_>>();
+            (words, replacement.as_str())
+        })
+        .filter(|(words, _)| !words.is_empty())
+        .collect();
+
+    if phrases.is_empty() {
+        return text.to_string();
+    }
+
+    // Longest phrases first so "new paragraph" wins over a hypothetical "new"
+    phrases.sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let normalized: Vec<String> = words.iter().map(|w| normalize_command_word(w)).collect();
+
+    let mut output = String::with_capacity(text.len());
+    let mut attach_next = false;
+    let mut i = 0;
+
+    while i < words.len() {
+        let matched = phrases.iter().find(|(phrase, _)| {
+            i + phrase.len() <= normalized.len() && normalized[i..i + phrase.len()] == phrase[..]
+        });
+
+        match matched {
+            Some((phrase, replacement)) => {
+                match classify_replacement(replacement, &output) {
+                    Attachment::Break => {
+                        output.truncate(output.trim_end_matches(' ').len());
+                        output.push_str(replacement);
+                        attach_next = true;
+                    }
+                    Attachment::Closing => {
+                        output.truncate(output.trim_end().len());
+                        output.truncate(output.trim_end_matches(AUTO_PUNCTUATION).len());
+                        output.push_str(replacement);
+                        attach_next = false;
+                    }
+                    Attachment::Opening => {
+                        if !output.is_empty()
+                            && !attach_next
+                            && !output.ends_with(char::is_whitespace)
+                        {
+                            output.push(' ');
+                        }
+                        output.push_str(replacement);
+                        attach_next = true;
+                    }
+                    Attachment::Word => {
+                        if !output.is_empty()
+                            && !attach_next
+                            && !output.ends_with(char::is_whitespace)
+                        {
+                            output.push(' ');
+                        }
+                        output.push_str(replacement);
+                        attach_next = false;
+                    }
+                }
+                i += phrase.len();
+            }
+            None => {
+                if !output.is_empty() && !attach_next && !output.ends_with(char::is_whitespace) {
+                    output.push(' ');
+                }
+                output.push_str(words[i]);
+                attach_next = false;
+                i += 1;
+            }
+        }
+    }
+
+    output.trim_end_matches(' ').to_string()
+}
+
+/// Symbol names understood in literal mode. The symbol joins the words on
+/// both sides so identifiers and paths can be dictated piece by piece.
+const LITERAL_SYMBOLS: &[(&str, &str)] = &[
+    ("underscore", "_"),
+    ("dash", "-"),
+    ("hyphen", "-"),
+    ("dot", "."),
+    ("slash", "/"),
+    ("backslash", "\\"),
+    ("colon", ":"),
+    ("tilde", "~"),
+    ("hash", "#"),
+    ("equals", "="),
+];
+
+/// Splits a transcription at the first literal mode trigger ("literally",
+/// "spell out", ...).
+///
+/// # Returns
+/// The words before the trigger, and the words after it when a trigger was
+/// spoken. The trigger itself is dropped.
+pub fn split_literal_segment(text: &str, triggers: &[String]) -> (String, Option<String>) {
+    let triggers: Vec<Vec<String>> = triggers
+        .iter()
+        .map(|trigger| {
+            trigger
+                .split_whitespace()
+                .map(normalize_command_word)
+                .filter(|w| !w.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|words| !words.is_empty())
+        .collect();
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let normalized: Vec<String> = words.iter().map(|w| normalize_command_word(w)).collect();
+
+    for i in 0..words.len() {
+        let matched = triggers.iter().find(|trigger| {
+            i + trigger.len() <= normalized.len() && normalized[i..i + trigger.len()] == trigger[..]
+        });
+        if let Some(trigger) = matched {
+            let before = words[..i].join(" ");
+            let after = words[i + trigger.len()..].join(" ");
+            return (before, Some(after));
+        }
+    }
+
+    (text.to_string(), None)
+}
+
+/// Formats the verbatim part of a transcription. Words are kept exactly as
+/// recognised apart from punctuation the speech model appended, and spoken
+/// symbol names ("underscore", "dot", "slash", ...) are inserted without
+/// surrounding spaces, so "my_var underscore two" becomes "my_var_two".
+pub fn format_literal(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut attach_next = true;
+
+    for word in text.split_whitespace() {
+        let normalized = normalize_command_word(word);
+        if let Some((_, symbol)) = LITERAL_SYMBOLS.iter().find(|(name, _)| *name == normalized) {
+            output.push_str(symbol);
+            attach_next = true;
+            continue;
+        }
+
+        let word = word.trim_end_matches(AUTO_PUNCTUATION);
+        if word.is_empty() {
+            continue;
+        }
+        if !attach_next {
+            output.push(' ');
+        }
+        output.push_str(word);
+        attach_next = false;
+    }
+
+    output
+}
+
+/// Most words of overlap looked for between neighbouring chunks
+const MAX_OVERLAP_WORDS: usize = 20;
+
+/// Fewer words in common than this are taken as chance, not overlap
+const MIN_OVERLAP_WORDS: usize = 2;
+
+/// Words at the edge of a chunk that may be cut off and misheard
+const CHUNK_EDGE_WORDS: usize = 2;
+
+/// Joins the transcripts of two audio chunks that overlap. The overlapping
+/// audio is transcribed in both, so the longest run of words ending
+/// `previous` that also starts `next` is kept once, allowing for a word or
+/// two cut off at either edge. Words are compared like command words.
+pub fn stitch_overlapping(previous: &str, next: &str) -> String {
+    let previous_words: Vec<&str> = previous.split_whitespace().collect();
+    let next_words: Vec<&str> = next.split_whitespace().collect();
+    let previous_normalized: Vec<String> = previous_words
+        .iter()
+        .map(|w| normalize_command_word(w))
+        .collect();
+    let next_normalized: Vec<String> = next_words
+        .iter()
+        .map(|w| normalize_command_word(w))
+        .collect();
+
+    // (words in common, end of them in `previous`, end of them in `next`)
+    let mut best: Option<(usize, usize, usize)> = None;
+    for tail in 0..=CHUNK_EDGE_WORDS.min(previous_words.len()) {
+        let previous_end = previous_words.len() - tail;
+        for head in 0..=CHUNK_EDGE_WORDS.min(next_words.len()) {
+            let longest = MAX_OVERLAP_WORDS
+                .min(previous_end)
+                .min(next_words.len() - head);
+            let found = (MIN_OVERLAP_WORDS..=longest).rev().find(|&n| {
+                previous_normalized[previous_end - n..previous_end]
+                    == next_normalized[head..head + n]
+            });
+            if let Some(n) = found {
+                if best.is_none_or(|(best_n, _, _)| n > best_n) {
+                    best = Some((n, previous_end, head + n));
+                }
+            }
+        }
+    }
+
+    let (previous_end, next_start) = best
+        .map(|(_, previous_end, next_start)| (previous_end, next_start))
+        .unwrap_or((previous_words.len(), 0));
+    previous_words[..previous_end]
+        .iter()
+        .chain(&next_words[next_start..])
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -444,6 +742,90 @@ mod tests {
         assert!(result.contains("MacBook"));
     }
 
+    fn spoken_commands() -> HashMap<String, String> {
+        [
+            ("comma", ","),
+            ("period", "."),
+            ("question mark", "?"),
+            ("new line", "\n"),
+            ("new paragraph", "\n\n"),
+            ("open quote", "\""),
+            ("close quote", "\""),
+            ("open paren", "("),
+            ("close paren", ")"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+    }
+
+    #[test]
+    fn test_spoken_commands_punctuation_attaches_to_previous_word() {
+        let result = apply_spoken_commands("hello comma world period", &spoken_commands());
+        assert_eq!(result, "hello, world.");
+    }
+
+    #[test]
+    fn test_spoken_commands_replace_model_punctuation() {
+        let result = apply_spoken_commands(
+            "Hello, comma, how are you? Question mark.",
+            &spoken_commands(),
+        );
+        assert_eq!(result, "Hello, how are you?");
+    }
+
+    #[test]
+    fn test_spoken_commands_line_breaks() {
+        let result = apply_spoken_commands(
+            "first line new line second New paragraph. third",
+            &spoken_commands(),
+        );
+        assert_eq!(result, "first line\nsecond\n\nthird");
+    }
+
+    #[test]
+    fn test_spoken_commands_quotes_and_parens() {
+        let result = apply_spoken_commands(
+            "he said open quote hi close quote open paren twice close paren",
+            &spoken_commands(),
+        );
+        assert_eq!(result, "he said \"hi\" (twice)");
+    }
+
+    #[test]
+    fn test_split_literal_segment() {
+        let triggers = vec!["literally".to_string(), "spell out".to_string()];
+        assert_eq!(
+            split_literal_segment("Rename it to, literally, my_var underscore two.", &triggers),
+            (
+                "Rename it to,".to_string(),
+                Some("my_var underscore two.".to_string())
+            )
+        );
+        assert_eq!(
+            split_literal_segment("Spell out src slash main dot rs", &triggers),
+            (String::new(), Some("src slash main dot rs".to_string()))
+        );
+        assert_eq!(
+            split_literal_segment("nothing special here", &triggers),
+            ("nothing special here".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn test_format_literal_joins_symbols() {
+        assert_eq!(format_literal("my_var underscore two."), "my_var_two");
+        assert_eq!(format_literal("src slash main dot rs"), "src/main.rs");
+        assert_eq!(format_literal("Tilde slash Notes"), "~/Notes");
+        assert_eq!(format_literal("hello world"), "hello world");
+    }
+
+    #[test]
+    fn test_spoken_commands_empty_mapping_is_noop() {
+        let result = apply_spoken_commands("hello comma world", &HashMap::new());
+        assert_eq!(result, "hello comma world");
+    }
+
     #[test]
     fn test_apply_custom_words_trailing_number_not_doubled() {
         // Verify that trailing non-alpha chars (like numbers) aren't double-counted
@@ -458,4 +840,20 @@ mod tests {
             result
         );
     }
+
+    #[test]
+    fn test_stitch_overlapping_keeps_overlap_once() {
+        assert_eq!(
+            stitch_overlapping(
+                "so the plan for today is to ship the rel",
+                "ship the release by Friday afternoon."
+            ),
+            "so the plan for today is to ship the release by Friday afternoon."
+        );
+        assert_eq!(
+            stitch_overlapping("We met on Monday.", "Monday, and again later"),
+            "We met on Monday. Monday, and again later"
+        );
+        assert_eq!(stitch_overlapping("", " first words "), "first words");
+    }
 }
@@ -1,12 +1,29 @@
-use clap::Parser;
+use crate::managers::model::ModelManager;
+use crate::settings::{self, SettingsProfile};
+use clap::{Parser, Subcommand};
+use log::{info, warn};
+use std::path::PathBuf;
+use tauri::AppHandle;
 
 #[derive(Parser, Debug, Clone, Default)]
 #[command(name = "handy", about = "Handy - Speech to Text")]
 pub struct CliArgs {
     /// Start with the main window hidden
-    #[arg(long)]
+    #[arg(long, env = "HANDY_START_HIDDEN")]
     pub start_hidden: bool,
 
+    /// Use a saved profile, by name or id, for this session
+    #[arg(long, env = "HANDY_PROFILE")]
+    pub profile: Option<String>,
+
+    /// Use a downloaded transcription model for this session
+    #[arg(long, env = "HANDY_MODEL")]
+    pub model: Option<String>,
+
+    /// Keep the settings file in this folder instead of the app data folder
+    #[arg(long, env = "HANDY_SETTINGS_DIR")]
+    pub settings_dir: Option<PathBuf>,
+
     /// Disable the system tray icon
     #[arg(long)]
     pub no_tray: bool,
@@ -23,7 +40,99 @@ pub struct CliArgs {
     #[arg(long)]
     pub cancel: bool,
 
+    /// Deliver dictation to the currently focused window (sent to running instance)
+    #[arg(long)]
+    pub lock_target: bool,
+
+    /// Stop delivering dictation to a locked window (sent to running instance)
+    #[arg(long)]
+    pub unlock_target: bool,
+
+    /// Toggle incognito dictation for this session (sent to running instance)
+    #[arg(long)]
+    pub toggle_incognito: bool,
+
     /// Enable debug mode with verbose logging
     #[arg(long)]
     pub debug: bool,
+
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+/// Commands for a running instance, sent through its local HTTP API
+#[derive(Subcommand, Debug, Clone)]
+pub enum CliCommand {
+    /// Start recording
+    Start {
+        /// Post-process the transcript
+        #[arg(long)]
+        post_process: bool,
+    },
+    /// Stop recording and transcribe
+    Stop,
+    /// Cancel the current operation
+    Cancel,
+    /// Transcribe an audio file and print the text
+    Transcribe { file: PathBuf },
+    /// Print the most recent transcript
+    Last {
+        /// Also copy it to the clipboard, which needs api_server.allow_clipboard
+        #[arg(long)]
+        copy: bool,
+    },
+    /// Serve MCP over stdin and stdout, for clients that launch a command
+    Mcp,
+}
+
+impl CliArgs {
+    /// Applies `--profile` and `--model` as session overrides, leaving the
+    /// stored settings untouched. Runs before anything reads the settings it
+    /// overrides, such as the model preload.
+    pub fn apply_session_overrides(&self, app: &AppHandle, model_manager: &ModelManager) {
+        if let Some(name) = &self.profile {
+            let stored = settings::get_settings(app);
+            match stored
+                .profiles
+                .iter()
+                .find(|p| p.id == *name || p.name.eq_ignore_ascii_case(name))
+            {
+                Some(profile) => override_with_profile(&stored, profile),
+                None => warn!("Profile '{}' from the command line doesn't exist", name),
+            }
+        }
+
+        if let Some(model_id) = &self.model {
+            let downloaded = model_manager
+                .get_model_info(model_id)
+                .is_some_and(|model| model.is_downloaded);
+            if downloaded {
+                info!("Using model {} for this session", model_id);
+                settings::set_session_override("selected_model", serde_json::json!(model_id));
+            } else {
+                warn!(
+                    "Model '{}' from the command line isn't downloaded",
+                    model_id
+                );
+            }
+        }
+    }
+}
+
+/// Overrides every setting the profile changes
+fn override_with_profile(stored: &settings::AppSettings, profile: &SettingsProfile) {
+    let mut with_profile = stored.clone();
+    profile.apply_to(&mut with_profile);
+    with_profile.active_profile_id = Some(profile.id.clone());
+
+    let before = serde_json::to_value(stored).unwrap_or_default();
+    let after = serde_json::to_value(&with_profile).unwrap_or_default();
+    if let Some(after) = after.as_object() {
+        for (key, value) in after {
+            if before.get(key) != Some(value) {
+                settings::set_session_override(key, value.clone());
+            }
+        }
+    }
+    info!("Using profile '{}' for this session", profile.name);
 }
@@ -0,0 +1,240 @@
+//! `handy start`, `handy transcribe note.mp3` and the other subcommands.
+//!
+//! They drive an already running Handy through its local HTTP API (see
+//! `api_server`), so it has to be turned on in the settings. The port is read
+//! from the settings file and the token from the credential store;
+//! `HANDY_API_PORT` and `HANDY_API_TOKEN` override them.
+//!
+//! `handy mcp` relays MCP messages between stdin/stdout and `/mcp`.
+
+use crate::api_server;
+use crate::cli::{CliArgs, CliCommand};
+use crate::settings::{default_api_server_port, SETTINGS_STORE_PATH};
+use reqwest::{Client, Method, StatusCode};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+/// Folder tauri keeps app data in, named after the bundle identifier
+const APP_IDENTIFIER: &str = env!("HANDY_APP_IDENTIFIER");
+
+/// Runs `command` against the running instance and returns the exit code
+pub fn run(args: &CliArgs, command: &CliCommand) -> i32 {
+    attach_console();
+    if let CliCommand::Mcp = command {
+        return match tauri::async_runtime::block_on(relay_mcp(args)) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("handy: {}", e);
+                1
+            }
+        };
+    }
+    match tauri::async_runtime::block_on(send(args, command)) {
+        Ok(Some(output)) => {
+            println!("{}", output);
+            0
+        }
+        Ok(None) => 0,
+        Err(e) => {
+            eprintln!("handy: {}", e);
+            1
+        }
+    }
+}
+
+/// Release builds on Windows have no console of their own
+#[cfg(all(windows, not(debug_assertions)))]
+fn attach_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(all(windows, not(debug_assertions))))]
+fn attach_console() {}
+
+fn settings_file(args: &CliArgs) -> Option<PathBuf> {
+    match &args.settings_dir {
+        Some(dir) => Some(dir.join(SETTINGS_STORE_PATH)),
+        None => dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER).join(SETTINGS_STORE_PATH)),
+    }
+}
+
+/// Port of the HTTP API, from the environment, the settings file or the default
+fn port(args: &CliArgs) -> u16 {
+    if let Some(port) = std::env::var("HANDY_API_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+    {
+        return port;
+    }
+    settings_file(args)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str::<Value>(&contents).ok())
+        .and_then(|store| store["settings"]["api_server"]["port"].as_u64())
+        .and_then(|port| u16::try_from(port).ok())
+        .unwrap_or_else(default_api_server_port)
+}
+
+fn token() -> Result<String, String> {
+    std::env::var("HANDY_API_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty())
+        .or_else(api_server::stored_token)
+        .ok_or_else(|| {
+            "No API token found. Turn on the HTTP API in Handy or set HANDY_API_TOKEN.".to_string()
+        })
+}
+
+fn request_error(e: reqwest::Error, port: u16) -> String {
+    if e.is_connect() {
+        format!(
+            "Can't reach Handy on port {}. Is it running with the HTTP API turned on?",
+            port
+        )
+    } else {
+        e.to_string()
+    }
+}
+
+async fn send(args: &CliArgs, command: &CliCommand) -> Result<Option<String>, String> {
+    let (method, path, body) = match command {
+        CliCommand::Start { post_process } => (
+            Method::POST,
+            format!("/v1/recording/start?post_process={}", post_process),
+            None,
+        ),
+        CliCommand::Stop => (Method::POST, "/v1/recording/stop".to_string(), None),
+        CliCommand::Cancel => (Method::POST, "/v1/recording/cancel".to_string(), None),
+        CliCommand::Transcribe { file } => {
+            let audio = std::fs::read(file)
+                .map_err(|e| format!("Failed to read '{}': {}", file.display(), e))?;
+            let path = match file.extension().and_then(|extension| extension.to_str()) {
+                Some(extension) => format!("/v1/transcribe?format={}", extension),
+                None => "/v1/transcribe".to_string(),
+            };
+            (Method::POST, path, Some(audio))
+        }
+        CliCommand::Last { copy: true } => (Method::POST, "/v1/result/copy".to_string(), None),
+        CliCommand::Last { copy: false } => (Method::GET, "/v1/result".to_string(), None),
+        CliCommand::Mcp => unreachable!("handled by relay_mcp"),
+    };
+
+    let port = port(args);
+    let mut request = Client::new()
+        .request(method, format!("http://127.0.0.1:{}{}", port, path))
+        .bearer_auth(token()?);
+    if let Some(body) = body {
+        request = request.body(body);
+    }
+    let response = request.send().await.map_err(|e| request_error(e, port))?;
+
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or_default();
+    decode_response(status, &body)
+}
+
+/// The text of an answer, if it has one, or the error it carries
+fn decode_response(status: StatusCode, body: &Value) -> Result<Option<String>, String> {
+    if status == StatusCode::UNAUTHORIZED {
+        return Err("Handy refused the API token".to_string());
+    }
+    if !status.is_success() {
+        return Err(body["error"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("Request failed with {}", status)));
+    }
+    Ok(body["text"].as_str().map(str::to_string))
+}
+
+/// Forwards each line of stdin to `/mcp` and prints the answers. Requests
+/// that can't be delivered are answered with a JSON-RPC error so the client
+/// doesn't wait for them.
+async fn relay_mcp(args: &CliArgs) -> Result<(), String> {
+    let port = port(args);
+    let token = token()?;
+    let url = format!("http://127.0.0.1:{}/mcp", port);
+    let client = Client::new();
+
+    for line in std::io::stdin().lock().lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let id = serde_json::from_str::<Value>(&line)
+            .ok()
+            .and_then(|message| message.get("id").cloned());
+
+        let reply = match client
+            .post(&url)
+            .bearer_auth(&token)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(line)
+            .send()
+            .await
+        {
+            Ok(response) if response.status() == StatusCode::UNAUTHORIZED => {
+                Err("Handy refused the API token".to_string())
+            }
+            Ok(response) => response.text().await.map_err(|e| e.to_string()),
+            Err(e) => Err(request_error(e, port)),
+        };
+
+        let reply = match reply {
+            Ok(text) if text.is_empty() => continue,
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("handy: {}", e);
+                match id {
+                    Some(id) => json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": -32000, "message": e }
+                    })
+                    .to_string(),
+                    None => continue,
+                }
+            }
+        };
+        let mut stdout = std::io::stdout().lock();
+        writeln!(stdout, "{}", reply)
+            .and_then(|()| stdout.flush())
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_answers_and_errors() {
+        assert_eq!(
+            decode_response(StatusCode::OK, &json!({ "text": "hello" })),
+            Ok(Some("hello".to_string()))
+        );
+        assert_eq!(
+            decode_response(StatusCode::ACCEPTED, &json!({ "status": "starting" })),
+            Ok(None)
+        );
+        assert_eq!(
+            decode_response(
+                StatusCode::CONFLICT,
+                &json!({ "error": "Already recording" })
+            ),
+            Err("Already recording".to_string())
+        );
+        assert_eq!(
+            decode_response(StatusCode::INTERNAL_SERVER_ERROR, &Value::Null),
+            Err("Request failed with 500 Internal Server Error".to_string())
+        );
+        assert_eq!(
+            decode_response(StatusCode::UNAUTHORIZED, &Value::Null),
+            Err("Handy refused the API token".to_string())
+        );
+    }
+}
@@ -1,81 +1,283 @@
-use crate::input::{self, EnigoState};
+use crate::accessibility::{self, FocusedControl};
+use crate::active_app::{self, ActiveApp};
+use crate::app_overrides;
+use crate::input::{self, InjectorState, KeyInjector};
+use crate::markdown;
+use crate::notifications::{self, NotificationKind};
+use crate::settings::{
+    get_settings, AppPasteRule, AutoSubmitKey, ClipboardHandling, MarkdownOutputMode, PasteMethod,
+    TypingSpeed,
+};
 #[cfg(target_os = "linux")]
-use crate::settings::TypingTool;
-use crate::settings::{get_settings, AutoSubmitKey, ClipboardHandling, PasteMethod};
-use enigo::{Direction, Enigo, Key, Keyboard};
-use log::info;
+use crate::settings::{PrimarySelectionMode, TypingTool};
+use crate::smart_insert;
+use enigo::{Direction, Key};
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
 use std::process::Command;
-use std::time::Duration;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::image::Image;
 use tauri::{AppHandle, Manager};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 
+#[cfg(target_os = "linux")]
+use crate::events::{self, ErrorSource};
 #[cfg(target_os = "linux")]
 use crate::utils::{is_kde_wayland, is_wayland};
 
-/// Pastes text using the clipboard: saves current content, writes text, sends paste keystroke, restores clipboard.
-fn paste_via_clipboard(
-    enigo: &mut Enigo,
-    text: &str,
-    app_handle: &AppHandle,
-    paste_method: &PasteMethod,
-    paste_delay_ms: u64,
-) -> Result<(), String> {
+/// Time for a locked target window to take focus before input is sent
+const TARGET_ACTIVATION_DELAY: Duration = Duration::from_millis(150);
+
+/// Undoing is refused once the last paste is older than this
+const UNDO_WINDOW: Duration = Duration::from_secs(300);
+
+/// What the most recent paste inserted, kept so it can be undone
+struct LastPaste {
+    text: String,
+    method: PasteMethod,
+    app: Option<ActiveApp>,
+    /// Pasted into a terminal, where the undo shortcut suspends the
+    /// foreground process instead
+    terminal: bool,
+    at: Instant,
+}
+
+static LAST_PASTE: Lazy<Mutex<Option<LastPaste>>> = Lazy::new(|| Mutex::new(None));
+
+/// Clipboard contents captured before pasting so they can be put back afterwards.
+enum ClipboardSnapshot {
+    Text(String),
+    Image(Image<'static>),
+    Empty,
+}
+
+/// Captures the current clipboard. Text is preferred; images are kept when
+/// there is no text. Other rich formats can't be read back and are lost.
+fn snapshot_clipboard(app_handle: &AppHandle) -> ClipboardSnapshot {
     let clipboard = app_handle.clipboard();
-    let clipboard_content = clipboard.read_text().unwrap_or_default();
+    if let Ok(text) = clipboard.read_text() {
+        return ClipboardSnapshot::Text(text);
+    }
+    if let Ok(image) = clipboard.read_image() {
+        return ClipboardSnapshot::Image(image.to_owned());
+    }
+    ClipboardSnapshot::Empty
+}
 
-    // Write text to clipboard first
+fn restore_clipboard(app_handle: &AppHandle, snapshot: ClipboardSnapshot) {
+    let clipboard = app_handle.clipboard();
+    let result = match snapshot {
+        ClipboardSnapshot::Text(text) => write_clipboard_text(app_handle, &text),
+        ClipboardSnapshot::Image(image) => clipboard
+            .write_image(&image)
+            .map_err(|e| format!("Failed to restore clipboard image: {}", e)),
+        ClipboardSnapshot::Empty => clipboard
+            .clear()
+            .map_err(|e| format!("Failed to clear clipboard: {}", e)),
+    };
+
+    if let Err(e) = result {
+        warn!("{}", e);
+    }
+}
+
+fn write_clipboard_text(app_handle: &AppHandle, text: &str) -> Result<(), String> {
     // On Wayland, prefer wl-copy for better compatibility (especially with umlauts)
     #[cfg(target_os = "linux")]
-    let write_result = if is_wayland() && is_wl_copy_available() {
+    if is_wayland() && is_wl_copy_available() {
         info!("Using wl-copy for clipboard write on Wayland");
-        write_clipboard_via_wl_copy(text)
-    } else {
-        clipboard
-            .write_text(text)
-            .map_err(|e| format!("Failed to write to clipboard: {}", e))
-    };
+        return write_clipboard_via_wl_copy(text);
+    }
 
-    #[cfg(not(target_os = "linux"))]
-    let write_result = clipboard
+    app_handle
+        .clipboard()
         .write_text(text)
-        .map_err(|e| format!("Failed to write to clipboard: {}", e));
+        .map_err(|e| format!("Failed to write to clipboard: {}", e))
+}
 
-    write_result?;
+/// Copies `text` without pasting it, for when delivering it elsewhere failed
+pub fn copy_text(app_handle: &AppHandle, text: &str) -> Result<(), String> {
+    write_clipboard_text(app_handle, text)
+}
 
-    std::thread::sleep(Duration::from_millis(paste_delay_ms));
+/// Formatted flavors of a transcript, written to the clipboard next to the plain text
+struct RichText {
+    html: String,
+    rtf: String,
+}
 
-    // Send paste key combo
-    #[cfg(target_os = "linux")]
-    let key_combo_sent = try_send_key_combo_linux(paste_method)?;
+impl RichText {
+    fn from_markdown(text: &str) -> Self {
+        Self {
+            html: markdown::markdown_to_html(text),
+            rtf: markdown::markdown_to_rtf(text),
+        }
+    }
+}
 
-    #[cfg(not(target_os = "linux"))]
-    let key_combo_sent = false;
+/// Writes plain text, HTML and RTF in a single clipboard transaction so word
+/// processors pick the richest flavor they understand.
+#[cfg(target_os = "windows")]
+fn write_clipboard_rich(
+    _app_handle: &AppHandle,
+    text: &str,
+    rich: &RichText,
+) -> Result<(), String> {
+    use windows::core::w;
+    use windows::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, OpenClipboard, RegisterClipboardFormatW,
+    };
 
-    // Fall back to enigo if no native tool handled it
-    if !key_combo_sent {
-        match paste_method {
-            PasteMethod::CtrlV => input::send_paste_ctrl_v(enigo)?,
-            PasteMethod::CtrlShiftV => input::send_paste_ctrl_shift_v(enigo)?,
-            PasteMethod::ShiftInsert => input::send_paste_shift_insert(enigo)?,
-            _ => return Err("Invalid paste method for clipboard paste".into()),
+    const CF_UNICODETEXT: u32 = 13;
+
+    let html_format = unsafe { RegisterClipboardFormatW(w!("HTML Format")) };
+    let rtf_format = unsafe { RegisterClipboardFormatW(w!("Rich Text Format")) };
+    if html_format == 0 || rtf_format == 0 {
+        return Err("Failed to register rich text clipboard formats".into());
+    }
+
+    // All flavors are null-terminated; plain text is UTF-16, the others UTF-8
+    let unicode_text: Vec<u8> = text
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    let mut html = cf_html(&rich.html).into_bytes();
+    html.push(0);
+    let mut rtf = rich.rtf.clone().into_bytes();
+    rtf.push(0);
+
+    unsafe { OpenClipboard(None) }.map_err(|e| format!("Failed to open clipboard: {}", e))?;
+    let result = unsafe { EmptyClipboard() }
+        .map_err(|e| format!("Failed to empty clipboard: {}", e))
+        .and_then(|()| set_clipboard_bytes(CF_UNICODETEXT, &unicode_text))
+        .and_then(|()| set_clipboard_bytes(html_format, &html))
+        .and_then(|()| set_clipboard_bytes(rtf_format, &rtf));
+    let _ = unsafe { CloseClipboard() };
+    result
+}
+
+/// Copies `bytes` into global memory and hands it to the clipboard, which
+/// must already be open.
+#[cfg(target_os = "windows")]
+fn set_clipboard_bytes(format: u32, bytes: &[u8]) -> Result<(), String> {
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::DataExchange::SetClipboardData;
+    use windows::Win32::System::Memory::{
+        GlobalAlloc, GlobalFree, GlobalLock, GlobalUnlock, GMEM_MOVEABLE,
+    };
+
+    unsafe {
+        let memory = GlobalAlloc(GMEM_MOVEABLE, bytes.len())
+            .map_err(|e| format!("Failed to allocate clipboard memory: {}", e))?;
+        let target = GlobalLock(memory);
+        if target.is_null() {
+            let _ = GlobalFree(Some(memory));
+            return Err("Failed to lock clipboard memory".into());
+        }
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), target.cast::<u8>(), bytes.len());
+        let _ = GlobalUnlock(memory);
+
+        // The clipboard owns the memory once this succeeds
+        if let Err(e) = SetClipboardData(format, Some(HANDLE(memory.0))) {
+            let _ = GlobalFree(Some(memory));
+            return Err(format!("Failed to set clipboard data: {}", e));
         }
     }
+    Ok(())
+}
+
+/// Wraps an HTML fragment in the CF_HTML envelope, whose header gives byte
+/// offsets of the document and the fragment within it.
+#[cfg(target_os = "windows")]
+fn cf_html(fragment: &str) -> String {
+    const PREFIX: &str = "<html><body>\r\n<!--StartFragment-->";
+    const SUFFIX: &str = "<!--EndFragment-->\r\n</body></html>";
+
+    // Offsets are zero-padded so the header length doesn't depend on them
+    let header = |offsets: [usize; 4]| {
+        format!(
+            "Version:0.9\r\nStartHTML:{:010}\r\nEndHTML:{:010}\r\nStartFragment:{:010}\r\nEndFragment:{:010}\r\n",
+            offsets[0], offsets[1], offsets[2], offsets[3]
+        )
+    };
+    let start_html = header([0; 4]).len();
+    let start_fragment = start_html + PREFIX.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + SUFFIX.len();
+
+    format!(
+        "{}{}{}{}",
+        header([start_html, end_html, start_fragment, end_fragment]),
+        PREFIX,
+        fragment,
+        SUFFIX
+    )
+}
+
+/// Writes HTML with a plain text alternative. The clipboard plugin has no
+/// RTF support, so apps that only read RTF receive the plain text.
+#[cfg(not(target_os = "windows"))]
+fn write_clipboard_rich(app_handle: &AppHandle, text: &str, rich: &RichText) -> Result<(), String> {
+    debug!(
+        "Skipping {} byte RTF flavor, not supported on this platform",
+        rich.rtf.len()
+    );
+    app_handle
+        .clipboard()
+        .write_html(&rich.html, Some(text))
+        .map_err(|e| format!("Failed to write HTML to clipboard: {}", e))
+}
+
+/// Pastes text using the clipboard: saves current content, writes text, sends paste keystroke, restores clipboard.
+/// When `rich` is given its formatted flavors are written alongside `text`.
+fn paste_via_clipboard(
+    injector: &mut dyn KeyInjector,
+    text: &str,
+    rich: Option<&RichText>,
+    app_handle: &AppHandle,
+    paste_method: &PasteMethod,
+    paste_delay_ms: u64,
+    restore_delay_ms: u64,
+) -> Result<(), String> {
+    let snapshot = snapshot_clipboard(app_handle);
+
+    let write_result = match rich {
+        Some(rich) => write_clipboard_rich(app_handle, text, rich),
+        None => write_clipboard_text(app_handle, text),
+    };
+    if let Err(e) = write_result {
+        restore_clipboard(app_handle, snapshot);
+        return Err(e);
+    }
 
-    std::thread::sleep(std::time::Duration::from_millis(50));
+    std::thread::sleep(Duration::from_millis(paste_delay_ms));
 
-    // Restore original clipboard content
-    // On Wayland, prefer wl-copy for better compatibility
+    // Send paste key combo
     #[cfg(target_os = "linux")]
-    if is_wayland() && is_wl_copy_available() {
-        let _ = write_clipboard_via_wl_copy(&clipboard_content);
-    } else {
-        let _ = clipboard.write_text(&clipboard_content);
-    }
+    let key_combo_sent = try_send_key_combo_linux(paste_method);
 
     #[cfg(not(target_os = "linux"))]
-    let _ = clipboard.write_text(&clipboard_content);
+    let key_combo_sent: Result<bool, String> = Ok(false);
 
-    Ok(())
+    // Fall back to enigo if no native tool handled it
+    let paste_result = match key_combo_sent {
+        Ok(true) => Ok(()),
+        Ok(false) => match paste_method {
+            PasteMethod::CtrlV => input::send_paste_ctrl_v(injector),
+            PasteMethod::CtrlShiftV => input::send_paste_ctrl_shift_v(injector),
+            PasteMethod::ShiftInsert => input::send_paste_shift_insert(injector),
+            _ => Err("Invalid paste method for clipboard paste".into()),
+        },
+        Err(e) => Err(e),
+    };
+
+    // Give the target app time to read the clipboard before restoring it
+    std::thread::sleep(Duration::from_millis(restore_delay_ms));
+    restore_clipboard(app_handle, snapshot);
+
+    paste_result
 }
 
 /// Attempts to send a key combination using Linux-native tools.
@@ -198,6 +400,64 @@ fn try_direct_typing_linux(text: &str, preferred_tool: TypingTool) -> Result<boo
     Ok(false)
 }
 
+/// On Wayland, enigo can only reach XWayland windows, so pasting into native
+/// Wayland apps needs one of the external tools. Returns an actionable message
+/// when none is installed.
+#[cfg(target_os = "linux")]
+pub(crate) fn check_wayland_backend(
+    paste_method: PasteMethod,
+    typing_tool: TypingTool,
+) -> Result<(), String> {
+    if !is_wayland() {
+        return Ok(());
+    }
+
+    let key_combo_tool = (!is_kde_wayland() && is_wtype_available())
+        || is_dotool_available()
+        || is_ydotool_available();
+
+    let available = match paste_method {
+        // A user-specified tool reports its own error when missing
+        PasteMethod::Direct if typing_tool != TypingTool::Auto => true,
+        PasteMethod::Direct => (is_kde_wayland() && is_kwtype_available()) || key_combo_tool,
+        PasteMethod::CtrlV | PasteMethod::CtrlShiftV | PasteMethod::ShiftInsert => key_combo_tool,
+        // Accessibility checks its clipboard fallback separately
+        PasteMethod::None
+        | PasteMethod::ExternalScript
+        | PasteMethod::Accessibility
+        | PasteMethod::CopyOnly => true,
+    };
+
+    if available {
+        Ok(())
+    } else if is_kde_wayland() {
+        Err("No Wayland input tool found. Install kwtype, dotool or ydotool (with ydotoold running) to paste on KDE Wayland.".into())
+    } else {
+        Err("No Wayland input tool found. Install wtype, dotool or ydotool (with ydotoold running) to paste on Wayland.".into())
+    }
+}
+
+/// Reports a missing Wayland input tool as a paste error. Pasting still goes
+/// ahead through enigo, which reaches XWayland windows.
+#[cfg(target_os = "linux")]
+fn warn_without_wayland_backend(
+    app_handle: &AppHandle,
+    paste_method: PasteMethod,
+    typing_tool: TypingTool,
+) {
+    if let Err(e) = check_wayland_backend(paste_method, typing_tool) {
+        warn!(
+            "{} Falling back to enigo, which only reaches XWayland windows.",
+            e
+        );
+        events::emit_error(
+            app_handle,
+            ErrorSource::Paste,
+            format!("{} Until then only XWayland windows can be pasted into.", e),
+        );
+    }
+}
+
 /// Returns the list of available typing tools on this system.
 /// Always includes "auto" as the first entry.
 #[cfg(target_os = "linux")]
@@ -401,6 +661,64 @@ fn write_clipboard_via_wl_copy(text: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Write text to the primary selection so it can be pasted with a middle click.
+/// Uses wl-copy on Wayland and xclip (or xsel) on X11.
+#[cfg(target_os = "linux")]
+fn write_primary_selection(text: &str) -> Result<(), String> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    if is_wayland() {
+        if !is_wl_copy_available() {
+            return Err("wl-copy is required to set the primary selection on Wayland".into());
+        }
+        let status = Command::new("wl-copy")
+            .arg("--primary")
+            .arg("--")
+            .arg(text)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|e| format!("Failed to execute wl-copy: {}", e))?;
+        if !status.success() {
+            return Err("wl-copy --primary failed".into());
+        }
+        return Ok(());
+    }
+
+    // Both tools fork to keep serving the selection, so only wait for the
+    // foreground process after handing over the text on stdin
+    let candidates: [(&str, &[&str]); 2] = [
+        ("xclip", &["-selection", "primary"]),
+        ("xsel", &["--primary", "--input"]),
+    ];
+    for (tool, args) in candidates {
+        let Ok(mut child) = Command::new(tool)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .map_err(|e| format!("Failed to write to {}: {}", tool, e))?;
+        }
+        let status = child
+            .wait()
+            .map_err(|e| format!("Failed to wait for {}: {}", tool, e))?;
+        if !status.success() {
+            return Err(format!("{} failed to set the primary selection", tool));
+        }
+        return Ok(());
+    }
+
+    Err("xclip or xsel is required to set the primary selection on X11".into())
+}
+
 /// Send a key combination (e.g., Ctrl+V) via wtype on Wayland.
 #[cfg(target_os = "linux")]
 fn send_key_combo_via_wtype(paste_method: &PasteMethod) -> Result<(), String> {
@@ -524,9 +842,52 @@ fn paste_via_external_script(text: &str, script_path: &str) -> Result<(), String
     Ok(())
 }
 
-/// Types text directly by simulating individual key presses.
+/// Types text directly by simulating individual key presses, paced according to `speed`.
 fn paste_direct(
-    enigo: &mut Enigo,
+    injector: &mut dyn KeyInjector,
+    text: &str,
+    #[cfg(target_os = "linux")] typing_tool: TypingTool,
+    speed: TypingSpeed,
+) -> Result<(), String> {
+    for (index, chunk) in split_typing_chunks(text, speed.chunk_size)
+        .into_iter()
+        .enumerate()
+    {
+        if index > 0 && speed.delay_ms > 0 {
+            std::thread::sleep(Duration::from_millis(speed.delay_ms));
+        }
+        type_text_chunk(
+            injector,
+            chunk,
+            #[cfg(target_os = "linux")]
+            typing_tool,
+        )?;
+    }
+    Ok(())
+}
+
+/// Splits text into chunks of at most `chunk_size` characters. A size of 0 keeps it whole.
+fn split_typing_chunks(text: &str, chunk_size: usize) -> Vec<&str> {
+    if chunk_size == 0 {
+        return vec![text];
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    for (count, (offset, _)) in text.char_indices().enumerate() {
+        if count > 0 && count % chunk_size == 0 {
+            chunks.push(&text[start..offset]);
+            start = offset;
+        }
+    }
+    if start < text.len() {
+        chunks.push(&text[start..]);
+    }
+    chunks
+}
+
+fn type_text_chunk(
+    injector: &mut dyn KeyInjector,
     text: &str,
     #[cfg(target_os = "linux")] typing_tool: TypingTool,
 ) -> Result<(), String> {
@@ -538,44 +899,44 @@ fn paste_direct(
         info!("Falling back to enigo for direct text input");
     }
 
-    input::paste_text_direct(enigo, text)
+    input::paste_text_direct(injector, text)
 }
 
-fn send_return_key(enigo: &mut Enigo, key_type: AutoSubmitKey) -> Result<(), String> {
+fn send_return_key(injector: &mut dyn KeyInjector, key_type: AutoSubmitKey) -> Result<(), String> {
     match key_type {
         AutoSubmitKey::Enter => {
-            enigo
+            injector
                 .key(Key::Return, Direction::Press)
                 .map_err(|e| format!("Failed to press Return key: {}", e))?;
-            enigo
+            injector
                 .key(Key::Return, Direction::Release)
                 .map_err(|e| format!("Failed to release Return key: {}", e))?;
         }
         AutoSubmitKey::CtrlEnter => {
-            enigo
+            injector
                 .key(Key::Control, Direction::Press)
                 .map_err(|e| format!("Failed to press Control key: {}", e))?;
-            enigo
+            injector
                 .key(Key::Return, Direction::Press)
                 .map_err(|e| format!("Failed to press Return key: {}", e))?;
-            enigo
+            injector
                 .key(Key::Return, Direction::Release)
                 .map_err(|e| format!("Failed to release Return key: {}", e))?;
-            enigo
+            injector
                 .key(Key::Control, Direction::Release)
                 .map_err(|e| format!("Failed to release Control key: {}", e))?;
         }
         AutoSubmitKey::CmdEnter => {
-            enigo
+            injector
                 .key(Key::Meta, Direction::Press)
                 .map_err(|e| format!("Failed to press Meta/Cmd key: {}", e))?;
-            enigo
+            injector
                 .key(Key::Return, Direction::Press)
                 .map_err(|e| format!("Failed to press Return key: {}", e))?;
-            enigo
+            injector
                 .key(Key::Return, Direction::Release)
                 .map_err(|e| format!("Failed to release Return key: {}", e))?;
-            enigo
+            injector
                 .key(Key::Meta, Direction::Release)
                 .map_err(|e| format!("Failed to release Meta/Cmd key: {}", e))?;
         }
@@ -584,15 +945,136 @@ fn send_return_key(enigo: &mut Enigo, key_type: AutoSubmitKey) -> Result<(), Str
     Ok(())
 }
 
+/// Finds the rule for the frontmost app; the first matching rule wins.
+fn find_app_rule<'a>(
+    rules: &'a [AppPasteRule],
+    active_app: Option<&ActiveApp>,
+) -> Option<&'a AppPasteRule> {
+    active_app.and_then(|app| rules.iter().find(|rule| app.matches(&rule.app_match)))
+}
+
+/// Whether the paste method puts text into the focused app
+fn inserts_text(paste_method: PasteMethod) -> bool {
+    !matches!(paste_method, PasteMethod::None | PasteMethod::CopyOnly)
+}
+
 fn should_send_auto_submit(auto_submit: bool, paste_method: PasteMethod) -> bool {
-    auto_submit && paste_method != PasteMethod::None
+    auto_submit && inserts_text(paste_method)
 }
 
-pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
+/// First line of copied text to show, nothing for incognito dictations
+fn copied_preview(text: &str, incognito: bool) -> String {
+    if incognito || crate::incognito::is_session_enabled() {
+        String::new()
+    } else {
+        notifications::preview(text)
+    }
+}
+
+/// Confirms a copy-only paste, since nothing visibly lands in the app: the
+/// result HUD if it's enabled, otherwise a native notification with the
+/// copied text
+fn notify_copied(app_handle: &AppHandle, text: &str, incognito: bool) {
+    let preview = copied_preview(text, incognito);
+    if get_settings(app_handle).result_hud_enabled {
+        crate::overlay::show_result_hud(app_handle, &preview);
+        return;
+    }
+    notifications::notify(app_handle, NotificationKind::Copied, &preview, incognito);
+}
+
+/// Pastes `text` with the configured method. `incognito` keeps it out of
+/// anything shown about the paste.
+pub fn paste(text: String, app_handle: AppHandle, incognito: bool) -> Result<(), String> {
     let settings = get_settings(&app_handle);
-    let paste_method = settings.paste_method;
+    // A failed or partial paste must not leave an older paste up for undo
+    LAST_PASTE.lock().unwrap_or_else(|e| e.into_inner()).take();
+    // Deliver to the locked target window even if focus has moved elsewhere
+    let target = active_app::locked_target();
+    if let Some(target) = &target {
+        active_app::activate_target(target)?;
+        std::thread::sleep(TARGET_ACTIVATION_DELAY);
+    }
+
+    let active_app = if let Some(target) = target {
+        Some(target.app)
+    } else if settings.app_paste_rules.is_empty()
+        && settings.app_overrides.is_empty()
+        && !settings.smart_insert_enabled
+        && !settings.focused_control_detection
+    {
+        None
+    } else {
+        active_app::frontmost_app()
+    };
+    let settings = app_overrides::resolve(&settings, active_app.as_ref()).settings;
+    let app_rule = find_app_rule(&settings.app_paste_rules, active_app.as_ref());
+    let paste_method = app_rule.map_or(settings.paste_method, |rule| rule.paste_method);
+
+    let control = if settings.focused_control_detection && inserts_text(paste_method) {
+        accessibility::focused_control(active_app.as_ref())
+    } else {
+        None
+    };
+    debug!("Focused control: {:?}", control);
+    let paste_method = match control {
+        Some(FocusedControl::Secure) => {
+            info!("Focused control is a password field, not pasting");
+            notifications::notify(
+                &app_handle,
+                NotificationKind::PasswordFieldSkipped,
+                "Dictation is never pasted into password fields.",
+                incognito,
+            );
+            return Ok(());
+        }
+        Some(FocusedControl::NotEditable) => PasteMethod::CopyOnly,
+        // Terminals differ in their paste shortcut, but all take typing. An
+        // app rule is the user's explicit choice, so it wins.
+        Some(FocusedControl::Terminal)
+            if app_rule.is_none()
+                && matches!(
+                    paste_method,
+                    PasteMethod::CtrlV
+                        | PasteMethod::CtrlShiftV
+                        | PasteMethod::ShiftInsert
+                        | PasteMethod::Accessibility
+                ) =>
+        {
+            PasteMethod::Direct
+        }
+        _ => paste_method,
+    };
+    let typing_speed = if app_rule.is_some_and(|rule| rule.slow_typing) {
+        settings.slow_typing_speed
+    } else {
+        settings.typing_speed
+    };
     let paste_delay_ms = settings.paste_delay_ms;
 
+    let markdown_mode = app_rule
+        .and_then(|rule| rule.markdown_mode)
+        .unwrap_or(settings.markdown_output_mode);
+    let (text, rich) = match markdown_mode {
+        MarkdownOutputMode::Keep => (text, None),
+        MarkdownOutputMode::Strip => (markdown::strip_markdown(&text), None),
+        // Rich text needs the clipboard; other paste methods only use the
+        // stripped text. Unformatted text is left as plain text only.
+        MarkdownOutputMode::RichText => {
+            let rich =
+                markdown::has_markdown_formatting(&text).then(|| RichText::from_markdown(&text));
+            (markdown::strip_markdown(&text), rich)
+        }
+    };
+
+    // Join cleanly with the text already before the caret
+    let text = if settings.smart_insert_enabled && inserts_text(paste_method) {
+        let preceding = smart_insert::preceding_context(active_app.as_ref());
+        smart_insert::adjust_for_context(&text, preceding.as_deref())
+    } else {
+        text
+    };
+
     // Append trailing space if setting is enabled
     let text = if settings.append_trailing_space {
         format!("{} ", text)
@@ -605,67 +1087,244 @@ pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
         paste_method, paste_delay_ms
     );
 
-    // Get the managed Enigo instance
-    let enigo_state = app_handle
-        .try_state::<EnigoState>()
-        .ok_or("Enigo state not initialized")?;
-    let mut enigo = enigo_state
-        .0
-        .lock()
-        .map_err(|e| format!("Failed to lock Enigo: {}", e))?;
+    #[cfg(target_os = "linux")]
+    match settings.primary_selection_mode {
+        PrimarySelectionMode::Off => {}
+        PrimarySelectionMode::Also => {
+            if let Err(e) = write_primary_selection(&text) {
+                warn!("Failed to set primary selection: {}", e);
+            }
+        }
+        PrimarySelectionMode::Only => {
+            write_primary_selection(&text)?;
+            info!("Wrote transcription to the primary selection only");
+            return Ok(());
+        }
+    }
+
+    // Without a Wayland tool, enigo below still reaches XWayland windows
+    #[cfg(target_os = "linux")]
+    warn_without_wayland_backend(&app_handle, paste_method, settings.typing_tool);
+
+    let auto_submitted = should_send_auto_submit(settings.auto_submit, paste_method);
 
     // Perform the paste operation
     match paste_method {
         PasteMethod::None => {
             info!("PasteMethod::None selected - skipping paste action");
         }
-        PasteMethod::Direct => {
-            paste_direct(
-                &mut enigo,
-                &text,
-                #[cfg(target_os = "linux")]
-                settings.typing_tool,
-            )?;
-        }
-        PasteMethod::CtrlV | PasteMethod::CtrlShiftV | PasteMethod::ShiftInsert => {
-            paste_via_clipboard(
-                &mut enigo,
-                &text,
-                &app_handle,
-                &paste_method,
-                paste_delay_ms,
-            )?
+        PasteMethod::CopyOnly => {
+            match &rich {
+                Some(rich) => write_clipboard_rich(&app_handle, &text, rich)?,
+                None => write_clipboard_text(&app_handle, &text)?,
+            }
+            if control == Some(FocusedControl::NotEditable) {
+                warn!("No text field has focus, copied the transcription instead");
+                notifications::notify(
+                    &app_handle,
+                    NotificationKind::NoTextField,
+                    &format!("Copied instead: {}", copied_preview(&text, incognito)),
+                    incognito,
+                );
+            } else {
+                notify_copied(&app_handle, &text, incognito);
+            }
+            info!("Copied transcription to the clipboard without pasting");
         }
-        PasteMethod::ExternalScript => {
-            let script_path = settings
-                .external_script_path
-                .as_ref()
-                .filter(|p| !p.is_empty())
-                .ok_or("External script path is not configured")?;
-            paste_via_external_script(&text, script_path)?;
+        _ => {
+            // Copy-only and no-paste modes must keep working where synthetic
+            // input is blocked, so only inserting text needs the key injector
+            let injector_state = app_handle
+                .try_state::<InjectorState>()
+                .ok_or("Key injector not initialized")?;
+            let mut injector_guard = injector_state
+                .0
+                .lock()
+                .map_err(|e| format!("Failed to lock key injector: {}", e))?;
+            let injector = injector_guard.as_mut();
+
+            // Let go of the shortcut's modifiers before injecting anything
+            input::wait_for_modifier_release(settings.key_injection.modifier_settle_ms);
+            if settings.key_injection.release_modifiers {
+                input::release_modifiers(injector)?;
+            }
+
+            match paste_method {
+                PasteMethod::Direct => {
+                    paste_direct(
+                        injector,
+                        &text,
+                        #[cfg(target_os = "linux")]
+                        settings.typing_tool,
+                        typing_speed,
+                    )?;
+                }
+                PasteMethod::CtrlV | PasteMethod::CtrlShiftV | PasteMethod::ShiftInsert => {
+                    paste_via_clipboard(
+                        injector,
+                        &text,
+                        rich.as_ref(),
+                        &app_handle,
+                        &paste_method,
+                        paste_delay_ms,
+                        settings.clipboard_restore_delay_ms,
+                    )?
+                }
+                PasteMethod::ExternalScript => {
+                    let script_path = settings
+                        .external_script_path
+                        .as_ref()
+                        .filter(|p| !p.is_empty())
+                        .ok_or("External script path is not configured")?;
+                    paste_via_external_script(&text, script_path)?;
+                }
+                PasteMethod::Accessibility => {
+                    if accessibility::insert_text(&text)? {
+                        info!("Inserted text via accessibility API");
+                    } else {
+                        info!(
+                            "Accessibility insertion not supported here, falling back to clipboard paste"
+                        );
+                        #[cfg(target_os = "linux")]
+                        warn_without_wayland_backend(
+                            &app_handle,
+                            PasteMethod::CtrlV,
+                            settings.typing_tool,
+                        );
+                        paste_via_clipboard(
+                            injector,
+                            &text,
+                            None,
+                            &app_handle,
+                            &PasteMethod::CtrlV,
+                            paste_delay_ms,
+                            settings.clipboard_restore_delay_ms,
+                        )?
+                    }
+                }
+                // Handled above without the key injector
+                PasteMethod::None | PasteMethod::CopyOnly => {}
+            }
+
+            if auto_submitted {
+                std::thread::sleep(Duration::from_millis(50));
+                send_return_key(injector, settings.auto_submit_key)?;
+            }
         }
     }
 
-    if should_send_auto_submit(settings.auto_submit, paste_method) {
-        std::thread::sleep(Duration::from_millis(50));
-        send_return_key(&mut enigo, settings.auto_submit_key)?;
+    // Remember what was inserted for undo. Auto-submitted text has already
+    // been sent and external scripts may do anything, so neither can be undone.
+    let undoable = !auto_submitted
+        && !matches!(
+            paste_method,
+            PasteMethod::None | PasteMethod::ExternalScript | PasteMethod::CopyOnly
+        );
+    *LAST_PASTE.lock().unwrap_or_else(|e| e.into_inner()) = undoable.then(|| LastPaste {
+        text: text.clone(),
+        method: paste_method,
+        app: active_app.clone(),
+        terminal: control == Some(FocusedControl::Terminal)
+            || active_app
+                .as_ref()
+                .is_some_and(accessibility::is_terminal_app),
+        at: Instant::now(),
+    });
+
+    if settings.smart_insert_enabled && inserts_text(paste_method) {
+        let inserted = if auto_submitted {
+            format!("{}\n", text)
+        } else {
+            text.clone()
+        };
+        smart_insert::record_output(&inserted, active_app.as_ref());
     }
 
     // After pasting, optionally copy to clipboard based on settings
-    if settings.clipboard_handling == ClipboardHandling::CopyToClipboard {
-        let clipboard = app_handle.clipboard();
-        clipboard
-            .write_text(&text)
-            .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    if settings.clipboard_handling == ClipboardHandling::CopyToClipboard
+        && paste_method != PasteMethod::CopyOnly
+    {
+        match &rich {
+            Some(rich) => write_clipboard_rich(&app_handle, &text, rich)?,
+            None => app_handle
+                .clipboard()
+                .write_text(&text)
+                .map_err(|e| format!("Failed to copy to clipboard: {}", e))?,
+        }
     }
 
     Ok(())
 }
 
+/// Removes the text inserted by the most recent paste. Typed text is erased
+/// with one Backspace per character; clipboard and accessibility insertions are
+/// a single edit in the target app and are reverted with Cmd/Ctrl+Z, except in
+/// terminals, where Ctrl+Z suspends the foreground process and Backspace is
+/// used too.
+///
+/// Refuses when nothing undoable was pasted recently or a different app is now
+/// in front. Keys go through the key injector, so on Wayland this only reaches
+/// XWayland windows.
+pub fn undo_last_paste(app_handle: &AppHandle) -> Result<(), String> {
+    let settings = get_settings(app_handle);
+    let Some(last) = LAST_PASTE.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return Err("Nothing to undo".into());
+    };
+    if last.at.elapsed() > UNDO_WINDOW {
+        return Err("The last dictation is too old to undo".into());
+    }
+    if let Some(pasted_into) = &last.app {
+        if active_app::frontmost_app().is_some_and(|app| &app != pasted_into) {
+            return Err(format!(
+                "The last dictation went to {}, which is no longer in front",
+                pasted_into.name
+            ));
+        }
+    }
+
+    let injector_state = app_handle
+        .try_state::<InjectorState>()
+        .ok_or("Key injector not initialized")?;
+    let mut injector_guard = injector_state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to lock key injector: {}", e))?;
+    let injector = injector_guard.as_mut();
+
+    // The undo shortcut's own modifiers would otherwise turn Backspace into
+    // word deletion or Ctrl+Z into redo
+    input::wait_for_modifier_release(settings.key_injection.modifier_settle_ms);
+    input::release_modifiers(injector)?;
+
+    // The terminal paste methods are only used in terminals
+    let in_terminal = last.terminal
+        || matches!(
+            last.method,
+            PasteMethod::CtrlShiftV | PasteMethod::ShiftInsert
+        );
+    if last.method == PasteMethod::Direct || in_terminal {
+        let count = last.text.chars().count();
+        info!("Undoing dictation with {} backspaces", count);
+        input::send_backspaces(injector, count)
+    } else {
+        info!("Undoing pasted dictation with the undo shortcut");
+        input::send_undo(injector)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn incognito_copies_show_no_text() {
+        assert_eq!(
+            copied_preview("meet at noon\nthanks", false),
+            "meet at noon"
+        );
+        assert_eq!(copied_preview("meet at noon", true), "");
+    }
+
     #[test]
     fn auto_submit_requires_setting_enabled() {
         assert!(!should_send_auto_submit(false, PasteMethod::CtrlV));
@@ -675,6 +1334,7 @@ mod tests {
     #[test]
     fn auto_submit_skips_none_paste_method() {
         assert!(!should_send_auto_submit(true, PasteMethod::None));
+        assert!(!should_send_auto_submit(true, PasteMethod::CopyOnly));
     }
 
     #[test]
@@ -684,4 +1344,65 @@ mod tests {
         assert!(should_send_auto_submit(true, PasteMethod::CtrlShiftV));
         assert!(should_send_auto_submit(true, PasteMethod::ShiftInsert));
     }
+
+    #[test]
+    fn app_rules_override_global_paste_method() {
+        let resolve = |rules: &[AppPasteRule], app: Option<&ActiveApp>| {
+            find_app_rule(rules, app).map_or(PasteMethod::CtrlV, |rule| rule.paste_method)
+        };
+        let rules = vec![
+            AppPasteRule {
+                app_match: "terminal".to_string(),
+                paste_method: PasteMethod::Direct,
+                slow_typing: false,
+                markdown_mode: None,
+            },
+            AppPasteRule {
+                app_match: "com.citrix".to_string(),
+                paste_method: PasteMethod::CtrlShiftV,
+                slow_typing: true,
+                markdown_mode: None,
+            },
+        ];
+        let terminal = ActiveApp {
+            name: "Terminal".to_string(),
+            identifier: Some("com.apple.Terminal".to_string()),
+        };
+        let citrix = ActiveApp {
+            name: "Citrix Viewer".to_string(),
+            identifier: Some("com.citrix.receiver.icaviewer.mac".to_string()),
+        };
+        let editor = ActiveApp {
+            name: "TextEdit".to_string(),
+            identifier: None,
+        };
+
+        assert_eq!(resolve(&rules, Some(&terminal)), PasteMethod::Direct);
+        assert_eq!(resolve(&rules, Some(&citrix)), PasteMethod::CtrlShiftV);
+        assert_eq!(resolve(&rules, Some(&editor)), PasteMethod::CtrlV);
+        assert_eq!(resolve(&rules, None), PasteMethod::CtrlV);
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn cf_html_offsets_point_at_fragment() {
+        let html = cf_html("<p>caf\u{e9}</p>");
+        let offset =
+            |key: &str| -> usize { html.split(key).nth(1).unwrap()[..10].parse().unwrap() };
+
+        assert_eq!(
+            &html[offset("StartFragment:")..offset("EndFragment:")],
+            "<p>caf\u{e9}</p>"
+        );
+        assert!(html[offset("StartHTML:")..].starts_with("<html>"));
+        assert_eq!(offset("EndHTML:"), html.len());
+    }
+
+    #[test]
+    fn typing_chunks_respect_character_boundaries() {
+        assert_eq!(split_typing_chunks("hello", 0), vec!["hello"]);
+        assert_eq!(split_typing_chunks("hello", 2), vec!["he", "ll", "o"]);
+        assert_eq!(split_typing_chunks("äöü", 1), vec!["ä", "ö", "ü"]);
+        assert!(split_typing_chunks("", 3).is_empty());
+    }
 }
@@ -0,0 +1,15 @@
+use crate::api_server;
+
+/// Token clients of the local HTTP API send as `Authorization: Bearer <token>`
+#[tauri::command]
+#[specta::specta]
+pub fn get_api_server_token() -> Result<String, String> {
+    api_server::token()
+}
+
+/// Replaces the HTTP API token and returns the new one
+#[tauri::command]
+#[specta::specta]
+pub fn regenerate_api_server_token() -> Result<String, String> {
+    api_server::regenerate_token()
+}
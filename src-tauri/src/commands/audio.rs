@@ -14,7 +14,7 @@ pub struct CustomSounds {
     stop: bool,
 }
 
-fn custom_sound_exists(app: &AppHandle, sound_type: &str) -> bool {
+pub fn custom_sound_exists(app: &AppHandle, sound_type: &str) -> bool {
     app.path()
         .resolve(
             format!("custom_{}.wav", sound_type),
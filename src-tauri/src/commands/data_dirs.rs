@@ -0,0 +1,21 @@
+use crate::data_dirs::{self, DataKind};
+use tauri::AppHandle;
+
+/// The folder models, recordings or the history database are kept in
+#[tauri::command]
+#[specta::specta]
+pub fn get_data_directory(app: AppHandle, kind: DataKind) -> Result<String, String> {
+    Ok(data_dirs::dir(&app, kind)?.to_string_lossy().to_string())
+}
+
+/// Moves models, recordings or the history database to `path`, or back to
+/// the default folder when `path` is empty, then restarts Handy.
+#[tauri::command]
+#[specta::specta]
+pub fn move_data_directory(
+    app: AppHandle,
+    kind: DataKind,
+    path: Option<String>,
+) -> Result<(), String> {
+    data_dirs::move_dir(&app, kind, path)
+}
@@ -0,0 +1,173 @@
+//! One zip with what a bug report needs: recent logs and the settings with
+//! secrets and transcripts taken out, the model and GPUs, and which
+//! permissions Handy has.
+
+use crate::commands::permissions::{get_permission_status, PermissionStatus};
+use crate::error_reports;
+use crate::managers::model::{ModelInfo, ModelManager};
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::get_settings;
+use log::{info, warn};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::Write;
+use std::process::Command;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+#[derive(Serialize)]
+struct SystemReport {
+    app_version: &'static str,
+    os: &'static str,
+    os_version: String,
+    arch: &'static str,
+    gpus: Vec<String>,
+    selected_model: Option<ModelInfo>,
+    /// Model in memory right now, if any
+    loaded_model: Option<String>,
+    permissions: PermissionStatus,
+}
+
+/// Output lines of a command, empty if it can't run
+fn command_lines(program: &str, args: &[&str]) -> Vec<String> {
+    match Command::new(program).args(args).output() {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect(),
+        Err(e) => {
+            warn!("Failed to run {} for diagnostics: {}", program, e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn gpu_names() -> Vec<String> {
+    command_lines("system_profiler", &["SPDisplaysDataType"])
+        .iter()
+        .filter_map(|line| line.trim().strip_prefix("Chipset Model:"))
+        .map(|name| name.trim().to_string())
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn gpu_names() -> Vec<String> {
+    command_lines(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "(Get-CimInstance Win32_VideoController).Name",
+        ],
+    )
+    .into_iter()
+    .map(|name| name.trim().to_string())
+    .filter(|name| !name.is_empty())
+    .collect()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn gpu_names() -> Vec<String> {
+    command_lines("lspci", &[])
+        .iter()
+        .filter(|line| {
+            [
+                "VGA compatible controller:",
+                "3D controller:",
+                "Display controller:",
+            ]
+            .iter()
+            .any(|class| line.contains(class))
+        })
+        .filter_map(|line| {
+            line.split_once(": ")
+                .map(|(_, name)| name.trim().to_string())
+        })
+        .collect()
+}
+
+fn write_bundle(app: &AppHandle, path: &str, permissions: PermissionStatus) -> Result<(), String> {
+    let settings = get_settings(app);
+    let report = SystemReport {
+        app_version: env!("CARGO_PKG_VERSION"),
+        os: tauri_plugin_os::platform(),
+        os_version: tauri_plugin_os::version().to_string(),
+        arch: tauri_plugin_os::arch(),
+        gpus: gpu_names(),
+        selected_model: app
+            .state::<Arc<ModelManager>>()
+            .get_model_info(&settings.selected_model),
+        loaded_model: app.state::<Arc<TranscriptionManager>>().get_current_model(),
+        permissions,
+    };
+
+    let file = File::create(path).map_err(|e| format!("Failed to create '{}': {}", path, e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut add = |name: &str, contents: &[u8]| -> Result<(), String> {
+        zip.start_file(name, options)
+            .map_err(|e| e.to_string())
+            .and_then(|()| zip.write_all(contents).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Failed to add {} to the diagnostics: {}", name, e))
+    };
+
+    add(
+        "system.json",
+        serde_json::to_string_pretty(&report)
+            .map_err(|e| format!("Failed to serialize the system report: {}", e))?
+            .as_bytes(),
+    )?;
+    add(
+        "settings.json",
+        serde_json::to_string_pretty(&settings.sanitized())
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?
+            .as_bytes(),
+    )?;
+
+    let log_dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to get log directory: {}", e))?;
+    let mut logs: Vec<_> = fs::read_dir(&log_dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    logs.retain(|log| log.extension().is_some_and(|ext| ext == "log"));
+    logs.sort();
+    for log in logs {
+        let Some(name) = log
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+        else {
+            continue;
+        };
+        match fs::read(&log) {
+            Ok(contents) => add(
+                &format!("logs/{}", name),
+                error_reports::redact_log(app, &String::from_utf8_lossy(&contents)).as_bytes(),
+            )?,
+            Err(e) => warn!("Failed to read {} for diagnostics: {}", log.display(), e),
+        }
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+    Ok(())
+}
+
+/// Writes a zip for bug reports to `path`: recent logs, the settings without
+/// secrets, the model and GPUs, and permission states
+#[tauri::command]
+#[specta::specta]
+pub async fn export_diagnostics(app: AppHandle, path: String) -> Result<(), String> {
+    let permissions = get_permission_status().await;
+    tauri::async_runtime::spawn_blocking(move || {
+        write_bundle(&app, &path, permissions)?;
+        info!("Exported diagnostics to {}", path);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Failed to export diagnostics: {}", e))?
+}
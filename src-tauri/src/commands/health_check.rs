@@ -0,0 +1,204 @@
+//! Self-test of the dictation pipeline, for the support flow.
+//!
+//! Each stage reports on its own, so one failure doesn't hide the others.
+//! Handy doesn't ship a speech sample, so the transcription stage runs the
+//! model on one of its own sounds: it checks that the model runs, not what
+//! it hears.
+
+use crate::audio_toolkit::decode_audio_samples;
+use crate::commands::settings_validation::provider_issue;
+use crate::input::InjectorState;
+use crate::managers::audio::AudioRecordingManager;
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{get_settings, AppSettings};
+use log::info;
+use serde::Serialize;
+use specta::Type;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+const SAMPLE_CLIP: &str = "resources/pop_start.wav";
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStage {
+    AudioDevice,
+    ModelLoad,
+    Transcription,
+    PostProcess,
+    Paste,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthOutcome {
+    Passed,
+    Failed,
+    /// Not configured, or couldn't run because an earlier stage failed
+    Skipped,
+}
+
+#[derive(Serialize, Debug, Clone, Type)]
+pub struct HealthCheckResult {
+    pub stage: HealthStage,
+    pub outcome: HealthOutcome,
+    pub message: String,
+    pub duration_ms: u64,
+}
+
+/// How a stage went, or why it failed
+type Check = Result<(HealthOutcome, String), String>;
+
+fn stage_result(stage: HealthStage, started: Instant, check: Check) -> HealthCheckResult {
+    let (outcome, message) = check.unwrap_or_else(|e| (HealthOutcome::Failed, e));
+    HealthCheckResult {
+        stage,
+        outcome,
+        message,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+fn check_audio_device(app: &AppHandle) -> Check {
+    let audio = app.state::<Arc<AudioRecordingManager>>();
+    if audio.is_recording() {
+        return Ok((HealthOutcome::Skipped, "Handy is recording".to_string()));
+    }
+    if audio.is_microphone_open() {
+        return Ok((HealthOutcome::Passed, "The microphone is open".to_string()));
+    }
+    audio
+        .start_microphone_stream()
+        .map_err(|e| format!("Failed to open the microphone: {}", e))?;
+    audio.stop_microphone_stream();
+    Ok((HealthOutcome::Passed, "Opened the microphone".to_string()))
+}
+
+fn check_model_load(app: &AppHandle, settings: &AppSettings) -> Check {
+    if settings.selected_model.is_empty() {
+        return Err("No model is selected".to_string());
+    }
+    let tm = app.state::<Arc<TranscriptionManager>>();
+    if tm.is_model_loaded() && tm.get_current_model().as_ref() == Some(&settings.selected_model) {
+        return Ok((
+            HealthOutcome::Passed,
+            format!("{} is loaded", settings.selected_model),
+        ));
+    }
+    tm.load_model(&settings.selected_model)
+        .map_err(|e| format!("Failed to load {}: {}", settings.selected_model, e))?;
+    Ok((
+        HealthOutcome::Passed,
+        format!("Loaded {}", settings.selected_model),
+    ))
+}
+
+fn check_transcription(app: &AppHandle, settings: &AppSettings) -> Check {
+    let path = app
+        .path()
+        .resolve(SAMPLE_CLIP, tauri::path::BaseDirectory::Resource)
+        .map_err(|e| format!("Failed to find the sample clip: {}", e))?;
+    let data =
+        std::fs::read(&path).map_err(|e| format!("Failed to read the sample clip: {}", e))?;
+    let samples = decode_audio_samples(data, Some("wav"))
+        .map_err(|e| format!("Failed to decode the sample clip: {}", e))?;
+    app.state::<Arc<TranscriptionManager>>()
+        .transcribe(Arc::new(samples), settings)
+        .map_err(|e| format!("Transcription failed: {}", e))?;
+    Ok((
+        HealthOutcome::Passed,
+        "Transcribed the sample clip".to_string(),
+    ))
+}
+
+async fn check_post_process(settings: &AppSettings) -> Check {
+    if !settings.post_process_enabled {
+        return Ok((HealthOutcome::Skipped, "Post-processing is off".to_string()));
+    }
+    let provider = settings
+        .active_post_process_provider()
+        .ok_or_else(|| "No post-processing provider is selected".to_string())?;
+    match provider_issue(settings).await {
+        Some(issue) => Err(issue.message),
+        None => Ok((
+            HealthOutcome::Passed,
+            format!("{} is reachable", provider.label),
+        )),
+    }
+}
+
+async fn check_paste(app: &AppHandle, settings: &AppSettings) -> Check {
+    if app.try_state::<InjectorState>().is_none() {
+        return Err("Key injection couldn't start".to_string());
+    }
+
+    #[cfg(target_os = "macos")]
+    if !tauri_plugin_macos_permissions::check_accessibility_permission().await {
+        return Err("Handy needs Accessibility permission to paste".to_string());
+    }
+
+    #[cfg(target_os = "linux")]
+    crate::clipboard::check_wayland_backend(settings.paste_method, settings.typing_tool)?;
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = settings;
+
+    Ok((HealthOutcome::Passed, "Handy can paste".to_string()))
+}
+
+/// Runs every stage of the pipeline: opening the microphone, loading the
+/// model, transcribing a sample clip, reaching the post-processing provider
+/// and pasting
+#[tauri::command]
+#[specta::specta]
+pub async fn run_health_check(app: AppHandle) -> Result<Vec<HealthCheckResult>, String> {
+    let settings = get_settings(&app);
+
+    let blocking_app = app.clone();
+    let blocking_settings = settings.clone();
+    let mut results = tauri::async_runtime::spawn_blocking(move || {
+        let (app, settings) = (&blocking_app, &blocking_settings);
+
+        let started = Instant::now();
+        let audio = stage_result(HealthStage::AudioDevice, started, check_audio_device(app));
+
+        let started = Instant::now();
+        let model = stage_result(
+            HealthStage::ModelLoad,
+            started,
+            check_model_load(app, settings),
+        );
+
+        let started = Instant::now();
+        let transcription = match model.outcome {
+            HealthOutcome::Passed => check_transcription(app, settings),
+            _ => Ok((HealthOutcome::Skipped, "No model is loaded".to_string())),
+        };
+        let transcription = stage_result(HealthStage::Transcription, started, transcription);
+
+        vec![audio, model, transcription]
+    })
+    .await
+    .map_err(|e| format!("Health check failed: {}", e))?;
+
+    let started = Instant::now();
+    let post_process = check_post_process(&settings).await;
+    results.push(stage_result(
+        HealthStage::PostProcess,
+        started,
+        post_process,
+    ));
+
+    let started = Instant::now();
+    let paste = check_paste(&app, &settings).await;
+    results.push(stage_result(HealthStage::Paste, started, paste));
+
+    for result in &results {
+        info!(
+            "Health check {:?}: {:?} ({})",
+            result.stage, result.outcome, result.message
+        );
+    }
+    Ok(results)
+}
@@ -1,6 +1,13 @@
-use crate::managers::history::{HistoryEntry, HistoryManager};
+use crate::history_export::{self, ExportFormat};
+use crate::managers::analytics::{self, UsageReport};
+use crate::managers::history::{HistoryAppCount, HistoryEntry, HistoryManager, HistoryPage};
+use crate::managers::history_search::HistorySearchResult;
+use crate::managers::history_sync::SyncResult;
+use crate::managers::stats::DictationStats;
+use log::error;
 use std::sync::Arc;
-use tauri::{AppHandle, State};
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, Runtime, State, UriSchemeContext, UriSchemeResponder};
 
 #[tauri::command]
 #[specta::specta]
@@ -14,6 +21,76 @@ pub async fn get_history_entries(
         .map_err(|e| e.to_string())
 }
 
+/// Returns one page of history, newest first, optionally filtered by a
+/// search over the raw and post-processed text.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_page(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    offset: usize,
+    limit: usize,
+    search: Option<String>,
+    app: Option<String>,
+) -> Result<HistoryPage, String> {
+    history_manager
+        .get_history_page(offset, limit, search, app)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_apps(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<Vec<HistoryAppCount>, String> {
+    history_manager
+        .get_history_apps()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn search_history(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    query: String,
+    limit: usize,
+) -> Result<Vec<HistorySearchResult>, String> {
+    history_manager
+        .search_history(query, limit)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Pastes a history entry into the focused app, preferring its post-processed text.
+#[tauri::command]
+#[specta::specta]
+pub async fn paste_history_entry(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<(), String> {
+    let entry = history_manager
+        .get_entry_by_id(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("History entry {} not found", id))?;
+    let text = entry
+        .post_processed_text
+        .unwrap_or(entry.transcription_text);
+    crate::tray::paste_history_text(&app, text);
+    Ok(())
+}
+
+/// Pastes the most recent transcript again into the focused app.
+#[tauri::command]
+#[specta::specta]
+pub fn paste_last_transcript(app: AppHandle) -> Result<(), String> {
+    crate::tray::paste_last_transcript(&app)
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn toggle_history_entry_saved(
@@ -27,6 +104,34 @@ pub async fn toggle_history_entry_saved(
         .map_err(|e| e.to_string())
 }
 
+/// Pins (saves) or unpins a history entry. Pinned entries are kept regardless
+/// of the retention settings.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_history_entry_saved(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    saved: bool,
+) -> Result<(), String> {
+    history_manager
+        .set_saved_status(id, saved)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_saved_history_entries(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<Vec<HistoryEntry>, String> {
+    history_manager
+        .get_saved_entries()
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn get_audio_file_path(
@@ -40,6 +145,77 @@ pub async fn get_audio_file_path(
         .map(|s| s.to_string())
 }
 
+/// Serves `recording://localhost/<file name>` to the history player, so
+/// encrypted recordings are decrypted in memory rather than to a file.
+pub fn serve_recording<R: Runtime>(
+    ctx: UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app = ctx.app_handle().clone();
+    let file_name = request.uri().path().trim_start_matches('/').to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        let audio = match app.try_state::<Arc<HistoryManager>>() {
+            Some(history_manager) => history_manager
+                .read_recording(&file_name)
+                .map_err(|e| e.to_string()),
+            None => Err("History is not loaded yet".to_string()),
+        };
+        let response = match audio {
+            Ok(audio) => Response::builder()
+                .header(header::CONTENT_TYPE, "audio/wav")
+                .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+                .body(audio),
+            Err(e) => Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+                .body(e.into_bytes()),
+        };
+        match response {
+            Ok(response) => responder.respond(response),
+            Err(e) => error!("Failed to build recording response: {}", e),
+        }
+    });
+}
+
+async fn recording_audio(history_manager: &HistoryManager, id: i64) -> Result<Vec<u8>, String> {
+    let entry = history_manager
+        .get_entry_by_id(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("History entry {} not found", id))?;
+    history_manager
+        .read_recording(&entry.file_name)
+        .map_err(|e| e.to_string())
+}
+
+/// Plays back the audio kept for a history entry.
+#[tauri::command]
+#[specta::specta]
+pub async fn play_history_recording(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<(), String> {
+    let audio = recording_audio(&history_manager, id).await?;
+    crate::audio_feedback::play_recording(&app, audio);
+    Ok(())
+}
+
+/// Saves the audio kept for a history entry to `path` as a WAV file.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_history_recording(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    path: String,
+) -> Result<(), String> {
+    let audio = recording_audio(&history_manager, id).await?;
+    std::fs::write(&path, audio)
+        .map_err(|e| format!("Failed to export recording to '{}': {}", path, e))
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn delete_history_entry(
@@ -53,6 +229,96 @@ pub async fn delete_history_entry(
         .map_err(|e| e.to_string())
 }
 
+/// Writes history between the optional `start` and `end` Unix timestamps to
+/// `path` as "csv", "json" or "markdown". Returns the number of entries written.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_history(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    format: String,
+    path: String,
+    start: Option<i64>,
+    end: Option<i64>,
+) -> Result<usize, String> {
+    let format = ExportFormat::parse(&format)?;
+    let entries = history_manager
+        .get_entries_between(start, end)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let contents = history_export::export(&entries, format, &chrono::Local)?;
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+    Ok(entries.len())
+}
+
+/// Returns dictation statistics for the last `days` days, or all time.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_dictation_stats(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    days: Option<u32>,
+) -> Result<DictationStats, String> {
+    history_manager
+        .get_dictation_stats(days)
+        .map_err(|e| e.to_string())
+}
+
+/// Returns local usage analytics for the last `days` days, or all time.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_usage_report(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    days: Option<u32>,
+) -> Result<UsageReport, String> {
+    history_manager
+        .get_usage_report(days)
+        .map_err(|e| e.to_string())
+}
+
+/// Writes usage analytics with the app version and platform to `path` as JSON
+/// so it can be shared with a bug report.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_usage_report(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    path: String,
+    days: Option<u32>,
+) -> Result<(), String> {
+    let report = history_manager
+        .get_usage_report(days)
+        .map_err(|e| e.to_string())?;
+    let contents = analytics::shareable_json(&report).map_err(|e| e.to_string())?;
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write '{}': {}", path, e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_usage_analytics(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<(), String> {
+    history_manager
+        .clear_usage_analytics()
+        .map_err(|e| e.to_string())
+}
+
+/// Encrypts or decrypts the history database and recordings, and saves the setting.
+#[tauri::command]
+#[specta::specta]
+pub async fn change_history_encryption_setting(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    enabled: bool,
+) -> Result<(), String> {
+    history_manager
+        .set_encryption(enabled)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn update_history_limit(
@@ -71,6 +337,79 @@ pub async fn update_history_limit(
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_keep_recordings_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = crate::settings::get_settings(&app);
+    settings.keep_recordings = enabled;
+    crate::settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn update_recordings_size_limit(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    limit_mb: u32,
+) -> Result<(), String> {
+    let mut settings = crate::settings::get_settings(&app);
+    settings.recordings_size_limit_mb = limit_mb;
+    crate::settings::write_settings(&app, settings);
+
+    history_manager
+        .enforce_recordings_size_limit()
+        .map_err(|e| e.to_string())
+}
+
+/// Sets or clears the folder used to sync history between machines, then syncs.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_history_sync_dir(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    path: Option<String>,
+) -> Result<SyncResult, String> {
+    let mut settings = crate::settings::get_settings(&app);
+    settings.history_sync_dir = path.filter(|path| !path.trim().is_empty());
+    crate::settings::write_settings(&app, settings);
+
+    history_manager.sync().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn sync_history_now(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<SyncResult, String> {
+    history_manager.sync().map_err(|e| e.to_string())
+}
+
+/// Returns this machine's history key, for importing on the other machines
+/// that sync the same folder so they can merge its encrypted entries.
+#[tauri::command]
+#[specta::specta]
+pub fn export_history_key(_app: AppHandle) -> Result<String, String> {
+    crate::encryption::history_key(false)?
+        .map(|key| crate::encryption::export_key(&key))
+        .ok_or_else(|| "History encryption has never been turned on".to_string())
+}
+
+/// Remembers another machine's history key, then syncs to merge the entries
+/// that were waiting for it.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_history_key(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    key: String,
+) -> Result<SyncResult, String> {
+    let key = crate::encryption::parse_key(&key)?;
+    crate::encryption::add_peer_key(&key)?;
+    history_manager.sync().map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn update_recording_retention_period(
@@ -0,0 +1,17 @@
+use crate::meeting_detection;
+use crate::signal_handle;
+use tauri::AppHandle;
+
+/// Calendars meeting detection can be limited to
+#[tauri::command]
+#[specta::specta]
+pub async fn get_meeting_calendars(app: AppHandle) -> Result<Vec<String>, String> {
+    Ok(meeting_detection::calendar_names(&app).await)
+}
+
+/// Starts recording the meeting offered by a `meeting_started` event
+#[tauri::command]
+#[specta::specta]
+pub fn start_meeting_transcription(app: AppHandle) -> Result<(), String> {
+    signal_handle::start_recording(&app, false, "calendar")
+}
@@ -1,10 +1,29 @@
+pub mod api_server;
 pub mod audio;
+pub mod data_dirs;
+pub mod diagnostics;
+pub mod health_check;
 pub mod history;
+pub mod meetings;
 pub mod models;
+pub mod permissions;
+pub mod profiles;
+pub mod prompts;
+pub mod secrets;
+pub mod settings_backups;
+pub mod settings_bundle;
+pub mod settings_reset;
+pub mod settings_validation;
 pub mod transcription;
 
+use crate::data_dirs::{self, DataKind};
+use crate::managers::audio::AudioRecordingManager;
+use crate::policy::PolicyStatus;
 use crate::settings::{get_settings, write_settings, AppSettings, LogLevel};
+use crate::signal_handle;
 use crate::utils::cancel_current_operation;
+use log::debug;
+use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 use tauri_plugin_opener::OpenerExt;
 
@@ -14,6 +33,18 @@ pub fn cancel_operation(app: AppHandle) {
     cancel_current_operation(&app);
 }
 
+/// Stops the current recording and transcribes it, the same as pressing its
+/// shortcut again. Used by the overlay's stop control.
+#[tauri::command]
+#[specta::specta]
+pub fn stop_current_recording(app: AppHandle) {
+    let audio_manager = app.state::<Arc<AudioRecordingManager>>();
+    match audio_manager.active_binding() {
+        Some(binding_id) => signal_handle::send_transcription_input(&app, &binding_id, "overlay"),
+        None => debug!("Stop requested while not recording"),
+    }
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn get_app_dir_path(app: AppHandle) -> Result<String, String> {
@@ -31,6 +62,13 @@ pub fn get_app_settings(app: AppHandle) -> Result<AppSettings, String> {
     Ok(get_settings(&app))
 }
 
+/// What the managed policy enforces, including which settings it locks
+#[tauri::command]
+#[specta::specta]
+pub fn get_settings_policy() -> PolicyStatus {
+    crate::policy::status()
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn get_default_settings() -> Result<AppSettings, String> {
@@ -69,12 +107,7 @@ pub fn set_log_level(app: AppHandle, level: LogLevel) -> Result<(), String> {
 #[specta::specta]
 #[tauri::command]
 pub fn open_recordings_folder(app: AppHandle) -> Result<(), String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-    let recordings_dir = app_data_dir.join("recordings");
+    let recordings_dir = data_dirs::dir(&app, DataKind::Recordings)?;
 
     let path = recordings_dir.to_string_lossy().as_ref().to_string();
     app.opener()
@@ -131,23 +164,73 @@ pub fn check_apple_intelligence_available() -> bool {
     }
 }
 
+/// Name of the app dictation is locked to, if any.
+#[specta::specta]
+#[tauri::command]
+pub fn get_dictation_target() -> Option<String> {
+    crate::active_app::locked_target().map(|target| target.app.name)
+}
+
+/// Releases the dictation target lock.
+#[specta::specta]
+#[tauri::command]
+pub fn unlock_dictation_target(app: AppHandle) {
+    crate::tray::set_target_lock(&app, false);
+}
+
+/// Whether session incognito dictation is on.
+#[specta::specta]
+#[tauri::command]
+pub fn get_incognito_mode() -> bool {
+    crate::incognito::is_session_enabled()
+}
+
+/// Turns session incognito dictation on or off.
+#[specta::specta]
+#[tauri::command]
+pub fn set_incognito_mode(app: AppHandle, enabled: bool) {
+    crate::incognito::set_session_enabled(&app, enabled);
+}
+
+/// Whether Handy is paused, with every shortcut unregistered.
+#[specta::specta]
+#[tauri::command]
+pub fn get_paused() -> bool {
+    crate::pause::is_paused()
+}
+
+/// Pauses or resumes Handy's shortcuts.
+#[specta::specta]
+#[tauri::command]
+pub fn set_paused(app: AppHandle, paused: bool) {
+    crate::pause::set_paused(&app, paused);
+}
+
+/// Connected monitors the overlay can be pinned to.
+#[specta::specta]
+#[tauri::command]
+pub fn get_monitors(app: AppHandle) -> Vec<crate::overlay::MonitorInfo> {
+    crate::overlay::list_monitors(&app)
+}
+
 /// Try to initialize Enigo (keyboard/mouse simulation).
 /// On macOS, this will return an error if accessibility permissions are not granted.
 #[specta::specta]
 #[tauri::command]
 pub fn initialize_enigo(app: AppHandle) -> Result<(), String> {
-    use crate::input::EnigoState;
+    use crate::input::InjectorState;
 
     // Check if already initialized
-    if app.try_state::<EnigoState>().is_some() {
+    if app.try_state::<InjectorState>().is_some() {
         log::debug!("Enigo already initialized");
         return Ok(());
     }
 
     // Try to initialize
-    match EnigoState::new() {
-        Ok(enigo_state) => {
-            app.manage(enigo_state);
+    let settings = crate::settings::get_settings(&app);
+    match InjectorState::new(&settings.key_injection) {
+        Ok(injector_state) => {
+            app.manage(injector_state);
             log::info!("Enigo initialized successfully after permission grant");
             Ok(())
         }
@@ -33,6 +33,25 @@ pub async fn download_model(
         .map_err(|e| e.to_string())
 }
 
+/// Downloads a corrupted model again, see the `model_corrupted` event
+#[tauri::command]
+#[specta::specta]
+pub async fn repair_model(
+    model_manager: State<'_, Arc<ModelManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    model_id: String,
+) -> Result<(), String> {
+    if transcription_manager.get_current_model().as_deref() == Some(model_id.as_str()) {
+        transcription_manager
+            .unload_model()
+            .map_err(|e| format!("Failed to unload model: {}", e))?;
+    }
+    model_manager
+        .repair_model(&model_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn delete_model(
@@ -0,0 +1,326 @@
+//! Which system permissions Handy has, for the settings checklist.
+//!
+//! macOS gates the microphone, Accessibility (pasting and reading the
+//! focused field) and Input Monitoring (shortcuts). Windows only gates the
+//! microphone, through its privacy settings. Linux gates none of them.
+//!
+//! Shortcuts with the fn key are watched with a global event monitor, which
+//! on macOS needs both Accessibility and Input Monitoring, so they're checked
+//! when such a shortcut is registered.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::AppHandle;
+
+/// Whether a recording can start, checked before each one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MicrophoneAccess {
+    Granted,
+    /// The system is asking the user for the first time
+    Prompting,
+    Denied,
+}
+
+#[cfg(target_os = "macos")]
+pub const MICROPHONE_DENIED: &str =
+    "Turn on Handy in System Settings > Privacy & Security > Microphone, then try again.";
+#[cfg(target_os = "windows")]
+pub const MICROPHONE_DENIED: &str =
+    "Turn on microphone access for desktop apps in Settings > Privacy & security > Microphone, then try again.";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub const MICROPHONE_DENIED: &str = "Handy isn't allowed to use the microphone.";
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    /// This platform doesn't ask for it
+    NotRequired,
+}
+
+impl From<bool> for PermissionState {
+    fn from(granted: bool) -> Self {
+        if granted {
+            Self::Granted
+        } else {
+            Self::Denied
+        }
+    }
+}
+
+/// A permission with its own pane in the system's privacy settings
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemPermission {
+    Microphone,
+    Accessibility,
+    InputMonitoring,
+}
+
+impl SystemPermission {
+    /// The privacy settings pane that turns it on, where there is one
+    fn settings_url(self) -> Option<&'static str> {
+        #[cfg(target_os = "macos")]
+        {
+            Some(match self {
+                Self::Microphone => {
+                    "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone"
+                }
+                Self::Accessibility => {
+                    "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"
+                }
+                Self::InputMonitoring => {
+                    "x-apple.systempreferences:com.apple.preference.security?Privacy_ListenEvent"
+                }
+            })
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            (self == Self::Microphone).then_some("ms-settings:privacy-microphone")
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        {
+            None
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, Type)]
+pub struct PermissionStatus {
+    pub microphone: PermissionState,
+    pub accessibility: PermissionState,
+    pub input_monitoring: PermissionState,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_permission_status() -> PermissionStatus {
+    #[cfg(target_os = "macos")]
+    {
+        use tauri_plugin_macos_permissions::{
+            check_accessibility_permission, check_input_monitoring_permission,
+            check_microphone_permission,
+        };
+        PermissionStatus {
+            microphone: check_microphone_permission().await.into(),
+            accessibility: check_accessibility_permission().await.into(),
+            input_monitoring: check_input_monitoring_permission().await.into(),
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        PermissionStatus {
+            microphone: windows_microphone_permission(),
+            accessibility: PermissionState::NotRequired,
+            input_monitoring: PermissionState::NotRequired,
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        PermissionStatus {
+            microphone: PermissionState::NotRequired,
+            accessibility: PermissionState::NotRequired,
+            input_monitoring: PermissionState::NotRequired,
+        }
+    }
+}
+
+impl PermissionStatus {
+    /// What fn-key shortcuts need and Handy doesn't have
+    pub fn missing_for_fn_shortcuts(&self) -> Vec<SystemPermission> {
+        [
+            (SystemPermission::Accessibility, self.accessibility),
+            (SystemPermission::InputMonitoring, self.input_monitoring),
+        ]
+        .into_iter()
+        .filter(|(_, state)| *state == PermissionState::Denied)
+        .map(|(permission, _)| permission)
+        .collect()
+    }
+}
+
+/// Permissions Handy lacks for shortcuts with the fn key, empty when they
+/// will work
+#[tauri::command]
+#[specta::specta]
+pub async fn missing_fn_shortcut_permissions() -> Vec<SystemPermission> {
+    get_permission_status().await.missing_for_fn_shortcuts()
+}
+
+/// Opens the privacy settings pane where `permission` is turned on
+#[tauri::command]
+#[specta::specta]
+pub fn open_permission_settings(
+    app: AppHandle,
+    permission: SystemPermission,
+) -> Result<(), String> {
+    use tauri_plugin_opener::OpenerExt;
+    let url = permission
+        .settings_url()
+        .ok_or_else(|| "This platform has no settings for that permission".to_string())?;
+    app.opener()
+        .open_url(url, None::<String>)
+        .map_err(|e| format!("Failed to open privacy settings: {}", e))
+}
+
+/// Microphone access is off if either the system-wide switch or the one for
+/// desktop apps is. Both are on unless the user turned them off.
+#[cfg(target_os = "windows")]
+fn windows_microphone_permission() -> PermissionState {
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_SZ};
+
+    let denied = |key: PCWSTR| {
+        let mut value = [0u16; 16];
+        let mut size = std::mem::size_of_val(&value) as u32;
+        let err = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                key,
+                w!("Value"),
+                RRF_RT_REG_SZ,
+                None,
+                Some(value.as_mut_ptr().cast()),
+                Some(&mut size),
+            )
+        };
+        // The size includes the terminating null
+        let len = (size as usize / 2).saturating_sub(1).min(value.len());
+        err.is_ok() && String::from_utf16_lossy(&value[..len]) == "Deny"
+    };
+
+    let denied = denied(w!(
+        r"Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone"
+    )) || denied(w!(
+        r"Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone\NonPackaged"
+    ));
+    (!denied).into()
+}
+
+/// `AVCaptureDevice`'s authorization for audio, which unlike the permissions
+/// plugin tells a user who hasn't been asked from one who said no
+#[cfg(target_os = "macos")]
+mod av_capture {
+    use std::ffi::{c_char, c_void};
+
+    pub const NOT_DETERMINED: isize = 0;
+    pub const AUTHORIZED: isize = 3;
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    extern "C" {
+        static AVMediaTypeAudio: *const c_void;
+    }
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+    }
+
+    /// `objc_msgSend` as `authorizationStatusForMediaType:` is called
+    type StatusForMediaType =
+        unsafe extern "C" fn(*mut c_void, *mut c_void, *const c_void) -> isize;
+
+    /// `[AVCaptureDevice authorizationStatusForMediaType:AVMediaTypeAudio]`
+    pub fn audio_authorization_status() -> isize {
+        unsafe {
+            let class = objc_getClass(b"AVCaptureDevice\0".as_ptr().cast());
+            let selector = sel_registerName(b"authorizationStatusForMediaType:\0".as_ptr().cast());
+            let send =
+                std::mem::transmute::<unsafe extern "C" fn(), StatusForMediaType>(objc_msgSend);
+            send(class, selector, AVMediaTypeAudio)
+        }
+    }
+}
+
+/// Checks microphone access before recording. If the user hasn't been asked
+/// yet, the system prompt is shown and this recording is skipped, since it
+/// would only capture silence while the prompt is up.
+pub fn microphone_access() -> MicrophoneAccess {
+    #[cfg(target_os = "macos")]
+    {
+        match av_capture::audio_authorization_status() {
+            av_capture::AUTHORIZED => MicrophoneAccess::Granted,
+            av_capture::NOT_DETERMINED => {
+                tauri::async_runtime::spawn(async {
+                    if let Err(e) =
+                        tauri_plugin_macos_permissions::request_microphone_permission().await
+                    {
+                        log::warn!("Failed to ask for microphone access: {}", e);
+                    }
+                });
+                MicrophoneAccess::Prompting
+            }
+            // Denied, or restricted by a device policy
+            _ => MicrophoneAccess::Denied,
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        match windows_microphone_permission() {
+            PermissionState::Denied => MicrophoneAccess::Denied,
+            _ => MicrophoneAccess::Granted,
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        MicrophoneAccess::Granted
+    }
+}
+
+/// Asks for microphone access, or opens the privacy settings where it's
+/// turned back on
+#[tauri::command]
+#[specta::specta]
+pub async fn request_microphone_permission(app: AppHandle) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let _ = app;
+        tauri_plugin_macos_permissions::request_microphone_permission().await
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use tauri_plugin_opener::OpenerExt;
+        app.opener()
+            .open_url("ms-settings:privacy-microphone", None::<String>)
+            .map_err(|e| format!("Failed to open privacy settings: {}", e))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Ok(())
+    }
+}
+
+/// Shows the system prompt that leads to the Accessibility settings
+#[tauri::command]
+#[specta::specta]
+pub async fn request_accessibility_permission() {
+    #[cfg(target_os = "macos")]
+    tauri_plugin_macos_permissions::request_accessibility_permission().await;
+}
+
+/// Shows the system prompt that leads to the Input Monitoring settings
+#[tauri::command]
+#[specta::specta]
+pub async fn request_input_monitoring_permission() -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        tauri_plugin_macos_permissions::request_input_monitoring_permission().await
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use crate::profiles;
+use crate::settings::{get_settings, SettingsProfile};
+use tauri::AppHandle;
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_profiles(app: AppHandle) -> Vec<SettingsProfile> {
+    get_settings(&app).profiles
+}
+
+/// Saves the current bindings, model, prompts and output options as a new
+/// profile, which becomes the active one.
+#[tauri::command]
+#[specta::specta]
+pub fn create_profile(app: AppHandle, name: String) -> Result<SettingsProfile, String> {
+    profiles::create_profile(&app, &name)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn switch_profile(app: AppHandle, id: String) -> Result<(), String> {
+    profiles::switch_profile(&app, &id)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_profile(app: AppHandle, id: String) -> Result<(), String> {
+    profiles::delete_profile(&app, &id)
+}
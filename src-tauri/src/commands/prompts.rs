@@ -0,0 +1,246 @@
+use crate::settings::{get_settings, write_settings, AppSettings, LLMPrompt};
+use log::info;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use tauri::AppHandle;
+
+/// Current version of the prompt library file format
+const PROMPT_LIBRARY_VERSION: u32 = 1;
+
+/// Shareable collection of post-processing prompts and spoken command rules
+#[derive(Serialize, Deserialize, Debug, Clone, Type)]
+pub struct PromptLibrary {
+    #[serde(default = "default_library_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub prompts: Vec<LLMPrompt>,
+    #[serde(default)]
+    pub spoken_commands: HashMap<String, String>,
+}
+
+fn default_library_version() -> u32 {
+    PROMPT_LIBRARY_VERSION
+}
+
+/// How to handle imported entries that collide with existing ones
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflictStrategy {
+    /// Keep the existing entry and ignore the imported one
+    Skip,
+    /// Replace the existing entry with the imported one
+    Overwrite,
+    /// Keep both, renaming the imported prompt
+    KeepBoth,
+}
+
+#[derive(Serialize, Debug, Default, PartialEq, Eq, Type)]
+pub struct PromptImportResult {
+    pub added: usize,
+    pub updated: usize,
+    pub skipped: usize,
+}
+
+/// A prompt conflicts with an existing one if it shares the id or the (case-insensitive) name
+fn find_conflict(prompts: &[LLMPrompt], incoming: &LLMPrompt) -> Option<usize> {
+    prompts.iter().position(|p| {
+        p.id == incoming.id || p.name.trim().eq_ignore_ascii_case(incoming.name.trim())
+    })
+}
+
+fn merge_prompt_library(
+    settings: &mut AppSettings,
+    library: PromptLibrary,
+    strategy: ImportConflictStrategy,
+    id_seed: i64,
+) -> PromptImportResult {
+    let mut result = PromptImportResult::default();
+
+    for (index, mut prompt) in library.prompts.into_iter().enumerate() {
+        match find_conflict(&settings.post_process_prompts, &prompt) {
+            None => {
+                settings.post_process_prompts.push(prompt);
+                result.added += 1;
+            }
+            Some(existing_index) => match strategy {
+                ImportConflictStrategy::Skip => result.skipped += 1,
+                ImportConflictStrategy::Overwrite => {
+                    let existing = &mut settings.post_process_prompts[existing_index];
+                    // Keep the local id so the selected prompt stays valid
+                    prompt.id = existing.id.clone();
+                    *existing = prompt;
+                    result.updated += 1;
+                }
+                ImportConflictStrategy::KeepBoth => {
+                    prompt.id = format!("prompt_{}_{}", id_seed, index);
+                    prompt.name = format!("{} (imported)", prompt.name);
+                    settings.post_process_prompts.push(prompt);
+                    result.added += 1;
+                }
+            },
+        }
+    }
+
+    for (phrase, replacement) in library.spoken_commands {
+        let phrase = phrase.trim().to_lowercase();
+        if phrase.is_empty() {
+            continue;
+        }
+        match settings.spoken_commands.get(&phrase) {
+            None => {
+                settings.spoken_commands.insert(phrase, replacement);
+                result.added += 1;
+            }
+            Some(existing) if *existing == replacement => {}
+            Some(_) if strategy == ImportConflictStrategy::Overwrite => {
+                settings.spoken_commands.insert(phrase, replacement);
+                result.updated += 1;
+            }
+            // A phrase can only map to one replacement, so "keep both" keeps the local one
+            Some(_) => result.skipped += 1,
+        }
+    }
+
+    result
+}
+
+/// Export all post-processing prompts and spoken command rules to a JSON file.
+#[tauri::command]
+#[specta::specta]
+pub fn export_prompt_library(app: AppHandle, path: String) -> Result<(), String> {
+    let settings = get_settings(&app);
+    let library = PromptLibrary {
+        version: PROMPT_LIBRARY_VERSION,
+        prompts: settings.post_process_prompts,
+        spoken_commands: settings.spoken_commands,
+    };
+
+    let json = serde_json::to_string_pretty(&library)
+        .map_err(|e| format!("Failed to serialize prompt library: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+
+    info!(
+        "Exported {} prompts and {} spoken commands to {}",
+        library.prompts.len(),
+        library.spoken_commands.len(),
+        path
+    );
+    Ok(())
+}
+
+/// Import prompts and spoken command rules from a JSON file, merging them
+/// into the current settings using the given conflict strategy.
+#[tauri::command]
+#[specta::specta]
+pub fn import_prompt_library(
+    app: AppHandle,
+    path: String,
+    strategy: ImportConflictStrategy,
+) -> Result<PromptImportResult, String> {
+    let contents =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+    let library: PromptLibrary = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid prompt library file: {}", e))?;
+
+    if library.version > PROMPT_LIBRARY_VERSION {
+        return Err(format!(
+            "Prompt library version {} is newer than supported version {}",
+            library.version, PROMPT_LIBRARY_VERSION
+        ));
+    }
+
+    let mut settings = get_settings(&app);
+    let result = merge_prompt_library(
+        &mut settings,
+        library,
+        strategy,
+        chrono::Utc::now().timestamp_millis(),
+    );
+    write_settings(&app, settings);
+
+    info!(
+        "Imported prompt library from {}: {} added, {} updated, {} skipped",
+        path, result.added, result.updated, result.skipped
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::get_default_settings;
+
+    fn prompt(id: &str, name: &str, text: &str) -> LLMPrompt {
+        LLMPrompt {
+            id: id.to_string(),
+            name: name.to_string(),
+            prompt: text.to_string(),
+            system_prompt: None,
+            temperature: None,
+            max_tokens: None,
+            top_p: None,
+        }
+    }
+
+    fn library(prompts: Vec<LLMPrompt>) -> PromptLibrary {
+        PromptLibrary {
+            version: PROMPT_LIBRARY_VERSION,
+            prompts,
+            spoken_commands: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn new_prompts_are_added() {
+        let mut settings = get_default_settings();
+        let before = settings.post_process_prompts.len();
+        let result = merge_prompt_library(
+            &mut settings,
+            library(vec![prompt("prompt_1", "Brainstorm", "ideas")]),
+            ImportConflictStrategy::Skip,
+            42,
+        );
+        assert_eq!(result.added, 1);
+        assert_eq!(settings.post_process_prompts.len(), before + 1);
+    }
+
+    #[test]
+    fn conflicting_prompt_respects_strategy() {
+        let existing = get_default_settings().post_process_prompts[0].clone();
+        let incoming = prompt(&existing.id, &existing.name, "replacement");
+
+        let mut settings = get_default_settings();
+        let result = merge_prompt_library(
+            &mut settings,
+            library(vec![incoming.clone()]),
+            ImportConflictStrategy::Skip,
+            42,
+        );
+        assert_eq!(result.skipped, 1);
+        assert_eq!(settings.post_process_prompts[0].prompt, existing.prompt);
+
+        let mut settings = get_default_settings();
+        let result = merge_prompt_library(
+            &mut settings,
+            library(vec![incoming.clone()]),
+            ImportConflictStrategy::Overwrite,
+            42,
+        );
+        assert_eq!(result.updated, 1);
+        assert_eq!(settings.post_process_prompts[0].prompt, "replacement");
+
+        let mut settings = get_default_settings();
+        let result = merge_prompt_library(
+            &mut settings,
+            library(vec![incoming]),
+            ImportConflictStrategy::KeepBoth,
+            42,
+        );
+        assert_eq!(result.added, 1);
+        let imported = settings.post_process_prompts.last().unwrap();
+        assert_eq!(imported.id, "prompt_42_0");
+        assert!(imported.name.ends_with("(imported)"));
+    }
+}
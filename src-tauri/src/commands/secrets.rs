@@ -0,0 +1,47 @@
+use crate::secrets::{self, SecretKind};
+use crate::settings::get_settings;
+use crate::shortcut;
+use tauri::AppHandle;
+
+/// Stores a credential. Values are never sent back to the frontend; use
+/// `has_secret` to check whether one is set.
+#[tauri::command]
+#[specta::specta]
+pub fn set_secret(
+    app: AppHandle,
+    kind: SecretKind,
+    id: String,
+    value: String,
+) -> Result<(), String> {
+    match kind {
+        // API keys go through settings, which keep them in the settings file
+        // if the credential store is unavailable
+        SecretKind::PostProcessApiKey => {
+            shortcut::change_post_process_api_key_setting(app, id, value)
+        }
+        _ => secrets::set_secret(&secrets::secret_name(kind, &id), &value),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn has_secret(app: AppHandle, kind: SecretKind, id: String) -> bool {
+    match kind {
+        SecretKind::PostProcessApiKey => get_settings(&app)
+            .post_process_api_keys
+            .get(&id)
+            .is_some_and(|api_key| !api_key.is_empty()),
+        _ => secrets::has_secret(&secrets::secret_name(kind, &id)),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_secret(app: AppHandle, kind: SecretKind, id: String) -> Result<(), String> {
+    match kind {
+        SecretKind::PostProcessApiKey => {
+            shortcut::change_post_process_api_key_setting(app, id, String::new())
+        }
+        _ => secrets::delete_secret(&secrets::secret_name(kind, &id)),
+    }
+}
@@ -0,0 +1,18 @@
+use crate::settings::AppSettings;
+use crate::settings_backups::{self, SettingsBackup};
+use tauri::AppHandle;
+
+/// Automatic backups of the settings file, newest first
+#[tauri::command]
+#[specta::specta]
+pub fn list_settings_backups(app: AppHandle) -> Vec<SettingsBackup> {
+    settings_backups::list_backups(&app)
+}
+
+/// Replaces the settings with a backup from `list_settings_backups` and
+/// returns the settings now in use
+#[tauri::command]
+#[specta::specta]
+pub fn restore_settings_backup(app: AppHandle, file_name: String) -> Result<AppSettings, String> {
+    settings_backups::restore_backup(&app, &file_name)
+}
@@ -0,0 +1,208 @@
+use crate::overlay::{MAX_OVERLAY_SCALE, MIN_OVERLAY_SCALE};
+use crate::pause;
+use crate::settings::{get_settings, write_settings, AppSettings};
+use crate::settings_migration::{self, CURRENT_SETTINGS_VERSION};
+use crate::shortcut;
+use log::info;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use tauri::AppHandle;
+
+/// Current version of the settings bundle format
+const SETTINGS_BUNDLE_SCHEMA: u32 = 1;
+
+/// Every setting in one file, for moving to another machine or keeping a
+/// backup. API keys and calendar feed addresses are left out.
+#[derive(Serialize, Deserialize, Debug, Clone, Type)]
+pub struct SettingsBundle {
+    pub schema_version: u32,
+    /// Handy version that wrote the bundle
+    pub app_version: String,
+    pub exported_at: i64,
+    pub settings: AppSettings,
+}
+
+fn create_bundle(settings: &AppSettings, exported_at: i64) -> SettingsBundle {
+    let mut settings = settings.clone();
+    for api_key in settings.post_process_api_keys.values_mut() {
+        api_key.clear();
+    }
+    // Private calendar feed addresses work like passwords
+    settings.meeting_detection.ics_urls.clear();
+    SettingsBundle {
+        schema_version: SETTINGS_BUNDLE_SCHEMA,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at,
+        settings,
+    }
+}
+
+/// Parses a bundle file, first running the settings migrations an older
+/// bundle hasn't had, the same as for settings loaded at startup
+fn parse_bundle(contents: &str) -> Result<SettingsBundle, String> {
+    let mut value: serde_json::Value =
+        serde_json::from_str(contents).map_err(|e| format!("Invalid settings file: {}", e))?;
+    if let Some(settings) = value.get_mut("settings") {
+        settings_migration::migrate(settings);
+    }
+    serde_json::from_value(value).map_err(|e| format!("Invalid settings file: {}", e))
+}
+
+/// Checks an imported bundle and returns the settings to use. API keys,
+/// calendar feeds and settings that only make sense on this machine are kept
+/// from `current`.
+fn settings_from_bundle(
+    current: &AppSettings,
+    bundle: SettingsBundle,
+) -> Result<AppSettings, String> {
+    if bundle.schema_version == 0 || bundle.schema_version > SETTINGS_BUNDLE_SCHEMA {
+        return Err(format!(
+            "Settings bundle schema {} is not supported (expected up to {})",
+            bundle.schema_version, SETTINGS_BUNDLE_SCHEMA
+        ));
+    }
+
+    // Older bundles were migrated in `parse_bundle`; settings from a newer
+    // build are written as this build's version
+    let mut settings = bundle.settings;
+    settings.version = CURRENT_SETTINGS_VERSION;
+    settings.keep_machine_settings(current);
+
+    for (id, binding) in &settings.bindings {
+        if shortcut::should_register_binding(&settings, id) {
+            shortcut::validate_shortcut_for_implementation(
+                &binding.current_binding,
+                settings.keyboard_implementation,
+            )
+            .map_err(|e| format!("Invalid shortcut for '{}': {}", id, e))?;
+        }
+    }
+    settings.overlay_appearance = settings.overlay_appearance.validated()?;
+    if !(MIN_OVERLAY_SCALE..=MAX_OVERLAY_SCALE).contains(&settings.overlay_scale) {
+        return Err(format!(
+            "Overlay scale must be between {} and {}",
+            MIN_OVERLAY_SCALE, MAX_OVERLAY_SCALE
+        ));
+    }
+    if let Some(id) = &settings.post_process_selected_prompt_id {
+        if !settings.post_process_prompts.iter().any(|p| &p.id == id) {
+            settings.post_process_selected_prompt_id = None;
+        }
+    }
+
+    Ok(settings)
+}
+
+/// Export all settings, bindings, prompts and replacement rules, without API
+/// keys or calendar feeds, to a JSON file.
+#[tauri::command]
+#[specta::specta]
+pub fn export_settings(app: AppHandle, path: String) -> Result<(), String> {
+    let bundle = create_bundle(&get_settings(&app), chrono::Utc::now().timestamp());
+
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+
+    info!("Exported settings to {}", path);
+    Ok(())
+}
+
+/// Replace the settings with a bundle from `export_settings`. Nothing is
+/// changed unless the whole bundle is valid.
+#[tauri::command]
+#[specta::specta]
+pub fn import_settings(app: AppHandle, path: String) -> Result<(), String> {
+    let contents =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+    let bundle = parse_bundle(&contents)?;
+    let settings = settings_from_bundle(&get_settings(&app), bundle)?;
+
+    // Shortcuts are swapped together so the old and new bindings never mix.
+    // While paused they're registered on resume instead.
+    let paused = pause::is_paused();
+    if !paused {
+        shortcut::unregister_all(&app);
+    }
+    write_settings(&app, settings);
+    if !paused {
+        shortcut::register_all(&app);
+    }
+
+    crate::utils::update_overlay_position(&app);
+    crate::dictation::refresh_tray_menu(&app);
+
+    info!("Imported settings from {}", path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::get_default_settings;
+
+    #[test]
+    fn export_leaves_out_api_keys() {
+        let mut settings = get_default_settings();
+        settings
+            .post_process_api_keys
+            .insert("openai".to_string(), "sk-secret".to_string());
+        settings
+            .meeting_detection
+            .ics_urls
+            .push("https://calendar.example.com/private/feed.ics".to_string());
+
+        let bundle = create_bundle(&settings, 0);
+        assert!(bundle
+            .settings
+            .post_process_api_keys
+            .values()
+            .all(|key| key.is_empty()));
+        assert!(bundle.settings.meeting_detection.ics_urls.is_empty());
+    }
+
+    #[test]
+    fn import_keeps_local_secrets_and_checks_schema() {
+        let mut current = get_default_settings();
+        current
+            .post_process_api_keys
+            .insert("openai".to_string(), "sk-local".to_string());
+        current.history_sync_device_id = Some("laptop".to_string());
+        current
+            .meeting_detection
+            .ics_urls
+            .push("https://calendar.example.com/local.ics".to_string());
+
+        let mut exported = get_default_settings();
+        exported.history_sync_device_id = Some("desktop".to_string());
+        exported.push_to_talk = false;
+        let bundle = create_bundle(&exported, 0);
+
+        let imported = settings_from_bundle(&current, bundle.clone()).unwrap();
+        assert!(!imported.push_to_talk);
+        assert_eq!(imported.post_process_api_keys["openai"], "sk-local");
+        assert_eq!(imported.history_sync_device_id.as_deref(), Some("laptop"));
+        assert_eq!(
+            imported.meeting_detection.ics_urls,
+            current.meeting_detection.ics_urls
+        );
+
+        let newer = SettingsBundle {
+            schema_version: SETTINGS_BUNDLE_SCHEMA + 1,
+            ..bundle
+        };
+        assert!(settings_from_bundle(&current, newer).is_err());
+    }
+
+    #[test]
+    fn import_migrates_older_settings() {
+        let mut bundle = serde_json::to_value(create_bundle(&get_default_settings(), 0)).unwrap();
+        bundle["settings"]["version"] = serde_json::json!(1);
+        bundle["settings"]["capture_window_titles"] = serde_json::json!(true);
+
+        let bundle = parse_bundle(&bundle.to_string()).unwrap();
+        assert_eq!(bundle.settings.version, CURRENT_SETTINGS_VERSION);
+        assert!(!bundle.settings.capture_window_titles);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::managers::audio::{AudioRecordingManager, MicrophoneMode};
+use crate::pause;
+use crate::settings::{get_default_settings, get_settings, write_settings, AppSettings};
+use crate::settings_watcher;
+use crate::shortcut;
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+/// How long a token from `request_full_settings_reset` stays valid
+const FULL_RESET_TOKEN_TTL: Duration = Duration::from_secs(60);
+
+static FULL_RESET_TOKEN: Lazy<Mutex<Option<(String, Instant)>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingsCategory {
+    Bindings,
+    Audio,
+    PostProcessing,
+    Overlay,
+    /// Every setting. Needs a token from `request_full_settings_reset`.
+    All,
+}
+
+/// Puts the settings in `category` back to their defaults. API keys are kept
+/// when post-processing is reset, and a full reset keeps the settings that
+/// belong to this machine.
+fn reset_category(settings: &mut AppSettings, category: SettingsCategory) {
+    let defaults = get_default_settings();
+    match category {
+        SettingsCategory::Bindings => {
+            settings.bindings = defaults.bindings;
+            settings.push_to_talk = defaults.push_to_talk;
+        }
+        SettingsCategory::Audio => {
+            settings.audio_feedback = defaults.audio_feedback;
+            settings.audio_feedback_volume = defaults.audio_feedback_volume;
+            settings.sound_theme = defaults.sound_theme;
+            settings.always_on_microphone = defaults.always_on_microphone;
+            settings.selected_microphone = defaults.selected_microphone;
+            settings.clamshell_microphone = defaults.clamshell_microphone;
+            settings.selected_output_device = defaults.selected_output_device;
+            settings.mute_while_recording = defaults.mute_while_recording;
+            settings.max_recording_duration_secs = defaults.max_recording_duration_secs;
+        }
+        SettingsCategory::PostProcessing => {
+            settings.post_process_enabled = defaults.post_process_enabled;
+            settings.post_process_provider_id = defaults.post_process_provider_id;
+            settings.post_process_providers = defaults.post_process_providers;
+            settings.post_process_models = defaults.post_process_models;
+            settings.post_process_prompts = defaults.post_process_prompts;
+            settings.post_process_selected_prompt_id = defaults.post_process_selected_prompt_id;
+            settings.post_process_system_prompt = defaults.post_process_system_prompt;
+            settings.openrouter_routing = defaults.openrouter_routing;
+        }
+        SettingsCategory::Overlay => {
+            settings.overlay_position = defaults.overlay_position;
+            settings.overlay_appearance = defaults.overlay_appearance;
+            settings.overlay_scale = defaults.overlay_scale;
+            settings.overlay_monitor = defaults.overlay_monitor;
+        }
+        SettingsCategory::All => {
+            let current = std::mem::replace(settings, defaults);
+            settings.keep_machine_settings(&current);
+        }
+    }
+}
+
+/// Uses up the pending full reset token if `confirmation` matches it
+fn confirm_full_reset(confirmation: Option<&str>) -> Result<(), String> {
+    let mut pending = FULL_RESET_TOKEN.lock().unwrap_or_else(|e| e.into_inner());
+    match (pending.take(), confirmation) {
+        (Some((token, issued)), Some(confirmation))
+            if token == confirmation && issued.elapsed() < FULL_RESET_TOKEN_TTL =>
+        {
+            Ok(())
+        }
+        _ => Err("Resetting all settings needs a valid confirmation token".to_string()),
+    }
+}
+
+/// Starts a full reset. The returned token confirms it in `reset_settings`
+/// within a minute, and can only be used once.
+#[tauri::command]
+#[specta::specta]
+pub fn request_full_settings_reset() -> String {
+    let token = format!(
+        "{:x}",
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    );
+    *FULL_RESET_TOKEN.lock().unwrap_or_else(|e| e.into_inner()) =
+        Some((token.clone(), Instant::now()));
+    token
+}
+
+/// Resets one category of settings to its defaults, or all of them with a
+/// confirmation token, and returns the settings now in use.
+#[tauri::command]
+#[specta::specta]
+pub fn reset_settings(
+    app: AppHandle,
+    category: SettingsCategory,
+    confirmation: Option<String>,
+) -> Result<AppSettings, String> {
+    if category == SettingsCategory::All {
+        confirm_full_reset(confirmation.as_deref())?;
+    }
+
+    let old = get_settings(&app);
+    let mut settings = old.clone();
+    reset_category(&mut settings, category);
+
+    // Which bindings are registered depends on more than the bindings, so
+    // they're swapped for every category. While paused they're registered on
+    // resume instead.
+    let paused = pause::is_paused();
+    if !paused {
+        shortcut::unregister_all(&app);
+    }
+    write_settings(&app, settings);
+    if !paused {
+        shortcut::register_all(&app);
+    }
+
+    let new = get_settings(&app);
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    if old.always_on_microphone != new.always_on_microphone {
+        let mode = if new.always_on_microphone {
+            MicrophoneMode::AlwaysOn
+        } else {
+            MicrophoneMode::OnDemand
+        };
+        if let Err(e) = rm.update_mode(mode) {
+            warn!("Failed to update microphone mode: {}", e);
+        }
+    }
+    if old.selected_microphone != new.selected_microphone {
+        if let Err(e) = rm.update_selected_device() {
+            warn!("Failed to update selected device: {}", e);
+        }
+    }
+    settings_watcher::apply_changes(&app, &old, &new);
+
+    info!("Reset {:?} settings to defaults", category);
+    Ok(new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resets_only_the_chosen_category() {
+        let mut settings = get_default_settings();
+        settings.overlay_scale = 1.5;
+        settings.push_to_talk = !settings.push_to_talk;
+        settings
+            .post_process_api_keys
+            .insert("openai".to_string(), "sk-local".to_string());
+        settings.post_process_enabled = !settings.post_process_enabled;
+
+        reset_category(&mut settings, SettingsCategory::Overlay);
+        assert_eq!(settings.overlay_scale, get_default_settings().overlay_scale);
+        assert_ne!(settings.push_to_talk, get_default_settings().push_to_talk);
+
+        reset_category(&mut settings, SettingsCategory::PostProcessing);
+        assert_eq!(
+            settings.post_process_enabled,
+            get_default_settings().post_process_enabled
+        );
+        assert_eq!(settings.post_process_api_keys["openai"], "sk-local");
+    }
+
+    #[test]
+    fn full_reset_keeps_machine_settings() {
+        let mut settings = get_default_settings();
+        settings.overlay_scale = 1.5;
+        settings.encrypt_history = true;
+        settings.history_sync_device_id = Some("laptop".to_string());
+        settings
+            .post_process_api_keys
+            .insert("openai".to_string(), "sk-local".to_string());
+        settings.data_directories.models = Some("/mnt/models".to_string());
+
+        reset_category(&mut settings, SettingsCategory::All);
+        assert_eq!(settings.overlay_scale, get_default_settings().overlay_scale);
+        assert!(settings.encrypt_history);
+        assert_eq!(settings.history_sync_device_id.as_deref(), Some("laptop"));
+        assert_eq!(settings.post_process_api_keys["openai"], "sk-local");
+        assert_eq!(
+            settings.data_directories.models.as_deref(),
+            Some("/mnt/models")
+        );
+    }
+
+    #[test]
+    fn full_reset_token_is_single_use() {
+        assert!(confirm_full_reset(None).is_err());
+        let token = request_full_settings_reset();
+        assert!(confirm_full_reset(Some("wrong")).is_err());
+        // A wrong guess also uses up the token
+        assert!(confirm_full_reset(Some(&token)).is_err());
+
+        let token = request_full_settings_reset();
+        assert!(confirm_full_reset(Some(&token)).is_ok());
+        assert!(confirm_full_reset(Some(&token)).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::commands::audio::custom_sound_exists;
+use crate::managers::model::ModelManager;
+use crate::settings::{get_settings, AppSettings, SoundTheme, APPLE_INTELLIGENCE_PROVIDER_ID};
+use crate::shortcut;
+use serde::Serialize;
+use specta::Type;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// How long a post-processing provider gets to answer before it counts as
+/// unreachable
+const PROVIDER_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingsIssueKind {
+    InvalidShortcut,
+    MissingModel,
+    UnreachableProvider,
+    MissingSound,
+}
+
+/// A setting that can't work as configured
+#[derive(Serialize, Debug, Clone, Type)]
+pub struct SettingsIssue {
+    pub kind: SettingsIssueKind,
+    /// The setting at fault, e.g. `bindings.transcribe` or `selected_model`
+    pub setting: String,
+    pub message: String,
+}
+
+fn shortcut_issues(settings: &AppSettings) -> Vec<SettingsIssue> {
+    let mut ids: Vec<&String> = settings.bindings.keys().collect();
+    ids.sort();
+    ids.into_iter()
+        .filter(|id| shortcut::should_register_binding(settings, id))
+        .filter_map(|id| {
+            let binding = &settings.bindings[id];
+            shortcut::validate_shortcut_for_implementation(
+                &binding.current_binding,
+                settings.keyboard_implementation,
+            )
+            .err()
+            .map(|e| SettingsIssue {
+                kind: SettingsIssueKind::InvalidShortcut,
+                setting: format!("bindings.{}", id),
+                message: format!("'{}': {}", binding.current_binding, e),
+            })
+        })
+        .collect()
+}
+
+fn model_issue(app: &AppHandle, settings: &AppSettings) -> Option<SettingsIssue> {
+    // No model is selected until onboarding finishes
+    if settings.selected_model.is_empty() {
+        return None;
+    }
+    let message = match app
+        .state::<Arc<ModelManager>>()
+        .get_model_info(&settings.selected_model)
+    {
+        Some(model) if model.is_downloaded => return None,
+        Some(model) => format!("{} is not downloaded", model.name),
+        None => format!("Unknown model '{}'", settings.selected_model),
+    };
+    Some(SettingsIssue {
+        kind: SettingsIssueKind::MissingModel,
+        setting: "selected_model".to_string(),
+        message,
+    })
+}
+
+/// Any HTTP response counts as reachable; only failing to connect doesn't
+pub(crate) async fn provider_issue(settings: &AppSettings) -> Option<SettingsIssue> {
+    if !settings.post_process_enabled {
+        return None;
+    }
+    let provider = settings.active_post_process_provider()?;
+    if provider.id == APPLE_INTELLIGENCE_PROVIDER_ID {
+        return None;
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(PROVIDER_CHECK_TIMEOUT)
+        .build()
+        .ok()?;
+    let error = client.get(&provider.base_url).send().await.err()?;
+    Some(SettingsIssue {
+        kind: SettingsIssueKind::UnreachableProvider,
+        setting: "post_process_provider_id".to_string(),
+        message: format!("{} at {}: {}", provider.label, provider.base_url, error),
+    })
+}
+
+fn sound_issues(app: &AppHandle, settings: &AppSettings) -> Vec<SettingsIssue> {
+    if !settings.audio_feedback || settings.sound_theme != SoundTheme::Custom {
+        return Vec::new();
+    }
+    ["start", "stop"]
+        .into_iter()
+        .filter(|sound_type| !custom_sound_exists(app, sound_type))
+        .map(|sound_type| SettingsIssue {
+            kind: SettingsIssueKind::MissingSound,
+            setting: "sound_theme".to_string(),
+            message: format!(
+                "Custom {} sound (custom_{}.wav) is missing",
+                sound_type, sound_type
+            ),
+        })
+        .collect()
+}
+
+/// Checks the settings for problems that would stop them from working, such
+/// as shortcuts that can't be registered or a model that isn't downloaded.
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_settings(app: AppHandle) -> Result<Vec<SettingsIssue>, String> {
+    let settings = get_settings(&app);
+
+    let mut issues = shortcut_issues(&settings);
+    issues.extend(model_issue(&app, &settings));
+    issues.extend(provider_issue(&settings).await);
+    issues.extend(sound_issues(&app, &settings));
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::get_default_settings;
+
+    #[test]
+    fn reports_unparseable_shortcuts() {
+        let mut settings = get_default_settings();
+        assert!(shortcut_issues(&settings).is_empty());
+
+        settings
+            .bindings
+            .get_mut("transcribe")
+            .unwrap()
+            .current_binding = " ".to_string();
+        let issues = shortcut_issues(&settings);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, SettingsIssueKind::InvalidShortcut);
+        assert_eq!(issues[0].setting, "bindings.transcribe");
+    }
+}
@@ -0,0 +1,355 @@
+//! Locations of models, recordings and the history database.
+//!
+//! Each defaults to the app data folder, which follows `$XDG_DATA_HOME` on
+//! Linux, and can be moved elsewhere, e.g. models to a larger drive. Chosen
+//! folders may start with `~` and use environment variables such as
+//! `$XDG_DATA_HOME`. The managers keep their folders for the whole session,
+//! so moving one moves its data and then restarts Handy. A move that fails
+//! part way is undone, so the data is never split across both folders.
+
+use crate::managers::audio::AudioRecordingManager;
+use crate::managers::model::ModelManager;
+use crate::settings::{self, AppSettings};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DataKind {
+    Models,
+    Recordings,
+    History,
+}
+
+/// The history folder defaults to the app data folder itself, so only the
+/// database files are moved with it
+const HISTORY_DB_FILES: [&str; 3] = ["history.db", "history.db-wal", "history.db-shm"];
+
+/// Expands a leading `~` and `$NAME` or `${NAME}` variables. Unknown
+/// variables are left as they are.
+fn expand_path(raw: &str, home: Option<&Path>, var: impl Fn(&str) -> Option<String>) -> PathBuf {
+    let raw = raw.trim();
+    let mut expanded = String::new();
+    let mut rest = raw;
+    if let (Some(after_tilde), Some(home)) = (raw.strip_prefix('~'), home) {
+        if after_tilde.is_empty() || after_tilde.starts_with(['/', '\\']) {
+            expanded.push_str(&home.to_string_lossy());
+            rest = after_tilde;
+        }
+    }
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (name, remainder) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                None => ("", after),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+        match var(name).filter(|_| !name.is_empty()) {
+            Some(value) => {
+                expanded.push_str(&value);
+                rest = remainder;
+            }
+            None => {
+                expanded.push('$');
+                rest = after;
+            }
+        }
+    }
+    expanded.push_str(rest);
+    PathBuf::from(expanded)
+}
+
+fn default_dir(app: &AppHandle, kind: DataKind) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(match kind {
+        DataKind::Models => app_data_dir.join("models"),
+        DataKind::Recordings => app_data_dir.join("recordings"),
+        DataKind::History => app_data_dir,
+    })
+}
+
+fn configured_dir(settings: &AppSettings, kind: DataKind) -> Option<&str> {
+    let dirs = &settings.data_directories;
+    match kind {
+        DataKind::Models => dirs.models.as_deref(),
+        DataKind::Recordings => dirs.recordings.as_deref(),
+        DataKind::History => dirs.history.as_deref(),
+    }
+    .filter(|dir| !dir.trim().is_empty())
+}
+
+fn chosen_dir(app: &AppHandle, raw: &str) -> Result<PathBuf, String> {
+    let path = expand_path(raw, app.path().home_dir().ok().as_deref(), |name| {
+        std::env::var(name).ok()
+    });
+    if !path.is_absolute() {
+        return Err(format!("'{}' is not an absolute path", raw));
+    }
+    Ok(path)
+}
+
+/// The folder `kind` is kept in this session
+pub fn dir(app: &AppHandle, kind: DataKind) -> Result<PathBuf, String> {
+    match configured_dir(&settings::get_settings(app), kind) {
+        Some(raw) => chosen_dir(app, raw),
+        None => default_dir(app, kind),
+    }
+}
+
+fn copy_path(from: &Path, to: &Path) -> Result<(), String> {
+    if from.is_dir() {
+        fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+        for entry in fs::read_dir(from).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            copy_path(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy {}: {}", from.display(), e))
+    }
+}
+
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Entries moved to the new folder so far. Entries on the same drive are
+/// renamed; the others are copied and their originals only removed once the
+/// new folder is in use.
+#[derive(Default)]
+struct Transfer {
+    renamed: Vec<(PathBuf, PathBuf)>,
+    copied: Vec<(PathBuf, PathBuf)>,
+}
+
+impl Transfer {
+    fn add(&mut self, from: &Path, to: &Path) -> Result<(), String> {
+        if fs::rename(from, to).is_ok() {
+            self.renamed.push((from.to_path_buf(), to.to_path_buf()));
+            return Ok(());
+        }
+        // Noted first so a copy that fails part way is cleaned up too
+        self.copied.push((from.to_path_buf(), to.to_path_buf()));
+        copy_path(from, to)
+    }
+
+    fn len(&self) -> usize {
+        self.renamed.len() + self.copied.len()
+    }
+
+    /// Puts everything back the way it was
+    fn undo(self) {
+        for (from, to) in self.renamed.into_iter().rev() {
+            if let Err(e) = fs::rename(&to, &from) {
+                warn!("Failed to move {} back: {}", to.display(), e);
+            }
+        }
+        for (_, to) in self.copied {
+            if to.exists() {
+                if let Err(e) = remove_path(&to) {
+                    warn!("Failed to remove partial copy {}: {}", to.display(), e);
+                }
+            }
+        }
+    }
+
+    /// Removes the originals of copied entries
+    fn finish(self) {
+        for (from, _) in self.copied {
+            if let Err(e) = remove_path(&from) {
+                warn!("Failed to remove {} after moving it: {}", from.display(), e);
+            }
+        }
+    }
+}
+
+/// Moves `names` from `from` into `to`, or everything if `names` is `None`.
+/// Nothing is moved if any of them already exists in `to`, and everything is
+/// put back if one can't be moved. Copies are only complete once the
+/// returned transfer is finished.
+fn move_entries(from: &Path, to: &Path, names: Option<&[&str]>) -> Result<Transfer, String> {
+    let names: Vec<String> = match names {
+        Some(names) => names
+            .iter()
+            .filter(|name| from.join(name).exists())
+            .map(|name| name.to_string())
+            .collect(),
+        None if from.exists() => fs::read_dir(from)
+            .map_err(|e| format!("Failed to read {}: {}", from.display(), e))?
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect(),
+        None => Vec::new(),
+    };
+    if let Some(existing) = names.iter().find(|name| to.join(name).exists()) {
+        return Err(format!("{} already exists in {}", existing, to.display()));
+    }
+
+    fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+    let mut transfer = Transfer::default();
+    for name in &names {
+        if let Err(e) = transfer.add(&from.join(name), &to.join(name)) {
+            transfer.undo();
+            return Err(e);
+        }
+    }
+    Ok(transfer)
+}
+
+/// Moves the data of `kind` to `path`, or back to the default folder when
+/// `path` is `None`, saves the new folder and restarts Handy.
+pub fn move_dir(app: &AppHandle, kind: DataKind, path: Option<String>) -> Result<(), String> {
+    let path = path.filter(|path| !path.trim().is_empty());
+    let from = dir(app, kind)?;
+    let to = match &path {
+        Some(raw) => chosen_dir(app, raw)?,
+        None => default_dir(app, kind)?,
+    };
+    if from == to {
+        return Ok(());
+    }
+    if kind != DataKind::History && to.starts_with(&from) {
+        return Err("The new folder cannot be inside the current one".to_string());
+    }
+    if app.state::<Arc<AudioRecordingManager>>().is_recording() {
+        return Err("Cannot move data while recording".to_string());
+    }
+    if kind == DataKind::Models
+        && app
+            .state::<Arc<ModelManager>>()
+            .get_available_models()
+            .iter()
+            .any(|model| model.is_downloading)
+    {
+        return Err("Cannot move models while a download is in progress".to_string());
+    }
+
+    let names = (kind == DataKind::History).then_some(&HISTORY_DB_FILES[..]);
+    let transfer = move_entries(&from, &to, names)?;
+    info!(
+        "Moved {} item(s) of {:?} data from {} to {}",
+        transfer.len(),
+        kind,
+        from.display(),
+        to.display()
+    );
+
+    let mut settings = settings::get_settings(app);
+    let dirs = &mut settings.data_directories;
+    match kind {
+        DataKind::Models => dirs.models = path,
+        DataKind::Recordings => dirs.recordings = path,
+        DataKind::History => dirs.history = path,
+    }
+    settings::write_settings(app, settings);
+    // Only now that the new folder is in use
+    transfer.finish();
+
+    app.restart()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_home_and_variables() {
+        let home = Path::new("/home/me");
+        let var = |name: &str| (name == "XDG_DATA_HOME").then(|| "/data".to_string());
+
+        assert_eq!(
+            expand_path("~/models", Some(home), var),
+            PathBuf::from("/home/me/models")
+        );
+        assert_eq!(
+            expand_path("$XDG_DATA_HOME/handy", Some(home), var),
+            PathBuf::from("/data/handy")
+        );
+        assert_eq!(
+            expand_path("${XDG_DATA_HOME}/handy/$UNSET", Some(home), var),
+            PathBuf::from("/data/handy/$UNSET")
+        );
+        assert_eq!(
+            expand_path("/mnt/~other", Some(home), var),
+            PathBuf::from("/mnt/~other")
+        );
+    }
+
+    #[test]
+    fn moves_entries_without_overwriting() {
+        let root = tempfile::tempdir().unwrap();
+        let from = root.path().join("from");
+        let to = root.path().join("to");
+        fs::create_dir_all(from.join("parakeet")).unwrap();
+        fs::write(from.join("parakeet").join("model.onnx"), "onnx").unwrap();
+        fs::write(from.join("history.db"), "db").unwrap();
+        fs::write(from.join("settings_store.json"), "{}").unwrap();
+
+        let transfer = move_entries(&from, &to, Some(&HISTORY_DB_FILES[..])).unwrap();
+        assert_eq!(transfer.len(), 1);
+        transfer.finish();
+        assert!(to.join("history.db").exists());
+        assert!(from.join("settings_store.json").exists());
+
+        fs::write(from.join("history.db"), "db").unwrap();
+        assert!(move_entries(&from, &to, None).is_err());
+        assert!(from.join("parakeet").exists());
+
+        fs::remove_file(from.join("history.db")).unwrap();
+        let transfer = move_entries(&from, &to, None).unwrap();
+        assert_eq!(transfer.len(), 2);
+        transfer.finish();
+        assert!(to.join("parakeet").join("model.onnx").exists());
+        assert!(!from.join("parakeet").exists());
+    }
+
+    #[test]
+    fn failed_moves_are_undone() {
+        let root = tempfile::tempdir().unwrap();
+        let from = root.path().join("from");
+        let to = root.path().join("to");
+        fs::create_dir_all(from.join("parakeet")).unwrap();
+        fs::write(from.join("parakeet").join("model.onnx"), "onnx").unwrap();
+        fs::write(from.join("whisper.bin"), "bin").unwrap();
+        fs::create_dir_all(&to).unwrap();
+
+        let mut transfer = Transfer::default();
+        transfer
+            .add(&from.join("parakeet"), &to.join("parakeet"))
+            .unwrap();
+        // As if it were copied from another drive and failed part way
+        transfer
+            .copied
+            .push((from.join("whisper.bin"), to.join("whisper.bin")));
+        fs::write(to.join("whisper.bin"), "bi").unwrap();
+        transfer.undo();
+
+        assert!(from.join("parakeet").join("model.onnx").exists());
+        assert!(from.join("whisper.bin").exists());
+        assert!(!to.join("parakeet").exists());
+        assert!(!to.join("whisper.bin").exists());
+    }
+}
@@ -0,0 +1,230 @@
+//! `handy://` links, for launchers like Raycast or Alfred and bookmarks.
+//!
+//! - `handy://start`, with `?post_process=true` to post-process
+//! - `handy://stop` and `handy://cancel`
+//! - `handy://transcribe?path=/path/to/note.m4a` pastes the text of an audio file
+//! - `handy://prompt/<id>` selects a post-processing prompt
+//! - `handy://action/<id>` runs any shortcut action, e.g. `paste_last_transcript`
+//!
+//! Any web page can open these links, so only start, stop and cancel work
+//! unless `deep_link_actions` is turned on.
+//!
+//! Recording goes through the transcription coordinator and everything else
+//! through `ACTION_MAP`, the same as the keyboard shortcuts.
+
+use crate::actions::ACTION_MAP;
+use crate::events::{self, ErrorSource};
+use crate::settings::get_settings;
+use crate::signal_handle;
+use crate::transcription_coordinator::is_transcribe_binding;
+use crate::tray;
+use log::{debug, error, info, warn};
+use std::path::PathBuf;
+use tauri::{AppHandle, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+pub const SCHEME: &str = "handy";
+
+/// Links show up in the logs as this
+const SOURCE: &str = "deep link";
+
+#[derive(Debug, PartialEq)]
+enum DeepLink {
+    Start { post_process: bool },
+    Stop,
+    Transcribe { path: PathBuf },
+    Prompt { id: String },
+    Action { id: String },
+}
+
+fn parse(url: &Url) -> Result<DeepLink, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("Not a {}:// link", SCHEME));
+    }
+    let query = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+    let argument = url
+        .path_segments()
+        .and_then(|mut segments| segments.next())
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| segment.to_string());
+
+    match url.host_str().unwrap_or_default() {
+        "start" => Ok(DeepLink::Start {
+            post_process: matches!(query("post_process").as_deref(), Some("true" | "1")),
+        }),
+        "stop" => Ok(DeepLink::Stop),
+        "cancel" => Ok(DeepLink::Action {
+            id: "cancel".to_string(),
+        }),
+        "transcribe" => query("path")
+            .filter(|path| !path.is_empty())
+            .map(|path| DeepLink::Transcribe {
+                path: PathBuf::from(path),
+            })
+            .ok_or_else(|| "handy://transcribe needs a path".to_string()),
+        "prompt" => argument
+            .map(|id| DeepLink::Prompt { id })
+            .ok_or_else(|| "handy://prompt needs a prompt id".to_string()),
+        "action" => argument
+            .filter(|id| ACTION_MAP.contains_key(id))
+            .map(|id| DeepLink::Action { id })
+            .ok_or_else(|| "handy://action needs a known action id".to_string()),
+        other => Err(format!("Unknown link action '{}'", other)),
+    }
+}
+
+/// Whether `link` may run. Links that read files, paste or change settings
+/// need `deep_link_actions`.
+fn allowed(link: &DeepLink, actions_enabled: bool) -> bool {
+    match link {
+        DeepLink::Start { .. } | DeepLink::Stop => true,
+        DeepLink::Action { id } if id == "cancel" => true,
+        _ => actions_enabled,
+    }
+}
+
+/// Handles links opened while Handy runs and the one it was launched with
+pub fn setup(app: &AppHandle) {
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    if let Err(e) = app.deep_link().register_all() {
+        warn!("Failed to register {}:// links: {}", SCHEME, e);
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            open(&handle, &url);
+        }
+    });
+
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            open(app, &url);
+        }
+    }
+}
+
+fn open(app: &AppHandle, url: &Url) {
+    let link = match parse(url) {
+        Ok(link) => link,
+        Err(e) => {
+            warn!("Ignoring link {}: {}", url, e);
+            return;
+        }
+    };
+    if !allowed(&link, get_settings(app).deep_link_actions) {
+        warn!(
+            "Ignoring link {}: links may only start, stop and cancel unless deep_link_actions is on",
+            url
+        );
+        return;
+    }
+    debug!("Opening link {:?}", link);
+
+    match link {
+        DeepLink::Start { post_process } => {
+            if let Err(e) = signal_handle::start_recording(app, post_process, SOURCE) {
+                debug!("Ignoring start link: {}", e);
+            }
+        }
+        DeepLink::Stop => {
+            if let Err(e) = signal_handle::stop_recording(app, SOURCE) {
+                debug!("Ignoring stop link: {}", e);
+            }
+        }
+        DeepLink::Transcribe { path } => transcribe_file(app, path),
+        DeepLink::Prompt { id } => {
+            if get_settings(app)
+                .post_process_prompts
+                .iter()
+                .any(|prompt| prompt.id == id)
+            {
+                tray::select_prompt(app, id);
+            } else {
+                warn!("Ignoring link for unknown prompt {}", id);
+            }
+        }
+        // Transcription toggles go through the coordinator so they can't race
+        // a shortcut
+        DeepLink::Action { id } if is_transcribe_binding(&id) => {
+            signal_handle::send_transcription_input(app, &id, SOURCE);
+        }
+        DeepLink::Action { id } => {
+            if let Some(action) = ACTION_MAP.get(&id) {
+                action.start(app, &id, SOURCE);
+            }
+        }
+    }
+}
+
+/// Transcribes an audio file with the selected model and pastes the text
+fn transcribe_file(app: &AppHandle, path: PathBuf) {
+    let app = app.clone();
+    std::thread::spawn(move || match signal_handle::transcribe_file(&app, &path) {
+        Ok(text) if text.is_empty() => info!("No speech in {}", path.display()),
+        Ok(text) => tray::paste_history_text(&app, text),
+        Err(e) => {
+            error!("Failed to transcribe {}: {}", path.display(), e);
+            events::emit_error(&app, ErrorSource::Transcription, e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(url: &str) -> Result<DeepLink, String> {
+        parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn parses_links() {
+        assert_eq!(
+            parse_str("handy://start?post_process=true"),
+            Ok(DeepLink::Start { post_process: true })
+        );
+        assert_eq!(parse_str("handy://stop"), Ok(DeepLink::Stop));
+        assert_eq!(
+            parse_str("handy://transcribe?path=%2Ftmp%2Fnote%20one.wav"),
+            Ok(DeepLink::Transcribe {
+                path: PathBuf::from("/tmp/note one.wav")
+            })
+        );
+        assert_eq!(
+            parse_str("handy://prompt/default_improve_transcriptions"),
+            Ok(DeepLink::Prompt {
+                id: "default_improve_transcriptions".to_string()
+            })
+        );
+        assert_eq!(
+            parse_str("handy://action/paste_last_transcript"),
+            Ok(DeepLink::Action {
+                id: "paste_last_transcript".to_string()
+            })
+        );
+        assert!(parse_str("handy://action/rm").is_err());
+        assert!(parse_str("handy://transcribe").is_err());
+        assert!(parse_str("other://start").is_err());
+    }
+
+    #[test]
+    fn only_recording_links_work_without_opt_in() {
+        for url in ["handy://start", "handy://stop", "handy://cancel"] {
+            assert!(allowed(&parse_str(url).unwrap(), false), "{}", url);
+        }
+        for url in [
+            "handy://transcribe?path=%2Fetc%2Fpasswd",
+            "handy://action/paste_last_transcript",
+            "handy://prompt/default_improve_transcriptions",
+        ] {
+            let link = parse_str(url).unwrap();
+            assert!(!allowed(&link, false), "{}", url);
+            assert!(allowed(&link, true), "{}", url);
+        }
+    }
+}
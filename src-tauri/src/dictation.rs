@@ -0,0 +1,164 @@
+//! The dictation state, kept in one place.
+//!
+//! Recording, transcribing and post-processing each show in both the tray
+//! and the overlay. Switching those separately let a missed call leave the
+//! tray idle while the overlay still said "transcribing". Every change now
+//! goes through [`transition`], which refuses moves that can't happen,
+//! updates the tray and overlay together and sends a
+//! `dictation_state_changed` event.
+
+use crate::events::{self, AppEvent, DictationState};
+use crate::overlay::{
+    hide_recording_overlay, show_processing_overlay, show_recording_overlay,
+    show_transcribing_overlay,
+};
+use crate::tray::{change_tray_icon, update_tray_menu, TrayIconState};
+use log::{debug, warn};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::AppHandle;
+
+/// The state and when it was entered
+static STATE: Mutex<Option<(DictationState, Instant)>> = Mutex::new(None);
+
+/// Held for a whole transition so the tray and overlay of one can't be
+/// interleaved with another's
+static TRANSITION: Mutex<()> = Mutex::new(());
+
+/// Work put off until no dictation is in progress
+static WHEN_IDLE: Mutex<Vec<Box<dyn FnOnce() + Send>>> = Mutex::new(Vec::new());
+
+/// The current state and when it was entered
+pub fn current_since() -> (DictationState, Instant) {
+    *STATE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(|| (DictationState::Idle, Instant::now()))
+}
+
+pub fn current() -> DictationState {
+    current_since().0
+}
+
+/// Whether a dictation can go from `from` to `to`. Going idle always works,
+/// so a cancel or failure can end any of them.
+fn is_allowed(from: DictationState, to: DictationState) -> bool {
+    use DictationState::*;
+    matches!(
+        (from, to),
+        (_, Idle)
+            | (Idle, Recording)
+            // Files are transcribed without recording
+            | (Idle, Transcribing)
+            | (Recording, Transcribing)
+            | (Transcribing, PostProcessing)
+    )
+}
+
+fn tray_icon(state: DictationState) -> TrayIconState {
+    match state {
+        DictationState::Idle => TrayIconState::Idle,
+        DictationState::Recording => TrayIconState::Recording,
+        DictationState::Transcribing | DictationState::PostProcessing => {
+            TrayIconState::Transcribing
+        }
+    }
+}
+
+/// Moves the dictation to `to`, showing it in the tray and overlay. Moving
+/// to the current state shows it again, which clears an overlay left up.
+/// Returns false, leaving everything as it is, for a move that can't happen.
+pub fn transition(app: &AppHandle, to: DictationState) -> bool {
+    let _transition = TRANSITION.lock().unwrap_or_else(|e| e.into_inner());
+    let from = current();
+    if from != to && !is_allowed(from, to) {
+        warn!(
+            "Ignoring dictation state change from {} to {}",
+            from.as_str(),
+            to.as_str()
+        );
+        return false;
+    }
+    if from != to {
+        *STATE.lock().unwrap_or_else(|e| e.into_inner()) = Some((to, Instant::now()));
+        debug!("Dictation state: {} -> {}", from.as_str(), to.as_str());
+    }
+
+    change_tray_icon(app, tray_icon(to));
+    match to {
+        DictationState::Idle => hide_recording_overlay(app),
+        DictationState::Recording => show_recording_overlay(app),
+        DictationState::Transcribing => show_transcribing_overlay(app),
+        DictationState::PostProcessing => show_processing_overlay(app),
+    }
+
+    if from != to {
+        events::emit(app, AppEvent::DictationStateChanged { from, to });
+    }
+    if to == DictationState::Idle {
+        for job in WHEN_IDLE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain(..)
+        {
+            std::thread::spawn(job);
+        }
+    }
+    true
+}
+
+/// Runs `job` on its own thread once no dictation is in progress, right away
+/// if none is. For work like loading another model, which would hold up or
+/// swap the model under a dictation.
+pub fn when_idle(job: impl FnOnce() + Send + 'static) {
+    let _transition = TRANSITION.lock().unwrap_or_else(|e| e.into_inner());
+    if current() == DictationState::Idle {
+        std::thread::spawn(job);
+    } else {
+        debug!("Putting off work until the dictation is done");
+        WHEN_IDLE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Box::new(job));
+    }
+}
+
+/// Shows the current state in the tray again, e.g. after the theme or pause
+/// state changed its icons
+pub fn refresh_tray(app: &AppHandle) {
+    let _transition = TRANSITION.lock().unwrap_or_else(|e| e.into_inner());
+    change_tray_icon(app, tray_icon(current()));
+}
+
+/// Rebuilds the tray menu for the current state, e.g. after something shown
+/// in it changed. Building it for idle would drop Cancel mid-dictation.
+pub fn refresh_tray_menu(app: &AppHandle) {
+    let _transition = TRANSITION.lock().unwrap_or_else(|e| e.into_inner());
+    update_tray_menu(app, &tray_icon(current()), None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DictationState::*;
+
+    #[test]
+    fn only_allows_moves_a_dictation_makes() {
+        assert!(is_allowed(Idle, Recording));
+        assert!(is_allowed(Recording, Transcribing));
+        assert!(is_allowed(Transcribing, PostProcessing));
+        assert!(is_allowed(PostProcessing, Idle));
+        assert!(is_allowed(Recording, Idle));
+        assert!(!is_allowed(Idle, PostProcessing));
+        assert!(!is_allowed(Transcribing, Recording));
+        assert!(!is_allowed(PostProcessing, Recording));
+    }
+
+    #[test]
+    fn tray_follows_the_dictation() {
+        assert_eq!(tray_icon(Idle), TrayIconState::Idle);
+        assert_eq!(tray_icon(Recording), TrayIconState::Recording);
+        assert_eq!(tray_icon(Transcribing), TrayIconState::Transcribing);
+        assert_eq!(tray_icon(PostProcessing), TrayIconState::Transcribing);
+    }
+}
@@ -0,0 +1,198 @@
+//! Emails longform transcripts, like meetings and voice memos, to yourself.
+//!
+//! Only dictations of at least `min_duration_secs` are sent. With `smtp`
+//! delivery the mail goes out in the background, logging in as
+//! `smtp_username` with the `smtp_password` secret if one is set. With
+//! `mailto` the default mail app opens a draft to send by hand. The subject
+//! is a template with the same `{{variables}}` as HTTP actions. Like
+//! webhooks, incognito dictations are never sent.
+
+use crate::http_actions;
+use crate::policy;
+use crate::secrets::{self, SecretKind};
+use crate::settings::{EmailDelivery, EmailSettings, SmtpSecurity};
+use crate::webhooks::TranscriptionPayload;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Address, Message, SmtpTransport, Transport};
+use log::{info, warn};
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longer `mailto:` links are cut off or refused by some mail apps and by
+/// Windows, so drafts get the start of the transcript
+const MAILTO_BODY_LIMIT: usize = 1800;
+
+const DEFAULT_SUBJECT: &str = "Transcript";
+
+/// Checks the addresses and, for SMTP, the server
+pub fn validate(settings: &EmailSettings) -> Result<(), String> {
+    if !settings.enabled {
+        return Ok(());
+    }
+    settings
+        .to
+        .trim()
+        .parse::<Address>()
+        .map_err(|_| format!("'{}' is not an email address", settings.to))?;
+    if settings.delivery == EmailDelivery::Smtp {
+        if settings.smtp_host.trim().is_empty() {
+            return Err("SMTP server cannot be empty".to_string());
+        }
+        if settings.smtp_port == 0 {
+            return Err("SMTP port cannot be 0".to_string());
+        }
+        if let Some(from) = settings.from.as_deref().filter(|f| !f.trim().is_empty()) {
+            from.trim()
+                .parse::<Mailbox>()
+                .map_err(|_| format!("'{}' is not an email address", from))?;
+        }
+    }
+    Ok(())
+}
+
+/// Emails the transcript if it's long enough
+pub fn send_transcription(
+    app: &AppHandle,
+    settings: &EmailSettings,
+    payload: &TranscriptionPayload,
+) {
+    if !settings.enabled
+        || payload.text.trim().is_empty()
+        || payload.duration_ms < i64::from(settings.min_duration_secs) * 1000
+    {
+        return;
+    }
+    // Drafts go to the mail app, which sends them on
+    let destination = match settings.delivery {
+        EmailDelivery::Mailto => "mailto:",
+        EmailDelivery::Smtp => settings.smtp_host.trim(),
+    };
+    if !policy::allows_destination(destination) {
+        return;
+    }
+    let subject = subject(&settings.subject_template, payload);
+
+    match settings.delivery {
+        EmailDelivery::Mailto => {
+            let url = mailto_url(settings.to.trim(), &subject, &payload.text);
+            if let Err(e) = app.opener().open_url(url, None::<String>) {
+                warn!("Failed to open an email draft: {}", e);
+            }
+        }
+        EmailDelivery::Smtp => {
+            let settings = settings.clone();
+            let text = payload.text.clone();
+            thread::spawn(move || match send_smtp(&settings, &subject, &text) {
+                Ok(()) => info!("Emailed transcript to {}", settings.to),
+                Err(e) => warn!("Failed to email transcript: {}", e),
+            });
+        }
+    }
+}
+
+fn subject(template: &str, payload: &TranscriptionPayload) -> String {
+    let subject = http_actions::render(template, payload, |value| value.replace(['\r', '\n'], " "));
+    match subject.trim() {
+        "" => DEFAULT_SUBJECT.to_string(),
+        subject => subject.to_string(),
+    }
+}
+
+fn mailto_url(to: &str, subject: &str, text: &str) -> String {
+    let mut body: String = text.trim().chars().take(MAILTO_BODY_LIMIT).collect();
+    if body.len() < text.trim().len() {
+        body.push_str("…\n\n(The full transcript is in Handy's history.)");
+    }
+    // Mail apps expect CRLF line breaks in mailto bodies
+    let body = body.replace("\r\n", "\n").replace('\n', "\r\n");
+    format!(
+        "mailto:{}?subject={}&body={}",
+        to,
+        http_actions::escape_url(subject),
+        http_actions::escape_url(&body)
+    )
+}
+
+fn send_smtp(settings: &EmailSettings, subject: &str, text: &str) -> Result<(), String> {
+    let to: Mailbox = settings
+        .to
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not an email address", settings.to))?;
+    let from = match settings.from.as_deref().filter(|f| !f.trim().is_empty()) {
+        Some(from) => from
+            .trim()
+            .parse()
+            .map_err(|_| format!("'{}' is not an email address", from))?,
+        None => to.clone(),
+    };
+    let message = Message::builder()
+        .from(from)
+        .to(to)
+        .subject(subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(text.to_string())
+        .map_err(|e| format!("Failed to build the email: {}", e))?;
+
+    let host = settings.smtp_host.trim();
+    let mut transport = match settings.smtp_security {
+        SmtpSecurity::StartTls => SmtpTransport::starttls_relay(host),
+        SmtpSecurity::Tls => SmtpTransport::relay(host),
+    }
+    .map_err(|e| format!("Failed to set up SMTP for {}: {}", host, e))?
+    .port(settings.smtp_port)
+    .timeout(Some(SMTP_TIMEOUT));
+    if let Some(username) = settings
+        .smtp_username
+        .as_deref()
+        .filter(|u| !u.trim().is_empty())
+    {
+        let password =
+            secrets::get_secret(&secrets::secret_name(SecretKind::SmtpPassword, "default"))
+                .ok_or_else(|| "An SMTP username is set, but no password".to_string())?;
+        transport = transport.credentials(Credentials::new(username.trim().to_string(), password));
+    }
+
+    transport
+        .build()
+        .send(&message)
+        .map_err(|e| format!("{} refused the email: {}", host, e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_mailto_links() {
+        let payload = TranscriptionPayload {
+            event: TranscriptionPayload::EVENT,
+            text: "Line one\nline two".to_string(),
+            transcription_text: String::new(),
+            post_processed_text: None,
+            post_process_prompt: None,
+            timestamp: 0,
+            duration_ms: 90_000,
+            model: None,
+            app_name: Some("Zoom\nMeeting".to_string()),
+            window_title: None,
+            post_process_retries: 0,
+        };
+        assert_eq!(
+            subject("Notes from {{app}}", &payload),
+            "Notes from Zoom Meeting"
+        );
+        assert_eq!(subject("  ", &payload), DEFAULT_SUBJECT);
+        assert_eq!(
+            mailto_url("me@example.com", "A & B", &payload.text),
+            "mailto:me@example.com?subject=A%20%26%20B&body=Line%20one%0D%0Aline%20two"
+        );
+    }
+}
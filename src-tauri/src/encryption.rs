@@ -0,0 +1,202 @@
+//! At-rest encryption for transcription history.
+//!
+//! A random 256-bit key is kept in the platform credential store (see
+//! [`crate::secrets`]). The history database is encrypted with SQLCipher using
+//! that key, and recordings are sealed with ChaCha20-Poly1305 in a small
+//! envelope: `MAGIC || nonce || ciphertext`.
+
+use crate::secrets;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+const KEY_SECRET_NAME: &str = "history_encryption_key";
+/// Keys of other machines, one per line, for opening their synced history
+const PEER_KEYS_SECRET_NAME: &str = "history_sync_peer_keys";
+const MAGIC: &[u8; 8] = b"HNDYENC1";
+const NONCE_LEN: usize = 12;
+/// Header of an unencrypted SQLite database file
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+pub type HistoryKey = [u8; 32];
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Parses a key in the form [`export_key`] writes
+pub fn parse_key(text: &str) -> Result<HistoryKey, String> {
+    decode_hex(text.trim())
+        .and_then(|bytes| HistoryKey::try_from(bytes).ok())
+        .ok_or_else(|| "Not a valid history key".to_string())
+}
+
+/// Text form of a key, for moving it to another machine
+pub fn export_key(key: &HistoryKey) -> String {
+    encode_hex(key)
+}
+
+/// Returns the history key from the credential store. When the store confirms
+/// there is none and `create` is set, a new key is generated and stored. A
+/// failed read is an error, never a reason to replace the stored key.
+pub fn history_key(create: bool) -> Result<Option<HistoryKey>, String> {
+    if let Some(stored) = secrets::read_secret(KEY_SECRET_NAME)? {
+        return parse_key(&stored)
+            .map(Some)
+            .map_err(|_| "Stored history encryption key is malformed".to_string());
+    }
+    if !create {
+        return Ok(None);
+    }
+
+    let key: HistoryKey = ChaCha20Poly1305::generate_key(&mut OsRng).into();
+    secrets::set_secret(KEY_SECRET_NAME, &encode_hex(&key))?;
+    Ok(Some(key))
+}
+
+/// Keys imported from other machines, used only to open their synced records
+pub fn peer_keys() -> Result<Vec<HistoryKey>, String> {
+    let stored = secrets::read_secret(PEER_KEYS_SECRET_NAME)?.unwrap_or_default();
+    Ok(stored
+        .lines()
+        .filter_map(|line| parse_key(line).ok())
+        .collect())
+}
+
+/// Remembers another machine's key. Keys already known are ignored.
+pub fn add_peer_key(key: &HistoryKey) -> Result<(), String> {
+    let mut keys = peer_keys()?;
+    if keys.contains(key) || history_key(false)?.as_ref() == Some(key) {
+        return Ok(());
+    }
+    keys.push(*key);
+    let stored: Vec<String> = keys.iter().map(export_key).collect();
+    secrets::set_secret(PEER_KEYS_SECRET_NAME, &stored.join("\n"))
+}
+
+/// Raw key literal for SQLCipher's `PRAGMA key` and `ATTACH ... KEY`, which
+/// skips its passphrase derivation
+pub fn sqlcipher_key(key: &HistoryKey) -> String {
+    format!("\"x'{}'\"", encode_hex(key))
+}
+
+/// Whether the database file exists and is not a plain SQLite database
+pub fn database_is_encrypted(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    match fs::File::open(path).and_then(|mut file| file.read_exact(&mut header)) {
+        Ok(()) => &header != SQLITE_HEADER,
+        Err(_) => false,
+    }
+}
+
+/// Whether `data` was produced by [`encrypt`]
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Whether the file starts like data produced by [`encrypt`]
+pub fn file_is_encrypted(path: &Path) -> bool {
+    let mut magic = [0u8; MAGIC.len()];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|()| &magic == MAGIC)
+}
+
+pub fn encrypt(key: &HistoryKey, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| "Failed to encrypt data".to_string())?;
+
+    let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(MAGIC);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+pub fn decrypt(key: &HistoryKey, sealed: &[u8]) -> Result<Vec<u8>, String> {
+    let body = sealed
+        .strip_prefix(MAGIC)
+        .filter(|body| body.len() >= NONCE_LEN)
+        .ok_or("Data is not encrypted")?;
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+
+    ChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt data: wrong key or corrupted file".to_string())
+}
+
+/// Writes through a temporary file so an interrupted write can't truncate
+/// the only copy
+fn replace_file(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents)
+        .and_then(|()| fs::rename(&tmp, path))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Encrypts a file in place. Files that are already encrypted are left alone.
+pub fn encrypt_file(key: &HistoryKey, path: &Path) -> Result<(), String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if is_encrypted(&data) {
+        return Ok(());
+    }
+    replace_file(path, &encrypt(key, &data)?)
+}
+
+/// Decrypts a file in place. Plain files are left alone.
+pub fn decrypt_file(key: &HistoryKey, path: &Path) -> Result<(), String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if !is_encrypted(&data) {
+        return Ok(());
+    }
+    replace_file(path, &decrypt(key, &data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_rejects_tampering() {
+        let key = [7u8; 32];
+        let mut sealed = encrypt(&key, b"dictated secret").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert_eq!(decrypt(&key, &sealed).unwrap(), b"dictated secret");
+        assert!(decrypt(&[8u8; 32], &sealed).is_err());
+
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert!(decrypt(&key, &sealed).is_err());
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        let bytes = [0u8, 15, 16, 255];
+        assert_eq!(encode_hex(&bytes), "000f10ff");
+        assert_eq!(decode_hex("000f10ff").unwrap(), bytes);
+        assert!(decode_hex("abc").is_none());
+        assert!(decode_hex("zz").is_none());
+    }
+
+    #[test]
+    fn exported_keys_parse_back() {
+        let key = [42u8; 32];
+        assert_eq!(parse_key(&format!(" {}\n", export_key(&key))), Ok(key));
+        assert!(parse_key("00ff").is_err());
+    }
+}
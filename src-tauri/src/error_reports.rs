@@ -0,0 +1,324 @@
+//! Error reports users can share without sharing their dictation.
+//!
+//! Every error sent through `events::emit_error` is also written as a small
+//! JSON report to the `error_reports` folder in the app data directory.
+//! API keys, tokens and the text of recent transcripts are redacted from
+//! the message before it's written. `list_error_reports` returns them,
+//! newest first. Logs shared through diagnostics go through the same
+//! redaction, see `redact_log`.
+
+use crate::events::{AppEvent, ErrorSource};
+use crate::secrets::{self, SecretKind};
+use crate::settings::{get_settings, AppSettings};
+use log::warn;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use tauri::{AppHandle, Listener, Manager};
+
+const REPORTS_DIR: &str = "error_reports";
+
+/// Most reports kept, the oldest are removed past it
+const MAX_REPORTS: usize = 100;
+
+/// Transcripts remembered for redaction
+const RECENT_TRANSCRIPTS: usize = 5;
+
+/// Shorter transcripts are left alone, they'd match ordinary words
+const MIN_REDACTED_CHARS: usize = 4;
+
+const REDACTED: &str = "[redacted]";
+const TRANSCRIPT: &str = "[transcript]";
+
+static TOKEN_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    vec![
+        Regex::new(r"\b(?:sk|pk|rk|gsk|xai)-[A-Za-z0-9_\-]{8,}").unwrap(),
+        Regex::new(r"(?i)\b(bearer\s+)[A-Za-z0-9._~+/\-]+=*").unwrap(),
+        Regex::new(r#"(?i)\b((?:api[_-]?key|token|secret)["']?\s*[:=]\s*["']?)[^\s"'&,}]+"#)
+            .unwrap(),
+    ]
+});
+
+/// Log lines that print transcripts, which older versions wrote
+const TRANSCRIPT_LOG_LINES: [&str; 2] = ["Transcription result: ", "Transcription completed in "];
+
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+fn recent() -> MutexGuard<'static, VecDeque<String>> {
+    RECENT.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type)]
+pub struct ErrorReport {
+    /// File name of the report, without `.json`
+    pub id: String,
+    /// Unix time in milliseconds
+    pub timestamp: i64,
+    pub source: ErrorSource,
+    /// The error with secrets and transcript text redacted
+    pub message: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub selected_model: String,
+    /// Post-processing provider, when post-processing is on
+    pub post_process_provider: Option<String>,
+}
+
+/// `message` with `secrets` and tokens replaced by `[redacted]` and
+/// `transcripts` by `[transcript]`
+fn redact(message: &str, secrets: &[String], transcripts: &[String]) -> String {
+    let mut redacted = message.to_string();
+    for transcript in transcripts {
+        let transcript = transcript.trim();
+        if transcript.chars().count() >= MIN_REDACTED_CHARS {
+            redacted = redacted.replace(transcript, TRANSCRIPT);
+        }
+    }
+    for secret in secrets.iter().filter(|secret| !secret.trim().is_empty()) {
+        redacted = redacted.replace(secret.trim(), REDACTED);
+    }
+    for pattern in TOKEN_PATTERNS.iter() {
+        redacted = pattern
+            .replace_all(&redacted, |caps: &regex::Captures| {
+                format!(
+                    "{}{}",
+                    caps.get(1).map_or("", |prefix| prefix.as_str()),
+                    REDACTED
+                )
+            })
+            .into_owned();
+    }
+    redacted
+}
+
+fn reports_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(REPORTS_DIR))
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+/// Settings values that must never be shared: API keys and calendar feed
+/// addresses, in both the forms they're logged in
+fn settings_secrets(settings: &AppSettings) -> Vec<String> {
+    let feeds = settings.meeting_detection.ics_urls.iter().flat_map(|url| {
+        [
+            url.trim().to_string(),
+            url.trim().replacen("webcal://", "https://", 1),
+        ]
+    });
+    settings
+        .post_process_api_keys
+        .values()
+        .cloned()
+        .chain(feeds)
+        .collect()
+}
+
+/// Names of the tokens and passwords in the credential store that belong
+/// to the current settings
+fn stored_secret_names(settings: &AppSettings) -> Vec<String> {
+    let webhooks = settings
+        .webhooks
+        .iter()
+        .map(|hook| secrets::secret_name(SecretKind::WebhookToken, &hook.id));
+    let note_targets = settings
+        .note_targets
+        .iter()
+        .map(|target| secrets::secret_name(SecretKind::NotionToken, &target.id));
+    let single = [
+        SecretKind::ApiServerToken,
+        SecretKind::SmtpPassword,
+        SecretKind::MqttPassword,
+        SecretKind::ObsPassword,
+    ]
+    .into_iter()
+    .map(|kind| secrets::secret_name(kind, "default"));
+    webhooks.chain(note_targets).chain(single).collect()
+}
+
+/// Everything redacted from reports and logs: settings values and the
+/// credentials stored for them
+fn secrets(settings: &AppSettings) -> Vec<String> {
+    let mut secrets = settings_secrets(settings);
+    secrets.extend(
+        stored_secret_names(settings)
+            .iter()
+            .filter_map(|name| secrets::get_secret(name)),
+    );
+    secrets
+}
+
+fn redact_lines(log: &str, secrets: &[String], transcripts: &[String]) -> String {
+    log.lines()
+        .filter(|line| {
+            !TRANSCRIPT_LOG_LINES
+                .iter()
+                .any(|marker| line.contains(marker))
+        })
+        .map(|line| redact(line, secrets, transcripts) + "\n")
+        .collect()
+}
+
+/// `log` fit to share: lines that print transcripts are dropped and the rest
+/// are redacted like reports
+pub fn redact_log(app: &AppHandle, log: &str) -> String {
+    let transcripts: Vec<String> = recent().iter().cloned().collect();
+    redact_lines(log, &secrets(&get_settings(app)), &transcripts)
+}
+
+/// Remembers transcripts so they can be kept out of reports
+pub fn start(app: &AppHandle) {
+    app.listen_any("app-event", |event| {
+        let text = match serde_json::from_str::<AppEvent>(event.payload()) {
+            Ok(AppEvent::Transcribed { text } | AppEvent::TranscriptionCompleted { text }) => text,
+            _ => return,
+        };
+        let mut recent = recent();
+        if recent.len() >= RECENT_TRANSCRIPTS {
+            recent.pop_front();
+        }
+        recent.push_back(text);
+    });
+}
+
+/// Writes a report for an error from `source`
+pub fn record(app: &AppHandle, source: ErrorSource, message: &str) {
+    let settings = get_settings(app);
+    let secrets = secrets(&settings);
+    let transcripts: Vec<String> = recent().iter().cloned().collect();
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let source_name = serde_json::to_value(source)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let report = ErrorReport {
+        id: format!("{}-{}", timestamp, source_name),
+        timestamp,
+        source,
+        message: redact(message, &secrets, &transcripts),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: tauri_plugin_os::platform().to_string(),
+        arch: tauri_plugin_os::arch().to_string(),
+        selected_model: settings.selected_model.clone(),
+        post_process_provider: settings
+            .post_process_enabled
+            .then(|| settings.post_process_provider_id.clone()),
+    };
+
+    if let Err(e) = write_report(app, &report) {
+        warn!("Failed to write error report: {}", e);
+    }
+}
+
+fn write_report(app: &AppHandle, report: &ErrorReport) -> Result<(), String> {
+    let dir = reports_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let json = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}.json", report.id));
+    fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    let mut reports = report_files(&dir);
+    if reports.len() > MAX_REPORTS {
+        let excess = reports.len() - MAX_REPORTS;
+        for old in reports.drain(..excess) {
+            let _ = fs::remove_file(old);
+        }
+    }
+    Ok(())
+}
+
+/// Report files in `dir`, oldest first
+fn report_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    files.retain(|file| file.extension().is_some_and(|ext| ext == "json"));
+    // Names start with a millisecond timestamp of the same width for the
+    // foreseeable future, so they sort by age
+    files.sort();
+    files
+}
+
+/// Error reports kept on this machine, newest first
+#[tauri::command]
+#[specta::specta]
+pub fn list_error_reports(app: AppHandle) -> Result<Vec<ErrorReport>, String> {
+    let dir = reports_dir(&app)?;
+    let mut reports = Vec::new();
+    for file in report_files(&dir).into_iter().rev() {
+        let parsed = fs::read_to_string(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(report) => reports.push(report),
+            Err(e) => warn!("Skipping unreadable error report {}: {}", file.display(), e),
+        }
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_keys_tokens_and_transcripts() {
+        let message = "API request failed with status 401: key my-own-key rejected \
+                       for 'meet me at noon tomorrow', Authorization: Bearer abc.def-123 \
+                       {\"api_key\": \"xyz789\"} sk-proj-abcdefghijk";
+        let redacted = redact(
+            message,
+            &["my-own-key".to_string()],
+            &["meet me at noon tomorrow".to_string(), "ok".to_string()],
+        );
+        assert_eq!(
+            redacted,
+            "API request failed with status 401: key [redacted] rejected \
+             for '[transcript]', Authorization: Bearer [redacted] \
+             {\"api_key\": \"[redacted]\"} [redacted]"
+        );
+    }
+
+    #[test]
+    fn logs_lose_transcript_lines_and_feeds() {
+        let mut settings = crate::settings::get_default_settings();
+        settings
+            .meeting_detection
+            .ics_urls
+            .push("webcal://calendar.example.com/private-abc123/basic.ics".to_string());
+        let log = "[INFO] Transcription result: meet me at noon tomorrow\n\
+                   [WARN] Failed to fetch calendar https://calendar.example.com/private-abc123/basic.ics: timed out\n\
+                   [INFO] Pasted 'meet me at noon tomorrow'\n";
+        let redacted = redact_lines(
+            log,
+            &settings_secrets(&settings),
+            &["meet me at noon tomorrow".to_string()],
+        );
+        assert_eq!(
+            redacted,
+            "[WARN] Failed to fetch calendar [redacted]: timed out\n\
+             [INFO] Pasted '[transcript]'\n"
+        );
+    }
+
+    #[test]
+    fn stored_tokens_and_passwords_are_redacted() {
+        let mut settings = crate::settings::get_default_settings();
+        settings.webhooks.push(crate::settings::Webhook {
+            id: "slack".to_string(),
+            url: "https://hooks.slack.com/services/T0/B0/secret".to_string(),
+            enabled: true,
+        });
+        let names = stored_secret_names(&settings);
+        assert!(names.contains(&"webhook_token.slack".to_string()));
+        assert!(names.contains(&"api_server_token.default".to_string()));
+        assert!(names.contains(&"smtp_password.default".to_string()));
+    }
+}
@@ -0,0 +1,245 @@
+//! Typed events for the frontend and integrations.
+//!
+//! The dictation lifecycle, errors and settings changes are all sent as one
+//! `app-event` whose payload is an [`AppEvent`], tagged by `type`. It's
+//! exported to `bindings.ts` through tauri-specta, so every listener gets
+//! the same typed contract. Events are sent through [`emit`].
+
+use crate::commands::permissions::SystemPermission;
+use crate::dictation;
+use crate::error_reports;
+use crate::managers::audio::{MicLevel, RecordingTimer};
+use crate::managers::transcription::ModelLoadingEvent;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::AppHandle;
+use tauri_specta::Event;
+
+/// A top-level setting changed. API keys are reported as whether each one is
+/// set, and calendar feeds, webhook and HTTP action URLs are redacted.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type)]
+pub struct SettingsChanged {
+    pub setting: String,
+    pub old_value: serde_json::Value,
+    pub new_value: serde_json::Value,
+}
+
+/// What failed
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorSource {
+    Transcription,
+    PostProcess,
+    Paste,
+    Undo,
+    /// Microphone access is off, so recording didn't start
+    Microphone,
+    /// Synced history couldn't be merged
+    HistorySync,
+    /// A transcript couldn't be sent to the binding's note target
+    NoteTarget,
+}
+
+/// A state the watchdog found a dictation stuck in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum StuckState {
+    /// Recording for longer than `watchdog.max_recording_mins`
+    Recording,
+    /// Transcribing or post-processing for longer than
+    /// `watchdog.max_processing_mins`
+    Processing,
+    /// The overlay stayed up with nothing going on
+    Overlay,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type, Event)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AppEvent {
+    RecordingStarted,
+    /// Smoothed microphone levels, sent steadily while recording
+    MicLevel(MicLevel),
+    RecordingTimer(RecordingTimer),
+    TranscriptionStarted,
+    /// Text from the model, before post-processing and other changes. Not
+    /// sent for incognito dictations, like `TranscriptionCompleted`.
+    Transcribed {
+        text: String,
+    },
+    PostProcessingStarted,
+    /// The text delivered to the target app
+    TranscriptionCompleted {
+        text: String,
+    },
+    /// The dictation finished, was cancelled or failed
+    DictationEnded,
+    Error {
+        source: ErrorSource,
+        message: String,
+    },
+    SettingsChanged(SettingsChanged),
+    /// A calendar meeting started while Handy was idle, see
+    /// `meeting_detection`
+    MeetingStarted {
+        title: String,
+        calendar: String,
+    },
+    /// macOS Secure Input turned on or off, see `secure_input`. `process`
+    /// is the app holding it, when known.
+    SecureInputChanged {
+        enabled: bool,
+        process: Option<String>,
+    },
+    /// A model failed to load and may be corrupted. `verified` is set when
+    /// it no longer matches its checksum, otherwise corruption is only the
+    /// likely cause. `repair_model` downloads it again.
+    ModelCorrupted {
+        model_id: String,
+        name: String,
+        verified: bool,
+    },
+    /// A model is loading, sent as it starts, periodically while it runs and
+    /// once it's ready or failed
+    ModelLoading(ModelLoadingEvent),
+    /// The watchdog cancelled a stuck dictation, see `watchdog`
+    StuckStateRecovered {
+        state: StuckState,
+        message: String,
+    },
+    /// A shortcut with the fn key was registered, but won't fire without
+    /// the `missing` permissions. `open_permission_settings` opens each.
+    ShortcutPermissionsMissing {
+        binding_id: String,
+        missing: Vec<SystemPermission>,
+    },
+    /// The dictation moved between states, see `dictation`. Sent after the
+    /// tray and overlay show `to`.
+    DictationStateChanged {
+        from: DictationState,
+        to: DictationState,
+    },
+    /// Handy was paused or resumed, see `pause`
+    PauseChanged {
+        paused: bool,
+    },
+    /// Another profile was switched to, see `profiles`
+    ProfileChanged {
+        profile_id: String,
+    },
+    /// The settings file was changed outside Handy and reloaded, see
+    /// `settings_watcher`
+    SettingsReloaded,
+    /// Session incognito was switched on or off, see `incognito`
+    IncognitoChanged {
+        enabled: bool,
+    },
+    /// Whether the dictation the overlay shows is incognito
+    OverlayIncognito {
+        active: bool,
+    },
+}
+
+/// Where a dictation is, for integrations that show it, like a button icon
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DictationState {
+    Idle,
+    Recording,
+    Transcribing,
+    PostProcessing,
+}
+
+impl DictationState {
+    /// The state after `event`, if it changes it
+    pub fn after(event: &AppEvent) -> Option<Self> {
+        match event {
+            AppEvent::RecordingStarted => Some(Self::Recording),
+            AppEvent::TranscriptionStarted => Some(Self::Transcribing),
+            AppEvent::PostProcessingStarted => Some(Self::PostProcessing),
+            AppEvent::DictationEnded => Some(Self::Idle),
+            _ => None,
+        }
+    }
+
+    /// Where the dictation is now, for clients that connect mid-dictation
+    pub fn current() -> Self {
+        dictation::current()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Recording => "recording",
+            Self::Transcribing => "transcribing",
+            Self::PostProcessing => "post_processing",
+        }
+    }
+}
+
+pub fn emit(app: &AppHandle, event: AppEvent) {
+    if let Err(e) = event.emit(app) {
+        warn!("Failed to emit app event: {}", e);
+    }
+}
+
+/// Sends an `error` event and keeps a redacted report of it, see
+/// `error_reports`
+pub fn emit_error(app: &AppHandle, source: ErrorSource, message: impl Into<String>) {
+    let message = message.into();
+    error_reports::record(app, source, &message);
+    emit(app, AppEvent::Error { source, message });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn events_are_tagged_by_type() {
+        assert_eq!(
+            serde_json::to_value(AppEvent::RecordingStarted).unwrap(),
+            json!({ "type": "recording_started" })
+        );
+        assert_eq!(
+            serde_json::to_value(AppEvent::Error {
+                source: ErrorSource::Paste,
+                message: "No focused window".to_string(),
+            })
+            .unwrap(),
+            json!({ "type": "error", "source": "paste", "message": "No focused window" })
+        );
+        let timer = RecordingTimer {
+            elapsed_ms: 1500,
+            limit_ms: None,
+            warning: false,
+        };
+        assert_eq!(
+            serde_json::to_value(AppEvent::RecordingTimer(timer)).unwrap(),
+            json!({ "type": "recording_timer", "elapsed_ms": 1500, "limit_ms": null, "warning": false })
+        );
+    }
+
+    #[test]
+    fn follows_dictation_events() {
+        assert_eq!(
+            DictationState::after(&AppEvent::TranscriptionStarted),
+            Some(DictationState::Transcribing)
+        );
+        assert_eq!(
+            DictationState::after(&AppEvent::DictationEnded),
+            Some(DictationState::Idle)
+        );
+        assert_eq!(
+            DictationState::after(&AppEvent::Transcribed {
+                text: String::new()
+            }),
+            None
+        );
+        assert_eq!(
+            serde_json::to_value(DictationState::PostProcessing).unwrap(),
+            json!(DictationState::PostProcessing.as_str())
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! gRPC control API, for integrations that want generated, typed clients.
+//!
+//! Built with the `grpc` feature. With `api_server.grpc` set, it listens on
+//! `127.0.0.1` at `api_server.grpc_port` and serves `handy.v1.Handy` from
+//! `proto/handy/v1/handy.proto`: the same commands as the HTTP API, history
+//! queries and a stream of dictation events. Calls authenticate with the
+//! HTTP API token as `authorization: Bearer <token>` metadata.
+
+use crate::api_server;
+use crate::audio_toolkit::decode_audio_samples;
+use crate::events::{AppEvent, DictationState};
+use crate::managers::history::{self, HistoryManager};
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::get_settings;
+use crate::signal_handle;
+use crate::utils;
+use log::{debug, error, info, warn};
+use once_cell::sync::Lazy;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Listener, Manager};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tonic::{Request, Response, Status};
+
+mod proto {
+    tonic::include_proto!("handy.v1");
+}
+
+use proto::handy_server::{Handy, HandyServer};
+
+/// Trigger name for calls from gRPC clients in the logs
+const SOURCE: &str = "gRPC";
+
+const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// Events a slow stream client may fall behind by before it misses some
+const EVENT_BUFFER: usize = 64;
+
+/// Stops the running server
+static SHUTDOWN: Lazy<Mutex<Option<oneshot::Sender<()>>>> = Lazy::new(|| Mutex::new(None));
+
+/// Shuts the gRPC server down and brings it back up if `api_server.grpc` is
+/// still set, so a new port takes effect
+pub fn restart(app: &AppHandle) -> Result<(), String> {
+    let mut shutdown = SHUTDOWN.lock().unwrap();
+    if let Some(running) = shutdown.take() {
+        let _ = running.send(());
+        info!("Stopped gRPC server");
+    }
+
+    let settings = get_settings(app).api_server;
+    if !settings.grpc {
+        return Ok(());
+    }
+    api_server::token()?;
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, settings.grpc_port));
+    let listener = TcpListener::bind(addr)
+        .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+        .map_err(|e| format!("Failed to start gRPC server on {}: {}", addr, e))?;
+    info!("gRPC server listening on {}", addr);
+
+    let (stop, stopped) = oneshot::channel();
+    let service = HandyServer::with_interceptor(Service { app: app.clone() }, check_token);
+    tauri::async_runtime::spawn(async move {
+        let incoming = match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => TcpListenerStream::new(listener),
+            Err(e) => {
+                error!("Failed to start gRPC server on {}: {}", addr, e);
+                return;
+            }
+        };
+        let served = tonic::transport::Server::builder()
+            .add_service(service)
+            .serve_with_incoming_shutdown(incoming, async {
+                let _ = stopped.await;
+            })
+            .await;
+        if let Err(e) = served {
+            warn!("gRPC server failed: {}", e);
+        }
+        debug!("gRPC server exited");
+    });
+    *shutdown = Some(stop);
+    Ok(())
+}
+
+fn check_token(request: Request<()>) -> Result<Request<()>, Status> {
+    let token = api_server::stored_token().unwrap_or_default();
+    let authorization = request
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok());
+    if !token.is_empty() && api_server::authorized(authorization, &token) {
+        Ok(request)
+    } else {
+        Err(Status::unauthenticated("Missing or invalid API token"))
+    }
+}
+
+fn entry_message(entry: history::HistoryEntry) -> proto::HistoryEntry {
+    proto::HistoryEntry {
+        id: entry.id,
+        text: entry
+            .post_processed_text
+            .clone()
+            .unwrap_or_else(|| entry.transcription_text.clone()),
+        transcription_text: entry.transcription_text,
+        post_processed_text: entry.post_processed_text,
+        timestamp: entry.timestamp,
+        duration_ms: entry.duration_ms,
+        model: entry.model,
+        app_name: entry.target_app,
+        window_title: entry.window_title,
+    }
+}
+
+fn state_event(state: DictationState) -> proto::Event {
+    let state = match state {
+        DictationState::Idle => proto::DictationState::Idle,
+        DictationState::Recording => proto::DictationState::Recording,
+        DictationState::Transcribing => proto::DictationState::Transcribing,
+        DictationState::PostProcessing => proto::DictationState::PostProcessing,
+    };
+    proto::Event {
+        event: Some(proto::event::Event::State(state.into())),
+    }
+}
+
+/// The stream message for `event`, if clients get one
+fn event_message(event: &AppEvent) -> Option<proto::Event> {
+    use proto::event::Event;
+
+    if let Some(state) = DictationState::after(event) {
+        return Some(state_event(state));
+    }
+    let event = match event {
+        AppEvent::Transcribed { text } => Event::Transcribed(text.clone()),
+        AppEvent::TranscriptionCompleted { text } => Event::Completed(text.clone()),
+        AppEvent::Error { message, .. } => Event::Error(message.clone()),
+        _ => return None,
+    };
+    Some(proto::Event { event: Some(event) })
+}
+
+struct Service {
+    app: AppHandle,
+}
+
+#[tonic::async_trait]
+impl Handy for Service {
+    async fn start_recording(
+        &self,
+        request: Request<proto::StartRecordingRequest>,
+    ) -> Result<Response<proto::StartRecordingResponse>, Status> {
+        signal_handle::start_recording(&self.app, request.into_inner().post_process, SOURCE)
+            .map_err(Status::failed_precondition)?;
+        Ok(Response::new(proto::StartRecordingResponse {}))
+    }
+
+    async fn stop_recording(
+        &self,
+        _request: Request<proto::StopRecordingRequest>,
+    ) -> Result<Response<proto::StopRecordingResponse>, Status> {
+        signal_handle::stop_recording(&self.app, SOURCE).map_err(Status::failed_precondition)?;
+        Ok(Response::new(proto::StopRecordingResponse {}))
+    }
+
+    async fn cancel_recording(
+        &self,
+        _request: Request<proto::CancelRecordingRequest>,
+    ) -> Result<Response<proto::CancelRecordingResponse>, Status> {
+        utils::cancel_current_operation(&self.app);
+        Ok(Response::new(proto::CancelRecordingResponse {}))
+    }
+
+    async fn transcribe(
+        &self,
+        request: Request<proto::TranscribeRequest>,
+    ) -> Result<Response<proto::TranscribeResponse>, Status> {
+        let request = request.into_inner();
+        let app = self.app.clone();
+        let text = tauri::async_runtime::spawn_blocking(move || {
+            let samples = decode_audio_samples(request.audio, request.format.as_deref())
+                .map_err(|e| Status::invalid_argument(format!("Unsupported audio file: {}", e)))?;
+            let tm = app.state::<Arc<TranscriptionManager>>();
+            tm.initiate_model_load();
+            tm.transcribe(Arc::new(samples), &get_settings(&app))
+                .map(|output| output.full_text())
+                .map_err(|e| Status::internal(format!("Transcription failed: {}", e)))
+        })
+        .await
+        .map_err(|e| Status::internal(e.to_string()))??;
+        Ok(Response::new(proto::TranscribeResponse { text }))
+    }
+
+    async fn get_last_result(
+        &self,
+        _request: Request<proto::GetLastResultRequest>,
+    ) -> Result<Response<proto::HistoryEntry>, Status> {
+        match self.app.state::<Arc<HistoryManager>>().get_latest_entry() {
+            Ok(Some(entry)) => Ok(Response::new(entry_message(entry))),
+            Ok(None) => Err(Status::not_found("No transcriptions yet")),
+            Err(e) => {
+                error!("Failed to read last transcription for gRPC: {}", e);
+                Err(Status::internal("Failed to read history"))
+            }
+        }
+    }
+
+    async fn list_history(
+        &self,
+        request: Request<proto::ListHistoryRequest>,
+    ) -> Result<Response<proto::ListHistoryResponse>, Status> {
+        let request = request.into_inner();
+        let limit = match request.limit {
+            0 => DEFAULT_PAGE_SIZE,
+            limit => limit,
+        };
+        let hm = Arc::clone(self.app.state::<Arc<HistoryManager>>().inner());
+        let page = hm
+            .get_history_page(
+                request.offset as usize,
+                limit as usize,
+                request.search,
+                request.app,
+            )
+            .await
+            .map_err(|e| {
+                error!("Failed to read history for gRPC: {}", e);
+                Status::internal("Failed to read history")
+            })?;
+        Ok(Response::new(proto::ListHistoryResponse {
+            entries: page.entries.into_iter().map(entry_message).collect(),
+            total: page.total,
+        }))
+    }
+
+    type StreamEventsStream = ReceiverStream<Result<proto::Event, Status>>;
+
+    async fn stream_events(
+        &self,
+        _request: Request<proto::StreamEventsRequest>,
+    ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        let (tx, rx) = mpsc::channel(EVENT_BUFFER);
+        let _ = tx.try_send(Ok(state_event(DictationState::current())));
+
+        let sender = tx.clone();
+        let listener = self.app.listen_any("app-event", move |event| {
+            let message = serde_json::from_str::<AppEvent>(event.payload())
+                .ok()
+                .and_then(|event| event_message(&event));
+            if let Some(message) = message {
+                let _ = sender.try_send(Ok(message));
+            }
+        });
+        debug!("gRPC client subscribed to events");
+
+        let app = self.app.clone();
+        tauri::async_runtime::spawn(async move {
+            tx.closed().await;
+            app.unlisten(listener);
+            debug!("gRPC client unsubscribed from events");
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
@@ -0,0 +1,167 @@
+//! Exports transcription history as CSV, JSON or a Markdown digest.
+
+use crate::managers::history::HistoryEntry;
+use chrono::{DateTime, TimeZone};
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            "markdown" => Ok(Self::Markdown),
+            other => Err(format!("Unsupported export format: {}", other)),
+        }
+    }
+}
+
+/// Final text of an entry: post-processed when available, otherwise raw
+fn entry_text(entry: &HistoryEntry) -> &str {
+    entry
+        .post_processed_text
+        .as_deref()
+        .unwrap_or(&entry.transcription_text)
+}
+
+fn local_time<Tz: TimeZone>(timestamp: i64, tz: &Tz) -> Option<DateTime<Tz>> {
+    DateTime::from_timestamp(timestamp, 0).map(|utc| utc.with_timezone(tz))
+}
+
+/// Quotes a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Renders entries in the order given, in `tz`.
+pub fn export<Tz: TimeZone>(
+    entries: &[HistoryEntry],
+    format: ExportFormat,
+    tz: &Tz,
+) -> Result<String, String>
+where
+    Tz::Offset: Display,
+{
+    match format {
+        ExportFormat::Csv => Ok(to_csv(entries, tz)),
+        ExportFormat::Json => serde_json::to_string_pretty(entries)
+            .map_err(|e| format!("Failed to serialize history: {}", e)),
+        ExportFormat::Markdown => Ok(to_markdown(entries, tz)),
+    }
+}
+
+fn to_csv<Tz: TimeZone>(entries: &[HistoryEntry], tz: &Tz) -> String
+where
+    Tz::Offset: Display,
+{
+    let mut csv = String::from(
+        "id,time,transcription,post_processed,duration_ms,model,target_app,window_title,saved\r\n",
+    );
+    for entry in entries {
+        let time = local_time(entry.timestamp, tz)
+            .map(|time| time.to_rfc3339())
+            .unwrap_or_default();
+        let fields = [
+            entry.id.to_string(),
+            time,
+            csv_field(&entry.transcription_text),
+            csv_field(entry.post_processed_text.as_deref().unwrap_or_default()),
+            entry
+                .duration_ms
+                .map(|ms| ms.to_string())
+                .unwrap_or_default(),
+            csv_field(entry.model.as_deref().unwrap_or_default()),
+            csv_field(entry.target_app.as_deref().unwrap_or_default()),
+            csv_field(entry.window_title.as_deref().unwrap_or_default()),
+            entry.saved.to_string(),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// One section per day, with each transcript under its time of day
+fn to_markdown<Tz: TimeZone>(entries: &[HistoryEntry], tz: &Tz) -> String
+where
+    Tz::Offset: Display,
+{
+    let mut markdown = String::from("# Handy Transcripts\n");
+    let mut current_day = String::new();
+    for entry in entries {
+        let Some(time) = local_time(entry.timestamp, tz) else {
+            continue;
+        };
+
+        let day = time.format("%Y-%m-%d").to_string();
+        if day != current_day {
+            markdown.push_str(&format!("\n## {}\n", time.format("%A, %B %-d, %Y")));
+            current_day = day;
+        }
+
+        markdown.push_str(&format!("\n### {}", time.format("%H:%M")));
+        if let Some(app) = &entry.target_app {
+            markdown.push_str(&format!(" · {}", app));
+        }
+        markdown.push_str(&format!("\n\n{}\n", entry_text(entry).trim()));
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn entry(id: i64, timestamp: i64, text: &str, target_app: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            id,
+            file_name: format!("handy-{}.wav", timestamp),
+            timestamp,
+            saved: false,
+            title: String::new(),
+            transcription_text: text.to_string(),
+            post_processed_text: None,
+            post_process_prompt: None,
+            duration_ms: Some(1200),
+            model: None,
+            target_app: target_app.map(str::to_string),
+            window_title: None,
+        }
+    }
+
+    #[test]
+    fn csv_quotes_special_fields() {
+        let entries = [entry(1, 0, "hello, \"world\"\nbye", None)];
+        let csv = export(&entries, ExportFormat::Csv, &Utc).unwrap();
+        assert_eq!(
+            csv.lines().nth(1).unwrap(),
+            "1,1970-01-01T00:00:00+00:00,\"hello, \"\"world\"\""
+        );
+    }
+
+    #[test]
+    fn markdown_groups_entries_by_day() {
+        let day = 24 * 60 * 60;
+        let entries = [
+            entry(1, 9 * 3600, "first", Some("Notes")),
+            entry(2, 10 * 3600, "second", None),
+            entry(3, day + 8 * 3600, "third", None),
+        ];
+        let markdown = export(&entries, ExportFormat::Markdown, &Utc).unwrap();
+
+        assert_eq!(markdown.matches("\n## ").count(), 2);
+        assert!(markdown.contains("## Thursday, January 1, 1970\n\n### 09:00 · Notes\n\nfirst\n"));
+        assert!(markdown.contains("## Friday, January 2, 1970\n\n### 08:00\n\nthird\n"));
+    }
+}
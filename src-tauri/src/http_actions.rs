@@ -0,0 +1,208 @@
+//! HTTP requests built from user templates, sent after each dictation.
+//!
+//! An action's URL, header values and body can use `{{transcript}}`,
+//! `{{raw_transcript}}`, `{{app}}`, `{{window_title}}`, `{{model}}`,
+//! `{{timestamp}}`, `{{date}}`, `{{time}}` and `{{duration_ms}}`. Values are
+//! percent-encoded in the URL and escaped as JSON string content in the body,
+//! so a body like `{"text": "{{transcript}}"}` stays valid whatever was said.
+//! Like webhooks, actions only run for dictations that are saved to history.
+
+use crate::policy;
+use crate::settings::{HttpAction, HttpMethod};
+use crate::webhooks::TranscriptionPayload;
+use chrono::{DateTime, Local};
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+static CLIENT: Lazy<Client> = Lazy::new(|| {
+    Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+fn local_time(timestamp: i64) -> DateTime<Local> {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .with_timezone(&Local)
+}
+
+/// Value of a template variable, `None` for unknown names
+fn variable(payload: &TranscriptionPayload, name: &str) -> Option<String> {
+    Some(match name {
+        "transcript" => payload.text.clone(),
+        "raw_transcript" => payload.transcription_text.clone(),
+        "app" => payload.app_name.clone().unwrap_or_default(),
+        "window_title" => payload.window_title.clone().unwrap_or_default(),
+        "model" => payload.model.clone().unwrap_or_default(),
+        "timestamp" => payload.timestamp.to_string(),
+        "date" => local_time(payload.timestamp).format("%Y-%m-%d").to_string(),
+        "time" => local_time(payload.timestamp).format("%H:%M").to_string(),
+        "duration_ms" => payload.duration_ms.to_string(),
+        _ => return None,
+    })
+}
+
+/// Fills in `{{name}}` variables, passing each value through `escape`.
+/// Unknown variables are left as written, and values are never expanded
+/// again, so braces in dictated text stay as they are.
+pub(crate) fn render(
+    template: &str,
+    payload: &TranscriptionPayload,
+    escape: impl Fn(&str) -> String,
+) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        rendered.push_str(&rest[..open]);
+        let after = &rest[open + 2..];
+        let Some(close) = after.find("}}") else {
+            rest = &rest[open..];
+            break;
+        };
+        match variable(payload, after[..close].trim()) {
+            Some(value) => rendered.push_str(&escape(&value)),
+            None => rendered.push_str(&rest[open..open + 2 + close + 2]),
+        }
+        rest = &after[close + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn escape_json(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+pub(crate) fn escape_url(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Header values can't hold line breaks
+fn escape_header(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+fn sample_payload() -> TranscriptionPayload {
+    TranscriptionPayload {
+        event: TranscriptionPayload::EVENT,
+        text: "Hello \"world\"".to_string(),
+        transcription_text: "hello world".to_string(),
+        post_processed_text: None,
+        post_process_prompt: None,
+        timestamp: 0,
+        duration_ms: 0,
+        model: None,
+        app_name: None,
+        window_title: None,
+        post_process_retries: 0,
+    }
+}
+
+/// Checks that the URL renders to an http(s) URL and the body, if any, to
+/// JSON
+pub fn validate(action: &HttpAction) -> Result<(), String> {
+    let payload = sample_payload();
+    let url = render(&action.url, &payload, escape_url);
+    match tauri::Url::parse(&url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+        _ => return Err(format!("'{}' is not an http(s) URL", action.url)),
+    }
+    if let Some(body) = action.body.as_deref().filter(|b| !b.trim().is_empty()) {
+        serde_json::from_str::<serde_json::Value>(&render(body, &payload, escape_json))
+            .map_err(|e| format!("The body is not valid JSON: {}", e))?;
+    }
+    for header in &action.headers {
+        if header.name.trim().is_empty() {
+            return Err("Header names cannot be empty".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Sends every enabled action in the background
+pub fn run_all(actions: &[HttpAction], payload: &TranscriptionPayload) {
+    for action in actions
+        .iter()
+        .filter(|action| action.enabled && policy::allows_destination(&action.url))
+    {
+        let action = action.clone();
+        let payload = payload.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = run(&action, &payload).await {
+                warn!("HTTP action '{}' failed: {}", action.name, e);
+            }
+        });
+    }
+}
+
+async fn run(action: &HttpAction, payload: &TranscriptionPayload) -> Result<(), String> {
+    let method = match action.method {
+        HttpMethod::Get => reqwest::Method::GET,
+        HttpMethod::Post => reqwest::Method::POST,
+        HttpMethod::Put => reqwest::Method::PUT,
+        HttpMethod::Patch => reqwest::Method::PATCH,
+        HttpMethod::Delete => reqwest::Method::DELETE,
+    };
+    let mut request = CLIENT.request(method, render(&action.url, payload, escape_url));
+
+    let mut has_content_type = false;
+    for header in &action.headers {
+        has_content_type |= header.name.eq_ignore_ascii_case("content-type");
+        request = request.header(
+            header.name.trim(),
+            render(&header.value, payload, escape_header),
+        );
+    }
+    if let Some(body) = action.body.as_deref().filter(|b| !b.trim().is_empty()) {
+        if !has_content_type {
+            request = request.header("Content-Type", "application/json");
+        }
+        request = request.body(render(body, payload, escape_json));
+    }
+
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("The server answered {}", response.status()));
+    }
+    debug!("Ran HTTP action '{}'", action.name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_templates() {
+        let mut payload = sample_payload();
+        payload.text = "Say \"hi\" {{app}}".to_string();
+        payload.app_name = Some("Notes & more".to_string());
+
+        assert_eq!(
+            render(
+                r#"{"text": "{{ transcript }}", "app": "{{app}}", "x": "{{nope}}"}"#,
+                &payload,
+                escape_json
+            ),
+            r#"{"text": "Say \"hi\" {{app}}", "app": "Notes & more", "x": "{{nope}}"}"#
+        );
+        assert_eq!(
+            render("https://example.com/?q={{app}}&t={{", &payload, escape_url),
+            "https://example.com/?q=Notes%20%26%20more&t={{"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Incognito dictation.
+//!
+//! Incognito transcriptions are delivered as usual but never saved to history,
+//! the recordings folder or the daily journal, and post-processing only runs
+//! when the provider is on this machine. It is either switched on for the
+//! session or used for a single dictation through the `transcribe_incognito`
+//! binding. The session state is never persisted, so Handy always starts with
+//! it off unless a managed policy forces it on.
+
+use crate::events::{self, AppEvent};
+use crate::policy;
+use crate::settings::{PostProcessProvider, APPLE_INTELLIGENCE_PROVIDER_ID};
+use log::info;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::AppHandle;
+
+static SESSION_INCOGNITO: AtomicBool = AtomicBool::new(false);
+
+pub fn is_session_enabled() -> bool {
+    SESSION_INCOGNITO.load(Ordering::Relaxed) || policy::forces_incognito()
+}
+
+/// Switches session incognito on or off, then refreshes the tray menu and
+/// notifies the frontend with an `IncognitoChanged` event.
+pub fn set_session_enabled(app: &AppHandle, enabled: bool) {
+    SESSION_INCOGNITO.store(enabled, Ordering::Relaxed);
+    info!(
+        "Incognito dictation {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    crate::dictation::refresh_tray_menu(app);
+    events::emit(app, AppEvent::IncognitoChanged { enabled });
+}
+
+/// Tells the recording overlay whether the dictation it shows is incognito
+pub fn show_overlay_indicator(app: &AppHandle, active: bool) {
+    events::emit(app, AppEvent::OverlayIncognito { active });
+}
+
+/// Whether a base URL, or a bare host, points at this machine
+pub fn is_loopback_url(base_url: &str) -> bool {
+    let rest = base_url
+        .split_once("://")
+        .map_or(base_url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = if let Some(bracketed) = authority.strip_prefix('[') {
+        bracketed.split(']').next().unwrap_or_default()
+    } else {
+        authority.split(':').next().unwrap_or_default()
+    };
+
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Whether a post-processing provider keeps the transcript on this machine
+pub fn is_local_provider(provider: &PostProcessProvider) -> bool {
+    provider.id == APPLE_INTELLIGENCE_PROVIDER_ID || is_loopback_url(&provider.base_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_loopback_base_urls() {
+        assert!(is_loopback_url("http://localhost:11434/v1"));
+        assert!(is_loopback_url("http://127.0.0.1:1234/v1"));
+        assert!(is_loopback_url("http://[::1]:8080"));
+        assert!(is_loopback_url("http://user@LOCALHOST/v1"));
+        assert!(!is_loopback_url("https://api.openai.com/v1"));
+        assert!(!is_loopback_url("https://localhost.example.com/v1"));
+        assert!(!is_loopback_url("http://127.evil.com/v1"));
+        assert!(!is_loopback_url("http://127.0.0.1.evil.com"));
+        assert!(is_loopback_url("http://127.1.2.3"));
+    }
+}
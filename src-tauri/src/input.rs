@@ -1,31 +1,195 @@
-use enigo::{Enigo, Key, Keyboard, Mouse, Settings};
+use crate::settings::KeyInjectionSettings;
+use enigo::{Direction, Enigo, Key, Keyboard, Mouse, Settings};
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
 
-/// Wrapper for Enigo to store in Tauri's managed state.
-/// Enigo is wrapped in a Mutex since it requires mutable access.
-pub struct EnigoState(pub Mutex<Enigo>);
+/// Keyboard injection backend used for pasting and typing.
+pub trait KeyInjector: Send {
+    fn key(&mut self, key: Key, direction: Direction) -> Result<(), String>;
+    fn text(&mut self, text: &str) -> Result<(), String>;
+    fn cursor_position(&self) -> Option<(i32, i32)>;
+}
+
+/// Enigo-based injector configured with the platform-specific options from
+/// [`KeyInjectionSettings`].
+pub struct EnigoInjector {
+    enigo: Enigo,
+    inter_key_delay: Duration,
+}
 
-impl EnigoState {
-    pub fn new() -> Result<Self, String> {
-        let enigo = Enigo::new(&Settings::default())
+impl EnigoInjector {
+    pub fn new(config: &KeyInjectionSettings) -> Result<Self, String> {
+        let enigo = Enigo::new(&platform_settings(config))
             .map_err(|e| format!("Failed to initialize Enigo: {}", e))?;
-        Ok(Self(Mutex::new(enigo)))
+        Ok(Self {
+            enigo,
+            inter_key_delay: Duration::from_millis(config.inter_key_delay_ms),
+        })
+    }
+
+    fn pause(&self) {
+        if !self.inter_key_delay.is_zero() {
+            std::thread::sleep(self.inter_key_delay);
+        }
+    }
+}
+
+impl KeyInjector for EnigoInjector {
+    fn key(&mut self, key: Key, direction: Direction) -> Result<(), String> {
+        self.enigo.key(key, direction).map_err(|e| e.to_string())?;
+        self.pause();
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) -> Result<(), String> {
+        if self.inter_key_delay.is_zero() {
+            return self.enigo.text(text).map_err(|e| e.to_string());
+        }
+        let mut buf = [0u8; 4];
+        for c in text.chars() {
+            self.enigo
+                .text(c.encode_utf8(&mut buf))
+                .map_err(|e| e.to_string())?;
+            self.pause();
+        }
+        Ok(())
+    }
+
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        self.enigo.location().ok()
+    }
+}
+
+/// Value placed in `dwExtraInfo` of injected events so they can be told apart
+/// from physical input (ASCII "HNDY").
+#[cfg(target_os = "windows")]
+const WINDOWS_INJECTED_EXTRA_INFO: usize = 0x484E_4459;
+
+#[cfg(target_os = "windows")]
+fn platform_settings(config: &KeyInjectionSettings) -> Settings {
+    Settings {
+        windows_dw_extra_info: config
+            .tag_injected_events
+            .then_some(WINDOWS_INJECTED_EXTRA_INFO),
+        ..Settings::default()
     }
 }
 
-/// Get the current mouse cursor position using the managed Enigo instance.
+/// Posting events independent of the keyboard state keeps modifiers the user
+/// is still physically holding from being merged into the injected keys.
+#[cfg(target_os = "macos")]
+fn platform_settings(config: &KeyInjectionSettings) -> Settings {
+    Settings {
+        independent_of_keyboard_state: config.ignore_held_modifiers,
+        ..Settings::default()
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn platform_settings(_config: &KeyInjectionSettings) -> Settings {
+    Settings::default()
+}
+
+/// Managed state holding the active key injector. The injector sits behind a
+/// Mutex since it requires mutable access, and can be swapped when the
+/// injection settings change.
+pub struct InjectorState(pub Mutex<Box<dyn KeyInjector>>);
+
+impl InjectorState {
+    pub fn new(config: &KeyInjectionSettings) -> Result<Self, String> {
+        let injector = EnigoInjector::new(config)?;
+        Ok(Self(Mutex::new(Box::new(injector))))
+    }
+
+    /// Recreates the injector with new settings
+    pub fn reconfigure(&self, config: &KeyInjectionSettings) -> Result<(), String> {
+        let injector = EnigoInjector::new(config)?;
+        *self
+            .0
+            .lock()
+            .map_err(|e| format!("Failed to lock key injector: {}", e))? = Box::new(injector);
+        Ok(())
+    }
+}
+
+/// Get the current mouse cursor position using the managed injector.
 /// Returns None if the state is not available or if getting the location fails.
 pub fn get_cursor_position(app_handle: &AppHandle) -> Option<(i32, i32)> {
-    let enigo_state = app_handle.try_state::<EnigoState>()?;
-    let enigo = enigo_state.0.lock().ok()?;
-    enigo.location().ok()
+    let state = app_handle.try_state::<InjectorState>()?;
+    let injector = state.0.lock().ok()?;
+    injector.cursor_position()
+}
+
+/// Waits until the user has let go of modifier keys (e.g. from the shortcut
+/// that stopped recording) so they don't combine with the first injected keys.
+/// Gives up after `timeout_ms`. Where held modifiers can't be queried the full
+/// timeout is used as a fixed settling delay.
+pub fn wait_for_modifier_release(timeout_ms: u64) {
+    if timeout_ms == 0 {
+        return;
+    }
+    let timeout = Duration::from_millis(timeout_ms);
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        match modifiers_held() {
+            Some(false) => return,
+            Some(true) => std::thread::sleep(Duration::from_millis(5)),
+            None => {
+                std::thread::sleep(timeout);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn modifiers_held() -> Option<bool> {
+    const COMBINED_SESSION_STATE: i32 = 0;
+    // Shift, Control, Option and Command
+    const MODIFIER_MASK: u64 = 0x0002_0000 | 0x0004_0000 | 0x0008_0000 | 0x0010_0000;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceFlagsState(state_id: i32) -> u64;
+    }
+
+    let flags = unsafe { CGEventSourceFlagsState(COMBINED_SESSION_STATE) };
+    Some(flags & MODIFIER_MASK != 0)
+}
+
+#[cfg(target_os = "windows")]
+fn modifiers_held() -> Option<bool> {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        GetAsyncKeyState, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT,
+    };
+
+    let held = [VK_SHIFT, VK_CONTROL, VK_MENU, VK_LWIN, VK_RWIN]
+        .iter()
+        .any(|vk| unsafe { GetAsyncKeyState(i32::from(vk.0)) } as u16 & 0x8000 != 0);
+    Some(held)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn modifiers_held() -> Option<bool> {
+    None
+}
+
+/// Sends key-up events for all modifiers in case the target app still
+/// considers one of them pressed.
+pub fn release_modifiers(injector: &mut dyn KeyInjector) -> Result<(), String> {
+    for key in [Key::Shift, Key::Control, Key::Alt, Key::Meta] {
+        injector
+            .key(key, Direction::Release)
+            .map_err(|e| format!("Failed to release modifier key: {}", e))?;
+    }
+    Ok(())
 }
 
 /// Sends a Ctrl+V or Cmd+V paste command using platform-specific virtual key codes.
 /// This ensures the paste works regardless of keyboard layout (e.g., Russian, AZERTY, DVORAK).
 /// Note: On Wayland, this may not work - callers should check for Wayland and use alternative methods.
-pub fn send_paste_ctrl_v(enigo: &mut Enigo) -> Result<(), String> {
+pub fn send_paste_ctrl_v(injector: &mut dyn KeyInjector) -> Result<(), String> {
     // Platform-specific key definitions
     #[cfg(target_os = "macos")]
     let (modifier_key, v_key_code) = (Key::Meta, Key::Other(9));
@@ -35,17 +199,17 @@ pub fn send_paste_ctrl_v(enigo: &mut Enigo) -> Result<(), String> {
     let (modifier_key, v_key_code) = (Key::Control, Key::Unicode('v'));
 
     // Press modifier + V
-    enigo
-        .key(modifier_key, enigo::Direction::Press)
+    injector
+        .key(modifier_key, Direction::Press)
         .map_err(|e| format!("Failed to press modifier key: {}", e))?;
-    enigo
-        .key(v_key_code, enigo::Direction::Click)
+    injector
+        .key(v_key_code, Direction::Click)
         .map_err(|e| format!("Failed to click V key: {}", e))?;
 
     std::thread::sleep(std::time::Duration::from_millis(100));
 
-    enigo
-        .key(modifier_key, enigo::Direction::Release)
+    injector
+        .key(modifier_key, Direction::Release)
         .map_err(|e| format!("Failed to release modifier key: {}", e))?;
 
     Ok(())
@@ -54,7 +218,7 @@ pub fn send_paste_ctrl_v(enigo: &mut Enigo) -> Result<(), String> {
 /// Sends a Ctrl+Shift+V paste command.
 /// This is commonly used in terminal applications on Linux to paste without formatting.
 /// Note: On Wayland, this may not work - callers should check for Wayland and use alternative methods.
-pub fn send_paste_ctrl_shift_v(enigo: &mut Enigo) -> Result<(), String> {
+pub fn send_paste_ctrl_shift_v(injector: &mut dyn KeyInjector) -> Result<(), String> {
     // Platform-specific key definitions
     #[cfg(target_os = "macos")]
     let (modifier_key, v_key_code) = (Key::Meta, Key::Other(9)); // Cmd+Shift+V on macOS
@@ -64,23 +228,23 @@ pub fn send_paste_ctrl_shift_v(enigo: &mut Enigo) -> Result<(), String> {
     let (modifier_key, v_key_code) = (Key::Control, Key::Unicode('v'));
 
     // Press Ctrl/Cmd + Shift + V
-    enigo
-        .key(modifier_key, enigo::Direction::Press)
+    injector
+        .key(modifier_key, Direction::Press)
         .map_err(|e| format!("Failed to press modifier key: {}", e))?;
-    enigo
-        .key(Key::Shift, enigo::Direction::Press)
+    injector
+        .key(Key::Shift, Direction::Press)
         .map_err(|e| format!("Failed to press Shift key: {}", e))?;
-    enigo
-        .key(v_key_code, enigo::Direction::Click)
+    injector
+        .key(v_key_code, Direction::Click)
         .map_err(|e| format!("Failed to click V key: {}", e))?;
 
     std::thread::sleep(std::time::Duration::from_millis(100));
 
-    enigo
-        .key(Key::Shift, enigo::Direction::Release)
+    injector
+        .key(Key::Shift, Direction::Release)
         .map_err(|e| format!("Failed to release Shift key: {}", e))?;
-    enigo
-        .key(modifier_key, enigo::Direction::Release)
+    injector
+        .key(modifier_key, Direction::Release)
         .map_err(|e| format!("Failed to release modifier key: {}", e))?;
 
     Ok(())
@@ -89,33 +253,68 @@ pub fn send_paste_ctrl_shift_v(enigo: &mut Enigo) -> Result<(), String> {
 /// Sends a Shift+Insert paste command (Windows and Linux only).
 /// This is more universal for terminal applications and legacy software.
 /// Note: On Wayland, this may not work - callers should check for Wayland and use alternative methods.
-pub fn send_paste_shift_insert(enigo: &mut Enigo) -> Result<(), String> {
+pub fn send_paste_shift_insert(injector: &mut dyn KeyInjector) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     let insert_key_code = Key::Other(0x2D); // VK_INSERT
     #[cfg(not(target_os = "windows"))]
     let insert_key_code = Key::Other(0x76); // XK_Insert (keycode 118 / 0x76, also used as fallback)
 
     // Press Shift + Insert
-    enigo
-        .key(Key::Shift, enigo::Direction::Press)
+    injector
+        .key(Key::Shift, Direction::Press)
         .map_err(|e| format!("Failed to press Shift key: {}", e))?;
-    enigo
-        .key(insert_key_code, enigo::Direction::Click)
+    injector
+        .key(insert_key_code, Direction::Click)
         .map_err(|e| format!("Failed to click Insert key: {}", e))?;
 
     std::thread::sleep(std::time::Duration::from_millis(100));
 
-    enigo
-        .key(Key::Shift, enigo::Direction::Release)
+    injector
+        .key(Key::Shift, Direction::Release)
         .map_err(|e| format!("Failed to release Shift key: {}", e))?;
 
     Ok(())
 }
 
-/// Pastes text directly using the enigo text method.
+/// Sends Cmd+Z on macOS or Ctrl+Z elsewhere, using virtual key codes like the paste commands.
+pub fn send_undo(injector: &mut dyn KeyInjector) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let (modifier_key, z_key_code) = (Key::Meta, Key::Other(6)); // kVK_ANSI_Z
+    #[cfg(target_os = "windows")]
+    let (modifier_key, z_key_code) = (Key::Control, Key::Other(0x5A)); // VK_Z
+    #[cfg(target_os = "linux")]
+    let (modifier_key, z_key_code) = (Key::Control, Key::Unicode('z'));
+
+    injector
+        .key(modifier_key, Direction::Press)
+        .map_err(|e| format!("Failed to press modifier key: {}", e))?;
+    injector
+        .key(z_key_code, Direction::Click)
+        .map_err(|e| format!("Failed to click Z key: {}", e))?;
+
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    injector
+        .key(modifier_key, Direction::Release)
+        .map_err(|e| format!("Failed to release modifier key: {}", e))?;
+
+    Ok(())
+}
+
+/// Presses Backspace `count` times.
+pub fn send_backspaces(injector: &mut dyn KeyInjector, count: usize) -> Result<(), String> {
+    for _ in 0..count {
+        injector
+            .key(Key::Backspace, Direction::Click)
+            .map_err(|e| format!("Failed to click Backspace key: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Pastes text directly using the injector's text method.
 /// This tries to use system input methods if possible, otherwise simulates keystrokes one by one.
-pub fn paste_text_direct(enigo: &mut Enigo, text: &str) -> Result<(), String> {
-    enigo
+pub fn paste_text_direct(injector: &mut dyn KeyInjector, text: &str) -> Result<(), String> {
+    injector
         .text(text)
         .map_err(|e| format!("Failed to send text directly: {}", e))?;
 
@@ -1,27 +1,73 @@
+mod accessibility;
 mod actions;
+mod active_app;
+mod api_server;
+mod app_overrides;
 #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
 mod apple_intelligence;
 mod audio_feedback;
 pub mod audio_toolkit;
 pub mod cli;
+pub mod cli_client;
 mod clipboard;
 mod commands;
+mod data_dirs;
+mod deep_link;
+mod dictation;
+mod email;
+mod encryption;
+mod error_reports;
+mod events;
+#[cfg(feature = "grpc")]
+mod grpc;
 mod helpers;
+mod history_export;
+mod http_actions;
+mod incognito;
 mod input;
 mod llm_client;
+mod log_stream;
 mod managers;
+mod markdown;
+mod mcp;
+mod meeting_detection;
+mod mqtt;
+#[cfg(windows)]
+mod named_pipe;
+mod note_targets;
+mod notification_i18n;
+mod notifications;
+mod obs_captions;
 mod overlay;
+mod pause;
+mod policy;
+mod post_process_queue;
+mod preview;
+mod profiles;
+#[cfg(target_os = "macos")]
+mod scripting;
+mod secrets;
+#[cfg(target_os = "macos")]
+mod secure_input;
 mod settings;
+mod settings_backups;
+mod settings_migration;
+mod settings_watcher;
 mod shortcut;
 mod signal_handle;
+mod smart_insert;
+mod stream_deck;
+mod transcript_output;
 mod transcription_coordinator;
 mod tray;
 mod tray_i18n;
 mod utils;
+mod watchdog;
+mod webhooks;
 
 pub use cli::CliArgs;
 use specta_typescript::{BigIntExportBehavior, Typescript};
-use tauri_specta::{collect_commands, Builder};
+use tauri_specta::{collect_commands, collect_events, Builder};
 
 use env_filter::Builder as EnvFilterBuilder;
 use managers::audio::AudioRecordingManager;
@@ -94,28 +140,26 @@ fn show_main_window(app: &AppHandle) {
         }
         // Optional: On macOS, ensure the app becomes active if it was an accessory
         #[cfg(target_os = "macos")]
-        {
-            if let Err(e) = app.set_activation_policy(tauri::ActivationPolicy::Regular) {
-                log::error!("Failed to set activation policy to Regular: {}", e);
-            }
-        }
+        utils::apply_activation_policy(app, true);
     } else {
         log::error!("Main window not found.");
     }
 }
 
-fn initialize_core_logic(app_handle: &AppHandle) {
+fn initialize_core_logic(app_handle: &AppHandle, cli_args: &CliArgs) {
     // Note: Enigo (keyboard/mouse simulation) is NOT initialized here.
     // The frontend is responsible for calling the `initialize_enigo` command
     // after onboarding completes. This avoids triggering permission dialogs
     // on macOS before the user is ready.
 
     // Initialize the managers
+    let model_manager =
+        Arc::new(ModelManager::new(app_handle).expect("Failed to initialize model manager"));
+    // Before the other managers read the settings it overrides
+    cli_args.apply_session_overrides(app_handle, &model_manager);
     let recording_manager = Arc::new(
         AudioRecordingManager::new(app_handle).expect("Failed to initialize recording manager"),
     );
-    let model_manager =
-        Arc::new(ModelManager::new(app_handle).expect("Failed to initialize model manager"));
     let transcription_manager = Arc::new(
         TranscriptionManager::new(app_handle, model_manager.clone())
             .expect("Failed to initialize transcription manager"),
@@ -129,6 +173,9 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     app_handle.manage(transcription_manager.clone());
     app_handle.manage(history_manager.clone());
 
+    // Pick up entries other machines added while this one was off
+    history_manager.sync_in_background();
+
     // Note: Shortcuts are NOT initialized here.
     // The frontend is responsible for calling the `initialize_shortcuts` command
     // after permissions are confirmed (on macOS) or after onboarding completes.
@@ -140,13 +187,33 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     #[cfg(unix)]
     signal_handle::setup_signal_handler(app_handle.clone(), signals);
 
-    // Apply macOS Accessory policy if starting hidden
+    if let Err(e) = api_server::restart(app_handle) {
+        log::error!("{}", e);
+    }
+    #[cfg(windows)]
+    named_pipe::restart(app_handle);
+    #[cfg(feature = "grpc")]
+    if let Err(e) = grpc::restart(app_handle) {
+        log::error!("{}", e);
+    }
+    if let Err(e) = stream_deck::restart(app_handle) {
+        log::error!("{}", e);
+    }
+    if let Err(e) = mqtt::restart(app_handle) {
+        log::error!("{}", e);
+    }
+    meeting_detection::restart(app_handle);
+    #[cfg(target_os = "macos")]
+    secure_input::start(app_handle);
+    watchdog::start(app_handle);
+    post_process_queue::start(app_handle);
+    error_reports::start(app_handle);
+
+    // Apply macOS Accessory policy if starting hidden in menu bar only mode
     #[cfg(target_os = "macos")]
     {
         let settings = settings::get_settings(app_handle);
-        if settings.start_hidden {
-            let _ = app_handle.set_activation_policy(tauri::ActivationPolicy::Accessory);
-        }
+        utils::apply_activation_policy(app_handle, !settings.start_hidden);
     }
     // Get the current theme to set the appropriate initial icon
     let initial_theme = tray::get_current_theme(app_handle);
@@ -164,6 +231,7 @@ fn initialize_core_logic(app_handle: &AppHandle) {
             )
             .unwrap(),
         )
+        .tooltip("Handy")
         .show_menu_on_left_click(true)
         .icon_as_template(true)
         .on_menu_event(|app, event| match event.id.as_ref() {
@@ -180,6 +248,23 @@ fn initialize_core_logic(app_handle: &AppHandle) {
             "copy_last_transcript" => {
                 tray::copy_last_transcript(app);
             }
+            "paste_last_transcript" => {
+                if let Err(e) = tray::paste_last_transcript(app) {
+                    log::warn!("{}", e);
+                }
+            }
+            "lock_target" => {
+                tray::set_target_lock(app, true);
+            }
+            "unlock_target" => {
+                tray::set_target_lock(app, false);
+            }
+            "incognito" => {
+                incognito::set_session_enabled(app, !incognito::is_session_enabled());
+            }
+            "pause" => {
+                pause::set_paused(app, !pause::is_paused());
+            }
             "unload_model" => {
                 let transcription_manager = app.state::<Arc<TranscriptionManager>>();
                 if !transcription_manager.is_model_loaded() {
@@ -200,6 +285,25 @@ fn initialize_core_logic(app_handle: &AppHandle) {
             "quit" => {
                 app.exit(0);
             }
+            id if id.starts_with(tray::RECENT_TRANSCRIPT_PREFIX) => {
+                if let Ok(entry_id) = id[tray::RECENT_TRANSCRIPT_PREFIX.len()..].parse() {
+                    tray::copy_history_entry(app, entry_id);
+                }
+            }
+            id if id.starts_with(tray::SELECT_MODEL_PREFIX) => {
+                tray::select_model(app, id[tray::SELECT_MODEL_PREFIX.len()..].to_string());
+            }
+            id if id.starts_with(tray::SELECT_PROMPT_PREFIX) => {
+                tray::select_prompt(app, id[tray::SELECT_PROMPT_PREFIX.len()..].to_string());
+            }
+            id if id.starts_with(tray::SELECT_PROFILE_PREFIX) => {
+                let profile_id = &id[tray::SELECT_PROFILE_PREFIX.len()..];
+                if let Err(e) = profiles::switch_profile(app, profile_id) {
+                    log::error!("Failed to switch profile via tray: {}", e);
+                    // Undo the checkmark the click toggled
+                    dictation::refresh_tray_menu(app);
+                }
+            }
             _ => {}
         })
         .build(app_handle)
@@ -207,7 +311,7 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     app_handle.manage(tray);
 
     // Initialize tray menu with idle state
-    utils::update_tray_menu(app_handle, &utils::TrayIconState::Idle, None);
+    dictation::refresh_tray_menu(app_handle);
 
     // Apply show_tray_icon setting
     let settings = settings::get_settings(app_handle);
@@ -218,7 +322,24 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     // Refresh tray menu when model state changes
     let app_handle_for_listener = app_handle.clone();
     app_handle.listen("model-state-changed", move |_| {
-        tray::update_tray_menu(&app_handle_for_listener, &tray::TrayIconState::Idle, None);
+        dictation::refresh_tray_menu(&app_handle_for_listener);
+    });
+
+    // Warm the model up in the background so the first dictation doesn't
+    // wait for it, unless it would be unloaded again straight away. Session
+    // overrides were applied at the start, so a `--model` given on the
+    // command line is the one loaded.
+    if settings.preload_model
+        && !settings.selected_model.is_empty()
+        && settings.model_unload_timeout != settings::ModelUnloadTimeout::Immediately
+    {
+        transcription_manager.initiate_model_load();
+    }
+
+    // Refresh the recent transcripts when history changes
+    let app_handle_for_listener = app_handle.clone();
+    app_handle.listen("history-updated", move |_| {
+        dictation::refresh_tray_menu(&app_handle_for_listener);
     });
 
     // Get the autostart manager and configure based on user setting
@@ -251,102 +372,237 @@ fn trigger_update_check(app: AppHandle) -> Result<(), String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run(cli_args: CliArgs) {
+    // Settings are read during setup, so the folder has to be known first
+    if let Some(dir) = &cli_args.settings_dir {
+        match std::path::absolute(dir) {
+            Ok(dir) => settings::set_settings_dir(dir),
+            Err(e) => eprintln!("Ignoring settings folder {}: {}", dir.display(), e),
+        }
+    }
+
     // Parse console logging directives from RUST_LOG, falling back to info-level logging
     // when the variable is unset
     let console_filter = build_console_filter();
 
-    let specta_builder = Builder::<tauri::Wry>::new().commands(collect_commands![
-        shortcut::change_binding,
-        shortcut::reset_binding,
-        shortcut::change_ptt_setting,
-        shortcut::change_audio_feedback_setting,
-        shortcut::change_audio_feedback_volume_setting,
-        shortcut::change_sound_theme_setting,
-        shortcut::change_start_hidden_setting,
-        shortcut::change_autostart_setting,
-        shortcut::change_translate_to_english_setting,
-        shortcut::change_selected_language_setting,
-        shortcut::change_overlay_position_setting,
-        shortcut::change_debug_mode_setting,
-        shortcut::change_word_correction_threshold_setting,
-        shortcut::change_paste_method_setting,
-        shortcut::get_available_typing_tools,
-        shortcut::change_typing_tool_setting,
-        shortcut::change_external_script_path_setting,
-        shortcut::change_clipboard_handling_setting,
-        shortcut::change_auto_submit_setting,
-        shortcut::change_auto_submit_key_setting,
-        shortcut::change_post_process_enabled_setting,
-        shortcut::change_experimental_enabled_setting,
-        shortcut::change_post_process_base_url_setting,
-        shortcut::change_post_process_api_key_setting,
-        shortcut::change_post_process_model_setting,
-        shortcut::set_post_process_provider,
-        shortcut::fetch_post_process_models,
-        shortcut::add_post_process_prompt,
-        shortcut::update_post_process_prompt,
-        shortcut::delete_post_process_prompt,
-        shortcut::set_post_process_selected_prompt,
-        shortcut::update_custom_words,
-        shortcut::suspend_binding,
-        shortcut::resume_binding,
-        shortcut::change_mute_while_recording_setting,
-        shortcut::change_append_trailing_space_setting,
-        shortcut::change_app_language_setting,
-        shortcut::change_update_checks_setting,
-        shortcut::change_keyboard_implementation_setting,
-        shortcut::get_keyboard_implementation,
-        shortcut::change_show_tray_icon_setting,
-        shortcut::handy_keys::start_handy_keys_recording,
-        shortcut::handy_keys::stop_handy_keys_recording,
-        trigger_update_check,
-        commands::cancel_operation,
-        commands::get_app_dir_path,
-        commands::get_app_settings,
-        commands::get_default_settings,
-        commands::get_log_dir_path,
-        commands::set_log_level,
-        commands::open_recordings_folder,
-        commands::open_log_dir,
-        commands::open_app_data_dir,
-        commands::check_apple_intelligence_available,
-        commands::initialize_enigo,
-        commands::initialize_shortcuts,
-        commands::models::get_available_models,
-        commands::models::get_model_info,
-        commands::models::download_model,
-        commands::models::delete_model,
-        commands::models::cancel_download,
-        commands::models::set_active_model,
-        commands::models::get_current_model,
-        commands::models::get_transcription_model_status,
-        commands::models::is_model_loading,
-        commands::models::has_any_models_available,
-        commands::models::has_any_models_or_downloads,
-        commands::audio::update_microphone_mode,
-        commands::audio::get_microphone_mode,
-        commands::audio::get_available_microphones,
-        commands::audio::set_selected_microphone,
-        commands::audio::get_selected_microphone,
-        commands::audio::get_available_output_devices,
-        commands::audio::set_selected_output_device,
-        commands::audio::get_selected_output_device,
-        commands::audio::play_test_sound,
-        commands::audio::check_custom_sounds,
-        commands::audio::set_clamshell_microphone,
-        commands::audio::get_clamshell_microphone,
-        commands::audio::is_recording,
-        commands::transcription::set_model_unload_timeout,
-        commands::transcription::get_model_load_status,
-        commands::transcription::unload_model_manually,
-        commands::history::get_history_entries,
-        commands::history::toggle_history_entry_saved,
-        commands::history::get_audio_file_path,
-        commands::history::delete_history_entry,
-        commands::history::update_history_limit,
-        commands::history::update_recording_retention_period,
-        helpers::clamshell::is_laptop,
-    ]);
+    let specta_builder = Builder::<tauri::Wry>::new()
+        .commands(collect_commands![
+            shortcut::change_binding,
+            shortcut::reset_binding,
+            shortcut::change_ptt_setting,
+            shortcut::change_audio_feedback_setting,
+            shortcut::change_audio_feedback_volume_setting,
+            shortcut::change_sound_theme_setting,
+            shortcut::change_start_hidden_setting,
+            shortcut::change_menu_bar_only_setting,
+            shortcut::change_autostart_setting,
+            shortcut::change_translate_to_english_setting,
+            shortcut::change_selected_language_setting,
+            shortcut::change_overlay_position_setting,
+            shortcut::change_overlay_scale_setting,
+            shortcut::change_result_hud_setting,
+            shortcut::change_overlay_appearance_setting,
+            shortcut::change_overlay_monitor_setting,
+            shortcut::change_max_recording_duration_setting,
+            shortcut::change_debug_mode_setting,
+            shortcut::change_word_correction_threshold_setting,
+            shortcut::change_transcription_timeout_setting,
+            shortcut::change_transcription_workers_setting,
+            shortcut::change_preload_model_setting,
+            shortcut::change_paste_method_setting,
+            shortcut::get_available_typing_tools,
+            shortcut::change_typing_tool_setting,
+            shortcut::change_app_paste_rules_setting,
+            shortcut::change_app_overrides_setting,
+            shortcut::change_typing_speed_setting,
+            shortcut::change_transcript_pipe_path_setting,
+            shortcut::change_daily_journal_dir_setting,
+            shortcut::change_daily_journal_template_setting,
+            shortcut::change_capture_window_titles_setting,
+            shortcut::change_deep_link_actions_setting,
+            shortcut::change_usage_analytics_setting,
+            shortcut::change_external_script_path_setting,
+            shortcut::change_clipboard_handling_setting,
+            shortcut::change_primary_selection_mode_setting,
+            shortcut::change_markdown_output_mode_setting,
+            shortcut::change_clipboard_restore_delay_setting,
+            shortcut::change_smart_insert_setting,
+            shortcut::change_focused_control_detection_setting,
+            shortcut::change_undo_dictation_enabled_setting,
+            shortcut::change_paste_last_enabled_setting,
+            shortcut::change_incognito_shortcut_enabled_setting,
+            shortcut::change_preview_before_paste_setting,
+            preview::get_pending_preview,
+            preview::update_preview,
+            preview::confirm_preview,
+            preview::cancel_preview,
+            error_reports::list_error_reports,
+            log_stream::tail_log,
+            log_stream::start_log_stream,
+            log_stream::stop_log_stream,
+            shortcut::change_key_injection_setting,
+            shortcut::change_notification_settings,
+            shortcut::change_api_server_setting,
+            shortcut::change_stream_deck_setting,
+            shortcut::change_mqtt_setting,
+            shortcut::change_meeting_detection_setting,
+            shortcut::change_email_setting,
+            shortcut::change_watchdog_setting,
+            shortcut::change_obs_captions_setting,
+            shortcut::add_webhook,
+            shortcut::update_webhook,
+            shortcut::delete_webhook,
+            shortcut::add_http_action,
+            shortcut::update_http_action,
+            shortcut::delete_http_action,
+            shortcut::add_note_target,
+            shortcut::update_note_target,
+            shortcut::delete_note_target,
+            shortcut::change_binding_note_target,
+            shortcut::change_auto_submit_setting,
+            shortcut::change_auto_submit_key_setting,
+            shortcut::change_post_process_enabled_setting,
+            shortcut::change_binding_output_file,
+            shortcut::change_experimental_enabled_setting,
+            shortcut::change_post_process_base_url_setting,
+            shortcut::change_post_process_api_key_setting,
+            shortcut::change_post_process_model_setting,
+            shortcut::set_post_process_provider,
+            shortcut::fetch_post_process_models,
+            shortcut::search_post_process_models,
+            shortcut::change_openrouter_routing_setting,
+            shortcut::add_post_process_prompt,
+            shortcut::update_post_process_prompt,
+            shortcut::update_post_process_prompt_params,
+            shortcut::delete_post_process_prompt,
+            shortcut::set_post_process_selected_prompt,
+            shortcut::change_post_process_system_prompt_setting,
+            shortcut::update_custom_words,
+            shortcut::change_spoken_commands_enabled_setting,
+            shortcut::update_spoken_commands,
+            shortcut::change_literal_mode_enabled_setting,
+            shortcut::update_literal_mode_triggers,
+            shortcut::suspend_binding,
+            shortcut::resume_binding,
+            shortcut::change_mute_while_recording_setting,
+            shortcut::change_append_trailing_space_setting,
+            shortcut::change_app_language_setting,
+            shortcut::change_update_checks_setting,
+            shortcut::change_keyboard_implementation_setting,
+            shortcut::get_keyboard_implementation,
+            shortcut::change_show_tray_icon_setting,
+            shortcut::change_tray_recent_transcripts_setting,
+            shortcut::handy_keys::start_handy_keys_recording,
+            shortcut::handy_keys::stop_handy_keys_recording,
+            trigger_update_check,
+            commands::cancel_operation,
+            commands::stop_current_recording,
+            commands::get_app_dir_path,
+            commands::get_app_settings,
+            commands::get_settings_policy,
+            commands::get_default_settings,
+            commands::get_log_dir_path,
+            commands::set_log_level,
+            commands::open_recordings_folder,
+            commands::open_log_dir,
+            commands::open_app_data_dir,
+            commands::check_apple_intelligence_available,
+            commands::initialize_enigo,
+            commands::get_dictation_target,
+            commands::unlock_dictation_target,
+            commands::get_incognito_mode,
+            commands::set_incognito_mode,
+            commands::get_paused,
+            commands::set_paused,
+            commands::get_monitors,
+            commands::initialize_shortcuts,
+            commands::models::get_available_models,
+            commands::models::get_model_info,
+            commands::models::download_model,
+            commands::models::repair_model,
+            commands::models::delete_model,
+            commands::models::cancel_download,
+            commands::models::set_active_model,
+            commands::models::get_current_model,
+            commands::models::get_transcription_model_status,
+            commands::models::is_model_loading,
+            commands::models::has_any_models_available,
+            commands::models::has_any_models_or_downloads,
+            commands::audio::update_microphone_mode,
+            commands::audio::get_microphone_mode,
+            commands::audio::get_available_microphones,
+            commands::audio::set_selected_microphone,
+            commands::audio::get_selected_microphone,
+            commands::audio::get_available_output_devices,
+            commands::audio::set_selected_output_device,
+            commands::audio::get_selected_output_device,
+            commands::audio::play_test_sound,
+            commands::audio::check_custom_sounds,
+            commands::audio::set_clamshell_microphone,
+            commands::audio::get_clamshell_microphone,
+            commands::audio::is_recording,
+            commands::transcription::set_model_unload_timeout,
+            commands::transcription::get_model_load_status,
+            commands::transcription::unload_model_manually,
+            commands::history::get_history_entries,
+            commands::history::get_history_page,
+            commands::history::get_history_apps,
+            commands::history::search_history,
+            commands::history::get_dictation_stats,
+            commands::history::get_usage_report,
+            commands::history::export_usage_report,
+            commands::history::clear_usage_analytics,
+            commands::history::export_history,
+            commands::history::set_history_sync_dir,
+            commands::history::sync_history_now,
+            commands::history::export_history_key,
+            commands::history::import_history_key,
+            commands::history::paste_history_entry,
+            commands::history::paste_last_transcript,
+            commands::history::toggle_history_entry_saved,
+            commands::history::set_history_entry_saved,
+            commands::history::get_saved_history_entries,
+            commands::history::get_audio_file_path,
+            commands::history::delete_history_entry,
+            commands::history::update_history_limit,
+            commands::history::change_keep_recordings_setting,
+            commands::history::update_recordings_size_limit,
+            commands::history::play_history_recording,
+            commands::history::export_history_recording,
+            commands::history::change_history_encryption_setting,
+            commands::history::update_recording_retention_period,
+            commands::prompts::export_prompt_library,
+            commands::prompts::import_prompt_library,
+            commands::settings_bundle::export_settings,
+            commands::settings_bundle::import_settings,
+            commands::settings_backups::list_settings_backups,
+            commands::settings_backups::restore_settings_backup,
+            commands::settings_reset::request_full_settings_reset,
+            commands::settings_reset::reset_settings,
+            commands::settings_validation::validate_settings,
+            commands::health_check::run_health_check,
+            commands::diagnostics::export_diagnostics,
+            commands::data_dirs::get_data_directory,
+            commands::data_dirs::move_data_directory,
+            commands::api_server::get_api_server_token,
+            commands::api_server::regenerate_api_server_token,
+            commands::meetings::get_meeting_calendars,
+            commands::meetings::start_meeting_transcription,
+            commands::permissions::get_permission_status,
+            commands::permissions::request_microphone_permission,
+            commands::permissions::request_accessibility_permission,
+            commands::permissions::request_input_monitoring_permission,
+            commands::permissions::missing_fn_shortcut_permissions,
+            commands::permissions::open_permission_settings,
+            commands::secrets::set_secret,
+            commands::secrets::has_secret,
+            commands::secrets::delete_secret,
+            commands::profiles::get_profiles,
+            commands::profiles::create_profile,
+            commands::profiles::switch_profile,
+            commands::profiles::delete_profile,
+            helpers::clamshell::is_laptop,
+        ])
+        .events(collect_events![events::AppEvent]);
 
     #[cfg(debug_assertions)] // <- Only export on non-release builds
     specta_builder
@@ -356,6 +612,7 @@ pub fn run(cli_args: CliArgs) {
         )
         .expect("Failed to export typescript bindings");
 
+    let invoke_handler = specta_builder.invoke_handler();
     let mut builder = tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(
@@ -378,6 +635,8 @@ pub fn run(cli_args: CliArgs) {
                         let file_level = FILE_LOG_LEVEL.load(Ordering::Relaxed);
                         metadata.level() <= level_filter_from_u8(file_level)
                     }),
+                    // The debug view's live log, off until it subscribes
+                    log_stream::target(),
                 ])
                 .build(),
         );
@@ -395,15 +654,25 @@ pub fn run(cli_args: CliArgs) {
                 signal_handle::send_transcription_input(app, "transcribe_with_post_process", "CLI");
             } else if args.iter().any(|a| a == "--cancel") {
                 crate::utils::cancel_current_operation(app);
+            } else if args.iter().any(|a| a == "--lock-target") {
+                tray::set_target_lock(app, true);
+            } else if args.iter().any(|a| a == "--unlock-target") {
+                tray::set_target_lock(app, false);
+            } else if args.iter().any(|a| a == "--toggle-incognito") {
+                incognito::set_session_enabled(app, !incognito::is_session_enabled());
+            } else if args.iter().any(|a| a.starts_with("handy://")) {
+                // Forwarded to the deep link plugin
             } else {
                 show_main_window(app);
             }
         }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_macos_permissions::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::default().build())
@@ -412,8 +681,11 @@ pub fn run(cli_args: CliArgs) {
             MacosLauncher::LaunchAgent,
             Some(vec![]),
         ))
+        .register_asynchronous_uri_scheme_protocol("recording", commands::history::serve_recording)
         .manage(cli_args.clone())
         .setup(move |app| {
+            // Typed events can only be emitted once they're mounted
+            specta_builder.mount_events(app.handle());
             let mut settings = get_settings(&app.handle());
 
             // CLI --debug flag overrides debug_mode and log level (runtime-only, not persisted)
@@ -427,9 +699,14 @@ pub fn run(cli_args: CliArgs) {
             // Store the file log level in the atomic for the filter to use
             FILE_LOG_LEVEL.store(file_log_level.to_level_filter() as u8, Ordering::Relaxed);
             let app_handle = app.handle().clone();
+            log_stream::init(&app_handle);
             app.manage(TranscriptionCoordinator::new(app_handle.clone()));
 
-            initialize_core_logic(&app_handle);
+            initialize_core_logic(&app_handle, &cli_args);
+            settings_watcher::start(&app_handle);
+            deep_link::setup(&app_handle);
+            #[cfg(target_os = "macos")]
+            scripting::setup(&app_handle);
 
             // Hide tray icon if --no-tray was passed
             if cli_args.no_tray {
@@ -460,23 +737,16 @@ pub fn run(cli_args: CliArgs) {
                 api.prevent_close();
                 let _res = window.hide();
                 #[cfg(target_os = "macos")]
-                {
-                    let res = window
-                        .app_handle()
-                        .set_activation_policy(tauri::ActivationPolicy::Accessory);
-                    if let Err(e) = res {
-                        log::error!("Failed to set activation policy: {}", e);
-                    }
-                }
+                utils::apply_activation_policy(&window.app_handle(), false);
             }
             tauri::WindowEvent::ThemeChanged(theme) => {
                 log::info!("Theme changed to: {:?}", theme);
-                // Update tray icon to match new theme, maintaining idle state
-                utils::change_tray_icon(&window.app_handle(), utils::TrayIconState::Idle);
+                // Update tray icon to match the new theme
+                dictation::refresh_tray(window.app_handle());
             }
             _ => {}
         })
-        .invoke_handler(specta_builder.invoke_handler())
+        .invoke_handler(invoke_handler)
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
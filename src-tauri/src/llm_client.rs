@@ -1,8 +1,27 @@
-use crate::settings::PostProcessProvider;
-use log::debug;
+use crate::settings::{LLMPrompt, OpenRouterRouting, PostProcessProvider};
+use log::{debug, warn};
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, REFERER, USER_AGENT};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use specta::Type;
+use std::fmt;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const NITRO_SUFFIX: &str = ":nitro";
+const DEFAULT_MODEL_SEARCH_LIMIT: usize = 50;
+
+/// Times a request is retried after a transient error
+const MAX_RETRIES: u32 = 3;
+
+/// Delay before the first retry, doubled for each one after it
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest a single request may take, from connecting to the last byte
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Retries aren't started once this much time has gone into a request
+const MAX_RETRY_TIME: Duration = Duration::from_secs(90);
 
 #[derive(Debug, Serialize)]
 struct ChatMessage {
@@ -24,12 +43,103 @@ struct ResponseFormat {
     json_schema: JsonSchema,
 }
 
+/// Why a chat completion request failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompletionError {
+    /// The provider couldn't be reached at all, as when the network is down
+    Unreachable(String),
+    /// Rate limited, overloaded or timed out, so trying again may work
+    Transient(String),
+    Failed(String),
+}
+
+impl fmt::Display for CompletionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreachable(message) | Self::Transient(message) | Self::Failed(message) => {
+                f.write_str(message)
+            }
+        }
+    }
+}
+
+impl From<String> for CompletionError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+/// Optional sampling parameters sent with a chat completion request.
+/// Unset values are omitted so the provider's defaults apply.
+#[derive(Debug, Clone, Default)]
+pub struct CompletionParams {
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub top_p: Option<f32>,
+    /// OpenRouter routing preferences, only set for the OpenRouter provider
+    pub routing: Option<OpenRouterRouting>,
+}
+
+impl From<&LLMPrompt> for CompletionParams {
+    fn from(prompt: &LLMPrompt) -> Self {
+        Self {
+            temperature: prompt.temperature,
+            max_tokens: prompt.max_tokens,
+            top_p: prompt.top_p,
+            routing: None,
+        }
+    }
+}
+
+/// OpenRouter `provider` object controlling which upstream providers serve a request
+#[derive(Debug, Serialize)]
+struct ProviderPreferences {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    order: Vec<String>,
+    allow_fallbacks: bool,
+}
+
+fn provider_preferences(routing: &OpenRouterRouting) -> Option<ProviderPreferences> {
+    let order: Vec<String> = routing
+        .provider_order
+        .iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    if order.is_empty() && routing.allow_fallbacks {
+        return None;
+    }
+
+    Some(ProviderPreferences {
+        order,
+        allow_fallbacks: routing.allow_fallbacks,
+    })
+}
+
+/// Applies the `:nitro` variant to a model id unless it already names a variant
+fn routed_model_id(model: &str, routing: &OpenRouterRouting) -> String {
+    if routing.nitro && !model.contains(':') {
+        format!("{}{}", model, NITRO_SUFFIX)
+    } else {
+        model.to_string()
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct ChatCompletionRequest {
     model: String,
     messages: Vec<ChatMessage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     response_format: Option<ResponseFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<ProviderPreferences>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -89,22 +199,11 @@ fn create_client(provider: &PostProcessProvider, api_key: &str) -> Result<reqwes
     let headers = build_headers(provider, api_key)?;
     reqwest::Client::builder()
         .default_headers(headers)
+        .timeout(REQUEST_TIMEOUT)
         .build()
         .map_err(|e| format!("Failed to build HTTP client: {}", e))
 }
 
-/// Send a chat completion request to an OpenAI-compatible API
-/// Returns Ok(Some(content)) on success, Ok(None) if response has no content,
-/// or Err on actual errors (HTTP, parsing, etc.)
-pub async fn send_chat_completion(
-    provider: &PostProcessProvider,
-    api_key: String,
-    model: &str,
-    prompt: String,
-) -> Result<Option<String>, String> {
-    send_chat_completion_with_schema(provider, api_key, model, prompt, None, None).await
-}
-
 /// Send a chat completion request with structured output support
 /// When json_schema is provided, uses structured outputs mode
 /// system_prompt is used as the system message when provided
@@ -115,7 +214,8 @@ pub async fn send_chat_completion_with_schema(
     user_content: String,
     system_prompt: Option<String>,
     json_schema: Option<Value>,
-) -> Result<Option<String>, String> {
+    params: CompletionParams,
+) -> Result<Option<String>, CompletionError> {
     let base_url = provider.base_url.trim_end_matches('/');
     let url = format!("{}/chat/completions", base_url);
 
@@ -150,10 +250,22 @@ pub async fn send_chat_completion_with_schema(
         },
     });
 
+    let (model, provider_routing) = match &params.routing {
+        Some(routing) => (
+            routed_model_id(model, routing),
+            provider_preferences(routing),
+        ),
+        None => (model.to_string(), None),
+    };
+
     let request_body = ChatCompletionRequest {
-        model: model.to_string(),
+        model,
         messages,
         response_format,
+        temperature: params.temperature,
+        max_tokens: params.max_tokens,
+        top_p: params.top_p,
+        provider: provider_routing,
     };
 
     let response = client
@@ -161,7 +273,7 @@ pub async fn send_chat_completion_with_schema(
         .json(&request_body)
         .send()
         .await
-        .map_err(|e| format!("HTTP request failed: {}", e))?;
+        .map_err(|e| request_error(base_url, e))?;
 
     let status = response.status();
     if !status.is_success() {
@@ -169,16 +281,18 @@ pub async fn send_chat_completion_with_schema(
             .text()
             .await
             .unwrap_or_else(|_| "Failed to read error response".to_string());
-        return Err(format!(
-            "API request failed with status {}: {}",
-            status, error_text
-        ));
+        let message = format!("API request failed with status {}: {}", status, error_text);
+        return Err(if is_transient(status) {
+            CompletionError::Transient(message)
+        } else {
+            CompletionError::Failed(message)
+        });
     }
 
     let completion: ChatCompletionResponse = response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse API response: {}", e))?;
+        .map_err(|e| CompletionError::Failed(format!("Failed to parse API response: {}", e)))?;
 
     Ok(completion
         .choices
@@ -186,18 +300,98 @@ pub async fn send_chat_completion_with_schema(
         .and_then(|choice| choice.message.content.clone()))
 }
 
-/// Fetch available models from an OpenAI-compatible API
-/// Returns a list of model IDs
-pub async fn fetch_models(
+/// Sorts a request that got no response by whether trying again may help
+fn request_error(base_url: &str, e: reqwest::Error) -> CompletionError {
+    if e.is_connect() {
+        CompletionError::Unreachable(format!("Can't reach {}: {}", base_url, e))
+    } else if e.is_timeout() {
+        CompletionError::Transient(format!("HTTP request timed out: {}", e))
+    } else {
+        CompletionError::Failed(format!("HTTP request failed: {}", e))
+    }
+}
+
+fn is_transient(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status.is_server_error()
+}
+
+/// Backoff before retry number `retry`, counting from 1, with `jitter`
+/// between 0 and 1 adding up to half as much again so clients that failed
+/// together don't retry together
+fn retry_delay(retry: u32, jitter: f64) -> Duration {
+    let backoff = RETRY_BASE_DELAY * 2u32.pow(retry.saturating_sub(1));
+    backoff + backoff.mul_f64(jitter.clamp(0.0, 1.0) / 2.0)
+}
+
+/// Good enough randomness for spreading out retries
+fn jitter() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos())
+        .unwrap_or_default();
+    f64::from(nanos) / 1e9
+}
+
+/// Like [`send_chat_completion_with_schema`], but transient errors are
+/// retried with exponential backoff before giving up, for up to
+/// `MAX_RETRY_TIME` in all. Also returns how many retries it took.
+pub async fn send_chat_completion_with_retry(
     provider: &PostProcessProvider,
     api_key: String,
-) -> Result<Vec<String>, String> {
+    model: &str,
+    user_content: String,
+    system_prompt: Option<String>,
+    json_schema: Option<Value>,
+    params: CompletionParams,
+) -> (Result<Option<String>, CompletionError>, u32) {
+    let started = Instant::now();
+    let mut retries = 0;
+    loop {
+        let result = send_chat_completion_with_schema(
+            provider,
+            api_key.clone(),
+            model,
+            user_content.clone(),
+            system_prompt.clone(),
+            json_schema.clone(),
+            params.clone(),
+        )
+        .await;
+        match result {
+            Err(CompletionError::Transient(e)) if retries < MAX_RETRIES => {
+                let delay = retry_delay(retries + 1, jitter());
+                if started.elapsed() + delay >= MAX_RETRY_TIME {
+                    warn!(
+                        "Request to '{}' failed ({}), out of time for retries",
+                        provider.id, e
+                    );
+                    return (Err(CompletionError::Transient(e)), retries);
+                }
+                retries += 1;
+                warn!(
+                    "Request to '{}' failed ({}), retry {} of {} in {:?}",
+                    provider.id, e, retries, MAX_RETRIES, delay
+                );
+                tokio::time::sleep(delay).await;
+            }
+            result => return (result, retries),
+        }
+    }
+}
+
+/// Request the raw model list from an OpenAI-compatible API
+async fn fetch_models_response(
+    provider: &PostProcessProvider,
+    api_key: &str,
+) -> Result<Value, String> {
     let base_url = provider.base_url.trim_end_matches('/');
     let url = format!("{}/models", base_url);
 
     debug!("Fetching models from: {}", url);
 
-    let client = create_client(provider, &api_key)?;
+    let client = create_client(provider, api_key)?;
 
     let response = client
         .get(&url)
@@ -217,10 +411,19 @@ pub async fn fetch_models(
         ));
     }
 
-    let parsed: serde_json::Value = response
+    response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+/// Fetch available models from an OpenAI-compatible API
+/// Returns a list of model IDs
+pub async fn fetch_models(
+    provider: &PostProcessProvider,
+    api_key: String,
+) -> Result<Vec<String>, String> {
+    let parsed = fetch_models_response(provider, &api_key).await?;
 
     let mut models = Vec::new();
 
@@ -245,3 +448,203 @@ pub async fn fetch_models(
 
     Ok(models)
 }
+
+/// Model metadata used for searching large model catalogs such as OpenRouter's
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PostProcessModelInfo {
+    pub id: String,
+    pub name: String,
+    pub context_length: Option<u64>,
+    /// Prompt price in USD per million tokens
+    pub prompt_price: Option<f64>,
+    /// Completion price in USD per million tokens
+    pub completion_price: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+pub struct ModelSearchFilter {
+    /// Case-insensitive substring matched against model id and name
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Maximum prompt price in USD per million tokens
+    #[serde(default)]
+    pub max_prompt_price: Option<f64>,
+    #[serde(default)]
+    pub min_context_length: Option<u64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// OpenRouter reports prices as strings in USD per token
+fn parse_price_per_million(value: Option<&Value>) -> Option<f64> {
+    let per_token = match value? {
+        Value::String(s) => s.parse::<f64>().ok()?,
+        Value::Number(n) => n.as_f64()?,
+        _ => return None,
+    };
+    // Negative prices mark variable-priced router models
+    (per_token >= 0.0).then_some(per_token * 1_000_000.0)
+}
+
+fn parse_model_info(entry: &Value) -> Option<PostProcessModelInfo> {
+    let id = entry.get("id").and_then(|i| i.as_str())?.to_string();
+    let name = entry
+        .get("name")
+        .and_then(|n| n.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| id.clone());
+    let pricing = entry.get("pricing");
+
+    Some(PostProcessModelInfo {
+        id,
+        name,
+        context_length: entry.get("context_length").and_then(|c| c.as_u64()),
+        prompt_price: parse_price_per_million(pricing.and_then(|p| p.get("prompt"))),
+        completion_price: parse_price_per_million(pricing.and_then(|p| p.get("completion"))),
+    })
+}
+
+/// Filters models by name, price and context length, cheapest first
+pub fn filter_models(
+    models: Vec<PostProcessModelInfo>,
+    filter: &ModelSearchFilter,
+) -> Vec<PostProcessModelInfo> {
+    let query = filter
+        .query
+        .as_deref()
+        .map(|q| q.trim().to_lowercase())
+        .filter(|q| !q.is_empty());
+
+    let mut matches: Vec<PostProcessModelInfo> = models
+        .into_iter()
+        .filter(|model| match &query {
+            Some(q) => model.id.to_lowercase().contains(q) || model.name.to_lowercase().contains(q),
+            None => true,
+        })
+        .filter(|model| match filter.max_prompt_price {
+            Some(max) => model.prompt_price.is_some_and(|price| price <= max),
+            None => true,
+        })
+        .filter(|model| match filter.min_context_length {
+            Some(min) => model.context_length.is_some_and(|len| len >= min),
+            None => true,
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        let a_price = a.prompt_price.unwrap_or(f64::MAX);
+        let b_price = b.prompt_price.unwrap_or(f64::MAX);
+        a_price.total_cmp(&b_price).then_with(|| a.id.cmp(&b.id))
+    });
+    matches.truncate(filter.limit.unwrap_or(DEFAULT_MODEL_SEARCH_LIMIT));
+    matches
+}
+
+/// Fetch model metadata and return the entries matching the filter
+pub async fn search_models(
+    provider: &PostProcessProvider,
+    api_key: String,
+    filter: &ModelSearchFilter,
+) -> Result<Vec<PostProcessModelInfo>, String> {
+    let parsed = fetch_models_response(provider, &api_key).await?;
+    let models = parsed
+        .get("data")
+        .and_then(|d| d.as_array())
+        .map(|data| data.iter().filter_map(parse_model_info).collect())
+        .unwrap_or_default();
+
+    Ok(filter_models(models, filter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn retries_rate_limits_and_server_errors_with_growing_delays() {
+        assert!(is_transient(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_transient(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_transient(StatusCode::UNAUTHORIZED));
+        assert_eq!(retry_delay(1, 0.0), Duration::from_millis(500));
+        assert_eq!(retry_delay(2, 0.0), Duration::from_secs(1));
+        assert_eq!(retry_delay(3, 1.0), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn refused_connections_are_unreachable() {
+        // Nothing listens on a port that was just given back
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .unwrap()
+            .port();
+        let base_url = format!("http://127.0.0.1:{}", port);
+        let e = tauri::async_runtime::block_on(reqwest::Client::new().get(&base_url).send())
+            .unwrap_err();
+        assert!(matches!(
+            request_error(&base_url, e),
+            CompletionError::Unreachable(_)
+        ));
+    }
+
+    #[test]
+    fn timed_out_requests_are_transient() {
+        // Accepts the connection but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+        let e = tauri::async_runtime::block_on(client.get(&base_url).send()).unwrap_err();
+        assert!(matches!(
+            request_error(&base_url, e),
+            CompletionError::Transient(_)
+        ));
+    }
+
+    #[test]
+    fn nitro_variant_is_appended_once() {
+        let routing = OpenRouterRouting {
+            nitro: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            routed_model_id("meta-llama/llama-3.1-70b", &routing),
+            "meta-llama/llama-3.1-70b:nitro"
+        );
+        assert_eq!(
+            routed_model_id("meta-llama/llama-3.1-70b:free", &routing),
+            "meta-llama/llama-3.1-70b:free"
+        );
+        assert!(provider_preferences(&routing).is_none());
+    }
+
+    #[test]
+    fn search_filters_by_query_price_and_context() {
+        let data = json!([
+            {"id": "openai/gpt-4o", "name": "GPT-4o", "context_length": 128000,
+             "pricing": {"prompt": "0.0000025", "completion": "0.00001"}},
+            {"id": "openai/gpt-4o-mini", "name": "GPT-4o mini", "context_length": 128000,
+             "pricing": {"prompt": "0.00000015", "completion": "0.0000006"}},
+            {"id": "mistral/tiny", "name": "Mistral Tiny", "context_length": 8000,
+             "pricing": {"prompt": "0.0000001", "completion": "0.0000001"}}
+        ]);
+        let models: Vec<PostProcessModelInfo> = data
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(parse_model_info)
+            .collect();
+
+        let filter = ModelSearchFilter {
+            query: Some("GPT".to_string()),
+            max_prompt_price: Some(1.0),
+            min_context_length: Some(32000),
+            limit: None,
+        };
+        let result = filter_models(models, &filter);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id, "openai/gpt-4o-mini");
+    }
+}
@@ -0,0 +1,175 @@
+//! Application logs for the in-app debug view.
+//!
+//! `tail_log` reads the end of the log file. While the view is open,
+//! `start_log_stream` sends every new record at or above its level as a
+//! `log-line` event, through a log target of its own so it can be more
+//! verbose than the file without filling it up.
+
+use crate::settings::LogLevel;
+use log::LevelFilter;
+use serde::Serialize;
+use specta::Type;
+use std::cell::Cell;
+use std::fs;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_log::{fern, Target, TargetKind};
+
+/// Name the log plugin gives the log file
+const LOG_FILE: &str = "handy.log";
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Level records are streamed at, `Off` while nobody is listening
+static STREAM_LEVEL: AtomicU8 = AtomicU8::new(LevelFilter::Off as u8);
+
+thread_local! {
+    /// Set while a line is being emitted, so logs from emitting it aren't
+    /// streamed in turn
+    static EMITTING: Cell<bool> = const { Cell::new(false) };
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Type)]
+pub struct LogLine {
+    pub timestamp: String,
+    pub level: LogLevel,
+    /// Module the record came from
+    pub target: String,
+    /// Continuation lines of multi-line records included
+    pub message: String,
+}
+
+fn level_filter(level: LogLevel) -> LevelFilter {
+    let level: log::Level = tauri_plugin_log::LogLevel::from(level).into();
+    level.to_level_filter()
+}
+
+/// Splits a line as the log plugin writes it:
+/// `[2025-01-31][09:15:02][handy_app_lib::actions][INFO] message`
+fn parse_line(line: &str) -> Option<LogLine> {
+    let rest = line.strip_prefix('[')?;
+    let (date, rest) = rest.split_once("][")?;
+    let (time, rest) = rest.split_once("][")?;
+    let (target, rest) = rest.split_once("][")?;
+    let (level, message) = rest.split_once(']')?;
+    let level = match level {
+        "TRACE" => LogLevel::Trace,
+        "DEBUG" => LogLevel::Debug,
+        "INFO" => LogLevel::Info,
+        "WARN" => LogLevel::Warn,
+        "ERROR" => LogLevel::Error,
+        _ => return None,
+    };
+    Some(LogLine {
+        timestamp: format!("{} {}", date, time),
+        level,
+        target: target.to_string(),
+        message: message.strip_prefix(' ').unwrap_or(message).to_string(),
+    })
+}
+
+/// Records in `contents`, with lines that don't start a record added to the
+/// one before
+fn parse_log(contents: &str) -> Vec<LogLine> {
+    let mut lines: Vec<LogLine> = Vec::new();
+    for line in contents.lines() {
+        if let Some(parsed) = parse_line(line) {
+            lines.push(parsed);
+        } else if let Some(last) = lines.last_mut() {
+            last.message.push('\n');
+            last.message.push_str(line);
+        }
+    }
+    lines
+}
+
+/// The log target streamed lines come from, added to the log plugin
+pub fn target() -> Target {
+    let dispatch = fern::Dispatch::new().chain(fern::Output::call(send));
+    Target::new(TargetKind::Dispatch(dispatch)).filter(|metadata| {
+        metadata.level() <= crate::level_filter_from_u8(STREAM_LEVEL.load(Ordering::Relaxed))
+    })
+}
+
+/// Lets the stream emit events, once the app is running
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+fn send(record: &log::Record) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    if EMITTING.with(|emitting| emitting.replace(true)) {
+        return;
+    }
+    // The plugin's format has already been applied to the record
+    let text = record.args().to_string();
+    let line = parse_line(&text).unwrap_or_else(|| LogLine {
+        timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        level: record.level().into(),
+        target: record.target().to_string(),
+        message: text,
+    });
+    let _ = app.emit("log-line", line);
+    EMITTING.with(|emitting| emitting.set(false));
+}
+
+/// The last `lines` records in the log file at `level` or above
+#[tauri::command]
+#[specta::specta]
+pub fn tail_log(app: AppHandle, lines: u32, level: LogLevel) -> Result<Vec<LogLine>, String> {
+    let path = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to get log directory: {}", e))?
+        .join(LOG_FILE);
+    let contents = match fs::read(&path) {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    let max = level_filter(level);
+    let mut records: Vec<LogLine> = parse_log(&contents)
+        .into_iter()
+        .filter(|line| level_filter(line.level) <= max)
+        .collect();
+    let start = records.len().saturating_sub(lines as usize);
+    Ok(records.split_off(start))
+}
+
+/// Sends records at `level` or above as `log-line` events until
+/// `stop_log_stream`. Calling it again changes the level.
+#[tauri::command]
+#[specta::specta]
+pub fn start_log_stream(level: LogLevel) {
+    STREAM_LEVEL.store(level_filter(level) as u8, Ordering::Relaxed);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn stop_log_stream() {
+    STREAM_LEVEL.store(LevelFilter::Off as u8, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_multi_line_records() {
+        let log = "[2025-01-31][09:15:02][handy_app_lib::actions][INFO] Pasted\n\
+                   [2025-01-31][09:15:03][handy_app_lib::llm_client][ERROR] Request failed:\n\
+                   status 500\n";
+        let lines = parse_log(log);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].timestamp, "2025-01-31 09:15:02");
+        assert_eq!(lines[0].target, "handy_app_lib::actions");
+        assert_eq!(lines[0].level, LogLevel::Info);
+        assert_eq!(lines[0].message, "Pasted");
+        assert_eq!(lines[1].level, LogLevel::Error);
+        assert_eq!(lines[1].message, "Request failed:\nstatus 500");
+    }
+}
@@ -7,6 +7,10 @@ use handy_app_lib::CliArgs;
 fn main() {
     let cli_args = CliArgs::parse();
 
+    if let Some(command) = &cli_args.command {
+        std::process::exit(handy_app_lib::cli_client::run(&cli_args, command));
+    }
+
     #[cfg(target_os = "linux")]
     {
         // DMABUF renderer causes crashes on various GPU/display server configurations
@@ -0,0 +1,204 @@
+//! Opt-in usage analytics kept in the history database.
+//!
+//! When enabled, Handy counts per local day which actions, models and
+//! post-processing providers are used and which stages fail. Only names and
+//! counts are stored, never transcript text, and nothing leaves the machine
+//! unless the user exports a report to share.
+
+use crate::incognito;
+use crate::managers::history::HistoryManager;
+use anyhow::Result;
+use log::warn;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageCategory {
+    /// A shortcut action was started, by binding id
+    Action,
+    /// A transcription finished, by model id
+    Model,
+    /// Post-processing produced text, by provider id
+    Provider,
+    /// A stage of the pipeline failed, e.g. `transcription` or `paste`
+    Error,
+}
+
+impl UsageCategory {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Action => "action",
+            Self::Model => "model",
+            Self::Provider => "provider",
+            Self::Error => "error",
+        }
+    }
+
+    fn parse(category: &str) -> Option<Self> {
+        match category {
+            "action" => Some(Self::Action),
+            "model" => Some(Self::Model),
+            "provider" => Some(Self::Provider),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type, PartialEq)]
+pub struct UsageCount {
+    pub category: UsageCategory,
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct UsageReport {
+    /// First local day included as `YYYY-MM-DD`, or `None` for all time
+    pub since: Option<String>,
+    /// Counts by category, most used first within each category
+    pub counts: Vec<UsageCount>,
+    /// Share of transcriptions that failed
+    pub transcription_error_rate: Option<f64>,
+}
+
+/// Adds one use of `name` in `category` to the counts for `day`.
+pub fn record(conn: &Connection, day: &str, category: UsageCategory, name: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO usage_counts (day, category, name, count) VALUES (?1, ?2, ?3, 1)
+         ON CONFLICT(day, category, name) DO UPDATE SET count = count + 1",
+        params![day, category.as_str(), name],
+    )?;
+    Ok(())
+}
+
+/// Sums the days from `since` (inclusive, `YYYY-MM-DD`) onwards, or all days
+/// when `since` is `None`.
+pub fn query(conn: &Connection, since: Option<&str>) -> Result<UsageReport> {
+    let mut stmt = conn.prepare(
+        "SELECT category, name, SUM(count) AS count
+         FROM usage_counts
+         WHERE ?1 IS NULL OR day >= ?1
+         GROUP BY category, name
+         ORDER BY category, count DESC, name",
+    )?;
+    let rows = stmt.query_map(params![since], |row| {
+        Ok((
+            row.get::<_, String>("category")?,
+            row.get::<_, String>("name")?,
+            row.get::<_, i64>("count")?,
+        ))
+    })?;
+
+    let mut counts = Vec::new();
+    for row in rows {
+        let (category, name, count) = row?;
+        // Rows from a newer version with unknown categories are left out
+        if let Some(category) = UsageCategory::parse(&category) {
+            counts.push(UsageCount {
+                category,
+                name,
+                count,
+            });
+        }
+    }
+
+    let total = |category: UsageCategory, name: Option<&str>| -> i64 {
+        counts
+            .iter()
+            .filter(|c| c.category == category && name.is_none_or(|name| c.name == name))
+            .map(|c| c.count)
+            .sum()
+    };
+    let failed = total(UsageCategory::Error, Some("transcription"));
+    let attempts = total(UsageCategory::Model, None) + failed;
+    let transcription_error_rate = (attempts > 0).then(|| failed as f64 / attempts as f64);
+
+    Ok(UsageReport {
+        since: since.map(str::to_string),
+        counts,
+        transcription_error_rate,
+    })
+}
+
+/// Serializes a report along with the app version and platform, ready to
+/// attach to a bug report
+pub fn shareable_json(report: &UsageReport) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "app_version": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "report": report,
+    }))?)
+}
+
+pub fn clear(conn: &Connection) -> Result<()> {
+    conn.execute("DELETE FROM usage_counts", [])?;
+    Ok(())
+}
+
+/// Counts one use when analytics is enabled. Nothing is recorded during
+/// session incognito, and failures are only logged.
+pub fn track(app: &AppHandle, category: UsageCategory, name: &str) {
+    if !crate::settings::get_settings(app).usage_analytics_enabled
+        || incognito::is_session_enabled()
+    {
+        return;
+    }
+    let Some(history_manager) = app.try_state::<Arc<HistoryManager>>() else {
+        return;
+    };
+    if let Err(e) = history_manager.record_usage(category, name) {
+        warn!("Failed to record usage analytics: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        conn.execute_batch(
+            "CREATE TABLE usage_counts (
+                day TEXT NOT NULL,
+                category TEXT NOT NULL,
+                name TEXT NOT NULL,
+                count INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, category, name)
+            );",
+        )
+        .expect("create usage_counts table");
+        conn
+    }
+
+    #[test]
+    fn sums_counts_and_error_rate() {
+        let conn = setup_conn();
+        for _ in 0..3 {
+            record(&conn, "2026-01-01", UsageCategory::Model, "parakeet").unwrap();
+        }
+        record(&conn, "2026-01-02", UsageCategory::Model, "whisper-small").unwrap();
+        record(&conn, "2026-01-02", UsageCategory::Error, "transcription").unwrap();
+        record(&conn, "2026-01-02", UsageCategory::Action, "transcribe").unwrap();
+
+        let report = query(&conn, None).unwrap();
+        assert_eq!(
+            report.counts[2],
+            UsageCount {
+                category: UsageCategory::Model,
+                name: "parakeet".to_string(),
+                count: 3,
+            }
+        );
+        assert_eq!(report.transcription_error_rate, Some(0.2));
+
+        let recent = query(&conn, Some("2026-01-02")).unwrap();
+        assert_eq!(recent.counts.len(), 3);
+        assert_eq!(recent.transcription_error_rate, Some(0.5));
+    }
+}
@@ -1,12 +1,91 @@
-use crate::audio_toolkit::{list_input_devices, vad::SmoothedVad, AudioRecorder, SileroVad};
+use crate::audio_toolkit::{
+    list_input_devices, vad::SmoothedVad, AudioLevels, AudioRecorder, SileroVad,
+};
 use crate::helpers::clamshell;
 use crate::settings::{get_settings, AppSettings};
+use crate::signal_handle;
 use crate::utils;
 use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::thread;
+use std::time::{Duration, Instant};
 use tauri::Manager;
 
+/// Minimum time between `mic-level` events, for about 30 per second
+const LEVEL_EVENT_INTERVAL: Duration = Duration::from_millis(33);
+/// How far levels move toward a louder or quieter reading per analysis window
+const LEVEL_ATTACK: f32 = 0.6;
+const LEVEL_RELEASE: f32 = 0.2;
+/// Per-window decay of the held peak
+const PEAK_DECAY: f32 = 0.95;
+
+/// How often elapsed recording time is sent to the overlay
+const TIMER_TICK: Duration = Duration::from_millis(250);
+/// The overlay warns once this little time is left before the max duration
+const DURATION_WARNING: Duration = Duration::from_secs(10);
+
+/// Payload of the `mic_level` app event
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct MicLevel {
+    /// Smoothed RMS amplitude, 0.0 to 1.0
+    pub rms: f32,
+    /// Recent peak amplitude, decaying slowly, 0.0 to 1.0
+    pub peak: f32,
+    /// Smoothed loudness per frequency band from low to high, 0.0 to 1.0
+    pub bands: Vec<f32>,
+}
+
+/// Payload of the `recording_timer` app event
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+pub struct RecordingTimer {
+    pub elapsed_ms: u64,
+    /// Max recording duration, `None` when recordings aren't limited
+    pub limit_ms: Option<u64>,
+    /// The limit is close and the recording will soon stop on its own
+    pub warning: bool,
+}
+
+/// Turns analysis windows from the recorder into `mic_level` events at a
+/// steady rate. Levels rise quickly and fall slowly so drawings don't flicker.
+#[derive(Default)]
+struct LevelMeter {
+    level: MicLevel,
+    last_emit: Option<Instant>,
+}
+
+impl LevelMeter {
+    fn smooth(current: f32, target: f32) -> f32 {
+        let rate = if target > current {
+            LEVEL_ATTACK
+        } else {
+            LEVEL_RELEASE
+        };
+        current + (target - current) * rate
+    }
+
+    /// Folds in a new window and returns the level when an event is due
+    fn update(&mut self, levels: AudioLevels, now: Instant) -> Option<MicLevel> {
+        self.level.rms = Self::smooth(self.level.rms, levels.rms);
+        self.level.peak = levels.peak.max(self.level.peak * PEAK_DECAY);
+        self.level.bands.resize(levels.bands.len(), 0.0);
+        for (band, target) in self.level.bands.iter_mut().zip(levels.bands) {
+            *band = Self::smooth(*band, target);
+        }
+
+        if self
+            .last_emit
+            .is_some_and(|last| now.duration_since(last) < LEVEL_EVENT_INTERVAL)
+        {
+            return None;
+        }
+        self.last_emit = Some(now);
+        Some(self.level.clone())
+    }
+}
+
 fn set_mute(mute: bool) {
     // Expected behavior:
     // - Windows: works on most systems using standard audio drivers.
@@ -122,15 +201,22 @@ fn create_audio_recorder(
         .map_err(|e| anyhow::anyhow!("Failed to create SileroVad: {}", e))?;
     let smoothed_vad = SmoothedVad::new(Box::new(silero), 15, 15, 2);
 
-    // Recorder with VAD plus a spectrum-level callback that forwards updates to
+    // Recorder with VAD plus a level callback that forwards smoothed levels to
     // the frontend.
     let recorder = AudioRecorder::new()
         .map_err(|e| anyhow::anyhow!("Failed to create AudioRecorder: {}", e))?
         .with_vad(Box::new(smoothed_vad))
         .with_level_callback({
             let app_handle = app_handle.clone();
+            let meter = Mutex::new(LevelMeter::default());
             move |levels| {
-                utils::emit_levels(&app_handle, &levels);
+                let level = meter
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .update(levels, Instant::now());
+                if let Some(level) = level {
+                    utils::emit_levels(&app_handle, &level);
+                }
             }
         });
 
@@ -149,6 +235,9 @@ pub struct AudioRecordingManager {
     is_open: Arc<Mutex<bool>>,
     is_recording: Arc<Mutex<bool>>,
     did_mute: Arc<Mutex<bool>>,
+    /// Incremented on every recording start so a timer thread can tell its
+    /// recording has ended even if a new one has already started
+    recording_session: Arc<AtomicU64>,
 }
 
 impl AudioRecordingManager {
@@ -171,6 +260,7 @@ impl AudioRecordingManager {
             is_open: Arc::new(Mutex::new(false)),
             is_recording: Arc::new(Mutex::new(false)),
             did_mute: Arc::new(Mutex::new(false)),
+            recording_session: Arc::new(AtomicU64::new(0)),
         };
 
         // Always-on?  Open immediately.
@@ -351,6 +441,7 @@ impl AudioRecordingManager {
                         binding_id: binding_id.to_string(),
                     };
                     debug!("Recording started for binding {binding_id}");
+                    self.start_recording_timer(binding_id);
                     return true;
                 }
             }
@@ -361,6 +452,58 @@ impl AudioRecordingManager {
         }
     }
 
+    /// Sends elapsed time to the overlay and tray until the recording ends,
+    /// and stops the recording through the coordinator once it reaches the
+    /// max duration.
+    fn start_recording_timer(&self, binding_id: &str) {
+        let session = self.recording_session.fetch_add(1, Ordering::SeqCst) + 1;
+        let limit = match get_settings(&self.app_handle).max_recording_duration_secs {
+            0 => None,
+            secs => Some(Duration::from_secs(secs as u64)),
+        };
+        let manager = self.clone();
+        let binding_id = binding_id.to_string();
+
+        thread::spawn(move || {
+            let started = Instant::now();
+            let mut shown_secs = None;
+            let mut limit_reached = false;
+
+            while manager.recording_session.load(Ordering::SeqCst) == session
+                && manager.is_recording()
+            {
+                let elapsed = started.elapsed();
+                utils::emit_recording_timer(
+                    &manager.app_handle,
+                    &RecordingTimer {
+                        elapsed_ms: elapsed.as_millis() as u64,
+                        limit_ms: limit.map(|limit| limit.as_millis() as u64),
+                        warning: limit
+                            .is_some_and(|limit| limit.saturating_sub(elapsed) <= DURATION_WARNING),
+                    },
+                );
+
+                // The tray status only shows whole seconds
+                if shown_secs != Some(elapsed.as_secs()) {
+                    shown_secs = Some(elapsed.as_secs());
+                    utils::set_recording_elapsed(&manager.app_handle, elapsed);
+                }
+
+                if !limit_reached && limit.is_some_and(|limit| elapsed >= limit) {
+                    limit_reached = true;
+                    info!("Max recording duration reached, stopping {binding_id}");
+                    signal_handle::send_transcription_input(
+                        &manager.app_handle,
+                        &binding_id,
+                        "max_duration",
+                    );
+                }
+
+                thread::sleep(TIMER_TICK);
+            }
+        });
+    }
+
     pub fn update_selected_device(&self) -> Result<(), anyhow::Error> {
         // If currently open, restart the microphone stream to use the new device
         if *self.is_open.lock().unwrap() {
@@ -421,6 +564,19 @@ impl AudioRecordingManager {
         )
     }
 
+    /// Whether the microphone stream is open, recording or not
+    pub fn is_microphone_open(&self) -> bool {
+        *self.is_open.lock().unwrap()
+    }
+
+    /// The binding whose recording is in progress, if any
+    pub fn active_binding(&self) -> Option<String> {
+        match &*self.state.lock().unwrap() {
+            RecordingState::Recording { binding_id } => Some(binding_id.clone()),
+            _ => None,
+        }
+    }
+
     /// Cancel any ongoing recording without returning audio samples
     pub fn cancel_recording(&self) {
         let mut state = self.state.lock().unwrap();
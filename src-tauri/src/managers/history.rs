@@ -1,15 +1,27 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use chrono::{DateTime, Local, Utc};
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
+use parking_lot::{Mutex, RwLock, RwLockReadGuard};
 use rusqlite::{params, Connection, OptionalExtension};
 use rusqlite_migration::{Migrations, M};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::fs;
-use std::path::PathBuf;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
 
-use crate::audio_toolkit::save_wav_file;
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::encode_wav;
+use crate::data_dirs::{self, DataKind};
+use crate::encryption::{self, HistoryKey};
+use crate::events::{self, ErrorSource};
+use crate::managers::analytics::{self, UsageCategory, UsageReport};
+use crate::managers::history_search::{self, HistorySearchResult};
+use crate::managers::history_sync::{self, SyncResult};
+use crate::managers::stats::{self, DictationStats};
 
 /// Database migrations for transcription history.
 /// Each migration is applied in order. The library tracks which migrations
@@ -18,7 +30,7 @@ use crate::audio_toolkit::save_wav_file;
 /// Note: For users upgrading from tauri-plugin-sql, migrate_from_tauri_plugin_sql()
 /// converts the old _sqlx_migrations table tracking to the user_version pragma,
 /// ensuring migrations don't re-run on existing databases.
-static MIGRATIONS: &[M] = &[
+pub(crate) static MIGRATIONS: &[M] = &[
     M::up(
         "CREATE TABLE IF NOT EXISTS transcription_history (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -31,8 +43,84 @@ static MIGRATIONS: &[M] = &[
     ),
     M::up("ALTER TABLE transcription_history ADD COLUMN post_processed_text TEXT;"),
     M::up("ALTER TABLE transcription_history ADD COLUMN post_process_prompt TEXT;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN duration_ms INTEGER;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN model TEXT;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN target_app TEXT;"),
+    M::up(
+        "CREATE TABLE IF NOT EXISTS dictation_stats (
+            day TEXT PRIMARY KEY,
+            recordings INTEGER NOT NULL DEFAULT 0,
+            words INTEGER NOT NULL DEFAULT 0,
+            audio_ms INTEGER NOT NULL DEFAULT 0,
+            latency_ms INTEGER NOT NULL DEFAULT 0,
+            latency_samples INTEGER NOT NULL DEFAULT 0
+        );",
+    ),
+    M::up(
+        "ALTER TABLE transcription_history ADD COLUMN sync_id TEXT;
+         CREATE UNIQUE INDEX IF NOT EXISTS idx_transcription_history_sync_id
+             ON transcription_history(sync_id);",
+    ),
+    M::up(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS transcription_history_fts USING fts5(
+            transcription_text,
+            post_processed_text,
+            content='transcription_history',
+            content_rowid='id',
+            tokenize='unicode61 remove_diacritics 2'
+        );
+        CREATE VIRTUAL TABLE IF NOT EXISTS transcription_history_vocab
+            USING fts5vocab(transcription_history_fts, 'row');
+        CREATE TRIGGER IF NOT EXISTS transcription_history_fts_insert
+        AFTER INSERT ON transcription_history BEGIN
+            INSERT INTO transcription_history_fts(rowid, transcription_text, post_processed_text)
+            VALUES (new.id, new.transcription_text, new.post_processed_text);
+        END;
+        CREATE TRIGGER IF NOT EXISTS transcription_history_fts_delete
+        AFTER DELETE ON transcription_history BEGIN
+            INSERT INTO transcription_history_fts(transcription_history_fts, rowid, transcription_text, post_processed_text)
+            VALUES ('delete', old.id, old.transcription_text, old.post_processed_text);
+        END;
+        CREATE TRIGGER IF NOT EXISTS transcription_history_fts_update
+        AFTER UPDATE OF transcription_text, post_processed_text ON transcription_history BEGIN
+            INSERT INTO transcription_history_fts(transcription_history_fts, rowid, transcription_text, post_processed_text)
+            VALUES ('delete', old.id, old.transcription_text, old.post_processed_text);
+            INSERT INTO transcription_history_fts(rowid, transcription_text, post_processed_text)
+            VALUES (new.id, new.transcription_text, new.post_processed_text);
+        END;
+        INSERT INTO transcription_history_fts(transcription_history_fts) VALUES ('rebuild');",
+    ),
+    M::up("ALTER TABLE transcription_history ADD COLUMN window_title TEXT;"),
+    M::up(
+        "CREATE TABLE IF NOT EXISTS usage_counts (
+            day TEXT NOT NULL,
+            category TEXT NOT NULL,
+            name TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (day, category, name)
+        );",
+    ),
+    M::up(
+        "CREATE TABLE IF NOT EXISTS sync_journals (
+            path TEXT PRIMARY KEY,
+            exported_id INTEGER NOT NULL DEFAULT 0,
+            read_offset INTEGER NOT NULL DEFAULT 0,
+            read_tail BLOB NOT NULL DEFAULT x''
+        );
+        CREATE TABLE IF NOT EXISTS sync_tombstones (sync_id TEXT PRIMARY KEY);
+        CREATE TRIGGER IF NOT EXISTS transcription_history_sync_tombstone
+        AFTER DELETE ON transcription_history WHEN old.sync_id IS NOT NULL BEGIN
+            INSERT OR IGNORE INTO sync_tombstones(sync_id) VALUES (old.sync_id);
+        END;",
+    ),
 ];
 
+/// Columns read into a [`HistoryEntry`]
+pub const ENTRY_COLUMNS: &str = "id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, duration_ms, model, target_app, window_title";
+
+/// Largest page `get_history_page` returns
+const MAX_PAGE_SIZE: usize = 200;
+
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
 pub struct HistoryEntry {
     pub id: i64,
@@ -43,31 +131,104 @@ pub struct HistoryEntry {
     pub transcription_text: String,
     pub post_processed_text: Option<String>,
     pub post_process_prompt: Option<String>,
+    /// Length of the recording in milliseconds
+    pub duration_ms: Option<i64>,
+    /// Id of the model that produced the transcription
+    pub model: Option<String>,
+    /// Name of the app that had focus when the text was delivered
+    pub target_app: Option<String>,
+    /// Title of the focused window, unless title capture is turned off
+    pub window_title: Option<String>,
+}
+
+/// Details recorded alongside a transcription
+#[derive(Clone, Debug, Default)]
+pub struct TranscriptionMetadata {
+    pub model: Option<String>,
+    pub target_app: Option<String>,
+    pub window_title: Option<String>,
+    /// Time from the end of recording until the transcription was ready
+    pub latency_ms: Option<i64>,
+}
+
+/// Number of history entries delivered to an app
+#[derive(Clone, Debug, Serialize, Deserialize, Type, PartialEq)]
+pub struct HistoryAppCount {
+    pub app: String,
+    pub entries: i64,
+}
+
+/// One page of history, newest first
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    /// Number of entries matching the search across all pages
+    pub total: i64,
 }
 
 pub struct HistoryManager {
     app_handle: AppHandle,
     recordings_dir: PathBuf,
     db_path: PathBuf,
+    /// Held for writing while encryption is switched, so nothing writes to
+    /// the database or recordings being replaced
+    conversion: RwLock<()>,
+    /// Held while syncing, so two syncs can't export the same entries
+    sync_lock: Mutex<()>,
+    /// Synced records last reported as waiting for another machine's key
+    locked_records: AtomicUsize,
+}
+
+/// An open history database. Encryption can't be switched while it's open.
+struct HistoryConnection<'a> {
+    conn: Connection,
+    _conversion: RwLockReadGuard<'a, ()>,
+}
+
+impl Deref for HistoryConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+impl DerefMut for HistoryConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Connection {
+        &mut self.conn
+    }
 }
 
 impl HistoryManager {
     pub fn new(app_handle: &AppHandle) -> Result<Self> {
-        // Create recordings directory in app data dir
-        let app_data_dir = app_handle.path().app_data_dir()?;
-        let recordings_dir = app_data_dir.join("recordings");
-        let db_path = app_data_dir.join("history.db");
+        let recordings_dir =
+            data_dirs::dir(app_handle, DataKind::Recordings).map_err(|e| anyhow!(e))?;
+        let db_path = data_dirs::dir(app_handle, DataKind::History)
+            .map_err(|e| anyhow!(e))?
+            .join("history.db");
 
         // Ensure recordings directory exists
         if !recordings_dir.exists() {
             fs::create_dir_all(&recordings_dir)?;
             debug!("Created recordings directory: {:?}", recordings_dir);
         }
+        if let Some(db_dir) = db_path.parent() {
+            fs::create_dir_all(db_dir)?;
+        }
+
+        // Earlier builds decrypted recordings here for playback
+        let playback_dir = app_handle.path().app_cache_dir()?.join("playback");
+        if playback_dir.exists() {
+            fs::remove_dir_all(&playback_dir)?;
+        }
 
         let manager = Self {
             app_handle: app_handle.clone(),
             recordings_dir,
             db_path,
+            conversion: RwLock::new(()),
+            sync_lock: Mutex::new(()),
+            locked_records: AtomicUsize::new(0),
         };
 
         // Initialize database and run migrations synchronously
@@ -79,7 +240,7 @@ impl HistoryManager {
     fn init_database(&self) -> Result<()> {
         info!("Initializing database at {:?}", self.db_path);
 
-        let mut conn = Connection::open(&self.db_path)?;
+        let mut conn = self.get_connection()?;
 
         // Handle migration from tauri-plugin-sql to rusqlite_migration
         // tauri-plugin-sql used _sqlx_migrations table, rusqlite_migration uses user_version pragma
@@ -172,64 +333,307 @@ impl HistoryManager {
         Ok(())
     }
 
-    fn get_connection(&self) -> Result<Connection> {
-        Ok(Connection::open(&self.db_path)?)
+    fn get_connection(&self) -> Result<HistoryConnection<'_>> {
+        // Recursive so a nested connection can't deadlock behind a waiting switch
+        let conversion = self.conversion.read_recursive();
+        Ok(HistoryConnection {
+            conn: self.open_connection()?,
+            _conversion: conversion,
+        })
     }
 
-    /// Save a transcription to history (both database and WAV file)
+    /// Opens the database, unlocking it with the key from the credential store
+    /// when it is encrypted or when encryption is enabled for a new database.
+    fn open_connection(&self) -> Result<Connection> {
+        let is_new = !self.db_path.exists();
+        let encrypted = if is_new {
+            crate::settings::get_settings(&self.app_handle).encrypt_history
+        } else {
+            encryption::database_is_encrypted(&self.db_path)
+        };
+
+        let conn = Connection::open(&self.db_path)?;
+        if encrypted {
+            let key = encryption::history_key(is_new)
+                .map_err(|e| anyhow!(e))?
+                .ok_or_else(|| anyhow!("History is encrypted but its key is missing"))?;
+            conn.execute_batch(&format!(
+                "PRAGMA key = {};",
+                encryption::sqlcipher_key(&key)
+            ))?;
+        }
+        Ok(conn)
+    }
+
+    /// Encrypts or decrypts the database and all recordings, then saves the
+    /// setting. Encrypting creates the key on first use; it is kept afterwards
+    /// so older backups stay readable.
+    pub fn set_encryption(&self, enabled: bool) -> Result<()> {
+        let _conversion = self.conversion.write();
+        let Some(key) = encryption::history_key(enabled).map_err(|e| anyhow!(e))? else {
+            if self.has_encrypted_data()? {
+                return Err(anyhow!(
+                    "History is encrypted but its key is missing, so it can't be decrypted"
+                ));
+            }
+            // Never encrypted, so there is nothing to decrypt
+            self.save_encryption_setting(enabled);
+            return Ok(());
+        };
+
+        self.convert_database(&key, enabled)?;
+
+        let settings = crate::settings::get_settings(&self.app_handle);
+        if let (Some(dir), Some(device_id)) = (
+            settings
+                .history_sync_dir
+                .as_deref()
+                .map(str::trim)
+                .filter(|dir| !dir.is_empty()),
+            settings.history_sync_device_id.as_deref(),
+        ) {
+            history_sync::reseal_journal(Path::new(dir), device_id, &key, enabled)?;
+        }
+
+        for entry in fs::read_dir(&self.recordings_dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "wav") {
+                let result = if enabled {
+                    encryption::encrypt_file(&key, &path)
+                } else {
+                    encryption::decrypt_file(&key, &path)
+                };
+                result.map_err(|e| anyhow!(e))?;
+            }
+        }
+
+        // Saved before new writes can start, so they use the new format
+        self.save_encryption_setting(enabled);
+        info!(
+            "History encryption {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        Ok(())
+    }
+
+    fn save_encryption_setting(&self, enabled: bool) {
+        let mut settings = crate::settings::get_settings(&self.app_handle);
+        settings.encrypt_history = enabled;
+        crate::settings::write_settings(&self.app_handle, settings);
+    }
+
+    fn has_encrypted_data(&self) -> Result<bool> {
+        if encryption::database_is_encrypted(&self.db_path) {
+            return Ok(true);
+        }
+        for entry in fs::read_dir(&self.recordings_dir)? {
+            if encryption::file_is_encrypted(&entry?.path()) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Runs [`Self::sync`] on its own thread, so a slow or unreachable sync
+    /// folder doesn't hold up startup or dictation
+    pub fn sync_in_background(&self) {
+        let app = self.app_handle.clone();
+        std::thread::spawn(move || {
+            let Some(manager) = app.try_state::<Arc<HistoryManager>>() else {
+                return;
+            };
+            if let Err(e) = manager.sync() {
+                warn!("Failed to sync history: {}", e);
+            }
+        });
+    }
+
+    /// Exports new local entries to the sync folder and merges entries from
+    /// other machines. Does nothing when no sync folder is configured.
+    pub fn sync(&self) -> Result<SyncResult> {
+        let _sync = self.sync_lock.lock();
+        let mut settings = crate::settings::get_settings(&self.app_handle);
+        let Some(dir) = settings
+            .history_sync_dir
+            .as_deref()
+            .map(str::trim)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+        else {
+            return Ok(SyncResult::default());
+        };
+
+        let device_id = match settings.history_sync_device_id.clone() {
+            Some(id) => id,
+            None => {
+                let id = history_sync::new_device_id();
+                settings.history_sync_device_id = Some(id.clone());
+                crate::settings::write_settings(&self.app_handle, settings);
+                id
+            }
+        };
+
+        // Kept for reading sealed records even while encryption is off
+        let key = encryption::history_key(false).map_err(|e| anyhow!(e))?;
+        let seal_key = key.as_ref().filter(|_| settings.encrypt_history);
+        if settings.encrypt_history && seal_key.is_none() {
+            return Err(anyhow!("History encryption is on but its key is missing"));
+        }
+
+        let mut open_keys = encryption::peer_keys().map_err(|e| anyhow!(e))?;
+        open_keys.extend(key);
+
+        fs::create_dir_all(&dir)?;
+        let conn = self.get_connection()?;
+        let exported = history_sync::export_local(&conn, &dir, &device_id, seal_key)?;
+        let (imported, locked) = history_sync::import_remote(&conn, &dir, &device_id, &open_keys)?;
+        let result = SyncResult {
+            exported,
+            imported,
+            locked,
+        };
+
+        // Reported once each time the count changes, not on every sync
+        if locked > 0 && self.locked_records.swap(locked, Ordering::Relaxed) != locked {
+            events::emit_error(
+                &self.app_handle,
+                ErrorSource::HistorySync,
+                format!(
+                    "{} synced history entries are encrypted with another machine's key. Export the history key there and import it here to merge them.",
+                    locked
+                ),
+            );
+        } else if locked == 0 {
+            self.locked_records.store(0, Ordering::Relaxed);
+        }
+
+        if result.imported > 0 {
+            info!(
+                "Merged {} history entries from other machines",
+                result.imported
+            );
+            if let Err(e) = self.app_handle.emit("history-updated", ()) {
+                error!("Failed to emit history-updated event: {}", e);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Rewrites the database with or without SQLCipher encryption.
+    fn convert_database(&self, key: &HistoryKey, encrypt: bool) -> Result<()> {
+        if !self.db_path.exists() || encryption::database_is_encrypted(&self.db_path) == encrypt {
+            return Ok(());
+        }
+
+        let converted_path = self.db_path.with_extension("db.converting");
+        if converted_path.exists() {
+            fs::remove_file(&converted_path)?;
+        }
+        let target_key = if encrypt {
+            encryption::sqlcipher_key(key)
+        } else {
+            "''".to_string()
+        };
+
+        // The caller holds the conversion lock, so nothing else has it open
+        {
+            let conn = self.open_connection()?;
+            // sqlcipher_export copies the schema and data but not user_version
+            let version: i32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+            conn.execute_batch(&format!(
+                "ATTACH DATABASE '{}' AS converted KEY {};",
+                converted_path.to_string_lossy().replace('\'', "''"),
+                target_key
+            ))?;
+            conn.query_row("SELECT sqlcipher_export('converted')", [], |_| Ok(()))?;
+            conn.execute_batch(&format!(
+                "PRAGMA converted.user_version = {}; DETACH DATABASE converted;",
+                version
+            ))?;
+        }
+
+        fs::rename(&converted_path, &self.db_path)?;
+        Ok(())
+    }
+
+    /// Save a transcription to history (both database and WAV file),
+    /// returning the new entry's id
     pub async fn save_transcription(
         &self,
-        audio_samples: Vec<f32>,
+        audio_samples: Arc<Vec<f32>>,
         transcription_text: String,
         post_processed_text: Option<String>,
         post_process_prompt: Option<String>,
-    ) -> Result<()> {
+        metadata: TranscriptionMetadata,
+    ) -> Result<i64> {
         let timestamp = Utc::now().timestamp();
+        let duration_ms = (audio_samples.len() as u64 * 1000 / WHISPER_SAMPLE_RATE as u64) as i64;
         let file_name = format!("handy-{}.wav", timestamp);
         let title = self.format_timestamp_title(timestamp);
 
-        // Save WAV file
-        let file_path = self.recordings_dir.join(&file_name);
-        save_wav_file(file_path, &audio_samples).await?;
+        // Save WAV file, encrypted before it reaches the disk
+        if crate::settings::get_settings(&self.app_handle).keep_recordings {
+            let mut wav = encode_wav(&audio_samples)?;
+            let _conversion = self.conversion.read_recursive();
+            if crate::settings::get_settings(&self.app_handle).encrypt_history {
+                // The key is created with the database or when encryption is
+                // turned on, never here
+                let key = encryption::history_key(false)
+                    .map_err(|e| anyhow!(e))?
+                    .ok_or_else(|| anyhow!("History encryption key is missing"))?;
+                wav = encryption::encrypt(&key, &wav).map_err(|e| anyhow!(e))?;
+            }
+            fs::write(self.recordings_dir.join(&file_name), wav)?;
+        }
 
         // Save to database
-        self.save_to_database(
+        let entry = HistoryEntry {
+            id: 0,
             file_name,
             timestamp,
+            saved: false,
             title,
             transcription_text,
             post_processed_text,
             post_process_prompt,
+            duration_ms: Some(duration_ms),
+            model: metadata.model,
+            target_app: metadata.target_app,
+            window_title: metadata.window_title,
+        };
+        let id = self.save_to_database(&entry)?;
+
+        let conn = self.get_connection()?;
+        stats::record(
+            &conn,
+            &Local::now().format("%Y-%m-%d").to_string(),
+            stats::count_words(&entry.transcription_text),
+            duration_ms,
+            metadata.latency_ms,
         )?;
 
         // Clean up old entries
         self.cleanup_old_entries()?;
+        self.enforce_recordings_size_limit()?;
+
+        self.sync_in_background();
 
         // Emit history updated event
         if let Err(e) = self.app_handle.emit("history-updated", ()) {
             error!("Failed to emit history-updated event: {}", e);
         }
 
-        Ok(())
+        Ok(id)
     }
 
-    fn save_to_database(
-        &self,
-        file_name: String,
-        timestamp: i64,
-        title: String,
-        transcription_text: String,
-        post_processed_text: Option<String>,
-        post_process_prompt: Option<String>,
-    ) -> Result<()> {
+    /// Inserts `entry` and returns the `id` the database assigned it; the
+    /// entry's own `id` is ignored.
+    fn save_to_database(&self, entry: &HistoryEntry) -> Result<i64> {
         let conn = self.get_connection()?;
-        conn.execute(
-            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![file_name, timestamp, false, title, transcription_text, post_processed_text, post_process_prompt],
-        )?;
+        Self::insert_entry_with_conn(&conn, entry)?;
 
         debug!("Saved transcription to database");
-        Ok(())
+        Ok(conn.last_insert_rowid())
     }
 
     pub fn cleanup_old_entries(&self) -> Result<()> {
@@ -252,6 +656,52 @@ impl HistoryManager {
         }
     }
 
+    /// Deletes the audio (but not the transcripts) of the oldest unsaved
+    /// entries until all recordings fit in `recordings_size_limit_mb`.
+    pub fn enforce_recordings_size_limit(&self) -> Result<()> {
+        let limit_mb = crate::settings::get_settings(&self.app_handle).recordings_size_limit_mb;
+        if limit_mb == 0 {
+            return Ok(());
+        }
+
+        let mut total = 0;
+        for entry in fs::read_dir(&self.recordings_dir)? {
+            let entry = entry?;
+            if entry.path().extension().is_some_and(|ext| ext == "wav") {
+                total += entry.metadata()?.len();
+            }
+        }
+
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT file_name FROM transcription_history WHERE saved = 0 ORDER BY timestamp ASC",
+        )?;
+        let mut candidates = Vec::new();
+        for file_name in stmt.query_map([], |row| row.get::<_, String>("file_name"))? {
+            let file_name = file_name?;
+            match fs::metadata(self.recordings_dir.join(&file_name)) {
+                Ok(metadata) if metadata.is_file() => candidates.push((file_name, metadata.len())),
+                _ => {}
+            }
+        }
+
+        let to_delete = recordings_over_limit(&candidates, total, limit_mb as u64 * 1024 * 1024);
+        for file_name in &to_delete {
+            if let Err(e) = fs::remove_file(self.recordings_dir.join(file_name)) {
+                error!("Failed to delete recording {}: {}", file_name, e);
+            }
+        }
+        if !to_delete.is_empty() {
+            debug!(
+                "Deleted {} recordings to stay under {} MB",
+                to_delete.len(),
+                limit_mb
+            );
+        }
+
+        Ok(())
+    }
+
     fn delete_entries_and_files(&self, entries: &[(i64, String)]) -> Result<usize> {
         if entries.is_empty() {
             return Ok(0);
@@ -269,7 +719,7 @@ impl HistoryManager {
 
             // Delete WAV file
             let file_path = self.recordings_dir.join(file_name);
-            if file_path.exists() {
+            if file_path.is_file() {
                 if let Err(e) = fs::remove_file(&file_path) {
                     error!("Failed to delete WAV file {}: {}", file_name, e);
                 } else {
@@ -352,31 +802,216 @@ impl HistoryManager {
         Ok(())
     }
 
+    fn insert_entry_with_conn(conn: &Connection, entry: &HistoryEntry) -> Result<()> {
+        conn.execute(
+            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, duration_ms, model, target_app, window_title) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                entry.file_name,
+                entry.timestamp,
+                entry.saved,
+                entry.title,
+                entry.transcription_text,
+                entry.post_processed_text,
+                entry.post_process_prompt,
+                entry.duration_ms,
+                entry.model,
+                entry.target_app,
+                entry.window_title
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
+        Ok(HistoryEntry {
+            id: row.get("id")?,
+            file_name: row.get("file_name")?,
+            timestamp: row.get("timestamp")?,
+            saved: row.get("saved")?,
+            title: row.get("title")?,
+            transcription_text: row.get("transcription_text")?,
+            post_processed_text: row.get("post_processed_text")?,
+            post_process_prompt: row.get("post_process_prompt")?,
+            duration_ms: row.get("duration_ms")?,
+            model: row.get("model")?,
+            target_app: row.get("target_app")?,
+            window_title: row.get("window_title")?,
+        })
+    }
+
     pub async fn get_history_entries(&self) -> Result<Vec<HistoryEntry>> {
         let conn = self.get_connection()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt FROM transcription_history ORDER BY timestamp DESC"
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM transcription_history ORDER BY timestamp DESC",
+            ENTRY_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map([], Self::entry_from_row)?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+
+        Ok(entries)
+    }
+
+    /// Returns entries with `start <= timestamp <= end`, oldest first. Either
+    /// bound may be omitted.
+    pub async fn get_entries_between(
+        &self,
+        start: Option<i64>,
+        end: Option<i64>,
+    ) -> Result<Vec<HistoryEntry>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM transcription_history
+             WHERE (?1 IS NULL OR timestamp >= ?1) AND (?2 IS NULL OR timestamp <= ?2)
+             ORDER BY timestamp, id",
+            ENTRY_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![start, end], Self::entry_from_row)?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+
+        Ok(entries)
+    }
+
+    /// Returns up to `limit` entries starting at `offset`, newest first. When
+    /// `search` is non-empty only entries whose raw or post-processed text
+    /// contains it (case-insensitively for ASCII) are included, and when `app`
+    /// is set only entries delivered to that app.
+    pub async fn get_history_page(
+        &self,
+        offset: usize,
+        limit: usize,
+        search: Option<String>,
+        app: Option<String>,
+    ) -> Result<HistoryPage> {
+        let conn = self.get_connection()?;
+        Self::get_history_page_with_conn(&conn, offset, limit, search.as_deref(), app.as_deref())
+    }
+
+    fn get_history_page_with_conn(
+        conn: &Connection,
+        offset: usize,
+        limit: usize,
+        search: Option<&str>,
+        app: Option<&str>,
+    ) -> Result<HistoryPage> {
+        // LIKE wildcards in the search are matched literally
+        let pattern = search.map(str::trim).filter(|s| !s.is_empty()).map(|s| {
+            format!(
+                "%{}%",
+                s.replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            )
+        });
+        let filter = "(?1 IS NULL
+             OR transcription_text LIKE ?1 ESCAPE '\\'
+             OR post_processed_text LIKE ?1 ESCAPE '\\')
+             AND (?2 IS NULL OR target_app = ?2)";
+
+        let total: i64 = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM transcription_history WHERE {}",
+                filter
+            ),
+            params![pattern, app],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM transcription_history WHERE {} ORDER BY timestamp DESC, id DESC LIMIT ?3 OFFSET ?4",
+            ENTRY_COLUMNS, filter
+        ))?;
+        let limit = limit.min(MAX_PAGE_SIZE) as i64;
+        let rows = stmt.query_map(
+            params![pattern, app, limit, offset as i64],
+            Self::entry_from_row,
         )?;
 
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+
+        Ok(HistoryPage { entries, total })
+    }
+
+    /// Apps that received dictation, most used first
+    pub fn get_history_apps(&self) -> Result<Vec<HistoryAppCount>> {
+        let conn = self.get_connection()?;
+        Self::get_history_apps_with_conn(&conn)
+    }
+
+    fn get_history_apps_with_conn(conn: &Connection) -> Result<Vec<HistoryAppCount>> {
+        let mut stmt = conn.prepare(
+            "SELECT target_app, COUNT(*) AS entries FROM transcription_history
+             WHERE target_app IS NOT NULL
+             GROUP BY target_app
+             ORDER BY entries DESC, target_app",
+        )?;
         let rows = stmt.query_map([], |row| {
-            Ok(HistoryEntry {
-                id: row.get("id")?,
-                file_name: row.get("file_name")?,
-                timestamp: row.get("timestamp")?,
-                saved: row.get("saved")?,
-                title: row.get("title")?,
-                transcription_text: row.get("transcription_text")?,
-                post_processed_text: row.get("post_processed_text")?,
-                post_process_prompt: row.get("post_process_prompt")?,
+            Ok(HistoryAppCount {
+                app: row.get("target_app")?,
+                entries: row.get("entries")?,
             })
         })?;
 
-        let mut entries = Vec::new();
+        let mut apps = Vec::new();
         for row in rows {
-            entries.push(row?);
+            apps.push(row?);
         }
 
-        Ok(entries)
+        Ok(apps)
+    }
+
+    /// Full-text search over raw and post-processed text, best match first.
+    pub async fn search_history(
+        &self,
+        query: String,
+        limit: usize,
+    ) -> Result<Vec<HistorySearchResult>> {
+        let conn = self.get_connection()?;
+        history_search::search(&conn, &query, limit.min(MAX_PAGE_SIZE))
+    }
+
+    /// Dictation statistics for the last `days` days including today, or for
+    /// all time when `days` is `None`.
+    pub fn get_dictation_stats(&self, days: Option<u32>) -> Result<DictationStats> {
+        let since = first_day_of_last(days);
+        let conn = self.get_connection()?;
+        stats::query(&conn, since.as_deref())
+    }
+
+    /// Counts one use for today's usage analytics. Callers check the setting,
+    /// see [`analytics::track`].
+    pub fn record_usage(&self, category: UsageCategory, name: &str) -> Result<()> {
+        let conn = self.get_connection()?;
+        analytics::record(
+            &conn,
+            &Local::now().format("%Y-%m-%d").to_string(),
+            category,
+            name,
+        )
+    }
+
+    /// Usage analytics for the last `days` days including today, or for all
+    /// time when `days` is `None`.
+    pub fn get_usage_report(&self, days: Option<u32>) -> Result<UsageReport> {
+        let since = first_day_of_last(days);
+        let conn = self.get_connection()?;
+        analytics::query(&conn, since.as_deref())
+    }
+
+    pub fn clear_usage_analytics(&self) -> Result<()> {
+        let conn = self.get_connection()?;
+        analytics::clear(&conn)
     }
 
     pub fn get_latest_entry(&self) -> Result<Option<HistoryEntry>> {
@@ -385,31 +1020,35 @@ impl HistoryManager {
     }
 
     fn get_latest_entry_with_conn(conn: &Connection) -> Result<Option<HistoryEntry>> {
-        let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt
-             FROM transcription_history
-             ORDER BY timestamp DESC
-             LIMIT 1",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM transcription_history ORDER BY timestamp DESC LIMIT 1",
+            ENTRY_COLUMNS
+        ))?;
 
-        let entry = stmt
-            .query_row([], |row| {
-                Ok(HistoryEntry {
-                    id: row.get("id")?,
-                    file_name: row.get("file_name")?,
-                    timestamp: row.get("timestamp")?,
-                    saved: row.get("saved")?,
-                    title: row.get("title")?,
-                    transcription_text: row.get("transcription_text")?,
-                    post_processed_text: row.get("post_processed_text")?,
-                    post_process_prompt: row.get("post_process_prompt")?,
-                })
-            })
-            .optional()?;
+        let entry = stmt.query_row([], Self::entry_from_row).optional()?;
 
         Ok(entry)
     }
 
+    /// The newest `limit` entries, newest first
+    pub fn get_recent_entries(&self, limit: usize) -> Result<Vec<HistoryEntry>> {
+        let conn = self.get_connection()?;
+        Self::get_recent_entries_with_conn(&conn, limit)
+    }
+
+    fn get_recent_entries_with_conn(conn: &Connection, limit: usize) -> Result<Vec<HistoryEntry>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM transcription_history ORDER BY timestamp DESC LIMIT ?1",
+            ENTRY_COLUMNS
+        ))?;
+
+        let entries = stmt
+            .query_map(params![limit as i64], Self::entry_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(entries)
+    }
+
     pub async fn toggle_saved_status(&self, id: i64) -> Result<()> {
         let conn = self.get_connection()?;
 
@@ -437,43 +1076,121 @@ impl HistoryManager {
         Ok(())
     }
 
+    /// Saves (pins) or unsaves an entry. Saved entries are exempt from
+    /// retention cleanup.
+    pub async fn set_saved_status(&self, id: i64, saved: bool) -> Result<()> {
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE transcription_history SET saved = ?1 WHERE id = ?2",
+            params![saved, id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow!("History entry {} not found", id));
+        }
+
+        debug!("Set saved status for entry {}: {}", id, saved);
+
+        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+            error!("Failed to emit history-updated event: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Replaces an entry's post-processed text, as when post-processing
+    /// finishes after the entry was saved
+    pub async fn set_post_processed_text(
+        &self,
+        id: i64,
+        text: String,
+        prompt: Option<String>,
+    ) -> Result<()> {
+        let conn = self.get_connection()?;
+        let updated = conn.execute(
+            "UPDATE transcription_history SET post_processed_text = ?1, post_process_prompt = ?2 WHERE id = ?3",
+            params![text, prompt, id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow!("History entry {} not found", id));
+        }
+
+        debug!("Set post-processed text for entry {}", id);
+
+        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+            error!("Failed to emit history-updated event: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Returns only saved entries, newest first.
+    pub async fn get_saved_entries(&self) -> Result<Vec<HistoryEntry>> {
+        let conn = self.get_connection()?;
+        Self::get_saved_entries_with_conn(&conn)
+    }
+
+    fn get_saved_entries_with_conn(conn: &Connection) -> Result<Vec<HistoryEntry>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM transcription_history WHERE saved = 1 ORDER BY timestamp DESC",
+            ENTRY_COLUMNS
+        ))?;
+        let rows = stmt.query_map([], Self::entry_from_row)?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+
+        Ok(entries)
+    }
+
     pub fn get_audio_file_path(&self, file_name: &str) -> PathBuf {
         self.recordings_dir.join(file_name)
     }
 
+    /// Reads a recording as WAV data, decrypting it in memory when history
+    /// is encrypted so no plaintext copy is written.
+    pub fn read_recording(&self, file_name: &str) -> Result<Vec<u8>> {
+        if !is_recording_file_name(file_name) {
+            return Err(anyhow!("Invalid recording name"));
+        }
+        let path = self.get_audio_file_path(file_name);
+        // Entries merged from other machines have no file name
+        if !path.is_file() {
+            return Err(anyhow!("No recording was kept for this entry"));
+        }
+        let data = {
+            let _conversion = self.conversion.read_recursive();
+            fs::read(&path)?
+        };
+        if !encryption::is_encrypted(&data) {
+            return Ok(data);
+        }
+
+        let key = encryption::history_key(false)
+            .map_err(|e| anyhow!(e))?
+            .ok_or_else(|| anyhow!("Recording is encrypted but the key is missing"))?;
+        encryption::decrypt(&key, &data).map_err(|e| anyhow!(e))
+    }
+
     pub async fn get_entry_by_id(&self, id: i64) -> Result<Option<HistoryEntry>> {
         let conn = self.get_connection()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt
-             FROM transcription_history WHERE id = ?1",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM transcription_history WHERE id = ?1",
+            ENTRY_COLUMNS
+        ))?;
 
-        let entry = stmt
-            .query_row([id], |row| {
-                Ok(HistoryEntry {
-                    id: row.get("id")?,
-                    file_name: row.get("file_name")?,
-                    timestamp: row.get("timestamp")?,
-                    saved: row.get("saved")?,
-                    title: row.get("title")?,
-                    transcription_text: row.get("transcription_text")?,
-                    post_processed_text: row.get("post_processed_text")?,
-                    post_process_prompt: row.get("post_process_prompt")?,
-                })
-            })
-            .optional()?;
+        let entry = stmt.query_row([id], Self::entry_from_row).optional()?;
 
         Ok(entry)
     }
 
     pub async fn delete_entry(&self, id: i64) -> Result<()> {
-        let conn = self.get_connection()?;
-
         // Get the entry to find the file name
         if let Some(entry) = self.get_entry_by_id(id).await? {
             // Delete the audio file first
             let file_path = self.get_audio_file_path(&entry.file_name);
-            if file_path.exists() {
+            if file_path.is_file() {
                 if let Err(e) = fs::remove_file(&file_path) {
                     error!("Failed to delete audio file {}: {}", entry.file_name, e);
                     // Continue with database deletion even if file deletion fails
@@ -482,6 +1199,7 @@ impl HistoryManager {
         }
 
         // Delete from database
+        let conn = self.get_connection()?;
         conn.execute(
             "DELETE FROM transcription_history WHERE id = ?1",
             params![id],
@@ -508,6 +1226,34 @@ impl HistoryManager {
     }
 }
 
+/// Picks recordings to delete, oldest first, until `total` bytes fit in
+/// `limit`. `candidates` are (file name, size) pairs ordered oldest first.
+fn recordings_over_limit(candidates: &[(String, u64)], total: u64, limit: u64) -> Vec<String> {
+    let mut remaining = total;
+    candidates
+        .iter()
+        .take_while(|_| remaining > limit)
+        .map(|(file_name, size)| {
+            remaining = remaining.saturating_sub(*size);
+            file_name.clone()
+        })
+        .collect()
+}
+
+/// A bare file name, so a request can't reach outside the recordings folder
+fn is_recording_file_name(file_name: &str) -> bool {
+    !file_name.is_empty() && !file_name.contains(['/', '\\']) && file_name != ".."
+}
+
+/// First local day of the last `days` days including today as `YYYY-MM-DD`,
+/// or `None` for all time
+fn first_day_of_last(days: Option<u32>) -> Option<String> {
+    days.map(|days| {
+        let first_day = Local::now().date_naive() - chrono::Duration::days(days.max(1) as i64 - 1);
+        first_day.format("%Y-%m-%d").to_string()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -524,7 +1270,11 @@ mod tests {
                 title TEXT NOT NULL,
                 transcription_text TEXT NOT NULL,
                 post_processed_text TEXT,
-                post_process_prompt TEXT
+                post_process_prompt TEXT,
+                duration_ms INTEGER,
+                model TEXT,
+                target_app TEXT,
+                window_title TEXT
             );",
         )
         .expect("create transcription_history table");
@@ -532,20 +1282,21 @@ mod tests {
     }
 
     fn insert_entry(conn: &Connection, timestamp: i64, text: &str, post_processed: Option<&str>) {
-        conn.execute(
-            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![
-                format!("handy-{}.wav", timestamp),
-                timestamp,
-                false,
-                format!("Recording {}", timestamp),
-                text,
-                post_processed,
-                Option::<String>::None
-            ],
-        )
-        .expect("insert history entry");
+        let entry = HistoryEntry {
+            id: 0,
+            file_name: format!("handy-{}.wav", timestamp),
+            timestamp,
+            saved: false,
+            title: format!("Recording {}", timestamp),
+            transcription_text: text.to_string(),
+            post_processed_text: post_processed.map(str::to_string),
+            post_process_prompt: None,
+            duration_ms: Some(1500),
+            model: Some("parakeet-tdt-0.6b-v3".to_string()),
+            target_app: None,
+            window_title: None,
+        };
+        HistoryManager::insert_entry_with_conn(conn, &entry).expect("insert history entry");
     }
 
     #[test]
@@ -568,5 +1319,124 @@ mod tests {
         assert_eq!(entry.timestamp, 200);
         assert_eq!(entry.transcription_text, "second");
         assert_eq!(entry.post_processed_text.as_deref(), Some("processed"));
+        assert_eq!(entry.duration_ms, Some(1500));
+    }
+
+    #[test]
+    fn get_recent_entries_returns_newest_first() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "first", None);
+        insert_entry(&conn, 300, "third", None);
+        insert_entry(&conn, 200, "second", None);
+
+        let entries =
+            HistoryManager::get_recent_entries_with_conn(&conn, 2).expect("fetch recent entries");
+        let texts: Vec<&str> = entries
+            .iter()
+            .map(|entry| entry.transcription_text.as_str())
+            .collect();
+        assert_eq!(texts, ["third", "second"]);
+    }
+
+    #[test]
+    fn trims_oldest_recordings_until_under_limit() {
+        let candidates = vec![
+            ("a.wav".to_string(), 40),
+            ("b.wav".to_string(), 30),
+            ("c.wav".to_string(), 30),
+        ];
+        assert_eq!(
+            recordings_over_limit(&candidates, 100, 50),
+            ["a.wav", "b.wav"]
+        );
+        assert!(recordings_over_limit(&candidates, 100, 100).is_empty());
+        // Saved recordings aren't candidates, so the limit may stay exceeded
+        assert_eq!(recordings_over_limit(&candidates, 500, 50).len(), 3);
+    }
+
+    #[test]
+    fn saved_entries_lists_only_saved() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "boilerplate", None);
+        insert_entry(&conn, 200, "one-off", None);
+        conn.execute(
+            "UPDATE transcription_history SET saved = 1 WHERE timestamp = 100",
+            [],
+        )
+        .expect("save entry");
+
+        let saved = HistoryManager::get_saved_entries_with_conn(&conn).expect("fetch saved");
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].transcription_text, "boilerplate");
+    }
+
+    #[test]
+    fn history_page_paginates_and_searches() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "buy milk", None);
+        insert_entry(&conn, 200, "call Alice", Some("Call Alice at 5"));
+        insert_entry(&conn, 300, "100% done_now", None);
+
+        let page = HistoryManager::get_history_page_with_conn(&conn, 1, 1, None, None)
+            .expect("fetch page");
+        assert_eq!(page.total, 3);
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].timestamp, 200);
+
+        let page = HistoryManager::get_history_page_with_conn(&conn, 0, 10, Some("AT 5"), None)
+            .expect("search post-processed text");
+        assert_eq!(page.total, 1);
+        assert_eq!(page.entries[0].transcription_text, "call Alice");
+
+        // Wildcards in the search are literal
+        let page = HistoryManager::get_history_page_with_conn(&conn, 0, 10, Some("0%"), None)
+            .expect("search with wildcard");
+        assert_eq!(page.total, 1);
+        assert_eq!(page.entries[0].timestamp, 300);
+    }
+
+    #[test]
+    fn history_filters_and_counts_by_app() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "reply to Bob", None);
+        insert_entry(&conn, 200, "standup notes", None);
+        insert_entry(&conn, 300, "reply to Carol", None);
+        insert_entry(&conn, 400, "unknown target", None);
+        conn.execute_batch(
+            "UPDATE transcription_history SET target_app = 'Mail' WHERE timestamp IN (100, 300);
+             UPDATE transcription_history SET target_app = 'Notes' WHERE timestamp = 200;",
+        )
+        .expect("set target apps");
+
+        let page =
+            HistoryManager::get_history_page_with_conn(&conn, 0, 10, Some("reply"), Some("Mail"))
+                .expect("filter by app");
+        assert_eq!(page.total, 2);
+        assert_eq!(page.entries[0].timestamp, 300);
+
+        let apps = HistoryManager::get_history_apps_with_conn(&conn).expect("count apps");
+        assert_eq!(
+            apps,
+            vec![
+                HistoryAppCount {
+                    app: "Mail".to_string(),
+                    entries: 2
+                },
+                HistoryAppCount {
+                    app: "Notes".to_string(),
+                    entries: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn recordings_are_read_by_bare_file_name() {
+        assert!(is_recording_file_name("handy-1700000000.wav"));
+        assert!(!is_recording_file_name(""));
+        assert!(!is_recording_file_name(".."));
+        assert!(!is_recording_file_name("../settings_store.json"));
+        assert!(!is_recording_file_name("..\\history.db"));
+        assert!(!is_recording_file_name("/etc/passwd"));
     }
 }
@@ -0,0 +1,198 @@
+//! Ranked full-text search over history using SQLite FTS5.
+//!
+//! Queries are split into words that each match as a prefix. Words that match
+//! nothing in the index are swapped for close spellings from the index
+//! vocabulary, so small typos still find results. Results are ranked by BM25.
+
+use crate::managers::history::{HistoryEntry, HistoryManager, ENTRY_COLUMNS};
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Marks highlighted terms in FTS5 snippets. Private-use characters are
+/// chosen so they can't clash with transcript text.
+const HIGHLIGHT_START: char = '\u{E000}';
+const HIGHLIGHT_END: char = '\u{E001}';
+
+/// Most close spellings tried for a misspelled word
+const MAX_SPELLING_CANDIDATES: usize = 3;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct HistorySearchResult {
+    pub entry: HistoryEntry,
+    /// Excerpt around the best match
+    pub snippet: String,
+    /// `[start, end)` ranges of matched terms in `snippet`, in UTF-16 code
+    /// units so they can be used directly with JavaScript strings
+    pub highlights: Vec<[u32; 2]>,
+    /// BM25 score; lower is a better match
+    pub score: f64,
+}
+
+fn tokenize(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn fts_term(word: &str, prefix: bool) -> String {
+    format!(
+        "\"{}\"{}",
+        word.replace('"', "\"\""),
+        if prefix { "*" } else { "" }
+    )
+}
+
+/// Close spellings of `word` from the index vocabulary, nearest first
+fn spelling_candidates(word: &str, vocabulary: &[String]) -> Vec<String> {
+    let length = word.chars().count();
+    if length < 4 {
+        return Vec::new();
+    }
+    let max_distance = if length >= 8 { 2 } else { 1 };
+
+    let mut candidates: Vec<(usize, &String)> = vocabulary
+        .iter()
+        .map(|term| (strsim::damerau_levenshtein(word, term), term))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    candidates.sort();
+    candidates
+        .into_iter()
+        .take(MAX_SPELLING_CANDIDATES)
+        .map(|(_, term)| term.clone())
+        .collect()
+}
+
+/// Builds an FTS5 MATCH expression where any word may match, so BM25 ranks
+/// entries matching more of the words higher. Returns `None` for a query
+/// without any words.
+pub fn build_match_query(query: &str, vocabulary: &[String]) -> Option<String> {
+    let groups: Vec<String> = tokenize(query)
+        .into_iter()
+        .map(|word| {
+            let known = vocabulary.iter().any(|term| term.starts_with(&word));
+            let mut terms = vec![fts_term(&word, true)];
+            if !known {
+                terms.extend(
+                    spelling_candidates(&word, vocabulary)
+                        .iter()
+                        .map(|term| fts_term(term, false)),
+                );
+            }
+            terms.join(" OR ")
+        })
+        .collect();
+
+    (!groups.is_empty()).then(|| format!("({})", groups.join(") OR (")))
+}
+
+/// Removes highlight markers from a snippet and returns where they were
+pub fn extract_highlights(marked: &str) -> (String, Vec<[u32; 2]>) {
+    let mut snippet = String::with_capacity(marked.len());
+    let mut highlights = Vec::new();
+    let mut offset = 0u32;
+    let mut start = None;
+
+    for c in marked.chars() {
+        match c {
+            HIGHLIGHT_START => start = Some(offset),
+            HIGHLIGHT_END => {
+                if let Some(start) = start.take() {
+                    highlights.push([start, offset]);
+                }
+            }
+            c => {
+                snippet.push(c);
+                offset += c.len_utf16() as u32;
+            }
+        }
+    }
+
+    (snippet, highlights)
+}
+
+fn vocabulary(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT term FROM transcription_history_vocab")?;
+    let terms = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(terms)
+}
+
+/// Returns up to `limit` entries matching `query`, best match first.
+pub fn search(conn: &Connection, query: &str, limit: usize) -> Result<Vec<HistorySearchResult>> {
+    let Some(match_query) = build_match_query(query, &vocabulary(conn)?) else {
+        return Ok(Vec::new());
+    };
+
+    let columns: Vec<String> = ENTRY_COLUMNS
+        .split(',')
+        .map(|column| format!("h.{}", column.trim()))
+        .collect();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}, snippet(transcription_history_fts, -1, ?2, ?3, '…', 16) AS snippet,
+                bm25(transcription_history_fts) AS score
+         FROM transcription_history_fts
+         JOIN transcription_history h ON h.id = transcription_history_fts.rowid
+         WHERE transcription_history_fts MATCH ?1
+         ORDER BY score
+         LIMIT ?4",
+        columns.join(", ")
+    ))?;
+
+    let rows = stmt.query_map(
+        params![
+            match_query,
+            HIGHLIGHT_START.to_string(),
+            HIGHLIGHT_END.to_string(),
+            limit as i64
+        ],
+        |row| {
+            let marked: String = row.get("snippet")?;
+            let (snippet, highlights) = extract_highlights(&marked);
+            Ok(HistorySearchResult {
+                entry: HistoryManager::entry_from_row(row)?,
+                snippet,
+                highlights,
+                score: row.get("score")?,
+            })
+        },
+    )?;
+
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row?);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocab(terms: &[&str]) -> Vec<String> {
+        terms.iter().map(|term| term.to_string()).collect()
+    }
+
+    #[test]
+    fn builds_prefix_query_with_spelling_fallback() {
+        let vocabulary = vocab(&["quarterly", "invoice", "the"]);
+        assert_eq!(
+            build_match_query("the Quartrly invoice", &vocabulary).unwrap(),
+            "(\"the\"*) OR (\"quartrly\"* OR \"quarterly\") OR (\"invoice\"*)"
+        );
+        assert!(build_match_query(" ,. ", &vocabulary).is_none());
+    }
+
+    #[test]
+    fn extracts_highlights_as_utf16_ranges() {
+        let marked = format!("café {}invoice{} due", HIGHLIGHT_START, HIGHLIGHT_END);
+        let (snippet, highlights) = extract_highlights(&marked);
+        assert_eq!(snippet, "café invoice due");
+        assert_eq!(highlights, vec![[5, 12]]);
+    }
+}
@@ -0,0 +1,582 @@
+//! File-based history sync through a folder shared by a sync tool such as
+//! Dropbox or Syncthing.
+//!
+//! Each machine only writes its own journal, `handy-history-<device>.jsonl`,
+//! appending one record per transcription. Since no file has more than one
+//! writer the sync tool never sees conflicting edits. Records from every
+//! other journal are merged into the local database keyed by `sync_id`, so
+//! merging the same journal again is harmless.
+//!
+//! The database remembers how far each journal was written and read, so a
+//! sync only handles what was added since the last one. Merged entries that
+//! are deleted here leave a tombstone and aren't merged again. Recordings
+//! aren't synced, and deletions don't reach other machines.
+//!
+//! With history encryption on, records are sealed with the history key.
+//! Each machine has its own key, so merging another machine's sealed records
+//! needs that machine's key, exported there and imported here. Reading stops
+//! at the first record that can't be opened and resumes from it once the key
+//! is imported.
+
+use crate::encryption::{self, HistoryKey};
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use log::warn;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const JOURNAL_PREFIX: &str = "handy-history-";
+const JOURNAL_EXTENSION: &str = "jsonl";
+/// Starts a line holding a record sealed with the history key
+const SEALED_PREFIX: &str = "sealed:";
+/// Bytes kept from just before a journal's read position, to notice when the
+/// journal was rewritten rather than appended to
+const TAIL_LEN: usize = 64;
+
+/// One transcription as written to a journal
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SyncRecord {
+    /// `<device id>:<id on that device>`
+    pub sync_id: String,
+    pub timestamp: i64,
+    pub title: String,
+    pub transcription_text: String,
+    pub post_processed_text: Option<String>,
+    pub post_process_prompt: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub model: Option<String>,
+    pub target_app: Option<String>,
+    /// Missing from records written before window titles were kept
+    #[serde(default)]
+    pub window_title: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Type)]
+pub struct SyncResult {
+    /// Local entries appended to this machine's journal
+    pub exported: usize,
+    /// Entries merged in from other machines
+    pub imported: usize,
+    /// Records from other machines left unread because they are sealed with
+    /// a key this machine doesn't have yet
+    pub locked: usize,
+}
+
+/// Generates an id for this machine's journal
+pub fn new_device_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+    format!("{:016x}", nanos ^ ((std::process::id() as u64) << 32))
+}
+
+fn journal_path(dir: &Path, device_id: &str) -> PathBuf {
+    dir.join(format!(
+        "{}{}.{}",
+        JOURNAL_PREFIX, device_id, JOURNAL_EXTENSION
+    ))
+}
+
+fn journal_key(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+/// A journal line for `record`, sealed when a key is given
+fn record_line(record: &SyncRecord, key: Option<&HistoryKey>) -> Result<String> {
+    let json = serde_json::to_string(record)?;
+    match key {
+        Some(key) => {
+            let sealed = encryption::encrypt(key, json.as_bytes()).map_err(|e| anyhow!(e))?;
+            Ok(format!("{}{}", SEALED_PREFIX, STANDARD.encode(sealed)))
+        }
+        None => Ok(json),
+    }
+}
+
+/// Reads a journal line, trying each of `keys` on sealed lines. `Err` means
+/// it's sealed with a key this machine doesn't have; `Ok(None)` that it's
+/// malformed or cut off, e.g. by a write that hasn't finished syncing yet.
+fn parse_line(line: &str, keys: &[HistoryKey]) -> Result<Option<SyncRecord>, ()> {
+    let Some(sealed) = line.strip_prefix(SEALED_PREFIX) else {
+        return Ok(serde_json::from_str(line).ok());
+    };
+    let Ok(sealed) = STANDARD.decode(sealed.trim()) else {
+        return Ok(None);
+    };
+    let json = keys
+        .iter()
+        .find_map(|key| encryption::decrypt(key, &sealed).ok())
+        .ok_or(())?;
+    Ok(serde_json::from_slice(&json).ok())
+}
+
+/// Parses a journal, skipping lines that are malformed, cut off or sealed
+/// with another key
+pub fn parse_journal(contents: &str, key: Option<&HistoryKey>) -> Vec<SyncRecord> {
+    let keys = key.map(std::slice::from_ref).unwrap_or_default();
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| parse_line(line, keys).ok().flatten())
+        .collect()
+}
+
+/// Appends local entries added since the last export to this machine's
+/// journal, sealed when `key` is given.
+pub fn export_local(
+    conn: &Connection,
+    dir: &Path,
+    device_id: &str,
+    key: Option<&HistoryKey>,
+) -> Result<usize> {
+    let path = journal_path(dir, device_id);
+    let exported_id: Option<i64> = conn
+        .query_row(
+            "SELECT exported_id FROM sync_journals WHERE path = ?1",
+            params![journal_key(&path)],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    // Journals from before the export position was kept are matched by id once
+    let already_exported: HashSet<String> = match exported_id {
+        Some(_) => HashSet::new(),
+        None => match fs::read_to_string(&path) {
+            Ok(contents) => parse_journal(&contents, key)
+                .into_iter()
+                .map(|record| record.sync_id)
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(e.into()),
+        },
+    };
+
+    // Entries merged from other machines already carry a sync id
+    let mut stmt = conn.prepare(
+        "SELECT id, timestamp, title, transcription_text, post_processed_text, post_process_prompt, duration_ms, model, target_app, window_title
+         FROM transcription_history WHERE sync_id IS NULL AND id > ?1 ORDER BY id",
+    )?;
+    let rows = stmt.query_map(params![exported_id.unwrap_or(0)], |row| {
+        let id: i64 = row.get("id")?;
+        let record = SyncRecord {
+            sync_id: format!("{}:{}", device_id, id),
+            timestamp: row.get("timestamp")?,
+            title: row.get("title")?,
+            transcription_text: row.get("transcription_text")?,
+            post_processed_text: row.get("post_processed_text")?,
+            post_process_prompt: row.get("post_process_prompt")?,
+            duration_ms: row.get("duration_ms")?,
+            model: row.get("model")?,
+            target_app: row.get("target_app")?,
+            window_title: row.get("window_title")?,
+        };
+        Ok((id, record))
+    })?;
+
+    let mut lines = String::new();
+    let mut count = 0;
+    let mut last_id = exported_id.unwrap_or(0);
+    for row in rows {
+        let (id, record) = row?;
+        last_id = id;
+        if !already_exported.contains(&record.sync_id) {
+            lines.push_str(&record_line(&record, key)?);
+            lines.push('\n');
+            count += 1;
+        }
+    }
+
+    if count > 0 {
+        let mut journal = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+        // Finish a line left incomplete by an interrupted write
+        if ends_mid_line(&mut journal)? {
+            lines.insert(0, '\n');
+        }
+        journal.write_all(lines.as_bytes())?;
+    }
+    conn.execute(
+        "INSERT INTO sync_journals (path, exported_id) VALUES (?1, ?2)
+         ON CONFLICT(path) DO UPDATE SET exported_id = excluded.exported_id",
+        params![journal_key(&path), last_id],
+    )?;
+
+    Ok(count)
+}
+
+fn ends_mid_line(file: &mut File) -> Result<bool> {
+    let len = file.seek(SeekFrom::End(0))?;
+    if len == 0 {
+        return Ok(false);
+    }
+    let mut last = [0u8; 1];
+    file.seek(SeekFrom::Start(len - 1))?;
+    file.read_exact(&mut last)?;
+    Ok(last[0] != b'\n')
+}
+
+/// Where to continue reading a journal that was read up to `offset`, given
+/// the bytes from just before `offset` now (`tail_now`) and then (`tail`).
+/// A journal that shrank or changed there was rewritten, so it's read again
+/// from the start.
+fn resume_offset(len: u64, offset: u64, tail: &[u8], tail_now: &[u8]) -> u64 {
+    if offset > len || tail != tail_now {
+        0
+    } else {
+        offset
+    }
+}
+
+/// Up to `TAIL_LEN` bytes from just before `offset`
+fn tail_before(file: &mut File, offset: u64) -> Result<Vec<u8>> {
+    let mut tail = vec![0u8; TAIL_LEN.min(offset as usize)];
+    file.seek(SeekFrom::Start(offset - tail.len() as u64))?;
+    file.read_exact(&mut tail)?;
+    Ok(tail)
+}
+
+/// The complete lines of `chunk`, leaving a trailing partial line for the
+/// next sync
+fn complete_lines(chunk: &[u8]) -> &[u8] {
+    match chunk.iter().rposition(|&b| b == b'\n') {
+        Some(end) => &chunk[..=end],
+        None => &[],
+    }
+}
+
+/// Reads what was added to a journal since it was last read, returning the
+/// complete lines along with the new read position and tail.
+fn read_new_lines(path: &Path, offset: u64, tail: &[u8]) -> Result<(String, u64, Vec<u8>)> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+
+    let mut tail_now = vec![0u8; tail.len()];
+    let tail_start = offset.saturating_sub(tail.len() as u64);
+    let start = if offset <= len && file.seek(SeekFrom::Start(tail_start)).is_ok() {
+        file.read_exact(&mut tail_now)?;
+        resume_offset(len, offset, tail, &tail_now)
+    } else {
+        0
+    };
+
+    let mut chunk = Vec::new();
+    file.seek(SeekFrom::Start(start))?;
+    file.read_to_end(&mut chunk)?;
+    let lines = complete_lines(&chunk);
+
+    let end = start + lines.len() as u64;
+    // The tail may reach back into what was read before
+    let new_tail = tail_before(&mut file, end)?;
+
+    Ok((String::from_utf8_lossy(lines).into_owned(), end, new_tail))
+}
+
+/// Merges entries added to other machines' journals since the last sync into
+/// the local database, opening sealed records with any of `keys`. Entries
+/// deleted here are skipped. Returns how many entries were merged and how many
+/// records are waiting for a key; a journal is only read up to its first
+/// record that can't be opened, so those aren't lost.
+pub fn import_remote(
+    conn: &Connection,
+    dir: &Path,
+    device_id: &str,
+    keys: &[HistoryKey],
+) -> Result<(usize, usize)> {
+    let own_journal = journal_path(dir, device_id);
+    let mut imported = 0;
+    let mut locked = 0;
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_journal = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(JOURNAL_PREFIX))
+            && path.extension().is_some_and(|ext| ext == JOURNAL_EXTENSION);
+        if !is_journal || path == own_journal {
+            continue;
+        }
+
+        let (offset, tail): (i64, Vec<u8>) = conn
+            .query_row(
+                "SELECT read_offset, read_tail FROM sync_journals WHERE path = ?1",
+                params![journal_key(&path)],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .unwrap_or_default();
+        let (lines, mut offset, mut tail) = read_new_lines(&path, offset as u64, &tail)?;
+        let start = offset - lines.len() as u64;
+
+        let mut read = 0;
+        let mut stopped_at = None;
+        for line in lines.split_inclusive('\n') {
+            let line_start = read;
+            read += line.len();
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = match parse_line(line.trim_end(), keys) {
+                Ok(Some(record)) => record,
+                Ok(None) => continue,
+                Err(()) => {
+                    stopped_at = Some(line_start);
+                    break;
+                }
+            };
+            imported += conn.execute(
+                "INSERT OR IGNORE INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, duration_ms, model, target_app, window_title, sync_id)
+                 SELECT '', ?1, 0, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10
+                 WHERE NOT EXISTS (SELECT 1 FROM sync_tombstones WHERE sync_id = ?10)",
+                params![
+                    record.timestamp,
+                    record.title,
+                    record.transcription_text,
+                    record.post_processed_text,
+                    record.post_process_prompt,
+                    record.duration_ms,
+                    record.model,
+                    record.target_app,
+                    record.window_title,
+                    record.sync_id
+                ],
+            )?;
+        }
+        if let Some(line_start) = stopped_at {
+            let waiting = lines[line_start..]
+                .lines()
+                .filter(|line| line.starts_with(SEALED_PREFIX))
+                .count();
+            warn!(
+                "{} history records in {} are sealed with another machine's key; import it to merge them",
+                waiting,
+                path.display()
+            );
+            locked += waiting;
+            offset = start + line_start as u64;
+            tail = tail_before(&mut File::open(&path)?, offset)?;
+        }
+
+        conn.execute(
+            "INSERT INTO sync_journals (path, read_offset, read_tail) VALUES (?1, ?2, ?3)
+             ON CONFLICT(path) DO UPDATE SET read_offset = excluded.read_offset, read_tail = excluded.read_tail",
+            params![journal_key(&path), offset as i64, tail],
+        )?;
+    }
+
+    Ok((imported, locked))
+}
+
+/// Rewrites this machine's journal with every record sealed with `key`, or
+/// with none sealed, after history encryption was switched
+pub fn reseal_journal(dir: &Path, device_id: &str, key: &HistoryKey, seal: bool) -> Result<()> {
+    let path = journal_path(dir, device_id);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut lines = String::new();
+    for record in parse_journal(&contents, Some(key)) {
+        lines.push_str(&record_line(&record, seal.then_some(key))?);
+        lines.push('\n');
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, lines)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::history::MIGRATIONS;
+    use rusqlite_migration::Migrations;
+
+    fn record(sync_id: &str, text: &str) -> SyncRecord {
+        SyncRecord {
+            sync_id: sync_id.to_string(),
+            timestamp: 100,
+            title: "Recording".to_string(),
+            transcription_text: text.to_string(),
+            post_processed_text: None,
+            post_process_prompt: None,
+            duration_ms: Some(900),
+            model: None,
+            target_app: None,
+            window_title: None,
+        }
+    }
+
+    fn history_db() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        Migrations::new(MIGRATIONS.to_vec())
+            .to_latest(&mut conn)
+            .unwrap();
+        conn
+    }
+
+    fn add_local_entry(conn: &Connection, text: &str) {
+        conn.execute(
+            "INSERT INTO transcription_history (file_name, timestamp, title, transcription_text)
+             VALUES ('', 100, 'Recording', ?1)",
+            params![text],
+        )
+        .unwrap();
+    }
+
+    fn write_journal(dir: &Path, device_id: &str, records: &[SyncRecord]) {
+        let lines: String = records
+            .iter()
+            .map(|record| record_line(record, None).unwrap() + "\n")
+            .collect();
+        fs::write(journal_path(dir, device_id), lines).unwrap();
+    }
+
+    #[test]
+    fn parse_journal_skips_partial_lines() {
+        let record = record("laptop:1", "hello");
+        let line = serde_json::to_string(&record).unwrap();
+        let journal = format!("{}\n\n{}", line, &line[..line.len() / 2]);
+
+        assert_eq!(parse_journal(&journal, None), vec![record]);
+    }
+
+    #[test]
+    fn sealed_records_need_the_key() {
+        let key = [7u8; 32];
+        let record = record("laptop:1", "private words");
+        let line = record_line(&record, Some(&key)).unwrap();
+
+        assert!(!line.contains("private words"));
+        assert_eq!(parse_line(&line, &[[8u8; 32], key]), Ok(Some(record)));
+        assert_eq!(parse_line(&line, &[[8u8; 32]]), Err(()));
+        assert_eq!(parse_line(&line, &[]), Err(()));
+    }
+
+    #[test]
+    fn exports_only_new_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = history_db();
+        add_local_entry(&conn, "one");
+        add_local_entry(&conn, "two");
+
+        assert_eq!(export_local(&conn, dir.path(), "desk", None).unwrap(), 2);
+        assert_eq!(export_local(&conn, dir.path(), "desk", None).unwrap(), 0);
+        add_local_entry(&conn, "three");
+        assert_eq!(export_local(&conn, dir.path(), "desk", None).unwrap(), 1);
+
+        let journal = fs::read_to_string(journal_path(dir.path(), "desk")).unwrap();
+        let texts: Vec<String> = parse_journal(&journal, None)
+            .into_iter()
+            .map(|record| record.transcription_text)
+            .collect();
+        assert_eq!(texts, vec!["one", "two", "three"]);
+    }
+
+    #[test]
+    fn deleted_entries_are_not_merged_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = history_db();
+        write_journal(
+            dir.path(),
+            "laptop",
+            &[record("laptop:1", "keep"), record("laptop:2", "drop")],
+        );
+        assert_eq!(
+            import_remote(&conn, dir.path(), "desk", &[]).unwrap(),
+            (2, 0)
+        );
+
+        conn.execute(
+            "DELETE FROM transcription_history WHERE sync_id = 'laptop:2'",
+            [],
+        )
+        .unwrap();
+        // Read again from the start, as after the journal was rewritten
+        conn.execute("DELETE FROM sync_journals", []).unwrap();
+        assert_eq!(
+            import_remote(&conn, dir.path(), "desk", &[]).unwrap(),
+            (0, 0)
+        );
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transcription_history", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn waits_at_records_sealed_with_a_missing_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = history_db();
+        let key = [7u8; 32];
+        let lines = [
+            record_line(&record("laptop:1", "open"), None).unwrap(),
+            record_line(&record("laptop:2", "sealed"), Some(&key)).unwrap(),
+            record_line(&record("laptop:3", "after"), None).unwrap(),
+        ];
+        fs::write(journal_path(dir.path(), "laptop"), lines.join("\n") + "\n").unwrap();
+
+        assert_eq!(
+            import_remote(&conn, dir.path(), "desk", &[]).unwrap(),
+            (1, 1)
+        );
+        // Still waiting, rather than skipped for good
+        assert_eq!(
+            import_remote(&conn, dir.path(), "desk", &[]).unwrap(),
+            (0, 1)
+        );
+        assert_eq!(
+            import_remote(&conn, dir.path(), "desk", &[key]).unwrap(),
+            (2, 0)
+        );
+    }
+
+    #[test]
+    fn reads_only_what_was_appended() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = journal_path(dir.path(), "laptop");
+        let first = record_line(&record("laptop:1", "one"), None).unwrap() + "\n";
+        let second = record_line(&record("laptop:2", "two"), None).unwrap();
+
+        // A line still being written is left for the next read
+        fs::write(&path, format!("{}{}", first, &second[..10])).unwrap();
+        let (lines, offset, tail) = read_new_lines(&path, 0, &[]).unwrap();
+        assert_eq!(lines, first);
+        assert_eq!(offset, first.len() as u64);
+
+        fs::write(&path, format!("{}{}\n", first, second)).unwrap();
+        let (lines, offset, tail) = read_new_lines(&path, offset, &tail).unwrap();
+        assert_eq!(lines, format!("{}\n", second));
+        let (lines, _, _) = read_new_lines(&path, offset, &tail).unwrap();
+        assert_eq!(lines, "");
+
+        // A rewritten journal is read again from the start
+        let rewritten = record_line(&record("laptop:3", "three"), None).unwrap() + "\n";
+        fs::write(&path, rewritten.repeat(3)).unwrap();
+        let (lines, _, _) = read_new_lines(&path, offset, &tail).unwrap();
+        assert_eq!(lines, rewritten.repeat(3));
+    }
+
+    #[test]
+    fn resumes_only_where_the_journal_is_unchanged() {
+        assert_eq!(resume_offset(100, 40, b"abc", b"abc"), 40);
+        assert_eq!(resume_offset(100, 40, b"abc", b"xyz"), 0);
+        assert_eq!(resume_offset(30, 40, b"abc", b"abc"), 0);
+        assert_eq!(resume_offset(0, 0, b"", b""), 0);
+    }
+}
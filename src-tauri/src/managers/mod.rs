@@ -1,4 +1,8 @@
+pub mod analytics;
 pub mod audio;
 pub mod history;
+pub mod history_search;
+pub mod history_sync;
 pub mod model;
+pub mod stats;
 pub mod transcription;
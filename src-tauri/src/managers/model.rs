@@ -1,9 +1,11 @@
+use crate::data_dirs::{self, DataKind};
 use crate::settings::{get_settings, write_settings};
 use anyhow::Result;
 use flate2::read::GzDecoder;
 use futures_util::StreamExt;
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use specta::Type;
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -44,6 +46,27 @@ pub struct ModelInfo {
     pub is_recommended: bool,       // Whether this is the recommended model for new users
     pub supported_languages: Vec<String>, // Languages this model can transcribe
     pub is_custom: bool,            // Whether this is a user-provided custom model
+    /// Failed to load and no longer matches its checksum, see `repair_model`
+    #[serde(default)]
+    pub is_corrupted: bool,
+}
+
+/// Whether a downloaded model is still the file that was downloaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelIntegrity {
+    Intact,
+    Corrupted,
+    /// No checksum on record, for directory models, custom models and
+    /// downloads from before checksums were kept
+    Unverified,
+}
+
+/// SHA-256 of the file at `path`, as lowercase hex
+fn file_sha256(path: &Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -64,12 +87,8 @@ pub struct ModelManager {
 
 impl ModelManager {
     pub fn new(app_handle: &AppHandle) -> Result<Self> {
-        // Create models directory in app data
-        let models_dir = app_handle
-            .path()
-            .app_data_dir()
-            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?
-            .join("models");
+        let models_dir =
+            data_dirs::dir(app_handle, DataKind::Models).map_err(|e| anyhow::anyhow!(e))?;
 
         if !models_dir.exists() {
             fs::create_dir_all(&models_dir)?;
@@ -114,6 +133,7 @@ impl ModelManager {
                 is_recommended: false,
                 supported_languages: whisper_languages.clone(),
                 is_custom: false,
+                is_corrupted: false,
             },
         );
 
@@ -138,6 +158,7 @@ impl ModelManager {
                 is_recommended: false,
                 supported_languages: whisper_languages.clone(),
                 is_custom: false,
+                is_corrupted: false,
             },
         );
 
@@ -161,6 +182,7 @@ impl ModelManager {
                 is_recommended: false,
                 supported_languages: whisper_languages.clone(),
                 is_custom: false,
+                is_corrupted: false,
             },
         );
 
@@ -184,6 +206,7 @@ impl ModelManager {
                 is_recommended: false,
                 supported_languages: whisper_languages.clone(),
                 is_custom: false,
+                is_corrupted: false,
             },
         );
 
@@ -208,6 +231,7 @@ impl ModelManager {
                 is_recommended: false,
                 supported_languages: whisper_languages,
                 is_custom: false,
+                is_corrupted: false,
             },
         );
 
@@ -232,6 +256,7 @@ impl ModelManager {
                 is_recommended: false,
                 supported_languages: vec!["en".to_string()],
                 is_custom: false,
+                is_corrupted: false,
             },
         );
 
@@ -265,6 +290,7 @@ impl ModelManager {
                 is_recommended: true,
                 supported_languages: parakeet_v3_languages,
                 is_custom: false,
+                is_corrupted: false,
             },
         );
 
@@ -288,6 +314,7 @@ impl ModelManager {
                 is_recommended: false,
                 supported_languages: vec!["en".to_string()],
                 is_custom: false,
+                is_corrupted: false,
             },
         );
 
@@ -313,6 +340,7 @@ impl ModelManager {
                 is_recommended: false,
                 supported_languages: vec!["en".to_string()],
                 is_custom: false,
+                is_corrupted: false,
             },
         );
 
@@ -338,6 +366,7 @@ impl ModelManager {
                 is_recommended: false,
                 supported_languages: vec!["en".to_string()],
                 is_custom: false,
+                is_corrupted: false,
             },
         );
 
@@ -363,6 +392,7 @@ impl ModelManager {
                 is_recommended: false,
                 supported_languages: vec!["en".to_string()],
                 is_custom: false,
+                is_corrupted: false,
             },
         );
 
@@ -394,6 +424,7 @@ impl ModelManager {
                 is_recommended: false,
                 supported_languages: sense_voice_languages,
                 is_custom: false,
+                is_corrupted: false,
             },
         );
 
@@ -665,6 +696,7 @@ impl ModelManager {
                     is_recommended: false,
                     supported_languages: vec![],
                     is_custom: true,
+                    is_corrupted: false,
                 },
             );
         }
@@ -993,6 +1025,30 @@ impl ModelManager {
         } else {
             // Move partial file to final location for file-based models
             fs::rename(&partial_path, &model_path)?;
+
+            // Kept to tell a corrupted file from other load failures later.
+            // Only a file whose size matched the server's Content-Length is
+            // known to be good; the rest stay unverified.
+            let checksum_path = self.checksum_path(&model_info);
+            let hashed_path = model_path.clone();
+            if total_size == 0 {
+                warn!(
+                    "No size from the server for model {}, not keeping a checksum",
+                    model_id
+                );
+                let _ = fs::remove_file(&checksum_path);
+            } else {
+                match tauri::async_runtime::spawn_blocking(move || file_sha256(&hashed_path)).await
+                {
+                    Ok(Ok(checksum)) => {
+                        if let Err(e) = fs::write(&checksum_path, checksum) {
+                            warn!("Failed to save checksum for model {}: {}", model_id, e);
+                        }
+                    }
+                    Ok(Err(e)) => warn!("Failed to checksum model {}: {}", model_id, e),
+                    Err(e) => warn!("Failed to checksum model {}: {}", model_id, e),
+                }
+            }
         }
 
         // Update download status
@@ -1001,6 +1057,7 @@ impl ModelManager {
             if let Some(model) = models.get_mut(model_id) {
                 model.is_downloading = false;
                 model.is_downloaded = true;
+                model.is_corrupted = false;
                 model.partial_size = 0;
             }
         }
@@ -1062,6 +1119,8 @@ impl ModelManager {
             }
         }
 
+        let _ = fs::remove_file(self.checksum_path(&model_info));
+
         // Delete partial file if it exists (same for both types)
         if partial_path.exists() {
             info!("Deleting partial file at: {:?}", partial_path);
@@ -1092,6 +1151,58 @@ impl ModelManager {
         Ok(())
     }
 
+    fn checksum_path(&self, model_info: &ModelInfo) -> PathBuf {
+        self.models_dir
+            .join(format!("{}.sha256", &model_info.filename))
+    }
+
+    /// Compares a downloaded model with the checksum taken when it was
+    /// downloaded. Reads the whole file, so it's only done after a load fails.
+    pub fn verify_model(&self, model_id: &str) -> ModelIntegrity {
+        let Some(model_info) = self.get_model_info(model_id) else {
+            return ModelIntegrity::Unverified;
+        };
+        let Ok(expected) = fs::read_to_string(self.checksum_path(&model_info)) else {
+            return ModelIntegrity::Unverified;
+        };
+        match file_sha256(&self.models_dir.join(&model_info.filename)) {
+            Ok(actual) if actual == expected.trim() => ModelIntegrity::Intact,
+            Ok(_) => ModelIntegrity::Corrupted,
+            Err(e) => {
+                warn!("Failed to checksum model {}: {}", model_id, e);
+                ModelIntegrity::Corrupted
+            }
+        }
+    }
+
+    pub fn mark_corrupted(&self, model_id: &str) {
+        let mut models = self.available_models.lock().unwrap();
+        if let Some(model) = models.get_mut(model_id) {
+            model.is_corrupted = true;
+        }
+    }
+
+    /// Deletes a model and downloads it again
+    pub async fn repair_model(&self, model_id: &str) -> Result<()> {
+        let model_info = self
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        if model_info.url.is_none() {
+            return Err(anyhow::anyhow!(
+                "{} is a custom model and can't be downloaded again",
+                model_info.name
+            ));
+        }
+        if let Err(e) = self.delete_model(model_id) {
+            debug!(
+                "Nothing to delete before repairing model {}: {}",
+                model_id, e
+            );
+        }
+        info!("Downloading model {} again to repair it", model_id);
+        self.download_model(model_id).await
+    }
+
     pub fn get_model_path(&self, model_id: &str) -> Result<PathBuf> {
         let model_info = self
             .get_model_info(model_id)
@@ -1216,6 +1327,7 @@ mod tests {
                 is_recommended: false,
                 supported_languages: vec!["en".to_string()],
                 is_custom: false,
+                is_corrupted: false,
             },
         );
 
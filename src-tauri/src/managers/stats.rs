@@ -0,0 +1,166 @@
+//! Dictation statistics kept in the history database.
+//!
+//! Totals are aggregated per local day in their own table so they survive
+//! history retention purges and entry deletion.
+
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Typing speed assumed when estimating time saved, in words per minute
+const TYPING_WPM: u64 = 40;
+
+/// Totals for a single local day
+#[derive(Clone, Debug, Serialize, Deserialize, Type, PartialEq)]
+pub struct DailyStats {
+    /// Local date as `YYYY-MM-DD`
+    pub day: String,
+    pub recordings: i64,
+    pub words: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct DictationStats {
+    pub total_recordings: i64,
+    pub total_words: i64,
+    /// Total length of all recordings in milliseconds
+    pub total_audio_ms: i64,
+    /// Mean time from the end of recording to finished transcription
+    pub average_latency_ms: Option<i64>,
+    /// Time typing the same words at 40 WPM would have taken, minus the time
+    /// spent speaking and waiting for the transcription
+    pub typing_time_saved_ms: i64,
+    /// Days with at least one recording, oldest first
+    pub daily: Vec<DailyStats>,
+}
+
+pub fn count_words(text: &str) -> i64 {
+    text.split_whitespace().count() as i64
+}
+
+/// Adds one recording to the totals for `day`.
+pub fn record(
+    conn: &Connection,
+    day: &str,
+    words: i64,
+    audio_ms: i64,
+    latency_ms: Option<i64>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO dictation_stats (day, recordings, words, audio_ms, latency_ms, latency_samples)
+         VALUES (?1, 1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(day) DO UPDATE SET
+             recordings = recordings + 1,
+             words = words + excluded.words,
+             audio_ms = audio_ms + excluded.audio_ms,
+             latency_ms = latency_ms + excluded.latency_ms,
+             latency_samples = latency_samples + excluded.latency_samples",
+        params![
+            day,
+            words,
+            audio_ms,
+            latency_ms.unwrap_or(0),
+            latency_ms.is_some() as i64
+        ],
+    )?;
+    Ok(())
+}
+
+/// Aggregates the days from `since` (inclusive, `YYYY-MM-DD`) onwards, or all
+/// days when `since` is `None`.
+pub fn query(conn: &Connection, since: Option<&str>) -> Result<DictationStats> {
+    let mut stmt = conn.prepare(
+        "SELECT day, recordings, words, audio_ms, latency_ms, latency_samples
+         FROM dictation_stats
+         WHERE ?1 IS NULL OR day >= ?1
+         ORDER BY day",
+    )?;
+    let rows = stmt.query_map(params![since], |row| {
+        Ok((
+            DailyStats {
+                day: row.get("day")?,
+                recordings: row.get("recordings")?,
+                words: row.get("words")?,
+            },
+            row.get::<_, i64>("audio_ms")?,
+            row.get::<_, i64>("latency_ms")?,
+            row.get::<_, i64>("latency_samples")?,
+        ))
+    })?;
+
+    let mut stats = DictationStats {
+        total_recordings: 0,
+        total_words: 0,
+        total_audio_ms: 0,
+        average_latency_ms: None,
+        typing_time_saved_ms: 0,
+        daily: Vec::new(),
+    };
+    let (mut latency_total, mut latency_samples) = (0i64, 0i64);
+    for row in rows {
+        let (day, audio_ms, latency_ms, samples) = row?;
+        stats.total_recordings += day.recordings;
+        stats.total_words += day.words;
+        stats.total_audio_ms += audio_ms;
+        latency_total += latency_ms;
+        latency_samples += samples;
+        stats.daily.push(day);
+    }
+
+    if latency_samples > 0 {
+        stats.average_latency_ms = Some(latency_total / latency_samples);
+    }
+    let typing_ms = stats.total_words * 60_000 / TYPING_WPM as i64;
+    stats.typing_time_saved_ms = (typing_ms - stats.total_audio_ms - latency_total).max(0);
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory db");
+        conn.execute_batch(
+            "CREATE TABLE dictation_stats (
+                day TEXT PRIMARY KEY,
+                recordings INTEGER NOT NULL DEFAULT 0,
+                words INTEGER NOT NULL DEFAULT 0,
+                audio_ms INTEGER NOT NULL DEFAULT 0,
+                latency_ms INTEGER NOT NULL DEFAULT 0,
+                latency_samples INTEGER NOT NULL DEFAULT 0
+            );",
+        )
+        .expect("create dictation_stats table");
+        conn
+    }
+
+    #[test]
+    fn aggregates_days_and_estimates_time_saved() {
+        let conn = setup_conn();
+        record(&conn, "2026-01-01", 40, 10_000, Some(500)).unwrap();
+        record(&conn, "2026-01-02", 30, 8_000, Some(300)).unwrap();
+        record(&conn, "2026-01-02", 10, 2_000, None).unwrap();
+
+        let stats = query(&conn, None).unwrap();
+        assert_eq!(stats.total_recordings, 3);
+        assert_eq!(stats.total_words, 80);
+        assert_eq!(stats.average_latency_ms, Some(400));
+        // 80 words at 40 WPM is two minutes, minus 20s of audio and 0.8s waiting
+        assert_eq!(stats.typing_time_saved_ms, 120_000 - 20_000 - 800);
+        assert_eq!(
+            stats.daily[1],
+            DailyStats {
+                day: "2026-01-02".to_string(),
+                recordings: 2,
+                words: 40,
+            }
+        );
+
+        let recent = query(&conn, Some("2026-01-02")).unwrap();
+        assert_eq!(recent.total_recordings, 2);
+        assert_eq!(recent.daily.len(), 1);
+    }
+}
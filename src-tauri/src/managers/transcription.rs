@@ -1,14 +1,25 @@
-use crate::audio_toolkit::{apply_custom_words, filter_transcription_output};
-use crate::managers::model::{EngineType, ModelManager};
-use crate::settings::{get_settings, ModelUnloadTimeout};
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::{
+    apply_custom_words, apply_spoken_commands, filter_transcription_output, format_literal,
+    split_literal_segment, stitch_overlapping,
+};
+use crate::events::{self, AppEvent};
+use crate::managers::model::{EngineType, ModelIntegrity, ModelManager};
+use crate::settings::{get_settings, AppSettings, ModelUnloadTimeout};
+use crate::tray;
 use anyhow::Result;
 use log::{debug, error, info, warn};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::ops::Range;
 use std::panic::{catch_unwind, AssertUnwindSafe};
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::{Arc, Condvar, Mutex, MutexGuard};
 use std::thread;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 use tauri::{AppHandle, Emitter};
 use transcribe_rs::{
     engines::{
@@ -36,6 +47,78 @@ pub struct ModelStateEvent {
     pub error: Option<String>,
 }
 
+/// How far along a model load is
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelLoadingStage {
+    Started,
+    /// Still loading, sent every `LOADING_PROGRESS_INTERVAL`
+    Loading,
+    Ready,
+    Failed,
+}
+
+/// Payload of the `model_loading` app event, sent while a model loads so a
+/// dictation waiting on it can show the model warming up instead of looking
+/// stuck
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+pub struct ModelLoadingEvent {
+    pub model_id: String,
+    pub stage: ModelLoadingStage,
+    pub elapsed_ms: u64,
+    /// Between 0 and 1, estimated from how long the model took to load last
+    /// time. `None` for a model not loaded before in this session.
+    pub progress: Option<f32>,
+}
+
+/// Text produced by a transcription
+#[derive(Clone, Debug, Default)]
+pub struct TranscriptionOutput {
+    /// Spoken text after word correction, filtering and spoken commands
+    pub text: String,
+    /// Verbatim text dictated after a literal mode trigger, which must not be
+    /// reformatted by later stages such as LLM post-processing
+    pub literal: Option<String>,
+    /// Decoding timed out and only the start of the recording was transcribed
+    pub partial: bool,
+}
+
+impl TranscriptionOutput {
+    /// The spoken text followed by the verbatim text
+    pub fn full_text(&self) -> String {
+        Self::join(&self.text, self.literal.as_deref())
+    }
+
+    /// Appends verbatim text to (possibly post-processed) spoken text
+    pub fn join(text: &str, literal: Option<&str>) -> String {
+        match literal {
+            Some(literal) if !literal.is_empty() && !text.is_empty() => {
+                format!("{} {}", text, literal)
+            }
+            Some(literal) if !literal.is_empty() => literal.to_string(),
+            _ => text.to_string(),
+        }
+    }
+}
+
+const LOADING_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Recordings longer than this are transcribed in chunks, decoding them in
+/// one go made latency and memory grow with the length of the dictation
+const CHUNKING_THRESHOLD_SAMPLES: usize = 60 * WHISPER_SAMPLE_RATE as usize;
+
+const CHUNK_SAMPLES: usize = 30 * WHISPER_SAMPLE_RATE as usize;
+
+/// Audio shared by neighbouring chunks, so a word cut at the end of one is
+/// heard whole at the start of the next. The text it produces twice is
+/// removed when the chunks are stitched.
+const CHUNK_OVERLAP_SAMPLES: usize = 3 * WHISPER_SAMPLE_RATE as usize;
+
+/// Threads an engine decodes on, about what the inference libraries use by
+/// default. Workers are limited so their engines share the machine's cores
+/// rather than each taking all of them.
+const THREADS_PER_ENGINE: usize = 4;
+
 enum LoadedEngine {
     Whisper(WhisperEngine),
     Parakeet(ParakeetEngine),
@@ -44,9 +127,40 @@ enum LoadedEngine {
     SenseVoice(SenseVoiceEngine),
 }
 
+/// After a downloaded model fails to load, compares it with its checksum.
+/// A corrupted model isn't loaded again until it's repaired.
+fn check_failed_model(app_handle: &AppHandle, model_manager: &ModelManager, model_id: &str) {
+    let Some(model_info) = model_manager.get_model_info(model_id) else {
+        return;
+    };
+    if !model_info.is_downloaded || model_info.is_corrupted {
+        return;
+    }
+    let verified = match model_manager.verify_model(model_id) {
+        ModelIntegrity::Intact => return,
+        ModelIntegrity::Corrupted => {
+            warn!("Model {} doesn't match its checksum", model_id);
+            model_manager.mark_corrupted(model_id);
+            true
+        }
+        ModelIntegrity::Unverified => false,
+    };
+    events::emit(
+        app_handle,
+        AppEvent::ModelCorrupted {
+            model_id: model_id.to_string(),
+            name: model_info.name,
+            verified,
+        },
+    );
+}
+
 #[derive(Clone)]
 pub struct TranscriptionManager {
     engine: Arc<Mutex<Option<LoadedEngine>>>,
+    /// More engines for the current model, each with the id of its model,
+    /// for decoding chunks of a long recording in parallel
+    helper_engines: Arc<Mutex<Vec<(String, LoadedEngine)>>>,
     model_manager: Arc<ModelManager>,
     app_handle: AppHandle,
     current_model_id: Arc<Mutex<Option<String>>>,
@@ -55,12 +169,15 @@ pub struct TranscriptionManager {
     watcher_handle: Arc<Mutex<Option<thread::JoinHandle<()>>>>,
     is_loading: Arc<Mutex<bool>>,
     loading_condvar: Arc<Condvar>,
+    /// How long each model took to load last, to estimate loading progress
+    load_durations: Arc<Mutex<HashMap<String, Duration>>>,
 }
 
 impl TranscriptionManager {
     pub fn new(app_handle: &AppHandle, model_manager: Arc<ModelManager>) -> Result<Self> {
         let manager = Self {
             engine: Arc::new(Mutex::new(None)),
+            helper_engines: Arc::new(Mutex::new(Vec::new())),
             model_manager,
             app_handle: app_handle.clone(),
             current_model_id: Arc::new(Mutex::new(None)),
@@ -74,6 +191,7 @@ impl TranscriptionManager {
             watcher_handle: Arc::new(Mutex::new(None)),
             is_loading: Arc::new(Mutex::new(false)),
             loading_condvar: Arc::new(Condvar::new()),
+            load_durations: Arc::new(Mutex::new(HashMap::new())),
         };
 
         // Start the idle watcher
@@ -169,6 +287,7 @@ impl TranscriptionManager {
             }
             *engine = None; // Drop the engine to free memory
         }
+        self.lock_helpers().clear();
         {
             let mut current_model = self.current_model_id.lock().unwrap();
             *current_model = None;
@@ -206,7 +325,80 @@ impl TranscriptionManager {
         }
     }
 
+    /// Loads `model_id`, checking the file for corruption if it fails.
+    /// Progress is sent as `model_loading` events for as long as it takes.
     pub fn load_model(&self, model_id: &str) -> Result<()> {
+        let started = Instant::now();
+        let expected = self
+            .load_durations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(model_id)
+            .copied();
+        emit_model_loading(
+            &self.app_handle,
+            model_id,
+            ModelLoadingStage::Started,
+            Duration::ZERO,
+            expected,
+        );
+        tray::set_model_warming_up(&self.app_handle, true);
+
+        // Held while progress is sent so none arrives after the final event
+        let loading = Arc::new(Mutex::new(true));
+        {
+            let app_handle = self.app_handle.clone();
+            let loading = Arc::clone(&loading);
+            let model_id = model_id.to_string();
+            thread::spawn(move || loop {
+                thread::sleep(LOADING_PROGRESS_INTERVAL);
+                let loading = loading.lock().unwrap_or_else(|e| e.into_inner());
+                if !*loading {
+                    break;
+                }
+                emit_model_loading(
+                    &app_handle,
+                    &model_id,
+                    ModelLoadingStage::Loading,
+                    started.elapsed(),
+                    expected,
+                );
+            });
+        }
+
+        let result = self.try_load_model(model_id);
+        *loading.lock().unwrap_or_else(|e| e.into_inner()) = false;
+        tray::set_model_warming_up(&self.app_handle, false);
+
+        let stage = if result.is_ok() {
+            self.load_durations
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(model_id.to_string(), started.elapsed());
+            ModelLoadingStage::Ready
+        } else {
+            ModelLoadingStage::Failed
+        };
+        emit_model_loading(
+            &self.app_handle,
+            model_id,
+            stage,
+            started.elapsed(),
+            expected,
+        );
+        if result.is_err() {
+            // Hashing a model takes a while, so the failure is returned first
+            let app_handle = self.app_handle.clone();
+            let model_manager = Arc::clone(&self.model_manager);
+            let model_id = model_id.to_string();
+            tauri::async_runtime::spawn_blocking(move || {
+                check_failed_model(&app_handle, &model_manager, &model_id)
+            });
+        }
+        result
+    }
+
+    fn try_load_model(&self, model_id: &str) -> Result<()> {
         let load_start = std::time::Instant::now();
         debug!("Starting to load model: {}", model_id);
 
@@ -240,119 +432,46 @@ impl TranscriptionManager {
             return Err(anyhow::anyhow!(error_msg));
         }
 
+        if model_info.is_corrupted {
+            let error_msg = format!(
+                "{} is corrupted. Download it again to use it.",
+                model_info.name
+            );
+            let _ = self.app_handle.emit(
+                "model-state-changed",
+                ModelStateEvent {
+                    event_type: "loading_failed".to_string(),
+                    model_id: Some(model_id.to_string()),
+                    model_name: Some(model_info.name.clone()),
+                    error: Some(error_msg.clone()),
+                },
+            );
+            return Err(anyhow::anyhow!(error_msg));
+        }
+
         let model_path = self.model_manager.get_model_path(model_id)?;
 
         // Create appropriate engine based on model type
-        let loaded_engine = match model_info.engine_type {
-            EngineType::Whisper => {
-                let mut engine = WhisperEngine::new();
-                engine.load_model(&model_path).map_err(|e| {
-                    let error_msg = format!("Failed to load whisper model {}: {}", model_id, e);
-                    let _ = self.app_handle.emit(
-                        "model-state-changed",
-                        ModelStateEvent {
-                            event_type: "loading_failed".to_string(),
-                            model_id: Some(model_id.to_string()),
-                            model_name: Some(model_info.name.clone()),
-                            error: Some(error_msg.clone()),
-                        },
-                    );
-                    anyhow::anyhow!(error_msg)
-                })?;
-                LoadedEngine::Whisper(engine)
-            }
-            EngineType::Parakeet => {
-                let mut engine = ParakeetEngine::new();
-                engine
-                    .load_model_with_params(&model_path, ParakeetModelParams::int8())
-                    .map_err(|e| {
-                        let error_msg =
-                            format!("Failed to load parakeet model {}: {}", model_id, e);
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::Parakeet(engine)
-            }
-            EngineType::Moonshine => {
-                let mut engine = MoonshineEngine::new();
-                engine
-                    .load_model_with_params(
-                        &model_path,
-                        MoonshineModelParams::variant(ModelVariant::Base),
-                    )
-                    .map_err(|e| {
-                        let error_msg =
-                            format!("Failed to load moonshine model {}: {}", model_id, e);
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::Moonshine(engine)
-            }
-            EngineType::MoonshineStreaming => {
-                let mut engine = MoonshineStreamingEngine::new();
-                engine
-                    .load_model_with_params(&model_path, StreamingModelParams::default())
-                    .map_err(|e| {
-                        let error_msg = format!(
-                            "Failed to load moonshine streaming model {}: {}",
-                            model_id, e
-                        );
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::MoonshineStreaming(engine)
-            }
-            EngineType::SenseVoice => {
-                let mut engine = SenseVoiceEngine::new();
-                engine
-                    .load_model_with_params(&model_path, SenseVoiceModelParams::int8())
-                    .map_err(|e| {
-                        let error_msg =
-                            format!("Failed to load SenseVoice model {}: {}", model_id, e);
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::SenseVoice(engine)
-            }
-        };
+        let loaded_engine =
+            load_engine(model_id, &model_info.engine_type, &model_path).map_err(|error_msg| {
+                let _ = self.app_handle.emit(
+                    "model-state-changed",
+                    ModelStateEvent {
+                        event_type: "loading_failed".to_string(),
+                        model_id: Some(model_id.to_string()),
+                        model_name: Some(model_info.name.clone()),
+                        error: Some(error_msg.clone()),
+                    },
+                );
+                anyhow::anyhow!(error_msg)
+            })?;
 
         // Update the current engine and model ID
         {
             let mut engine = self.lock_engine();
             *engine = Some(loaded_engine);
         }
+        self.lock_helpers().clear();
         {
             let mut current_model = self.current_model_id.lock().unwrap();
             *current_model = Some(model_id.to_string());
@@ -380,7 +499,7 @@ impl TranscriptionManager {
 
     /// Kicks off the model loading in a background thread if it's not already loaded
     pub fn initiate_model_load(&self) {
-        let mut is_loading = self.is_loading.lock().unwrap();
+        let mut is_loading = self.is_loading.lock().unwrap_or_else(|e| e.into_inner());
         if *is_loading || self.is_model_loaded() {
             return;
         }
@@ -392,18 +511,211 @@ impl TranscriptionManager {
             if let Err(e) = self_clone.load_model(&settings.selected_model) {
                 error!("Failed to load model: {}", e);
             }
-            let mut is_loading = self_clone.is_loading.lock().unwrap();
+            let mut is_loading = self_clone
+                .is_loading
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
             *is_loading = false;
             self_clone.loading_condvar.notify_all();
         });
     }
 
+    fn lock_helpers(&self) -> MutexGuard<'_, Vec<(String, LoadedEngine)>> {
+        self.helper_engines
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Up to `count` engines for `model_id` left in the pool by earlier
+    /// transcriptions
+    fn take_pooled_helpers(&self, model_id: &str, count: usize) -> Vec<LoadedEngine> {
+        let mut pool = self.lock_helpers();
+        pool.retain(|(id, _)| id == model_id);
+        let taken = count.min(pool.len());
+        pool.drain(..taken).map(|(_, engine)| engine).collect()
+    }
+
+    /// Loads `count` more engines for `model_id` on their own threads, each
+    /// joining `job` once it's ready, so the main engine starts decoding
+    /// straight away. Returns how many were started.
+    fn spawn_helper_loaders(
+        &self,
+        model_id: &str,
+        count: usize,
+        audio: &Arc<Vec<f32>>,
+        job: &Arc<DecodeJob>,
+        tx: &mpsc::Sender<Decoded>,
+    ) -> usize {
+        let Some(model_info) = self.model_manager.get_model_info(model_id) else {
+            return 0;
+        };
+        let model_path = match self.model_manager.get_model_path(model_id) {
+            Ok(path) => path,
+            Err(e) => {
+                warn!("Decoding with fewer workers, no model path: {}", e);
+                return 0;
+            }
+        };
+        for _ in 0..count {
+            let model_id = model_id.to_string();
+            let engine_type = model_info.engine_type.clone();
+            let model_path = model_path.clone();
+            let audio = Arc::clone(audio);
+            let job = Arc::clone(job);
+            let tx = tx.clone();
+            thread::spawn(
+                move || match load_engine(&model_id, &engine_type, &model_path) {
+                    Ok(engine) => run_decode_worker(engine, false, audio, &job, &tx),
+                    Err(e) => {
+                        let _ = tx.send(Decoded::NotLoaded(e));
+                    }
+                },
+            );
+        }
+        count
+    }
+
+    /// Takes in a message from a decode worker, putting engines it's done
+    /// with back
+    fn collect(&self, collected: &mut Collected, decoded: Decoded, model_id: Option<&str>) {
+        match collected.take(decoded) {
+            Some((engine, true)) => *self.lock_engine() = Some(engine),
+            Some((engine, false)) => {
+                if let Some(model_id) = model_id {
+                    self.lock_helpers().push((model_id.to_string(), engine));
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Forgets the engine taken out for a transcription that can't give it
+    /// back, so the model reloads on the next attempt
+    fn discard_engine(&self, reason: String) {
+        self.lock_helpers().clear();
+        {
+            let mut current_model = self
+                .current_model_id
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            *current_model = None;
+        }
+
+        let _ = self.app_handle.emit(
+            "model-state-changed",
+            ModelStateEvent {
+                event_type: "unloaded".to_string(),
+                model_id: None,
+                model_name: None,
+                error: Some(reason),
+            },
+        );
+    }
+
+    /// Waits in the background for the `outstanding` workers of a
+    /// transcription that's over and puts their engines back. While the
+    /// main engine is still out, after a timeout, the model counts as
+    /// loading, so the next transcription waits for the engine instead of
+    /// another copy being loaded.
+    fn recover_engines(
+        &self,
+        rx: mpsc::Receiver<Decoded>,
+        mut outstanding: usize,
+        primary_back: bool,
+        model_id: Option<String>,
+    ) {
+        if !primary_back {
+            *self.is_loading.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        }
+        let engine = Arc::clone(&self.engine);
+        let helper_engines = Arc::clone(&self.helper_engines);
+        let current_model_id = Arc::clone(&self.current_model_id);
+        let is_loading = Arc::clone(&self.is_loading);
+        let loading_condvar = Arc::clone(&self.loading_condvar);
+        let app_handle = self.app_handle.clone();
+        thread::spawn(move || {
+            let mut primary = None;
+            let mut panicked = false;
+            while outstanding > 0 {
+                match rx.recv() {
+                    Ok(Decoded::Chunk(..)) => continue,
+                    Ok(Decoded::NotLoaded(_)) => {}
+                    Ok(Decoded::Finished {
+                        engine,
+                        primary: true,
+                    }) => primary = Some(engine),
+                    Ok(Decoded::Finished { engine, .. }) => {
+                        if let Some(model_id) = &model_id {
+                            helper_engines
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .push((model_id.clone(), engine));
+                        }
+                    }
+                    Ok(Decoded::Panicked { primary, .. }) => panicked |= primary,
+                    Err(_) => break,
+                }
+                outstanding -= 1;
+            }
+
+            let current = current_model_id
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone();
+            let mut slot = engine.lock().unwrap_or_else(|e| e.into_inner());
+            // Unless the model was unloaded or swapped in the meantime
+            if slot.is_none() && current.is_some() && current == model_id {
+                match primary {
+                    Some(engine) => {
+                        debug!("Engine of a timed out transcription is back");
+                        *slot = Some(engine);
+                    }
+                    None if !primary_back => {
+                        warn!("Engine of a timed out transcription was lost");
+                        helper_engines
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .clear();
+                        *current_model_id.lock().unwrap_or_else(|e| e.into_inner()) = None;
+                        let _ = app_handle.emit(
+                            "model-state-changed",
+                            ModelStateEvent {
+                                event_type: "unloaded".to_string(),
+                                model_id: None,
+                                model_name: None,
+                                error: Some(if panicked {
+                                    "Engine panicked".to_string()
+                                } else {
+                                    "Transcription worker stopped".to_string()
+                                }),
+                            },
+                        );
+                    }
+                    None => {}
+                }
+            }
+            drop(slot);
+
+            if !primary_back {
+                *is_loading.lock().unwrap_or_else(|e| e.into_inner()) = false;
+                loading_condvar.notify_all();
+            }
+        });
+    }
+
     pub fn get_current_model(&self) -> Option<String> {
         let current_model = self.current_model_id.lock().unwrap();
         current_model.clone()
     }
 
-    pub fn transcribe(&self, audio: Vec<f32>) -> Result<String> {
+    /// Transcribes with `settings`, which may carry per-app overrides. The
+    /// audio is shared rather than copied, a caller that keeps its own
+    /// reference costs one copy when the recording is decoded in one go.
+    pub fn transcribe(
+        &self,
+        audio: Arc<Vec<f32>>,
+        settings: &AppSettings,
+    ) -> Result<TranscriptionOutput> {
         // Update last activity timestamp
         self.last_activity.store(
             SystemTime::now()
@@ -420,15 +732,37 @@ impl TranscriptionManager {
         if audio.is_empty() {
             debug!("Empty audio vector");
             self.maybe_unload_immediately("empty audio");
-            return Ok(String::new());
+            return Ok(TranscriptionOutput::default());
         }
 
+        let timeout = (settings.transcription_timeout_secs > 0)
+            .then(|| Duration::from_secs(u64::from(settings.transcription_timeout_secs)));
+
         // Check if model is loaded, if not try to load it
         {
-            // If the model is loading, wait for it to complete.
-            let mut is_loading = self.is_loading.lock().unwrap();
+            // If the model is loading, or still busy with a transcription
+            // that timed out, wait for it within the same time limit
+            let wait_deadline = timeout.map(|timeout| Instant::now() + timeout);
+            let mut is_loading = self.is_loading.lock().unwrap_or_else(|e| e.into_inner());
             while *is_loading {
-                is_loading = self.loading_condvar.wait(is_loading).unwrap();
+                is_loading = match wait_deadline {
+                    Some(deadline) => {
+                        let remaining = deadline.saturating_duration_since(Instant::now());
+                        if remaining.is_zero() {
+                            return Err(anyhow::anyhow!(
+                                "The model is still loading or busy with a transcription that timed out. Try again once it has finished."
+                            ));
+                        }
+                        self.loading_condvar
+                            .wait_timeout(is_loading, remaining)
+                            .unwrap_or_else(|e| e.into_inner())
+                            .0
+                    }
+                    None => self
+                        .loading_condvar
+                        .wait(is_loading)
+                        .unwrap_or_else(|e| e.into_inner()),
+                };
             }
 
             let engine_guard = self.lock_engine();
@@ -437,162 +771,196 @@ impl TranscriptionManager {
             }
         }
 
-        // Get current settings for configuration
-        let settings = get_settings(&self.app_handle);
+        // Engines run on their own threads so a decode that hangs can be
+        // given up on. The main engine is taken out of its slot for the
+        // duration, so an engine panic drops it instead of poisoning the
+        // mutex, and is put back once its worker has finished.
+        let engine = match self.lock_engine().take() {
+            Some(e) => e,
+            None => {
+                return Err(anyhow::anyhow!(
+                    "Model failed to load after auto-load attempt. Please check your model settings."
+                ));
+            }
+        };
+        let model_id = self.get_current_model();
+
+        let chunks = chunk_ranges(audio.len());
+        let chunk_count = chunks.len();
+        let worker_count = worker_count(
+            settings.transcription_workers,
+            chunk_count,
+            thread::available_parallelism().map_or(1, |n| n.get()),
+        );
+        let helpers = match &model_id {
+            Some(model_id) if worker_count > 1 => {
+                self.take_pooled_helpers(model_id, worker_count - 1)
+            }
+            _ => Vec::new(),
+        };
+        if worker_count > 1 {
+            debug!(
+                "Decoding {} chunks on up to {} workers",
+                chunk_count, worker_count
+            );
+        }
+
+        let job = Arc::new(DecodeJob {
+            chunks,
+            next_chunk: AtomicUsize::new(0),
+            cancelled: AtomicBool::new(false),
+            settings: settings.clone(),
+        });
+        let (tx, rx) = mpsc::channel();
+        // Missing helpers join once they're loaded
+        let loading = match &model_id {
+            Some(model_id) if helpers.len() + 1 < worker_count => self.spawn_helper_loaders(
+                model_id,
+                worker_count - 1 - helpers.len(),
+                &audio,
+                &job,
+                &tx,
+            ),
+            _ => 0,
+        };
+        let engines: Vec<(LoadedEngine, bool)> = std::iter::once((engine, true))
+            .chain(helpers.into_iter().map(|helper| (helper, false)))
+            .collect();
+        let running = engines.len();
+        let mut outstanding = running + loading;
+        let mut audio = Some(audio);
+        for (index, (engine, primary)) in engines.into_iter().enumerate() {
+            // The last worker takes over this reference to the audio
+            let audio = if index + 1 == running {
+                audio.take()
+            } else {
+                audio.clone()
+            }
+            .expect("audio is kept for the last worker");
+            let job = Arc::clone(&job);
+            let tx = tx.clone();
+            thread::spawn(move || run_decode_worker(engine, primary, audio, &job, &tx));
+        }
+        drop(tx);
 
-        // Perform transcription with the appropriate engine.
-        // We use catch_unwind to prevent engine panics from poisoning the mutex,
-        // which would make the app hang indefinitely on subsequent operations.
-        let result = {
-            let mut engine_guard = self.lock_engine();
-
-            // Take the engine out so we own it during transcription.
-            // If the engine panics, we simply don't put it back (effectively unloading it)
-            // instead of poisoning the mutex.
-            let mut engine = match engine_guard.take() {
-                Some(e) => e,
-                None => {
-                    return Err(anyhow::anyhow!(
-                        "Model failed to load after auto-load attempt. Please check your model settings."
-                    ));
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut collected = Collected::new(chunk_count);
+        let mut timed_out = false;
+        let mut partial = false;
+        // Helpers still loading or decoding are handed to `recover_engines`
+        // once the main engine is done
+        while outstanding > 0 && !collected.is_done() {
+            let received = match deadline {
+                Some(deadline) => {
+                    rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
                 }
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
             };
-
-            // Release the lock before transcribing — no mutex held during the engine call
-            drop(engine_guard);
-
-            let transcribe_result = catch_unwind(AssertUnwindSafe(
-                || -> Result<transcribe_rs::TranscriptionResult> {
-                    match &mut engine {
-                        LoadedEngine::Whisper(whisper_engine) => {
-                            let whisper_language = if settings.selected_language == "auto" {
-                                None
-                            } else {
-                                let normalized = if settings.selected_language == "zh-Hans"
-                                    || settings.selected_language == "zh-Hant"
-                                {
-                                    "zh".to_string()
-                                } else {
-                                    settings.selected_language.clone()
-                                };
-                                Some(normalized)
-                            };
-
-                            let params = WhisperInferenceParams {
-                                language: whisper_language,
-                                translate: settings.translate_to_english,
-                                ..Default::default()
-                            };
-
-                            whisper_engine
-                                .transcribe_samples(audio, Some(params))
-                                .map_err(|e| anyhow::anyhow!("Whisper transcription failed: {}", e))
-                        }
-                        LoadedEngine::Parakeet(parakeet_engine) => {
-                            let params = ParakeetInferenceParams {
-                                timestamp_granularity: TimestampGranularity::Segment,
-                                ..Default::default()
-                            };
-                            parakeet_engine
-                                .transcribe_samples(audio, Some(params))
-                                .map_err(|e| {
-                                    anyhow::anyhow!("Parakeet transcription failed: {}", e)
-                                })
-                        }
-                        LoadedEngine::Moonshine(moonshine_engine) => moonshine_engine
-                            .transcribe_samples(audio, None)
-                            .map_err(|e| anyhow::anyhow!("Moonshine transcription failed: {}", e)),
-                        LoadedEngine::MoonshineStreaming(streaming_engine) => streaming_engine
-                            .transcribe_samples(audio, None)
-                            .map_err(|e| {
-                                anyhow::anyhow!("Moonshine streaming transcription failed: {}", e)
-                            }),
-                        LoadedEngine::SenseVoice(sense_voice_engine) => {
-                            let language = match settings.selected_language.as_str() {
-                                "zh" | "zh-Hans" | "zh-Hant" => SenseVoiceLanguage::Chinese,
-                                "en" => SenseVoiceLanguage::English,
-                                "ja" => SenseVoiceLanguage::Japanese,
-                                "ko" => SenseVoiceLanguage::Korean,
-                                "yue" => SenseVoiceLanguage::Cantonese,
-                                _ => SenseVoiceLanguage::Auto,
-                            };
-                            let params = SenseVoiceInferenceParams {
-                                language,
-                                use_itn: true,
-                            };
-                            sense_voice_engine
-                                .transcribe_samples(audio, Some(params))
-                                .map_err(|e| {
-                                    anyhow::anyhow!("SenseVoice transcription failed: {}", e)
-                                })
-                        }
+            match received {
+                Ok(decoded) => {
+                    if decoded.ends_worker() {
+                        outstanding -= 1;
+                    }
+                    self.collect(&mut collected, decoded, model_id.as_deref());
+                    if collected.panic.is_some() || collected.failure.is_some() {
+                        job.cancelled.store(true, Ordering::Relaxed);
                     }
-                },
-            ));
-
-            match transcribe_result {
-                Ok(inner_result) => {
-                    // Success or normal error — put the engine back
-                    let mut engine_guard = self.lock_engine();
-                    *engine_guard = Some(engine);
-                    inner_result?
                 }
-                Err(panic_payload) => {
-                    // Engine panicked — do NOT put it back (it's in an unknown state).
-                    // The engine is dropped here, effectively unloading it.
-                    let panic_msg = if let Some(s) = panic_payload.downcast_ref::<&str>() {
-                        s.to_string()
-                    } else if let Some(s) = panic_payload.downcast_ref::<String>() {
-                        s.clone()
-                    } else {
-                        "unknown panic".to_string()
-                    };
-                    error!(
-                        "Transcription engine panicked: {}. Model has been unloaded.",
-                        panic_msg
-                    );
-
-                    // Clear the model ID so it will be reloaded on next attempt
-                    {
-                        let mut current_model = self
-                            .current_model_id
-                            .lock()
-                            .unwrap_or_else(|e| e.into_inner());
-                        *current_model = None;
+                Err(RecvTimeoutError::Timeout) => {
+                    timed_out = true;
+                    job.cancelled.store(true, Ordering::Relaxed);
+                    // Workers may have finished just as time ran out
+                    for decoded in rx.try_iter() {
+                        if decoded.ends_worker() {
+                            outstanding -= 1;
+                        }
+                        self.collect(&mut collected, decoded, model_id.as_deref());
                     }
-
-                    let _ = self.app_handle.emit(
-                        "model-state-changed",
-                        ModelStateEvent {
-                            event_type: "unloaded".to_string(),
-                            model_id: None,
-                            model_name: None,
-                            error: Some(format!("Engine panicked: {}", panic_msg)),
-                        },
-                    );
-
-                    return Err(anyhow::anyhow!(
-                        "Transcription engine panicked: {}. The model has been unloaded and will reload on next attempt.",
-                        panic_msg
-                    ));
+                    partial = collected.texts.iter().any(Option::is_none);
+                    if partial {
+                        warn!(
+                            "Transcription timed out after {}s with {} of {} chunks decoded",
+                            settings.transcription_timeout_secs,
+                            collected.texts.iter().flatten().count(),
+                            chunk_count
+                        );
+                    }
+                    break;
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    collected.failure.get_or_insert_with(|| {
+                        anyhow::anyhow!(
+                            "Transcription stopped unexpectedly. The model will reload on next attempt."
+                        )
+                    });
+                    break;
                 }
             }
+        }
+
+        job.cancelled.store(true, Ordering::Relaxed);
+        if outstanding > 0 && collected.panic.is_none() && (timed_out || collected.primary_back) {
+            // A decode can't be interrupted, so the engines still busy are
+            // waited for rather than loading the model next to them
+            self.recover_engines(rx, outstanding, collected.primary_back, model_id.clone());
+        } else if !collected.primary_back {
+            let reason = match &collected.panic {
+                Some(panic_msg) => format!("Engine panicked: {}", panic_msg),
+                None => "Transcription worker stopped".to_string(),
+            };
+            self.discard_engine(reason);
+        }
+        if let Some(panic_msg) = collected.panic {
+            error!("Transcription engine panicked: {}", panic_msg);
+            return Err(anyhow::anyhow!(
+                "Transcription engine panicked: {}. The model has been unloaded and will reload on next attempt.",
+                panic_msg
+            ));
+        }
+        if let Some(e) = collected.failure {
+            return Err(e);
+        }
+
+        // Recordings of up to a minute are decoded in one piece, since
+        // splitting them costs accuracy, so a timeout leaves no partial text
+        // for them and is an error
+        let Some(text) = reassemble(collected.texts) else {
+            return Err(anyhow::anyhow!(
+                "Transcription timed out after {} seconds before any text was decoded",
+                settings.transcription_timeout_secs
+            ));
+        };
+
+        // Everything after a literal mode trigger ("literally", "spell out") is kept
+        // verbatim and skips the formatting stages below
+        let (spoken_text, literal_text) = if settings.literal_mode_enabled {
+            split_literal_segment(&text, &settings.literal_mode_triggers)
+        } else {
+            (text, None)
         };
 
         // Apply word correction if custom words are configured
         let corrected_result = if !settings.custom_words.is_empty() {
             apply_custom_words(
-                &result.text,
+                &spoken_text,
                 &settings.custom_words,
                 settings.word_correction_threshold,
             )
         } else {
-            result.text
+            spoken_text
         };
 
         // Filter out filler words and hallucinations
         let filtered_result = filter_transcription_output(&corrected_result);
 
+        // Convert spoken formatting commands ("new line", "comma", ...) into literal text.
+        // This runs after filtering since the filter collapses whitespace, including newlines.
+        let formatted_result = if settings.spoken_commands_enabled {
+            apply_spoken_commands(&filtered_result, &settings.spoken_commands)
+        } else {
+            filtered_result
+        };
+
         let et = std::time::Instant::now();
         let translation_note = if settings.translate_to_english {
             " (translated)"
@@ -600,17 +968,24 @@ impl TranscriptionManager {
             ""
         };
         info!(
-            "Transcription completed in {}ms{}",
+            "Transcription completed in {}ms{}{}",
             (et - st).as_millis(),
-            translation_note
+            translation_note,
+            if partial { " (partial)" } else { "" }
         );
 
-        let final_result = filtered_result;
+        let final_result = TranscriptionOutput {
+            text: formatted_result,
+            literal: literal_text.map(|text| format_literal(&text)),
+            partial,
+        };
+        let full_text = final_result.full_text();
 
-        if final_result.is_empty() {
+        if full_text.is_empty() {
             info!("Transcription result is empty");
         } else {
-            info!("Transcription result: {}", final_result);
+            // Never the text itself, logs end up in diagnostics
+            info!("Transcribed {} characters", full_text.chars().count());
         }
 
         self.maybe_unload_immediately("transcription");
@@ -636,3 +1011,432 @@ impl Drop for TranscriptionManager {
         }
     }
 }
+
+fn emit_model_loading(
+    app_handle: &AppHandle,
+    model_id: &str,
+    stage: ModelLoadingStage,
+    elapsed: Duration,
+    expected: Option<Duration>,
+) {
+    let progress = match stage {
+        ModelLoadingStage::Ready => Some(1.0),
+        ModelLoadingStage::Failed => None,
+        ModelLoadingStage::Started | ModelLoadingStage::Loading => {
+            expected.map(|expected| loading_progress(elapsed, expected))
+        }
+    };
+    events::emit(
+        app_handle,
+        AppEvent::ModelLoading(ModelLoadingEvent {
+            model_id: model_id.to_string(),
+            stage,
+            elapsed_ms: elapsed.as_millis() as u64,
+            progress,
+        }),
+    );
+}
+
+/// Share of a load done after `elapsed`, when the last one took `expected`.
+/// It stops short of done, a load can run longer than the last.
+fn loading_progress(elapsed: Duration, expected: Duration) -> f32 {
+    if expected.is_zero() {
+        return 0.0;
+    }
+    (elapsed.as_secs_f32() / expected.as_secs_f32()).min(0.95)
+}
+
+/// Creates the engine for a model of `engine_type` and loads it from
+/// `model_path`
+fn load_engine(
+    model_id: &str,
+    engine_type: &EngineType,
+    model_path: &Path,
+) -> std::result::Result<LoadedEngine, String> {
+    match engine_type {
+        EngineType::Whisper => {
+            let mut engine = WhisperEngine::new();
+            engine
+                .load_model(model_path)
+                .map_err(|e| format!("Failed to load whisper model {}: {}", model_id, e))?;
+            Ok(LoadedEngine::Whisper(engine))
+        }
+        EngineType::Parakeet => {
+            let mut engine = ParakeetEngine::new();
+            engine
+                .load_model_with_params(model_path, ParakeetModelParams::int8())
+                .map_err(|e| format!("Failed to load parakeet model {}: {}", model_id, e))?;
+            Ok(LoadedEngine::Parakeet(engine))
+        }
+        EngineType::Moonshine => {
+            let mut engine = MoonshineEngine::new();
+            engine
+                .load_model_with_params(
+                    model_path,
+                    MoonshineModelParams::variant(ModelVariant::Base),
+                )
+                .map_err(|e| format!("Failed to load moonshine model {}: {}", model_id, e))?;
+            Ok(LoadedEngine::Moonshine(engine))
+        }
+        EngineType::MoonshineStreaming => {
+            let mut engine = MoonshineStreamingEngine::new();
+            engine
+                .load_model_with_params(model_path, StreamingModelParams::default())
+                .map_err(|e| {
+                    format!(
+                        "Failed to load moonshine streaming model {}: {}",
+                        model_id, e
+                    )
+                })?;
+            Ok(LoadedEngine::MoonshineStreaming(engine))
+        }
+        EngineType::SenseVoice => {
+            let mut engine = SenseVoiceEngine::new();
+            engine
+                .load_model_with_params(model_path, SenseVoiceModelParams::int8())
+                .map_err(|e| format!("Failed to load SenseVoice model {}: {}", model_id, e))?;
+            Ok(LoadedEngine::SenseVoice(engine))
+        }
+    }
+}
+
+/// Decodes a chunk of audio with whichever engine is loaded
+fn decode(engine: &mut LoadedEngine, audio: Vec<f32>, settings: &AppSettings) -> Result<String> {
+    let result = match engine {
+        LoadedEngine::Whisper(whisper_engine) => {
+            let whisper_language = if settings.selected_language == "auto" {
+                None
+            } else {
+                let normalized = if settings.selected_language == "zh-Hans"
+                    || settings.selected_language == "zh-Hant"
+                {
+                    "zh".to_string()
+                } else {
+                    settings.selected_language.clone()
+                };
+                Some(normalized)
+            };
+
+            let params = WhisperInferenceParams {
+                language: whisper_language,
+                translate: settings.translate_to_english,
+                ..Default::default()
+            };
+
+            whisper_engine
+                .transcribe_samples(audio, Some(params))
+                .map_err(|e| anyhow::anyhow!("Whisper transcription failed: {}", e))
+        }
+        LoadedEngine::Parakeet(parakeet_engine) => {
+            let params = ParakeetInferenceParams {
+                timestamp_granularity: TimestampGranularity::Segment,
+                ..Default::default()
+            };
+            parakeet_engine
+                .transcribe_samples(audio, Some(params))
+                .map_err(|e| anyhow::anyhow!("Parakeet transcription failed: {}", e))
+        }
+        LoadedEngine::Moonshine(moonshine_engine) => moonshine_engine
+            .transcribe_samples(audio, None)
+            .map_err(|e| anyhow::anyhow!("Moonshine transcription failed: {}", e)),
+        LoadedEngine::MoonshineStreaming(streaming_engine) => streaming_engine
+            .transcribe_samples(audio, None)
+            .map_err(|e| anyhow::anyhow!("Moonshine streaming transcription failed: {}", e)),
+        LoadedEngine::SenseVoice(sense_voice_engine) => {
+            let language = match settings.selected_language.as_str() {
+                "zh" | "zh-Hans" | "zh-Hant" => SenseVoiceLanguage::Chinese,
+                "en" => SenseVoiceLanguage::English,
+                "ja" => SenseVoiceLanguage::Japanese,
+                "ko" => SenseVoiceLanguage::Korean,
+                "yue" => SenseVoiceLanguage::Cantonese,
+                _ => SenseVoiceLanguage::Auto,
+            };
+            let params = SenseVoiceInferenceParams {
+                language,
+                use_itn: true,
+            };
+            sense_voice_engine
+                .transcribe_samples(audio, Some(params))
+                .map_err(|e| anyhow::anyhow!("SenseVoice transcription failed: {}", e))
+        }
+    }?;
+    Ok(result.text)
+}
+
+/// A transcription shared by its decode workers
+struct DecodeJob {
+    chunks: Vec<Range<usize>>,
+    /// Index of the next chunk a worker should take
+    next_chunk: AtomicUsize,
+    /// Set once the result is no longer waited for
+    cancelled: AtomicBool,
+    settings: AppSettings,
+}
+
+/// What a decode worker sends back
+enum Decoded {
+    /// Text of the chunk at an index
+    Chunk(usize, Result<String>),
+    /// The worker has stopped and hands its engine back
+    Finished { engine: LoadedEngine, primary: bool },
+    /// The worker's engine panicked and was dropped
+    Panicked { message: String, primary: bool },
+    /// A helper engine failed to load, so its worker never started
+    NotLoaded(String),
+}
+
+impl Decoded {
+    /// Whether it's the last message from its worker
+    fn ends_worker(&self) -> bool {
+        !matches!(self, Decoded::Chunk(..))
+    }
+}
+
+/// What the decode workers of a transcription have sent back
+struct Collected {
+    /// Chunk texts in recording order
+    texts: Vec<Option<String>>,
+    failure: Option<anyhow::Error>,
+    panic: Option<String>,
+    /// Whether the main engine is back in its slot
+    primary_back: bool,
+    /// Whether the main engine's worker has stopped, with its engine or not
+    primary_done: bool,
+}
+
+impl Collected {
+    fn new(chunk_count: usize) -> Self {
+        Self {
+            texts: vec![None; chunk_count],
+            failure: None,
+            panic: None,
+            primary_back: false,
+            primary_done: false,
+        }
+    }
+
+    /// Takes in a message from a decode worker. Returns the engine of a
+    /// worker that's done with it and whether it's the main one.
+    fn take(&mut self, decoded: Decoded) -> Option<(LoadedEngine, bool)> {
+        match decoded {
+            Decoded::Chunk(index, Ok(text)) => self.texts[index] = Some(text),
+            Decoded::Chunk(_, Err(e)) => {
+                self.failure.get_or_insert(e);
+            }
+            Decoded::Finished { engine, primary } => {
+                if primary {
+                    self.primary_back = true;
+                    self.primary_done = true;
+                }
+                return Some((engine, primary));
+            }
+            Decoded::Panicked { message, primary } => {
+                self.primary_done |= primary;
+                self.panic.get_or_insert(message);
+            }
+            Decoded::NotLoaded(e) => warn!("Decoding with fewer workers: {}", e),
+        }
+        None
+    }
+
+    /// Whether the result is settled: the main worker has stopped and every
+    /// chunk is decoded, or something failed
+    fn is_done(&self) -> bool {
+        self.primary_done
+            && (self.failure.is_some()
+                || self.panic.is_some()
+                || self.texts.iter().all(Option::is_some))
+    }
+}
+
+/// The chunk texts decoded in a row from the start of the recording,
+/// stitched together. A timeout can leave later chunks decoded after a
+/// missing one, those are dropped. `None` without the first chunk.
+fn reassemble(texts: Vec<Option<String>>) -> Option<String> {
+    let mut texts = texts.into_iter().map_while(|text| text).peekable();
+    texts.peek()?;
+    Some(texts.fold(String::new(), |text, chunk| {
+        stitch_overlapping(&text, &chunk)
+    }))
+}
+
+/// Workers for a recording in `chunk_count` chunks, so their engines
+/// together use about the `parallelism` the machine has
+fn worker_count(requested: u32, chunk_count: usize, parallelism: usize) -> usize {
+    (requested.max(1) as usize)
+        .min(chunk_count)
+        .min((parallelism / THREADS_PER_ENGINE).max(1))
+}
+
+/// Decodes chunks of `job` with `engine` until none are left or the job is
+/// cancelled
+fn run_decode_worker(
+    mut engine: LoadedEngine,
+    primary: bool,
+    mut audio: Arc<Vec<f32>>,
+    job: &DecodeJob,
+    tx: &mpsc::Sender<Decoded>,
+) {
+    loop {
+        if job.cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+        let index = job.next_chunk.fetch_add(1, Ordering::Relaxed);
+        let Some(chunk) = job.chunks.get(index) else {
+            break;
+        };
+        let piece = if job.chunks.len() == 1 {
+            // A recording in one chunk goes to the engine whole, copied only
+            // when the caller still holds it
+            Arc::unwrap_or_clone(std::mem::take(&mut audio))
+        } else {
+            audio[chunk.clone()].to_vec()
+        };
+        match catch_unwind(AssertUnwindSafe(|| {
+            decode(&mut engine, piece, &job.settings)
+        })) {
+            Ok(result) => {
+                let _ = tx.send(Decoded::Chunk(index, result));
+            }
+            Err(payload) => {
+                let _ = tx.send(Decoded::Panicked {
+                    message: panic_message(payload),
+                    primary,
+                });
+                return;
+            }
+        }
+    }
+    // Once nobody's listening the engine is dropped along with the message
+    let _ = tx.send(Decoded::Finished { engine, primary });
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Sample ranges of the chunks `len` samples of audio are transcribed in
+fn chunk_ranges(len: usize) -> Vec<Range<usize>> {
+    if len <= CHUNKING_THRESHOLD_SAMPLES {
+        return vec![0..len];
+    }
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + CHUNK_SAMPLES).min(len);
+        ranges.push(start..end);
+        if end == len {
+            return ranges;
+        }
+        start = end - CHUNK_OVERLAP_SAMPLES;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_loading_progress_from_the_last_load() {
+        let expected = Duration::from_secs(4);
+        assert_eq!(loading_progress(Duration::ZERO, expected), 0.0);
+        assert_eq!(loading_progress(Duration::from_secs(1), expected), 0.25);
+        assert_eq!(loading_progress(Duration::from_secs(9), expected), 0.95);
+        assert_eq!(
+            loading_progress(Duration::from_secs(1), Duration::ZERO),
+            0.0
+        );
+    }
+
+    #[test]
+    fn reassembles_chunks_in_recording_order() {
+        let texts = vec![
+            Some("one two three".to_string()),
+            Some("two three four five".to_string()),
+            Some("six seven".to_string()),
+        ];
+        assert_eq!(
+            reassemble(texts).as_deref(),
+            Some("one two three four five six seven")
+        );
+        // A timeout keeps the run from the start
+        let texts = vec![Some("one two".to_string()), None, Some("five".to_string())];
+        assert_eq!(reassemble(texts).as_deref(), Some("one two"));
+        assert_eq!(reassemble(vec![None, Some("two".to_string())]), None);
+    }
+
+    #[test]
+    fn waits_for_the_main_engine_and_every_chunk() {
+        let mut collected = Collected::new(2);
+        assert!(collected
+            .take(Decoded::Chunk(1, Ok("two".to_string())))
+            .is_none());
+        assert!(!collected.is_done());
+        let engine = LoadedEngine::Whisper(WhisperEngine::new());
+        assert!(matches!(
+            collected.take(Decoded::Finished {
+                engine,
+                primary: true
+            }),
+            Some((_, true))
+        ));
+        // A helper is still decoding the first chunk
+        assert!(collected.primary_back);
+        assert!(!collected.is_done());
+        collected.take(Decoded::Chunk(0, Ok("one".to_string())));
+        assert!(collected.is_done());
+        assert_eq!(
+            collected.texts,
+            vec![Some("one".to_string()), Some("two".to_string())]
+        );
+
+        let mut collected = Collected::new(3);
+        collected.take(Decoded::NotLoaded("out of memory".to_string()));
+        collected.take(Decoded::Panicked {
+            message: "helper".to_string(),
+            primary: false,
+        });
+        assert!(!collected.is_done());
+        collected.take(Decoded::Panicked {
+            message: "main".to_string(),
+            primary: true,
+        });
+        assert!(collected.is_done());
+        assert!(!collected.primary_back);
+        assert_eq!(collected.panic.as_deref(), Some("helper"));
+    }
+
+    #[test]
+    fn workers_share_the_cores() {
+        assert_eq!(worker_count(4, 10, 16), 4);
+        assert_eq!(worker_count(4, 10, 8), 2);
+        assert_eq!(worker_count(4, 2, 16), 2);
+        assert_eq!(worker_count(4, 10, 2), 1);
+        assert_eq!(worker_count(0, 10, 16), 1);
+    }
+
+    #[test]
+    fn chunks_long_recordings_with_overlap() {
+        assert_eq!(chunk_ranges(100), vec![0..100]);
+        assert_eq!(
+            chunk_ranges(CHUNKING_THRESHOLD_SAMPLES),
+            vec![0..CHUNKING_THRESHOLD_SAMPLES]
+        );
+
+        let len = 70 * WHISPER_SAMPLE_RATE as usize;
+        let ranges = chunk_ranges(len);
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[0], 0..CHUNK_SAMPLES);
+        assert_eq!(ranges[1].start, CHUNK_SAMPLES - CHUNK_OVERLAP_SAMPLES);
+        assert_eq!(ranges[2].end, len);
+        assert!(ranges
+            .windows(2)
+            .all(|pair| pair[0].end - pair[1].start == CHUNK_OVERLAP_SAMPLES));
+    }
+}
@@ -0,0 +1,427 @@
+//! Markdown handling for transcripts, mostly produced by LLM post-processing.
+//!
+//! Depending on the output mode (globally or per app), Markdown is pasted as-is,
+//! stripped to plain text, or converted to HTML for rich text clipboard paste.
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+static HEADING: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(#{1,6})\s+(.*?)\s*#*\s*$").unwrap());
+static BULLET: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\s*)[-*+]\s+(.*)$").unwrap());
+static NUMBERED: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\s*)(\d+)[.)]\s+(.*)$").unwrap());
+static QUOTE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^>\s?(.*)$").unwrap());
+static RULE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*(?:(?:-\s*){3,}|(?:\*\s*){3,}|(?:_\s*){3,})$").unwrap());
+
+static IMAGE: Lazy<Regex> = Lazy::new(|| Regex::new(r"!\[([^\]]*)\]\(([^)\s]+)[^)]*\)").unwrap());
+static LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[([^\]]+)\]\(([^)\s]+)[^)]*\)").unwrap());
+static BOLD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\*\*(\S(?:.*?\S)?)\*\*|__(\S(?:.*?\S)?)__").unwrap());
+static ITALIC_STAR: Lazy<Regex> = Lazy::new(|| Regex::new(r"\*(\S(?:[^*]*?\S)?)\*").unwrap());
+// Underscores only count at word boundaries so identifiers like my_var survive
+static ITALIC_UNDERSCORE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(^|[^\w])_(\S(?:[^_]*?\S)?)_([^\w]|$)").unwrap());
+static STRIKE: Lazy<Regex> = Lazy::new(|| Regex::new(r"~~(\S(?:.*?\S)?)~~").unwrap());
+
+/// Inline formatting target
+#[derive(Clone, Copy, PartialEq)]
+enum Inline {
+    Plain,
+    Html,
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Applies inline formatting to a single line. Code spans are left untouched
+/// apart from HTML escaping.
+fn format_inline(line: &str, target: Inline) -> String {
+    let mut output = String::with_capacity(line.len());
+    for (index, segment) in line.split('`').enumerate() {
+        // Odd segments sit between backticks; an unmatched trailing backtick
+        // leaves an odd segment count which is treated as plain text
+        let is_code = index % 2 == 1 && line.matches('`').count() > index;
+        if is_code {
+            match target {
+                Inline::Plain => output.push_str(segment),
+                Inline::Html => {
+                    output.push_str("<code>");
+                    output.push_str(&escape_html(segment));
+                    output.push_str("</code>");
+                }
+            }
+            continue;
+        }
+        if index % 2 == 1 {
+            output.push('`');
+        }
+        output.push_str(&format_span(segment, target));
+    }
+    output
+}
+
+fn format_span(text: &str, target: Inline) -> String {
+    let text = match target {
+        Inline::Plain => text.to_string(),
+        Inline::Html => escape_html(text),
+    };
+    let pick = |caps: &Captures, first: usize, second: usize| -> String {
+        caps.get(first)
+            .or_else(|| caps.get(second))
+            .map_or(String::new(), |m| m.as_str().to_string())
+    };
+
+    let text = IMAGE.replace_all(&text, |caps: &Captures| match target {
+        Inline::Plain => caps[1].to_string(),
+        Inline::Html => format!("<img src=\"{}\" alt=\"{}\">", &caps[2], &caps[1]),
+    });
+    let text = LINK.replace_all(&text, |caps: &Captures| match target {
+        Inline::Plain if caps[1] == caps[2] => caps[1].to_string(),
+        Inline::Plain => format!("{} ({})", &caps[1], &caps[2]),
+        Inline::Html => format!("<a href=\"{}\">{}</a>", &caps[2], &caps[1]),
+    });
+    let text = BOLD.replace_all(&text, |caps: &Captures| match target {
+        Inline::Plain => pick(caps, 1, 2),
+        Inline::Html => format!("<strong>{}</strong>", pick(caps, 1, 2)),
+    });
+    let text = STRIKE.replace_all(&text, |caps: &Captures| match target {
+        Inline::Plain => caps[1].to_string(),
+        Inline::Html => format!("<del>{}</del>", &caps[1]),
+    });
+    let text = ITALIC_STAR.replace_all(&text, |caps: &Captures| match target {
+        Inline::Plain => caps[1].to_string(),
+        Inline::Html => format!("<em>{}</em>", &caps[1]),
+    });
+    let text = ITALIC_UNDERSCORE.replace_all(&text, |caps: &Captures| match target {
+        Inline::Plain => format!("{}{}{}", &caps[1], &caps[2], &caps[3]),
+        Inline::Html => format!("{}<em>{}</em>{}", &caps[1], &caps[2], &caps[3]),
+    });
+    text.into_owned()
+}
+
+/// Converts Markdown to plain text: markers are removed, list items keep a
+/// simple "- " or "1. " prefix and links become "text (url)".
+pub fn strip_markdown(text: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(line.to_string());
+            continue;
+        }
+        if RULE.is_match(line) {
+            continue;
+        }
+
+        let line = if let Some(caps) = HEADING.captures(line) {
+            format_inline(&caps[2], Inline::Plain)
+        } else if let Some(caps) = BULLET.captures(line) {
+            format!("{}- {}", &caps[1], format_inline(&caps[2], Inline::Plain))
+        } else if let Some(caps) = NUMBERED.captures(line) {
+            format!(
+                "{}{}. {}",
+                &caps[1],
+                &caps[2],
+                format_inline(&caps[3], Inline::Plain)
+            )
+        } else if let Some(caps) = QUOTE.captures(line) {
+            format_inline(&caps[1], Inline::Plain)
+        } else {
+            format_inline(line, Inline::Plain)
+        };
+        lines.push(line);
+    }
+
+    lines.join("\n").trim().to_string()
+}
+
+#[derive(PartialEq)]
+enum Block {
+    None,
+    Paragraph,
+    BulletList,
+    NumberedList,
+    Quote,
+}
+
+fn close_block(html: &mut String, block: &mut Block) {
+    match block {
+        Block::None => {}
+        Block::Paragraph => html.push_str("</p>"),
+        Block::BulletList => html.push_str("</ul>"),
+        Block::NumberedList => html.push_str("</ol>"),
+        Block::Quote => html.push_str("</blockquote>"),
+    }
+    *block = Block::None;
+}
+
+fn open_block(html: &mut String, block: &mut Block, next: Block) {
+    if *block == next {
+        return;
+    }
+    close_block(html, block);
+    html.push_str(match next {
+        Block::None => "",
+        Block::Paragraph => "<p>",
+        Block::BulletList => "<ul>",
+        Block::NumberedList => "<ol>",
+        Block::Quote => "<blockquote>",
+    });
+    *block = next;
+}
+
+/// Converts Markdown to an HTML fragment suitable for the clipboard. Covers
+/// the subset LLMs commonly produce: headings, paragraphs, lists, quotes,
+/// code blocks, emphasis, code spans and links.
+pub fn markdown_to_html(text: &str) -> String {
+    let mut html = String::with_capacity(text.len() * 2);
+    let mut block = Block::None;
+    let mut in_code_block = false;
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            if in_code_block {
+                html.push_str("</code></pre>");
+            } else {
+                close_block(&mut html, &mut block);
+                html.push_str("<pre><code>");
+            }
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            html.push_str(&escape_html(line));
+            html.push('\n');
+            continue;
+        }
+
+        if line.trim().is_empty() {
+            close_block(&mut html, &mut block);
+        } else if RULE.is_match(line) {
+            close_block(&mut html, &mut block);
+            html.push_str("<hr>");
+        } else if let Some(caps) = HEADING.captures(line) {
+            close_block(&mut html, &mut block);
+            let level = caps[1].len();
+            html.push_str(&format!(
+                "<h{level}>{}</h{level}>",
+                format_inline(&caps[2], Inline::Html)
+            ));
+        } else if let Some(caps) = BULLET.captures(line) {
+            open_block(&mut html, &mut block, Block::BulletList);
+            html.push_str(&format!(
+                "<li>{}</li>",
+                format_inline(&caps[2], Inline::Html)
+            ));
+        } else if let Some(caps) = NUMBERED.captures(line) {
+            open_block(&mut html, &mut block, Block::NumberedList);
+            html.push_str(&format!(
+                "<li>{}</li>",
+                format_inline(&caps[3], Inline::Html)
+            ));
+        } else if let Some(caps) = QUOTE.captures(line) {
+            let continuing = block == Block::Quote;
+            open_block(&mut html, &mut block, Block::Quote);
+            if continuing {
+                html.push_str("<br>");
+            }
+            html.push_str(&format_inline(&caps[1], Inline::Html));
+        } else {
+            let continuing = block == Block::Paragraph;
+            open_block(&mut html, &mut block, Block::Paragraph);
+            if continuing {
+                html.push_str("<br>");
+            }
+            html.push_str(&format_inline(line.trim(), Inline::Html));
+        }
+    }
+
+    if in_code_block {
+        html.push_str("</code></pre>");
+    }
+    close_block(&mut html, &mut block);
+    html
+}
+
+/// Whether the text uses any Markdown formatting, i.e. whether a rich text
+/// version would differ from the plain text.
+pub fn has_markdown_formatting(text: &str) -> bool {
+    strip_markdown(text) != text.trim()
+        || text
+            .lines()
+            .any(|line| BULLET.is_match(line) || NUMBERED.is_match(line))
+}
+
+/// Escapes text for RTF. Non-ASCII characters use `\uN?` escapes with
+/// UTF-16 code units as signed 16-bit values.
+fn escape_rtf(text: &str, rtf: &mut String) {
+    let mut units = [0u16; 2];
+    for c in text.chars() {
+        match c {
+            '\\' | '{' | '}' => {
+                rtf.push('\\');
+                rtf.push(c);
+            }
+            '\n' => rtf.push_str("\\line "),
+            c if c.is_ascii() => rtf.push(c),
+            c => {
+                for unit in c.encode_utf16(&mut units) {
+                    rtf.push_str(&format!("\\u{}?", *unit as i16));
+                }
+            }
+        }
+    }
+}
+
+fn decode_html_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
+
+fn html_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!("{}=\"", name))? + name.len() + 2;
+    let end = tag[start..].find('"')? + start;
+    Some(&tag[start..end])
+}
+
+/// Converts Markdown to an RTF document for apps that prefer RTF over HTML
+/// on the clipboard. Works from the HTML produced by [`markdown_to_html`],
+/// which uses a small fixed set of tags.
+pub fn markdown_to_rtf(text: &str) -> String {
+    let html = markdown_to_html(text);
+    let mut rtf = String::from(
+        "{\\rtf1\\ansi\\deff0{\\fonttbl{\\f0\\fswiss Helvetica;}{\\f1\\fmodern Courier New;}}\\fs24\\sa120 ",
+    );
+    let mut list_counters: Vec<Option<usize>> = Vec::new();
+    let mut in_pre = false;
+    let mut rest = html.as_str();
+
+    while !rest.is_empty() {
+        let Some(open) = rest.find('<') else {
+            escape_rtf(&decode_html_entities(rest), &mut rtf);
+            break;
+        };
+        escape_rtf(&decode_html_entities(&rest[..open]), &mut rtf);
+        let Some(close) = rest[open..].find('>') else {
+            escape_rtf(&decode_html_entities(&rest[open..]), &mut rtf);
+            break;
+        };
+        let tag = &rest[open + 1..open + close];
+        rest = &rest[open + close + 1..];
+
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        match name {
+            "h1" => rtf.push_str("{\\b\\fs36 "),
+            "h2" => rtf.push_str("{\\b\\fs32 "),
+            "h3" => rtf.push_str("{\\b\\fs28 "),
+            "h4" | "h5" | "h6" => rtf.push_str("{\\b "),
+            "/h1" | "/h2" | "/h3" | "/h4" | "/h5" | "/h6" => rtf.push_str("}\\par "),
+            "/p" | "hr" => rtf.push_str("\\par "),
+            "br" => rtf.push_str("\\line "),
+            "ul" => list_counters.push(None),
+            "ol" => list_counters.push(Some(0)),
+            "/ul" | "/ol" => {
+                list_counters.pop();
+            }
+            "li" => match list_counters.last_mut() {
+                Some(Some(count)) => {
+                    *count += 1;
+                    rtf.push_str(&format!("\\li360 {}.\\tab ", count));
+                }
+                _ => rtf.push_str("\\li360 \\bullet\\tab "),
+            },
+            "/li" => rtf.push_str("\\par\\pard\\sa120 "),
+            "blockquote" => rtf.push_str("\\pard\\li720\\sa120 "),
+            "/blockquote" => rtf.push_str("\\par\\pard\\sa120 "),
+            "pre" => {
+                in_pre = true;
+                rtf.push_str("{\\f1 ");
+            }
+            "/pre" => {
+                in_pre = false;
+                rtf.push_str("}\\par ");
+            }
+            "code" | "/code" if in_pre => {}
+            "code" => rtf.push_str("{\\f1 "),
+            "strong" => rtf.push_str("{\\b "),
+            "em" => rtf.push_str("{\\i "),
+            "del" => rtf.push_str("{\\strike "),
+            "/code" | "/strong" | "/em" | "/del" => rtf.push('}'),
+            "a" => {
+                let href = decode_html_entities(html_attribute(tag, "href").unwrap_or_default());
+                rtf.push_str("{\\field{\\*\\fldinst{HYPERLINK \"");
+                escape_rtf(&href, &mut rtf);
+                rtf.push_str("\"}}{\\fldrslt{\\ul ");
+            }
+            "/a" => rtf.push_str("}}}"),
+            "img" => {
+                let alt = decode_html_entities(html_attribute(tag, "alt").unwrap_or_default());
+                escape_rtf(&alt, &mut rtf);
+            }
+            _ => {}
+        }
+    }
+
+    rtf.push('}');
+    rtf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_common_markdown() {
+        let text = "# Summary\n\nThis is **important** and *urgent*.\n\n- first item\n- see [docs](https://example.com)\n\n```\nlet x = 1;\n```";
+        assert_eq!(
+            strip_markdown(text),
+            "Summary\n\nThis is important and urgent.\n\n- first item\n- see docs (https://example.com)\n\nlet x = 1;"
+        );
+    }
+
+    #[test]
+    fn strip_keeps_identifiers_and_code_spans() {
+        assert_eq!(
+            strip_markdown("rename my_var_two to `new_name`"),
+            "rename my_var_two to new_name"
+        );
+        assert_eq!(strip_markdown("plain text."), "plain text.");
+    }
+
+    #[test]
+    fn detects_markdown_formatting() {
+        assert!(has_markdown_formatting("This is **important**."));
+        assert!(has_markdown_formatting("- one\n- two"));
+        assert!(!has_markdown_formatting("Just a plain sentence."));
+        assert!(!has_markdown_formatting("rename my_var_two please"));
+    }
+
+    #[test]
+    fn converts_markdown_to_rtf() {
+        let rtf = markdown_to_rtf("Use **bold** {here} caf\u{e9}\n\n- one");
+        assert!(rtf.starts_with("{\\rtf1"));
+        assert!(rtf.ends_with('}'));
+        assert!(rtf.contains("Use {\\b bold} \\{here\\} caf\\u233?"));
+        assert!(rtf.contains("\\bullet\\tab one\\par"));
+    }
+
+    #[test]
+    fn converts_markdown_to_html() {
+        let text = "## Notes\nUse **bold** & `a<b`\n\n1. one\n2. two";
+        assert_eq!(
+            markdown_to_html(text),
+            "<h2>Notes</h2><p>Use <strong>bold</strong> &amp; <code>a&lt;b</code></p><ol><li>one</li><li>two</li></ol>"
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! MCP (Model Context Protocol) server, so AI assistants and agents can use
+//! Handy for speech to text.
+//!
+//! It's served at `POST /mcp` on the local HTTP API, which answers each
+//! JSON-RPC message with a single JSON response. Clients that only speak
+//! stdio run `handy mcp`, which relays to that endpoint. Tools:
+//!
+//! - `transcribe_audio` transcribes an audio file on this machine
+//! - `get_recent_transcripts` lists the latest transcripts in history
+//! - `start_dictation` starts recording
+//! - `stop_dictation` stops and waits for the transcript
+
+use crate::events::AppEvent;
+use crate::managers::history::HistoryManager;
+use crate::signal_handle;
+use log::debug;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+
+/// Newest protocol revision this server implements
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+const SUPPORTED_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26", "2025-06-18"];
+
+/// Logged when an MCP client starts or stops dictation
+const SOURCE: &str = "MCP";
+
+const MAX_RECENT_TRANSCRIPTS: u64 = 100;
+
+/// How long `stop_dictation` waits for transcription and post-processing
+const DICTATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+// JSON-RPC error codes
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INVALID_REQUEST: i64 = -32600;
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "transcribe_audio",
+            "description": "Transcribe an audio file (WAV, MP3, M4A, FLAC or Ogg) on this machine with Handy's selected speech model.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Absolute path of the audio file" }
+                },
+                "required": ["path"]
+            }
+        },
+        {
+            "name": "get_recent_transcripts",
+            "description": "The latest transcripts in Handy's history, newest first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_RECENT_TRANSCRIPTS,
+                        "description": "How many to return, 10 by default"
+                    }
+                }
+            }
+        },
+        {
+            "name": "start_dictation",
+            "description": "Start recording from the microphone. Call stop_dictation to get the text.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "post_process": {
+                        "type": "boolean",
+                        "description": "Clean up the transcript with the selected post-processing prompt"
+                    }
+                }
+            }
+        },
+        {
+            "name": "stop_dictation",
+            "description": "Stop recording and return the transcript once it's ready. Handy also pastes it as usual.",
+            "inputSchema": { "type": "object", "properties": {} }
+        }
+    ])
+}
+
+fn error(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message.into() }
+    })
+}
+
+fn tool_result(result: Result<String, String>) -> Value {
+    let (text, is_error) = match result {
+        Ok(text) => (text, false),
+        Err(e) => (e, true),
+    };
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error
+    })
+}
+
+/// Answers one JSON-RPC message. Notifications get no answer. Tool calls go
+/// to `call_tool` with the tool name and its arguments.
+fn respond(
+    message: &Value,
+    call_tool: impl FnOnce(&str, &Value) -> Option<Result<String, String>>,
+) -> Option<Value> {
+    let Some(method) = message["method"].as_str() else {
+        return Some(error(Value::Null, INVALID_REQUEST, "Expected a request"));
+    };
+    // Requests carry an id, notifications don't
+    let id = message.get("id")?.clone();
+    let params = &message["params"];
+
+    let result = match method {
+        "initialize" => {
+            let requested = params["protocolVersion"].as_str().unwrap_or_default();
+            let version = if SUPPORTED_VERSIONS.contains(&requested) {
+                requested
+            } else {
+                PROTOCOL_VERSION
+            };
+            json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "handy", "version": env!("CARGO_PKG_VERSION") }
+            })
+        }
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tools() }),
+        "tools/call" => {
+            let name = params["name"].as_str().unwrap_or_default();
+            match call_tool(name, &params["arguments"]) {
+                Some(result) => tool_result(result),
+                None => {
+                    return Some(error(
+                        id,
+                        INVALID_PARAMS,
+                        format!("Unknown tool '{}'", name),
+                    ))
+                }
+            }
+        }
+        other => {
+            return Some(error(
+                id,
+                METHOD_NOT_FOUND,
+                format!("Unknown method '{}'", other),
+            ))
+        }
+    };
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+/// Answers one JSON-RPC message from a client
+pub fn handle_message(app: &AppHandle, message: &Value) -> Option<Value> {
+    respond(message, |name, arguments| {
+        debug!("MCP tool call: {}", name);
+        Some(match name {
+            "transcribe_audio" => match arguments["path"].as_str() {
+                Some(path) => signal_handle::transcribe_file(app, Path::new(path)),
+                None => Err("transcribe_audio needs a path".to_string()),
+            },
+            "get_recent_transcripts" => {
+                let limit = arguments["limit"]
+                    .as_u64()
+                    .unwrap_or(10)
+                    .clamp(1, MAX_RECENT_TRANSCRIPTS);
+                recent_transcripts(app, limit as usize)
+            }
+            "start_dictation" => {
+                let post_process = arguments["post_process"].as_bool().unwrap_or(false);
+                signal_handle::start_recording(app, post_process, SOURCE)
+                    .map(|()| "Recording started".to_string())
+            }
+            "stop_dictation" => stop_dictation(app),
+            _ => return None,
+        })
+    })
+}
+
+fn recent_transcripts(app: &AppHandle, limit: usize) -> Result<String, String> {
+    let entries = app
+        .state::<Arc<HistoryManager>>()
+        .get_recent_entries(limit)
+        .map_err(|e| format!("Failed to read history: {}", e))?;
+    let transcripts: Vec<Value> = entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "text": entry.post_processed_text.as_deref().unwrap_or(&entry.transcription_text),
+                "transcription_text": entry.transcription_text,
+                "timestamp": entry.timestamp,
+                "app_name": entry.target_app,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&transcripts).map_err(|e| e.to_string())
+}
+
+/// Stops recording and waits for the text, which arrives as app events
+fn stop_dictation(app: &AppHandle) -> Result<String, String> {
+    let (tx, rx) = mpsc::channel();
+    let listener = app.listen_any("app-event", move |event| {
+        if let Ok(event) = serde_json::from_str::<AppEvent>(event.payload()) {
+            let _ = tx.send(event);
+        }
+    });
+
+    let result = signal_handle::stop_recording(app, SOURCE).and_then(|()| loop {
+        match rx.recv_timeout(DICTATION_TIMEOUT) {
+            Ok(AppEvent::TranscriptionCompleted { text }) => break Ok(text),
+            Ok(AppEvent::Error { message, .. }) => break Err(message),
+            Ok(AppEvent::DictationEnded) => break Ok(String::new()),
+            Ok(_) => continue,
+            Err(_) => break Err("Timed out waiting for the transcript".to_string()),
+        }
+    });
+    app.unlisten(listener);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn respond_with(message: Value) -> Option<Value> {
+        respond(&message, |name, arguments| match name {
+            "echo" => Some(Ok(arguments["text"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string())),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn answers_requests() {
+        let initialized = respond_with(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": { "protocolVersion": "2024-11-05" }
+        }))
+        .unwrap();
+        assert_eq!(initialized["result"]["protocolVersion"], "2024-11-05");
+
+        assert_eq!(
+            respond_with(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })),
+            None
+        );
+
+        let called = respond_with(json!({
+            "jsonrpc": "2.0",
+            "id": "a",
+            "method": "tools/call",
+            "params": { "name": "echo", "arguments": { "text": "hi" } }
+        }))
+        .unwrap();
+        assert_eq!(called["id"], "a");
+        assert_eq!(called["result"]["content"][0]["text"], "hi");
+        assert_eq!(called["result"]["isError"], false);
+
+        let unknown = respond_with(json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": { "name": "rm" }
+        }))
+        .unwrap();
+        assert_eq!(unknown["error"]["code"], INVALID_PARAMS);
+    }
+}
@@ -0,0 +1,630 @@
+//! Offers to transcribe meetings as they start, from the system calendar
+//! (EventKit, macOS) and ICS feeds.
+//!
+//! While enabled, calendars are checked every minute. When a timed event
+//! starts in a calendar that counts and Handy isn't already recording, a
+//! `meeting_started` app event and a notification go out, and the frontend
+//! can call `start_meeting_transcription`. ICS feeds are fetched every 15
+//! minutes; their recurring events follow daily and weekly rules, without
+//! exceptions.
+
+use crate::events::{self, AppEvent};
+use crate::managers::audio::AudioRecordingManager;
+use crate::notifications::{notify, NotificationKind};
+use crate::settings::{get_settings, MeetingDetectionSettings};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+const FEED_REFRESH: Duration = Duration::from_secs(15 * 60);
+
+const FEED_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Set to stop the running watcher
+static STOP: Lazy<Mutex<Option<Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Debug, Clone, PartialEq)]
+struct Meeting {
+    title: String,
+    calendar: String,
+    start: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+}
+
+/// The parts of an `RRULE` that are followed
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<NaiveDateTime>,
+    by_day: Vec<Weekday>,
+}
+
+impl Rule {
+    fn parse(value: &str) -> Option<Self> {
+        let mut rule = Rule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+        };
+        let mut frequency = None;
+        for part in value.split(';') {
+            let (key, value) = part.split_once('=')?;
+            match key {
+                "FREQ" => {
+                    frequency = match value {
+                        "DAILY" => Some(Frequency::Daily),
+                        "WEEKLY" => Some(Frequency::Weekly),
+                        _ => return None,
+                    }
+                }
+                "INTERVAL" => rule.interval = value.parse().ok().filter(|i| *i > 0)?,
+                "COUNT" => rule.count = value.parse().ok(),
+                "UNTIL" => {
+                    rule.until = parse_time(value)
+                        .map(|(time, _)| time)
+                        .or_else(|| parse_date(value).and_then(|d| d.and_hms_opt(23, 59, 59)))
+                }
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .filter_map(|day| {
+                            match day.trim_start_matches(['+', '-', '0', '1', '2', '3', '4', '5']) {
+                                "MO" => Some(Weekday::Mon),
+                                "TU" => Some(Weekday::Tue),
+                                "WE" => Some(Weekday::Wed),
+                                "TH" => Some(Weekday::Thu),
+                                "FR" => Some(Weekday::Fri),
+                                "SA" => Some(Weekday::Sat),
+                                "SU" => Some(Weekday::Sun),
+                                _ => None,
+                            }
+                        })
+                        .collect()
+                }
+                _ => {}
+            }
+        }
+        rule.frequency = frequency?;
+        Some(rule)
+    }
+
+    /// Whether the event happens on `day`, for an event first on `first`
+    fn matches(&self, first: NaiveDate, day: NaiveDate) -> bool {
+        let interval = i64::from(self.interval);
+        match self.frequency {
+            Frequency::Daily => (day - first).num_days() % interval == 0,
+            Frequency::Weekly => {
+                let on_day = if self.by_day.is_empty() {
+                    day.weekday() == first.weekday()
+                } else {
+                    self.by_day.contains(&day.weekday())
+                };
+                let week_start = |date: NaiveDate| {
+                    date - chrono::Duration::days(date.weekday().num_days_from_monday().into())
+                };
+                on_day && ((week_start(day) - week_start(first)).num_days() / 7) % interval == 0
+            }
+        }
+    }
+}
+
+/// A timed event from an ICS feed
+#[derive(Debug, Clone, PartialEq)]
+struct IcsEvent {
+    title: String,
+    start: NaiveDateTime,
+    /// Whether `start` is UTC rather than local time
+    utc: bool,
+    rule: Option<Rule>,
+}
+
+impl IcsEvent {
+    fn to_utc(&self, time: NaiveDateTime) -> Option<DateTime<Utc>> {
+        if self.utc {
+            Some(Utc.from_utc_datetime(&time))
+        } else {
+            Local
+                .from_local_datetime(&time)
+                .earliest()
+                .map(|time| time.with_timezone(&Utc))
+        }
+    }
+
+    /// When the event starts in `from`..=`to`, exclusive of `from`
+    fn start_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let in_range = |start: DateTime<Utc>| start > from && start <= to;
+        let Some(rule) = &self.rule else {
+            return self.to_utc(self.start).filter(|start| in_range(*start));
+        };
+
+        let first = self.start.date();
+        // Without a count, days long before `from` can't matter
+        let mut day = match rule.count {
+            None => first.max(from.date_naive() - chrono::Duration::days(1)),
+            Some(_) => first,
+        };
+        let mut seen = 0;
+        loop {
+            let time = day.and_time(self.start.time());
+            if rule.until.is_some_and(|until| time > until) {
+                return None;
+            }
+            let start = self.to_utc(time);
+            if start.is_some_and(|start| start > to) {
+                return None;
+            }
+            if rule.matches(first, day) {
+                seen += 1;
+                if rule.count.is_some_and(|count| seen > count) {
+                    return None;
+                }
+                if let Some(start) = start.filter(|start| in_range(*start)) {
+                    return Some(start);
+                }
+            }
+            day = day.succ_opt()?;
+        }
+    }
+}
+
+/// An ICS feed's calendar name and timed events
+#[derive(Debug, Clone, PartialEq)]
+struct Feed {
+    name: Option<String>,
+    events: Vec<IcsEvent>,
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+/// Parses `20260101T100000Z` or a local `20260101T100000`
+fn parse_time(value: &str) -> Option<(NaiveDateTime, bool)> {
+    let time = NaiveDateTime::parse_from_str(value.get(..15)?, "%Y%m%dT%H%M%S").ok()?;
+    Some((time, value.ends_with('Z')))
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+/// Reads the timed events of an ICS feed. Times with a `TZID` are taken as
+/// local time and all-day events are left out.
+fn parse_ics(text: &str) -> Feed {
+    // Long lines are folded onto lines starting with a space or tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match line.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut feed = Feed {
+        name: None,
+        events: Vec::new(),
+    };
+    let mut title = String::new();
+    let mut start = None;
+    let mut rule = None;
+    let mut cancelled = false;
+    let mut in_event = false;
+    for line in &lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let property = name.split(';').next().unwrap_or_default();
+        match (in_event, property) {
+            (false, "X-WR-CALNAME") => feed.name = Some(unescape(value)),
+            (false, "BEGIN") if value == "VEVENT" => {
+                in_event = true;
+                title.clear();
+                start = None;
+                rule = None;
+                cancelled = false;
+            }
+            (true, "END") if value == "VEVENT" => {
+                in_event = false;
+                if let (Some((start, utc)), false) = (start.take(), cancelled) {
+                    feed.events.push(IcsEvent {
+                        title: title.clone(),
+                        start,
+                        utc,
+                        rule: rule.take(),
+                    });
+                }
+            }
+            (true, "SUMMARY") => title = unescape(value),
+            (true, "DTSTART") => start = parse_time(value),
+            (true, "RRULE") => rule = Rule::parse(value),
+            (true, "STATUS") => cancelled = value == "CANCELLED",
+            _ => {}
+        }
+    }
+    feed
+}
+
+/// Host of a feed, which is all of its address that's safe to show. The rest
+/// is often a secret that gives access to the calendar.
+fn feed_host(url: &str) -> String {
+    reqwest::Url::parse(url.trim())
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "calendar feed".to_string())
+}
+
+async fn fetch_feed(url: &str) -> Result<Feed, String> {
+    let host = feed_host(url);
+    let url = match url.trim().strip_prefix("webcal://") {
+        Some(rest) => format!("https://{}", rest),
+        None => url.trim().to_string(),
+    };
+    let text = reqwest::Client::new()
+        .get(&url)
+        .timeout(FEED_TIMEOUT)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| {
+            format!(
+                "Failed to fetch calendar from {}: {}",
+                host,
+                e.without_url()
+            )
+        })?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read calendar from {}: {}", host, e.without_url()))?;
+    Ok(parse_ics(&text))
+}
+
+/// Feeds of `urls` by URL, skipping the ones that fail
+fn fetch_feeds(urls: &[String]) -> Vec<(String, Feed)> {
+    urls.iter()
+        .filter(|url| !url.trim().is_empty())
+        .filter_map(
+            |url| match tauri::async_runtime::block_on(fetch_feed(url)) {
+                Ok(feed) => Some((url.clone(), feed)),
+                Err(e) => {
+                    warn!("{}", e);
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+fn feed_name(url: &str, feed: &Feed) -> String {
+    feed.name.clone().unwrap_or_else(|| feed_host(url))
+}
+
+#[cfg(target_os = "macos")]
+mod system {
+    use super::Meeting;
+    use chrono::{DateTime, TimeZone, Utc};
+    use serde::Deserialize;
+    use std::ffi::{c_char, CStr};
+
+    extern "C" {
+        fn handy_calendar_authorize() -> bool;
+        fn handy_calendar_events(from: f64, to: f64) -> *mut c_char;
+        fn handy_calendar_names() -> *mut c_char;
+        fn handy_calendar_free_string(value: *mut c_char);
+    }
+
+    #[derive(Deserialize)]
+    struct SystemEvent {
+        title: String,
+        calendar: String,
+        start: f64,
+    }
+
+    fn take_json(value: *mut c_char) -> Option<String> {
+        if value.is_null() {
+            return None;
+        }
+        let json = unsafe { CStr::from_ptr(value) }
+            .to_string_lossy()
+            .into_owned();
+        unsafe { handy_calendar_free_string(value) };
+        Some(json)
+    }
+
+    /// Asks for calendar access the first time, blocking until answered
+    pub fn authorize() -> bool {
+        unsafe { handy_calendar_authorize() }
+    }
+
+    pub fn meetings(from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<Meeting> {
+        let json = take_json(unsafe {
+            handy_calendar_events(from.timestamp() as f64, to.timestamp() as f64)
+        });
+        let events: Vec<SystemEvent> = json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        events
+            .into_iter()
+            .filter_map(|event| {
+                let start = Utc.timestamp_opt(event.start as i64, 0).single()?;
+                (start > from && start <= to).then_some(Meeting {
+                    title: event.title,
+                    calendar: event.calendar,
+                    start,
+                })
+            })
+            .collect()
+    }
+
+    pub fn calendar_names() -> Vec<String> {
+        take_json(unsafe { handy_calendar_names() })
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn system_meetings(
+    settings: &MeetingDetectionSettings,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<Meeting> {
+    if settings.system_calendar {
+        system::meetings(from, to)
+    } else {
+        Vec::new()
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn system_meetings(
+    _settings: &MeetingDetectionSettings,
+    _from: DateTime<Utc>,
+    _to: DateTime<Utc>,
+) -> Vec<Meeting> {
+    Vec::new()
+}
+
+#[cfg(target_os = "macos")]
+fn system_calendar_names(settings: &MeetingDetectionSettings) -> Vec<String> {
+    if settings.system_calendar {
+        system::calendar_names()
+    } else {
+        Vec::new()
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn system_calendar_names(_settings: &MeetingDetectionSettings) -> Vec<String> {
+    Vec::new()
+}
+
+/// Whether meetings from `calendar` count, which all do when none are chosen
+fn calendar_counts(settings: &MeetingDetectionSettings, calendar: &str) -> bool {
+    settings.calendars.is_empty() || settings.calendars.iter().any(|c| c == calendar)
+}
+
+/// Ends the polling thread and starts a new one with the current settings,
+/// unless detection is off
+pub fn restart(app: &AppHandle) {
+    let mut stop = STOP.lock().unwrap();
+    if let Some(running) = stop.take() {
+        running.store(true, Ordering::Relaxed);
+        debug!("Stopped meeting detection");
+    }
+
+    let settings = get_settings(app).meeting_detection;
+    if !settings.enabled {
+        return;
+    }
+    let stopped = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&stopped);
+    let app = app.clone();
+    thread::spawn(move || watch(&app, &flag));
+    *stop = Some(stopped);
+    info!("Watching calendars for meetings");
+}
+
+fn watch(app: &AppHandle, stopped: &AtomicBool) {
+    #[cfg(target_os = "macos")]
+    if get_settings(app).meeting_detection.system_calendar && !system::authorize() {
+        warn!("No calendar access, only ICS feeds are checked for meetings");
+    }
+
+    let mut feeds: Vec<(String, Feed)> = Vec::new();
+    let mut feed_urls: Vec<String> = Vec::new();
+    let mut fetched_at: Option<Instant> = None;
+    let mut announced: HashSet<(String, DateTime<Utc>)> = HashSet::new();
+    let mut last_check = Utc::now() - chrono::Duration::from_std(POLL_INTERVAL).unwrap_or_default();
+
+    while !stopped.load(Ordering::Relaxed) {
+        let settings = get_settings(app).meeting_detection;
+        if feed_urls != settings.ics_urls
+            || fetched_at.is_none_or(|at| at.elapsed() >= FEED_REFRESH)
+        {
+            feeds = fetch_feeds(&settings.ics_urls);
+            feed_urls = settings.ics_urls.clone();
+            fetched_at = Some(Instant::now());
+        }
+
+        let now = Utc::now();
+        let meetings: Vec<Meeting> = feeds
+            .iter()
+            .flat_map(|(url, feed)| {
+                feed.events.iter().filter_map(move |event| {
+                    event.start_between(last_check, now).map(|start| Meeting {
+                        title: event.title.clone(),
+                        calendar: feed_name(url, feed),
+                        start,
+                    })
+                })
+            })
+            .chain(system_meetings(&settings, last_check, now))
+            .collect();
+        last_check = now;
+
+        announced.retain(|(_, start)| now - *start < chrono::Duration::days(1));
+        for meeting in meetings {
+            if calendar_counts(&settings, &meeting.calendar)
+                && announced.insert((meeting.title.clone(), meeting.start))
+            {
+                announce(app, meeting);
+            }
+        }
+
+        let waited = Instant::now();
+        while waited.elapsed() < POLL_INTERVAL && !stopped.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_secs(1));
+        }
+    }
+    debug!("Meeting detection exited");
+}
+
+fn announce(app: &AppHandle, meeting: Meeting) {
+    if app.state::<Arc<AudioRecordingManager>>().is_recording() {
+        debug!("Meeting '{}' started while recording", meeting.title);
+        return;
+    }
+    info!("Meeting '{}' started", meeting.title);
+    let title = if meeting.title.is_empty() {
+        "A meeting".to_string()
+    } else {
+        meeting.title.clone()
+    };
+    notify(
+        app,
+        NotificationKind::MeetingStarted,
+        &format!("{} started. Open Handy to transcribe it.", title),
+        false,
+    );
+    events::emit(
+        app,
+        AppEvent::MeetingStarted {
+            title: meeting.title,
+            calendar: meeting.calendar,
+        },
+    );
+}
+
+/// Names of the calendars that can be chosen: the system's and each feed's
+pub async fn calendar_names(app: &AppHandle) -> Vec<String> {
+    let settings = get_settings(app).meeting_detection;
+    let mut names = system_calendar_names(&settings);
+    for url in settings
+        .ics_urls
+        .iter()
+        .filter(|url| !url.trim().is_empty())
+    {
+        match fetch_feed(url).await {
+            Ok(feed) => names.push(feed_name(url, &feed)),
+            Err(e) => warn!("{}", e),
+        }
+    }
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        Utc.from_utc_datetime(&parse_time(value).unwrap().0)
+    }
+
+    const FEED: &str = "BEGIN:VCALENDAR\r
+X-WR-CALNAME:Work\r
+BEGIN:VEVENT\r
+SUMMARY:Design review\\, weekly\r
+DTSTART:20260105T150000Z\r
+RRULE:FREQ=WEEKLY;BYDAY=MO,TH;COUNT=4\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Offsite\r
+DTSTART;VALUE=DATE:20260110\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Long\r
+  title\r
+DTSTART:20260106T090000Z\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn parses_feed() {
+        let feed = parse_ics(FEED);
+        assert_eq!(feed.name.as_deref(), Some("Work"));
+        assert_eq!(feed.events.len(), 2);
+        assert_eq!(feed.events[0].title, "Design review, weekly");
+        assert_eq!(feed.events[1].title, "Long title");
+        assert!(feed.events[1].utc);
+    }
+
+    #[test]
+    fn unnamed_feeds_are_named_by_host() {
+        let feed = parse_ics("BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n");
+        assert_eq!(
+            feed_name(
+                "webcal://calendar.example.com/private-abc123/basic.ics",
+                &feed
+            ),
+            "calendar.example.com"
+        );
+        assert_eq!(feed_name("not a url", &feed), "calendar feed");
+    }
+
+    #[test]
+    fn finds_recurring_starts() {
+        let review = &parse_ics(FEED).events[0];
+        // Thursday 8 January, the second occurrence
+        assert_eq!(
+            review.start_between(utc("20260108T145900Z"), utc("20260108T150000Z")),
+            Some(utc("20260108T150000Z"))
+        );
+        // Not on Tuesdays
+        assert_eq!(
+            review.start_between(utc("20260106T145900Z"), utc("20260106T150000Z")),
+            None
+        );
+        // Past the count of four: 5th, 8th, 12th and 15th
+        assert!(review
+            .start_between(utc("20260115T145900Z"), utc("20260115T150000Z"))
+            .is_some());
+        assert_eq!(
+            review.start_between(utc("20260119T145900Z"), utc("20260119T150000Z")),
+            None
+        );
+    }
+
+    #[test]
+    fn follows_daily_interval() {
+        let rule = Rule::parse("FREQ=DAILY;INTERVAL=2").unwrap();
+        let first = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        assert!(rule.matches(first, NaiveDate::from_ymd_opt(2026, 1, 3).unwrap()));
+        assert!(!rule.matches(first, NaiveDate::from_ymd_opt(2026, 1, 4).unwrap()));
+        assert_eq!(Rule::parse("FREQ=MONTHLY"), None);
+    }
+}
@@ -0,0 +1,182 @@
+//! Dictation state and transcripts published to an MQTT broker, for home
+//! automation like a "mic live" light or a log of dictations.
+//!
+//! - `<prefix>/state` is retained and is `idle`, `recording`, `transcribing`,
+//!   `post_processing`, or `offline` once Handy quits or loses the broker
+//! - `<prefix>/transcript` gets the same JSON as the `transcription_completed`
+//!   webhook for every dictation that isn't incognito
+//!
+//! The connection runs on its own thread and reconnects by itself.
+
+use crate::events::{AppEvent, DictationState};
+use crate::policy;
+use crate::secrets::{self, SecretKind};
+use crate::settings::get_settings;
+use crate::webhooks::TranscriptionPayload;
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use rumqttc::{Client, Event, LastWill, MqttOptions, Packet, QoS, Transport};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, EventId, Listener};
+
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// Wait before reconnecting after the broker drops or refuses us
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+const OFFLINE: &str = "offline";
+
+struct Connection {
+    client: Client,
+    state_topic: String,
+    transcript_topic: String,
+    stopped: Arc<AtomicBool>,
+    listener: EventId,
+}
+
+static CONNECTION: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| Mutex::new(None));
+
+/// Last state published, sent again whenever the connection comes back
+static STATE: Lazy<Mutex<DictationState>> = Lazy::new(|| Mutex::new(DictationState::Idle));
+
+fn publish_state(client: &Client, topic: &str, state: &str) {
+    if let Err(e) = client.try_publish(topic, QoS::AtLeastOnce, true, state) {
+        debug!("Failed to queue MQTT state: {}", e);
+    }
+}
+
+/// Disconnects if connected and connects again if enabled
+pub fn restart(app: &AppHandle) -> Result<(), String> {
+    let mut connection = CONNECTION.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(old) = connection.take() {
+        app.unlisten(old.listener);
+        old.stopped.store(true, Ordering::Relaxed);
+        publish_state(&old.client, &old.state_topic, OFFLINE);
+        let _ = old.client.disconnect();
+        info!("Disconnected from MQTT broker");
+    }
+
+    let settings = get_settings(app).mqtt;
+    if !settings.enabled {
+        return Ok(());
+    }
+    if !policy::allows_destination(settings.host.trim()) {
+        return Err("MQTT is disabled by policy".to_string());
+    }
+    let prefix = settings.topic_prefix.trim_end_matches('/');
+    let state_topic = format!("{}/state", prefix);
+
+    let mut options = MqttOptions::new(
+        format!("handy-{}", std::process::id()),
+        settings.host.trim(),
+        settings.port,
+    );
+    options.set_keep_alive(KEEP_ALIVE);
+    options.set_last_will(LastWill::new(&state_topic, OFFLINE, QoS::AtLeastOnce, true));
+    if let Some(username) = settings.username.as_deref().filter(|u| !u.is_empty()) {
+        let password =
+            secrets::get_secret(&secrets::secret_name(SecretKind::MqttPassword, "default"))
+                .unwrap_or_default();
+        options.set_credentials(username, password);
+    }
+    if settings.tls {
+        let transport = match settings
+            .ca_cert_path
+            .as_deref()
+            .filter(|p| !p.trim().is_empty())
+        {
+            Some(path) => {
+                let ca = std::fs::read(path)
+                    .map_err(|e| format!("Failed to read MQTT CA certificate {}: {}", path, e))?;
+                Transport::tls(ca, None, None)
+            }
+            None => Transport::tls_with_default_config(),
+        };
+        options.set_transport(transport);
+    }
+
+    let (client, mut events) = Client::new(options, 10);
+    let stopped = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&stopped);
+    let publisher = client.clone();
+    let topic = state_topic.clone();
+    let address = format!("{}:{}", settings.host.trim(), settings.port);
+    thread::spawn(move || {
+        for event in events.iter() {
+            if flag.load(Ordering::Relaxed) {
+                break;
+            }
+            match event {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    info!("Connected to MQTT broker at {}", address);
+                    publish_state(
+                        &publisher,
+                        &topic,
+                        STATE
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .as_str(),
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    warn!("MQTT connection to {} failed: {}", address, e);
+                    thread::sleep(RECONNECT_DELAY);
+                }
+            }
+        }
+        debug!("MQTT connection closed");
+    });
+
+    *STATE.lock().unwrap_or_else(PoisonError::into_inner) = DictationState::current();
+    let listener = app.listen_any("app-event", |event| {
+        let Ok(event) = serde_json::from_str::<AppEvent>(event.payload()) else {
+            return;
+        };
+        let Some(next) = DictationState::after(&event) else {
+            return;
+        };
+        *STATE.lock().unwrap_or_else(PoisonError::into_inner) = next;
+        if let Some(connection) = CONNECTION
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+        {
+            publish_state(&connection.client, &connection.state_topic, next.as_str());
+        }
+    });
+
+    *connection = Some(Connection {
+        client,
+        state_topic,
+        transcript_topic: format!("{}/transcript", prefix),
+        stopped,
+        listener,
+    });
+    Ok(())
+}
+
+/// Publishes a finished transcript if connected
+pub fn publish_transcription(payload: &TranscriptionPayload) {
+    let connection = CONNECTION.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(connection) = connection.as_ref() else {
+        return;
+    };
+    let body = match serde_json::to_vec(payload) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Failed to serialize MQTT transcript: {}", e);
+            return;
+        }
+    };
+    if let Err(e) =
+        connection
+            .client
+            .try_publish(&connection.transcript_topic, QoS::AtLeastOnce, false, body)
+    {
+        warn!("Failed to publish transcript to MQTT: {}", e);
+    }
+}
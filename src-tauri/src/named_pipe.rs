@@ -0,0 +1,162 @@
+//! Named pipe for scripts on Windows, e.g. AutoHotkey or PowerShell.
+//!
+//! When enabled, Handy listens on `\\.\pipe\handy`. Each line written to it
+//! is a command, answered with one line of JSON, the same as the HTTP API:
+//!
+//! - `start`, or `start post_process` to post-process
+//! - `stop` and `cancel`
+//! - `transcribe C:\path\to\note.m4a`, answered with the text
+//! - `result` for the most recent transcript in history
+//! - `copy` copies that transcript to the clipboard, if allowed like for the
+//!   HTTP API
+//!
+//! No port is opened and no token is needed: remote clients are refused and
+//! the pipe's default permissions only let other users read from it.
+
+use crate::api_server::{self, Route};
+use crate::settings::get_settings;
+use crate::signal_handle;
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::async_runtime::{self, JoinHandle};
+use tauri::AppHandle;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::windows::named_pipe::{NamedPipeServer, ServerOptions};
+
+pub const PIPE_NAME: &str = r"\\.\pipe\handy";
+
+/// Logged as the trigger for commands read from the pipe
+const SOURCE: &str = "named pipe";
+
+static LISTENER: Lazy<Mutex<Option<JoinHandle<()>>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Debug, PartialEq)]
+enum PipeCommand {
+    Run(Route),
+    Transcribe { path: PathBuf },
+}
+
+fn parse(line: &str) -> Result<PipeCommand, String> {
+    let line = line.trim();
+    let (name, argument) = line
+        .split_once(char::is_whitespace)
+        .map_or((line, ""), |(name, argument)| (name, argument.trim()));
+    let route = match name.to_ascii_lowercase().as_str() {
+        "start" => Route::StartRecording {
+            post_process: argument == "post_process",
+        },
+        "stop" => Route::StopRecording,
+        "cancel" => Route::CancelRecording,
+        "result" => Route::LastResult,
+        "copy" => Route::CopyLastResult,
+        "transcribe" if argument.is_empty() => {
+            return Err("transcribe needs a file path".to_string())
+        }
+        "transcribe" => {
+            return Ok(PipeCommand::Transcribe {
+                path: PathBuf::from(argument.trim_matches('"')),
+            })
+        }
+        other => return Err(format!("Unknown command '{}'", other)),
+    };
+    Ok(PipeCommand::Run(route))
+}
+
+/// Stops listening and starts again if the pipe is enabled
+pub fn restart(app: &AppHandle) {
+    let mut listener = LISTENER.lock().unwrap();
+    if let Some(running) = listener.take() {
+        running.abort();
+        info!("Stopped named pipe");
+    }
+
+    if !get_settings(app).api_server.named_pipe {
+        return;
+    }
+    let app = app.clone();
+    *listener = Some(async_runtime::spawn(async move {
+        if let Err(e) = listen(app).await {
+            warn!("Named pipe {} stopped: {}", PIPE_NAME, e);
+        }
+    }));
+}
+
+async fn listen(app: AppHandle) -> std::io::Result<()> {
+    // Refuse to start if another process already owns the name
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .reject_remote_clients(true)
+        .create(PIPE_NAME)?;
+    info!("Listening on {}", PIPE_NAME);
+
+    loop {
+        server.connect().await?;
+        let client = server;
+        server = ServerOptions::new()
+            .reject_remote_clients(true)
+            .create(PIPE_NAME)?;
+        async_runtime::spawn(serve(app.clone(), client));
+    }
+}
+
+async fn serve(app: AppHandle, client: NamedPipeServer) {
+    let (reader, mut writer) = tokio::io::split(client);
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let app = app.clone();
+        // Transcribing a file blocks until it's done
+        let reply = async_runtime::spawn_blocking(move || respond(&app, &line))
+            .await
+            .unwrap_or_else(|e| json!({ "error": e.to_string() }));
+        let mut reply = reply.to_string();
+        reply.push('\n');
+        if let Err(e) = writer.write_all(reply.as_bytes()).await {
+            debug!("Named pipe client went away: {}", e);
+            break;
+        }
+    }
+}
+
+fn respond(app: &AppHandle, line: &str) -> Value {
+    match parse(line) {
+        Ok(PipeCommand::Run(route)) => {
+            debug!("Named pipe command: {:?}", route);
+            api_server::run_command(app, route, SOURCE).1
+        }
+        Ok(PipeCommand::Transcribe { path }) => match signal_handle::transcribe_file(app, &path) {
+            Ok(text) => json!({ "text": text }),
+            Err(e) => json!({ "error": e }),
+        },
+        Err(e) => json!({ "error": e }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            parse("start post_process\r\n"),
+            Ok(PipeCommand::Run(Route::StartRecording {
+                post_process: true
+            }))
+        );
+        assert_eq!(parse("STOP"), Ok(PipeCommand::Run(Route::StopRecording)));
+        assert_eq!(
+            parse(r#"transcribe "C:\Users\me\note one.m4a""#),
+            Ok(PipeCommand::Transcribe {
+                path: PathBuf::from(r"C:\Users\me\note one.m4a")
+            })
+        );
+        assert!(parse("transcribe").is_err());
+        assert!(parse("rm").is_err());
+    }
+}
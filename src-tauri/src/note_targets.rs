@@ -0,0 +1,226 @@
+//! Sends transcripts into note apps, so a binding can dictate straight into
+//! an inbox note without focusing the app.
+//!
+//! - Obsidian, through the Advanced URI plugin, appends to a note in a vault
+//! - Apple Notes, through AppleScript, appends to a note it creates if needed
+//! - Notion appends paragraphs to a page with an integration token, stored
+//!   as the `notion_token` secret of the target
+
+use crate::policy;
+use crate::secrets::{self, SecretKind};
+use crate::settings::{NoteDestination, NoteTarget};
+use log::info;
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+const NOTION_API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+
+/// Longest text Notion accepts in one rich text object
+const NOTION_TEXT_LIMIT: usize = 2000;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+static CLIENT: Lazy<Client> = Lazy::new(|| {
+    Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+/// Appends `text` to the target's note
+pub async fn send(app: &AppHandle, target: &NoteTarget, text: &str) -> Result<(), String> {
+    let text = text.trim();
+    match &target.destination {
+        NoteDestination::Obsidian { vault, file_path } => app
+            .opener()
+            .open_url(obsidian_uri(vault, file_path, text), None::<String>)
+            .map_err(|e| format!("Failed to open Obsidian: {}", e))?,
+        NoteDestination::AppleNotes { note, folder } => {
+            append_to_apple_note(note, folder.as_deref(), text)?
+        }
+        NoteDestination::Notion { page_id } => {
+            if !policy::allows_destination(NOTION_API) {
+                return Err("Notion is disabled by policy".to_string());
+            }
+            let token =
+                secrets::get_secret(&secrets::secret_name(SecretKind::NotionToken, &target.id))
+                    .filter(|token| !token.is_empty())
+                    .ok_or_else(|| format!("No Notion token is set for '{}'", target.name))?;
+            append_to_notion_page(&token, page_id, text).await?
+        }
+    }
+    info!("Sent transcript to {}", target.name);
+    Ok(())
+}
+
+/// Percent-encodes like JavaScript's `encodeURIComponent`, which is how
+/// Obsidian decodes its URIs
+fn encode_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'!'
+            | b'~'
+            | b'*'
+            | b'\''
+            | b'('
+            | b')' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Advanced URI that appends `text` to a note without opening it
+fn obsidian_uri(vault: &str, file_path: &str, text: &str) -> String {
+    format!(
+        "obsidian://advanced-uri?vault={}&filepath={}&data={}&mode=append&openmode=silent",
+        encode_component(vault),
+        encode_component(file_path),
+        encode_component(&format!("\n{}", text))
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn append_to_apple_note(note: &str, folder: Option<&str>, text: &str) -> Result<(), String> {
+    use std::process::Command;
+
+    // Arguments are passed through argv so nothing in them is run as script
+    const SCRIPT: &str = r#"
+on run argv
+    set noteName to item 1 of argv
+    set folderName to item 2 of argv
+    set noteText to item 3 of argv
+    tell application "Notes"
+        if folderName is "" then
+            set targetFolder to default folder of default account
+        else
+            set targetFolder to folder folderName of default account
+        end if
+        if exists note noteName of targetFolder then
+            set targetNote to note noteName of targetFolder
+            set body of targetNote to (body of targetNote) & "<div>" & noteText & "</div>"
+        else
+            make new note at targetFolder with properties {name:noteName, body:"<h1>" & noteName & "</h1><div>" & noteText & "</div>"}
+        end if
+    end tell
+end run
+"#;
+    let output = Command::new("osascript")
+        .args([
+            "-e",
+            SCRIPT,
+            note,
+            folder.unwrap_or_default(),
+            &escape_html(text),
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to append to Apple Notes: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn append_to_apple_note(_note: &str, _folder: Option<&str>, _text: &str) -> Result<(), String> {
+    Err("Apple Notes is only available on macOS".to_string())
+}
+
+/// Apple Notes bodies are HTML
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br>")
+}
+
+/// One paragraph block per paragraph of `text`, split where Notion's text
+/// limit needs it
+fn notion_blocks(text: &str) -> Vec<Value> {
+    text.split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| {
+            let chars: Vec<char> = paragraph.chars().collect();
+            let rich_text: Vec<Value> = chars
+                .chunks(NOTION_TEXT_LIMIT)
+                .map(|chunk| {
+                    json!({ "type": "text", "text": { "content": chunk.iter().collect::<String>() } })
+                })
+                .collect();
+            json!({
+                "object": "block",
+                "type": "paragraph",
+                "paragraph": { "rich_text": rich_text }
+            })
+        })
+        .collect()
+}
+
+async fn append_to_notion_page(token: &str, page_id: &str, text: &str) -> Result<(), String> {
+    let response = CLIENT
+        .patch(format!("{}/blocks/{}/children", NOTION_API, page_id.trim()))
+        .bearer_auth(token)
+        .header("Notion-Version", NOTION_VERSION)
+        .json(&json!({ "children": notion_blocks(text) }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Notion: {}", e))?;
+    if response.status().is_success() {
+        return Ok(());
+    }
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or_default();
+    Err(format!(
+        "Notion refused the transcript ({}): {}",
+        status,
+        body["message"].as_str().unwrap_or("no reason given")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_obsidian_uri() {
+        assert_eq!(
+            obsidian_uri("My Vault", "Inbox/Ideas.md", "a & b"),
+            "obsidian://advanced-uri?vault=My%20Vault&filepath=Inbox%2FIdeas.md&data=%0Aa%20%26%20b&mode=append&openmode=silent"
+        );
+    }
+
+    #[test]
+    fn splits_notion_paragraphs() {
+        let long = "x".repeat(NOTION_TEXT_LIMIT + 1);
+        let blocks = notion_blocks(&format!("First\n\n\n{}", long));
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(
+            blocks[0]["paragraph"]["rich_text"][0]["text"]["content"],
+            "First"
+        );
+        assert_eq!(
+            blocks[1]["paragraph"]["rich_text"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
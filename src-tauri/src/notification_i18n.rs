@@ -0,0 +1,22 @@
+//! Notification internationalization
+//!
+//! Generated at compile time by build.rs from the "notifications" section of
+//! the frontend locale files, the same way as the tray menu strings (see
+//! `tray_i18n`).
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+// Include the auto-generated NotificationStrings struct and TRANSLATIONS static
+include!(concat!(env!("OUT_DIR"), "/notification_translations.rs"));
+
+/// Get localized notification strings for the app language
+pub fn get_notification_translations(locale: &str) -> NotificationStrings {
+    let lang = locale.split(['-', '_']).next().unwrap_or("en");
+
+    TRANSLATIONS
+        .get(lang)
+        .or_else(|| TRANSLATIONS.get("en"))
+        .cloned()
+        .expect("English translations must exist")
+}
@@ -0,0 +1,180 @@
+//! Native notifications for failed and finished transcriptions.
+//!
+//! Each kind can be switched off in the notification settings. Completions
+//! are only announced while the main window is hidden, since the result is
+//! already on screen otherwise.
+
+use crate::events::{self, ErrorSource};
+use crate::incognito;
+use crate::notification_i18n::{get_notification_translations, NotificationStrings};
+use crate::settings::{get_settings, NotificationSettings};
+use log::warn;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// Longest notification body before the first line is cut off
+const PREVIEW_CHARS: usize = 120;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationKind {
+    TranscriptionError,
+    /// Transcription timed out and only part of the recording was kept
+    TranscriptionPartial,
+    PostProcessError,
+    Completed,
+    /// macOS Secure Input is keeping shortcuts from Handy
+    SecureInput,
+    /// A recording couldn't start without microphone access
+    MicrophoneDenied,
+    /// Post-processing was put off until the provider can be reached
+    PostProcessQueued,
+    /// Post-processing put off while offline has finished
+    PostProcessRefined,
+    /// A calendar meeting started, see `meeting_detection`
+    MeetingStarted,
+    /// A transcription was copied to the clipboard instead of pasted
+    Copied,
+    /// A password field had focus, so the transcription wasn't pasted
+    PasswordFieldSkipped,
+    /// No text field had focus, so the transcription was copied instead
+    NoTextField,
+}
+
+impl NotificationKind {
+    fn title(self, strings: &NotificationStrings) -> &str {
+        match self {
+            Self::TranscriptionError => &strings.transcription_error,
+            Self::TranscriptionPartial => &strings.transcription_partial,
+            Self::PostProcessError => &strings.post_process_error,
+            Self::Completed => &strings.completed,
+            Self::SecureInput => &strings.secure_input,
+            Self::MicrophoneDenied => &strings.microphone_denied,
+            Self::PostProcessQueued => &strings.post_process_queued,
+            Self::PostProcessRefined => &strings.post_process_refined,
+            Self::MeetingStarted => &strings.meeting_started,
+            Self::Copied => &strings.copied,
+            Self::PasswordFieldSkipped => &strings.password_field_skipped,
+            Self::NoTextField => &strings.no_text_field,
+        }
+    }
+
+    fn error_source(self) -> Option<ErrorSource> {
+        match self {
+            Self::TranscriptionError | Self::TranscriptionPartial => {
+                Some(ErrorSource::Transcription)
+            }
+            Self::PostProcessError => Some(ErrorSource::PostProcess),
+            Self::MicrophoneDenied => Some(ErrorSource::Microphone),
+            Self::Completed
+            | Self::SecureInput
+            | Self::PostProcessQueued
+            | Self::PostProcessRefined
+            | Self::MeetingStarted
+            | Self::Copied
+            | Self::PasswordFieldSkipped
+            | Self::NoTextField => None,
+        }
+    }
+
+    fn is_enabled(self, settings: &NotificationSettings) -> bool {
+        match self {
+            Self::TranscriptionError | Self::TranscriptionPartial | Self::MicrophoneDenied => {
+                settings.transcription_errors
+            }
+            Self::PostProcessError | Self::PostProcessQueued | Self::PostProcessRefined => {
+                settings.post_process_errors
+            }
+            Self::Completed => settings.completions,
+            Self::SecureInput => settings.secure_input,
+            Self::Copied => settings.copied,
+            Self::PasswordFieldSkipped | Self::NoTextField => settings.focused_control,
+            // Turning on meeting detection asks for these
+            Self::MeetingStarted => true,
+        }
+    }
+}
+
+/// First line of `text`, cut to fit a notification body
+pub fn preview(text: &str) -> String {
+    let first_line = text.trim().lines().next().unwrap_or_default();
+    if first_line.chars().count() > PREVIEW_CHARS {
+        let cut: String = first_line.chars().take(PREVIEW_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        first_line.to_string()
+    }
+}
+
+pub fn show(app: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        warn!("Failed to show notification '{}': {}", title, e);
+    }
+}
+
+/// Shows a notification of `kind` if it's enabled. For completions `body` is
+/// the transcript, which is previewed unless the dictation was incognito.
+/// Errors are also sent as an `error` app event, even when not shown.
+pub fn notify(app: &AppHandle, kind: NotificationKind, body: &str, incognito: bool) {
+    if let Some(source) = kind.error_source() {
+        events::emit_error(app, source, body);
+    }
+    let settings = get_settings(app);
+    if !kind.is_enabled(&settings.notifications) {
+        return;
+    }
+    let strings = get_notification_translations(&settings.app_language);
+
+    if kind == NotificationKind::Completed {
+        let main_visible = app
+            .get_webview_window("main")
+            .and_then(|window| window.is_visible().ok())
+            .unwrap_or(false);
+        if main_visible {
+            return;
+        }
+        let body = if incognito || incognito::is_session_enabled() {
+            String::new()
+        } else {
+            preview(body)
+        };
+        show(app, kind.title(&strings), &body);
+    } else {
+        show(app, kind.title(&strings), body);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preview_keeps_first_line_and_truncates() {
+        assert_eq!(preview("  hello\nworld"), "hello");
+        let long = "word ".repeat(40);
+        let cut = preview(&long);
+        assert!(cut.ends_with('…'));
+        assert_eq!(cut.chars().count(), PREVIEW_CHARS);
+    }
+
+    #[test]
+    fn every_language_has_titles() {
+        for (lang, strings) in crate::notification_i18n::TRANSLATIONS.iter() {
+            for kind in [
+                NotificationKind::TranscriptionError,
+                NotificationKind::TranscriptionPartial,
+                NotificationKind::PostProcessError,
+                NotificationKind::Completed,
+                NotificationKind::SecureInput,
+                NotificationKind::MicrophoneDenied,
+                NotificationKind::PostProcessQueued,
+                NotificationKind::PostProcessRefined,
+                NotificationKind::MeetingStarted,
+                NotificationKind::Copied,
+                NotificationKind::PasswordFieldSkipped,
+                NotificationKind::NoTextField,
+            ] {
+                assert!(!kind.title(strings).is_empty(), "{:?} in {}", kind, lang);
+            }
+        }
+    }
+}
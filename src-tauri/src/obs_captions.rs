@@ -0,0 +1,252 @@
+//! Captions for OBS from dictation, for streamers.
+//!
+//! While enabled, each transcript is shown as soon as the model has it and
+//! replaced by the final text once post-processing is done. Captions go to a
+//! text file an OBS text source reads from, or through obs-websocket (v5)
+//! either to a text source or as stream captions. Incognito dictations are
+//! never shown. The `toggle_obs_captions` shortcut turns captions on and off.
+
+use crate::secrets::{self, SecretKind};
+use crate::settings::{self, ObsCaptionOutput, ObsCaptionSettings};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::net::TcpStream;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Captions to show, with the settings they were sent under. An empty text
+/// clears the caption.
+static WORKER: Lazy<Mutex<mpsc::Sender<(String, ObsCaptionSettings)>>> = Lazy::new(|| {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || run(rx));
+    Mutex::new(tx)
+});
+
+fn send(text: String, settings: ObsCaptionSettings) {
+    let _ = WORKER.lock().unwrap().send((text, settings));
+}
+
+/// Shows `text` as the caption if captions are on
+pub fn caption(app: &AppHandle, text: &str) {
+    let settings = settings::get_settings(app).obs_captions;
+    if settings.enabled && !text.trim().is_empty() {
+        send(text.to_string(), settings);
+    }
+}
+
+/// Turns captions on or off, clearing the caption when they go off
+pub fn toggle(app: &AppHandle) {
+    let mut settings = settings::get_settings(app);
+    settings.obs_captions.enabled = !settings.obs_captions.enabled;
+    let obs_captions = settings.obs_captions.clone();
+    settings::write_settings(app, settings);
+
+    info!(
+        "OBS captions turned {}",
+        if obs_captions.enabled { "on" } else { "off" }
+    );
+    if !obs_captions.enabled {
+        send(String::new(), obs_captions);
+    }
+}
+
+fn run(rx: mpsc::Receiver<(String, ObsCaptionSettings)>) {
+    let mut connection: Option<ObsConnection> = None;
+    let mut clear_at: Option<(Instant, ObsCaptionSettings)> = None;
+
+    loop {
+        let (text, settings) = match clear_at.take() {
+            Some((at, settings)) => {
+                match rx.recv_timeout(at.saturating_duration_since(Instant::now())) {
+                    Ok(caption) => caption,
+                    Err(mpsc::RecvTimeoutError::Timeout) => (String::new(), settings),
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            None => match rx.recv() {
+                Ok(caption) => caption,
+                Err(_) => return,
+            },
+        };
+
+        if !text.is_empty() && settings.clear_after_secs > 0 {
+            let at = Instant::now() + Duration::from_secs(settings.clear_after_secs.into());
+            clear_at = Some((at, settings.clone()));
+        }
+        if let Err(e) = show(&mut connection, &text, &settings) {
+            warn!("Failed to update OBS captions: {}", e);
+        }
+    }
+}
+
+fn show(
+    connection: &mut Option<ObsConnection>,
+    text: &str,
+    settings: &ObsCaptionSettings,
+) -> Result<(), String> {
+    match settings.output {
+        ObsCaptionOutput::TextFile => {
+            let path = settings
+                .file_path
+                .as_deref()
+                .filter(|path| !path.trim().is_empty())
+                .ok_or_else(|| "No caption file is set".to_string())?;
+            std::fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path, e))
+        }
+        ObsCaptionOutput::WebSocket => {
+            let (request_type, request_data) = match settings.text_source.as_deref() {
+                Some(source) if !source.trim().is_empty() => (
+                    "SetInputSettings",
+                    json!({ "inputName": source, "inputSettings": { "text": text } }),
+                ),
+                _ => ("SendStreamCaption", json!({ "captionText": text })),
+            };
+
+            let reusable = connection
+                .as_ref()
+                .is_some_and(|c| c.host == settings.host && c.port == settings.port);
+            if reusable {
+                let open = connection.as_mut().expect("checked above");
+                match open.request(request_type, request_data.clone()) {
+                    Ok(()) => return Ok(()),
+                    // OBS may have restarted, so try once more on a new connection
+                    Err(e) => debug!("OBS connection failed, reconnecting: {}", e),
+                }
+            }
+            *connection = None;
+            let open = connection.insert(ObsConnection::open(settings)?);
+            open.request(request_type, request_data)
+        }
+    }
+}
+
+/// Answer to obs-websocket's authentication challenge
+fn authentication(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = STANDARD.encode(Sha256::digest(format!("{}{}", password, salt)));
+    STANDARD.encode(Sha256::digest(format!("{}{}", secret, challenge)))
+}
+
+struct ObsConnection {
+    host: String,
+    port: u16,
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    next_request_id: u64,
+}
+
+impl ObsConnection {
+    /// Connects and identifies, with the `obs_password` secret if OBS asks
+    /// for one
+    fn open(settings: &ObsCaptionSettings) -> Result<Self, String> {
+        let url = format!("ws://{}:{}", settings.host, settings.port);
+        let (socket, _) = tungstenite::connect(&url)
+            .map_err(|e| format!("Failed to connect to OBS at {}: {}", url, e))?;
+        if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+            let _ = stream.set_read_timeout(Some(RESPONSE_TIMEOUT));
+        }
+        let mut connection = Self {
+            host: settings.host.clone(),
+            port: settings.port,
+            socket,
+            next_request_id: 0,
+        };
+
+        let hello = connection.receive(0)?;
+        let mut identify = json!({ "rpcVersion": 1, "eventSubscriptions": 0 });
+        let auth = &hello["authentication"];
+        if let (Some(salt), Some(challenge)) = (auth["salt"].as_str(), auth["challenge"].as_str()) {
+            let password =
+                secrets::get_secret(&secrets::secret_name(SecretKind::ObsPassword, "default"))
+                    .ok_or_else(|| "OBS asks for a password, but none is set".to_string())?;
+            identify["authentication"] = json!(authentication(&password, salt, challenge));
+        }
+        connection.send(1, identify)?;
+        connection
+            .receive(2)
+            .map_err(|e| format!("OBS refused the connection: {}", e))?;
+        debug!("Connected to OBS at {}", url);
+        Ok(connection)
+    }
+
+    fn send(&mut self, op: u8, data: Value) -> Result<(), String> {
+        let message = json!({ "op": op, "d": data }).to_string();
+        self.socket
+            .send(Message::text(message))
+            .map_err(|e| e.to_string())
+    }
+
+    /// Waits for the next message with opcode `op` and returns its data
+    fn receive(&mut self, op: u64) -> Result<Value, String> {
+        loop {
+            match self.socket.read().map_err(|e| e.to_string())? {
+                Message::Text(text) => {
+                    let mut message: Value =
+                        serde_json::from_str(&text).map_err(|e| e.to_string())?;
+                    if message["op"].as_u64() == Some(op) {
+                        return Ok(message["d"].take());
+                    }
+                }
+                Message::Close(frame) => {
+                    return Err(frame
+                        .map(|frame| frame.reason.to_string())
+                        .unwrap_or_else(|| "Connection closed".to_string()))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn request(&mut self, request_type: &str, request_data: Value) -> Result<(), String> {
+        self.next_request_id += 1;
+        let request_id = self.next_request_id.to_string();
+        self.send(
+            6,
+            json!({
+                "requestType": request_type,
+                "requestId": request_id,
+                "requestData": request_data,
+            }),
+        )?;
+        loop {
+            let response = self.receive(7)?;
+            if response["requestId"].as_str() != Some(request_id.as_str()) {
+                continue;
+            }
+            let status = &response["requestStatus"];
+            return if status["result"].as_bool() == Some(true) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "{} failed: {}",
+                    request_type,
+                    status["comment"].as_str().unwrap_or("no reason given")
+                ))
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_challenge() {
+        assert_eq!(
+            authentication(
+                "supersecretpassword",
+                "lM1GncleQOaCu9lT1yeUZhFYnqhsLLP1G5lAGo3ixaI=",
+                "+IxH4CnCiqpX1rM9scsNynZzbOe4KhDeYcTNS3PDaeY="
+            ),
+            "1Ct943GAT+6YQUUX47Ia/ncufilbe6+oD6lY+5kaCu4="
+        );
+    }
+}
@@ -1,10 +1,16 @@
+use crate::accessibility;
+use crate::events::{self, AppEvent};
 use crate::input;
+use crate::managers::audio::{MicLevel, RecordingTimer};
+use crate::notifications;
 use crate::settings;
 use crate::settings::OverlayPosition;
-use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize};
-
-#[cfg(not(target_os = "macos"))]
 use log::debug;
+use serde::Serialize;
+use specta::Type;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize};
 
 #[cfg(not(target_os = "macos"))]
 use tauri::WebviewWindowBuilder;
@@ -33,6 +39,44 @@ tauri_panel! {
 const OVERLAY_WIDTH: f64 = 172.0;
 const OVERLAY_HEIGHT: f64 = 36.0;
 
+const RESULT_HUD_WIDTH: f64 = 320.0;
+const RESULT_HUD_HEIGHT: f64 = 64.0;
+
+/// How long the result HUD stays up
+const RESULT_HUD_DURATION: Duration = Duration::from_millis(2500);
+
+/// Time the fade-out animation takes before the window is hidden
+const FADE_OUT: Duration = Duration::from_millis(300);
+
+/// Bumped whenever the overlay is shown or hidden, so a delayed hide only
+/// applies if nothing has been shown since
+static OVERLAY_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Generation the result HUD was shown at. The end of the dictation that
+/// showed it comes later and must not hide it.
+static RESULT_HUD_GENERATION: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// Whether a hide started at `generation` should go ahead: nothing has been
+/// shown since, and what's up isn't the result HUD
+fn should_hide(generation: u64, current: u64, result_hud: u64) -> bool {
+    generation == current && generation != result_hud
+}
+
+pub const MIN_OVERLAY_SCALE: f64 = 0.75;
+pub const MAX_OVERLAY_SCALE: f64 = 2.0;
+
+/// Overlay window size in logical pixels at the configured scale
+fn overlay_size(app_handle: &AppHandle) -> (f64, f64) {
+    let scale = overlay_scale(app_handle);
+    (OVERLAY_WIDTH * scale, OVERLAY_HEIGHT * scale)
+}
+
+fn overlay_scale(app_handle: &AppHandle) -> f64 {
+    settings::get_settings(app_handle)
+        .overlay_scale
+        .clamp(MIN_OVERLAY_SCALE, MAX_OVERLAY_SCALE)
+}
+
 #[cfg(target_os = "macos")]
 const OVERLAY_TOP_OFFSET: f64 = 46.0;
 #[cfg(any(target_os = "windows", target_os = "linux"))]
@@ -44,6 +88,9 @@ const OVERLAY_BOTTOM_OFFSET: f64 = 15.0;
 #[cfg(any(target_os = "windows", target_os = "linux"))]
 const OVERLAY_BOTTOM_OFFSET: f64 = 40.0;
 
+/// Space between the caret and the overlay
+const OVERLAY_CARET_GAP: f64 = 8.0;
+
 #[cfg(target_os = "linux")]
 fn update_gtk_layer_shell_anchors(overlay_window: &tauri::webview::WebviewWindow) {
     let window_clone = overlay_window.clone();
@@ -56,7 +103,9 @@ fn update_gtk_layer_shell_anchors(overlay_window: &tauri::webview::WebviewWindow
                     gtk_window.set_anchor(Edge::Top, true);
                     gtk_window.set_anchor(Edge::Bottom, false);
                 }
-                OverlayPosition::Bottom | OverlayPosition::None => {
+                // Layer shell surfaces can't be placed at arbitrary
+                // positions, so the caret option stays at the bottom
+                OverlayPosition::Bottom | OverlayPosition::Caret | OverlayPosition::None => {
                     gtk_window.set_anchor(Edge::Bottom, true);
                     gtk_window.set_anchor(Edge::Top, false);
                 }
@@ -133,6 +182,60 @@ fn force_overlay_topmost(overlay_window: &tauri::webview::WebviewWindow) {
     });
 }
 
+/// A connected monitor as listed in the overlay settings
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct MonitorInfo {
+    /// Stored in `overlay_monitor` to pin the overlay to this monitor
+    pub id: String,
+    pub width: u32,
+    pub height: u32,
+    pub is_primary: bool,
+}
+
+/// Identifies a monitor by its name, or by its size and position for
+/// monitors the system doesn't name
+fn monitor_id(monitor: &tauri::Monitor) -> String {
+    monitor.name().cloned().unwrap_or_else(|| {
+        let (size, position) = (monitor.size(), monitor.position());
+        format!(
+            "{}x{}@{},{}",
+            size.width, size.height, position.x, position.y
+        )
+    })
+}
+
+pub fn list_monitors(app_handle: &AppHandle) -> Vec<MonitorInfo> {
+    let primary_id = app_handle
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map(|monitor| monitor_id(&monitor));
+
+    app_handle
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            let id = monitor_id(monitor);
+            MonitorInfo {
+                is_primary: primary_id.as_ref() == Some(&id),
+                id,
+                width: monitor.size().width,
+                height: monitor.size().height,
+            }
+        })
+        .collect()
+}
+
+/// The monitor the overlay is pinned to, if it's connected
+fn get_pinned_monitor(app_handle: &AppHandle, id: &str) -> Option<tauri::Monitor> {
+    app_handle
+        .available_monitors()
+        .ok()?
+        .into_iter()
+        .find(|monitor| monitor_id(monitor) == id)
+}
+
 fn get_monitor_with_cursor(app_handle: &AppHandle) -> Option<tauri::Monitor> {
     if let Some(mouse_location) = input::get_cursor_position(app_handle) {
         if let Ok(monitors) = app_handle.available_monitors() {
@@ -170,8 +273,88 @@ fn is_mouse_within_monitor(
         && mouse_y < (monitor_y + monitor_height as i32)
 }
 
+/// Places the overlay centered below the caret, or above it when there isn't
+/// room, keeping it on the caret's monitor. Returns `None` when the caret
+/// can't be found.
+fn calculate_caret_position(
+    app_handle: &AppHandle,
+    (overlay_width, overlay_height): (f64, f64),
+) -> Option<(f64, f64)> {
+    let caret = accessibility::caret_bounds()?;
+
+    // Caret bounds are physical pixels on Windows and points on macOS
+    let to_physical = |monitor: &tauri::Monitor| {
+        if cfg!(target_os = "windows") {
+            1.0
+        } else {
+            monitor.scale_factor()
+        }
+    };
+    let monitor = app_handle
+        .available_monitors()
+        .ok()?
+        .into_iter()
+        .find(|monitor| {
+            let scale = to_physical(monitor);
+            is_mouse_within_monitor(
+                ((caret.x * scale) as i32, (caret.y * scale) as i32),
+                monitor.position(),
+                monitor.size(),
+            )
+        })?;
+
+    let scale = monitor.scale_factor();
+    let to_logical = to_physical(&monitor) / scale;
+    let caret_x = (caret.x + caret.width / 2.0) * to_logical;
+    let caret_top = caret.y * to_logical;
+    let caret_bottom = (caret.y + caret.height) * to_logical;
+
+    let work_area = monitor.work_area();
+    let work_area_width = work_area.size.width as f64 / scale;
+    let work_area_height = work_area.size.height as f64 / scale;
+    let work_area_x = work_area.position.x as f64 / scale;
+    let work_area_y = work_area.position.y as f64 / scale;
+
+    let x = (caret_x - overlay_width / 2.0)
+        .min(work_area_x + work_area_width - overlay_width)
+        .max(work_area_x);
+    let below = caret_bottom + OVERLAY_CARET_GAP;
+    let y = if below + overlay_height <= work_area_y + work_area_height {
+        below
+    } else {
+        (caret_top - OVERLAY_CARET_GAP - overlay_height).max(work_area_y)
+    };
+
+    Some((x, y))
+}
+
 fn calculate_overlay_position(app_handle: &AppHandle) -> Option<(f64, f64)> {
-    if let Some(monitor) = get_monitor_with_cursor(app_handle) {
+    calculate_position_for_size(app_handle, overlay_size(app_handle))
+}
+
+fn calculate_position_for_size(
+    app_handle: &AppHandle,
+    (overlay_width, overlay_height): (f64, f64),
+) -> Option<(f64, f64)> {
+    let settings = settings::get_settings(app_handle);
+
+    if settings.overlay_position == OverlayPosition::Caret {
+        if let Some(position) =
+            calculate_caret_position(app_handle, (overlay_width, overlay_height))
+        {
+            return Some(position);
+        }
+        debug!("Caret position unavailable, showing overlay at the bottom");
+    }
+
+    // A pinned monitor that isn't connected falls back to the cursor's
+    let monitor = settings
+        .overlay_monitor
+        .as_deref()
+        .and_then(|id| get_pinned_monitor(app_handle, id))
+        .or_else(|| get_monitor_with_cursor(app_handle));
+
+    if let Some(monitor) = monitor {
         let work_area = monitor.work_area();
         let scale = monitor.scale_factor();
         let work_area_width = work_area.size.width as f64 / scale;
@@ -179,13 +362,11 @@ fn calculate_overlay_position(app_handle: &AppHandle) -> Option<(f64, f64)> {
         let work_area_x = work_area.position.x as f64 / scale;
         let work_area_y = work_area.position.y as f64 / scale;
 
-        let settings = settings::get_settings(app_handle);
-
-        let x = work_area_x + (work_area_width - OVERLAY_WIDTH) / 2.0;
+        let x = work_area_x + (work_area_width - overlay_width) / 2.0;
         let y = match settings.overlay_position {
             OverlayPosition::Top => work_area_y + OVERLAY_TOP_OFFSET,
-            OverlayPosition::Bottom | OverlayPosition::None => {
-                work_area_y + work_area_height - OVERLAY_HEIGHT - OVERLAY_BOTTOM_OFFSET
+            OverlayPosition::Bottom | OverlayPosition::Caret | OverlayPosition::None => {
+                work_area_y + work_area_height - overlay_height - OVERLAY_BOTTOM_OFFSET
             }
         };
 
@@ -198,6 +379,7 @@ fn calculate_overlay_position(app_handle: &AppHandle) -> Option<(f64, f64)> {
 #[cfg(not(target_os = "macos"))]
 pub fn create_recording_overlay(app_handle: &AppHandle) {
     let position = calculate_overlay_position(app_handle);
+    let (width, height) = overlay_size(app_handle);
 
     // On Linux (Wayland), monitor detection often fails, but we don't need exact coordinates
     // for Layer Shell as we use anchors. On other platforms, we require a position.
@@ -214,7 +396,7 @@ pub fn create_recording_overlay(app_handle: &AppHandle) {
     )
     .title("Recording")
     .resizable(false)
-    .inner_size(OVERLAY_WIDTH, OVERLAY_HEIGHT)
+    .inner_size(width, height)
     .shadow(false)
     .maximizable(false)
     .minimizable(false)
@@ -254,6 +436,7 @@ pub fn create_recording_overlay(app_handle: &AppHandle) {
 /// Creates the recording overlay panel and keeps it hidden by default (macOS)
 #[cfg(target_os = "macos")]
 pub fn create_recording_overlay(app_handle: &AppHandle) {
+    let (width, height) = overlay_size(app_handle);
     if let Some((x, y)) = calculate_overlay_position(app_handle) {
         // PanelBuilder creates a Tauri window then converts it to NSPanel.
         // The window remains registered, so get_webview_window() still works.
@@ -262,10 +445,7 @@ pub fn create_recording_overlay(app_handle: &AppHandle) {
             .title("Recording")
             .position(tauri::Position::Logical(tauri::LogicalPosition { x, y }))
             .level(PanelLevel::Status)
-            .size(tauri::Size::Logical(tauri::LogicalSize {
-                width: OVERLAY_WIDTH,
-                height: OVERLAY_HEIGHT,
-            }))
+            .size(tauri::Size::Logical(tauri::LogicalSize { width, height }))
             .has_shadow(false)
             .transparent(true)
             .no_activate(true)
@@ -288,7 +468,19 @@ pub fn create_recording_overlay(app_handle: &AppHandle) {
     }
 }
 
-fn show_overlay_state(app_handle: &AppHandle, state: &str) {
+/// Sends the appearance settings to the overlay
+pub fn emit_overlay_appearance(app_handle: &AppHandle) {
+    if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
+        let appearance = settings::get_settings(app_handle).overlay_appearance;
+        let _ = overlay_window.emit("overlay-appearance", appearance);
+    }
+}
+
+fn show_overlay_state(app_handle: &AppHandle, event: AppEvent) {
+    OVERLAY_GENERATION.fetch_add(1, Ordering::SeqCst);
+    // The overlay follows the same events as everyone else
+    events::emit(app_handle, event);
+
     // Check if overlay should be shown based on position setting
     let settings = settings::get_settings(app_handle);
     if settings.overlay_position == OverlayPosition::None {
@@ -304,26 +496,27 @@ fn show_overlay_state(app_handle: &AppHandle, state: &str) {
         #[cfg(target_os = "windows")]
         force_overlay_topmost(&overlay_window);
 
-        let _ = overlay_window.emit("show-overlay", state);
+        // Sent on every show since the webview may have loaded after a change
+        emit_overlay_appearance(app_handle);
     }
 }
 
 /// Shows the recording overlay window with fade-in animation
 pub fn show_recording_overlay(app_handle: &AppHandle) {
-    show_overlay_state(app_handle, "recording");
+    show_overlay_state(app_handle, AppEvent::RecordingStarted);
 }
 
 /// Shows the transcribing overlay window
 pub fn show_transcribing_overlay(app_handle: &AppHandle) {
-    show_overlay_state(app_handle, "transcribing");
+    show_overlay_state(app_handle, AppEvent::TranscriptionStarted);
 }
 
 /// Shows the processing overlay window
 pub fn show_processing_overlay(app_handle: &AppHandle) {
-    show_overlay_state(app_handle, "processing");
+    show_overlay_state(app_handle, AppEvent::PostProcessingStarted);
 }
 
-/// Updates the overlay window position based on current settings
+/// Updates the overlay window size and position based on current settings
 pub fn update_overlay_position(app_handle: &AppHandle) {
     if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
         #[cfg(target_os = "linux")]
@@ -331,6 +524,11 @@ pub fn update_overlay_position(app_handle: &AppHandle) {
             update_gtk_layer_shell_anchors(&overlay_window);
         }
 
+        // The content is laid out for 1x, so zoom it to fill the window
+        let (width, height) = overlay_size(app_handle);
+        let _ = overlay_window.set_size(tauri::Size::Logical(tauri::LogicalSize { width, height }));
+        let _ = overlay_window.set_zoom(overlay_scale(app_handle));
+
         if let Some((x, y)) = calculate_overlay_position(app_handle) {
             let _ = overlay_window
                 .set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }));
@@ -340,26 +538,92 @@ pub fn update_overlay_position(app_handle: &AppHandle) {
 
 /// Hides the recording overlay window with fade-out animation
 pub fn hide_recording_overlay(app_handle: &AppHandle) {
+    let generation = OVERLAY_GENERATION.load(Ordering::SeqCst);
+    // Triggers the fade-out animation
+    events::emit(app_handle, AppEvent::DictationEnded);
+
     // Always hide the overlay regardless of settings - if setting was changed while recording,
     // we still want to hide it properly
     if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
-        // Emit event to trigger fade-out animation
-        let _ = overlay_window.emit("hide-overlay", ());
-        // Hide the window after a short delay to allow animation to complete
+        // Hide the window after a short delay to allow animation to complete,
+        // unless it's been shown again or is the result HUD, which hides itself
         let window_clone = overlay_window.clone();
         std::thread::spawn(move || {
-            std::thread::sleep(std::time::Duration::from_millis(300));
-            let _ = window_clone.hide();
+            std::thread::sleep(FADE_OUT);
+            if should_hide(
+                generation,
+                OVERLAY_GENERATION.load(Ordering::SeqCst),
+                RESULT_HUD_GENERATION.load(Ordering::SeqCst),
+            ) {
+                let _ = window_clone.hide();
+            }
         });
     }
 }
 
-pub fn emit_levels(app_handle: &AppHandle, levels: &Vec<f32>) {
-    // emit levels to main app
-    let _ = app_handle.emit("mic-level", levels);
+/// Shows the final text in the overlay for a moment, marked as copied, for
+/// copy-only pastes. `text` is empty for incognito dictations. The overlay
+/// window grows for it and goes back to its size on the next recording.
+pub fn show_result_hud(app_handle: &AppHandle, text: &str) {
+    let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") else {
+        return;
+    };
+    let generation = OVERLAY_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    RESULT_HUD_GENERATION.store(generation, Ordering::SeqCst);
+
+    let scale = overlay_scale(app_handle);
+    let size = (RESULT_HUD_WIDTH * scale, RESULT_HUD_HEIGHT * scale);
+    let _ = overlay_window.set_size(tauri::Size::Logical(tauri::LogicalSize {
+        width: size.0,
+        height: size.1,
+    }));
+    let _ = overlay_window.set_zoom(scale);
+    if let Some((x, y)) = calculate_position_for_size(app_handle, size) {
+        let _ =
+            overlay_window.set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }));
+    }
+
+    emit_overlay_appearance(app_handle);
+    let _ = overlay_window.emit("result-hud", Some(notifications::preview(text)));
+    let _ = overlay_window.show();
+    #[cfg(target_os = "windows")]
+    force_overlay_topmost(&overlay_window);
 
-    // also emit to the recording overlay if it's open
-    if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
-        let _ = overlay_window.emit("mic-level", levels);
+    std::thread::spawn(move || {
+        std::thread::sleep(RESULT_HUD_DURATION);
+        if OVERLAY_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let _ = overlay_window.emit("result-hud", None::<String>);
+        std::thread::sleep(FADE_OUT);
+        if OVERLAY_GENERATION.load(Ordering::SeqCst) == generation {
+            let _ = overlay_window.hide();
+        }
+    });
+}
+
+/// Sends elapsed recording time
+pub fn emit_recording_timer(app_handle: &AppHandle, timer: &RecordingTimer) {
+    events::emit(app_handle, AppEvent::RecordingTimer(timer.clone()));
+}
+
+pub fn emit_levels(app_handle: &AppHandle, level: &MicLevel) {
+    events::emit(app_handle, AppEvent::MicLevel(level.clone()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dictation_end_leaves_the_result_hud_up() {
+        // Recording overlay shown, then hidden with nothing in between
+        assert!(should_hide(1, 1, u64::MAX));
+        // Shown again for the next recording before the fade-out finished
+        assert!(!should_hide(1, 2, u64::MAX));
+        // Copy-only paste showed the HUD, then the dictation went idle
+        assert!(!should_hide(2, 2, 2));
+        // A later dictation isn't held up by the old HUD
+        assert!(should_hide(3, 3, 2));
     }
 }
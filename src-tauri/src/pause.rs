@@ -0,0 +1,35 @@
+//! Pausing Handy.
+//!
+//! While paused every shortcut is unregistered, so keys that clash with a
+//! game or a screen share reach the focused app, and the tray shows a paused
+//! icon. Like session incognito the state is never persisted, so Handy
+//! always starts unpaused.
+
+use crate::dictation;
+use crate::events::{self, AppEvent};
+use crate::shortcut;
+use log::info;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::AppHandle;
+
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::Relaxed)
+}
+
+/// Pauses or resumes Handy, then refreshes the tray and notifies the
+/// frontend with a `PauseChanged` event.
+pub fn set_paused(app: &AppHandle, paused: bool) {
+    if PAUSED.swap(paused, Ordering::Relaxed) == paused {
+        return;
+    }
+    if paused {
+        shortcut::unregister_all(app);
+    } else {
+        shortcut::register_all(app);
+    }
+    info!("Handy {}", if paused { "paused" } else { "resumed" });
+    dictation::refresh_tray(app);
+    events::emit(app, AppEvent::PauseChanged { paused });
+}
@@ -0,0 +1,213 @@
+//! Managed policy for enterprise deployments.
+//!
+//! An administrator can place a read-only `policy.json` in a system location
+//! to force settings. Settings under `settings` are merged over the user's
+//! settings whenever they're read and locked: changes to them are never
+//! saved, so the user's own values come back if the policy is removed.
+//!
+//! ```json
+//! {
+//!   "settings": { "history_limit": 0, "update_checks_enabled": false },
+//!   "disable_cloud_providers": true,
+//!   "force_incognito": true
+//! }
+//! ```
+
+use crate::incognito;
+use crate::settings::{get_default_settings, AppSettings, PostProcessProvider};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use specta::Type;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Policy {
+    /// Forced values of top-level settings, by key
+    #[serde(default)]
+    pub settings: Map<String, Value>,
+    /// Transcripts stay on this machine: post-processing only runs with
+    /// local providers, and webhooks, HTTP actions, MQTT, email and Notion
+    /// only reach this machine
+    #[serde(default)]
+    pub disable_cloud_providers: bool,
+    /// Every dictation is incognito, so nothing is saved to history
+    #[serde(default)]
+    pub force_incognito: bool,
+}
+
+/// What the policy enforces, for the settings UI
+#[derive(Serialize, Debug, Clone, Type)]
+pub struct PolicyStatus {
+    /// Policy file in use, if any
+    pub path: Option<String>,
+    pub locked_settings: Vec<String>,
+    pub disable_cloud_providers: bool,
+    pub force_incognito: bool,
+}
+
+fn policy_path() -> PathBuf {
+    #[cfg(target_os = "macos")]
+    let path = PathBuf::from("/Library/Application Support/Handy/policy.json");
+    #[cfg(target_os = "windows")]
+    let path =
+        PathBuf::from(std::env::var_os("ProgramData").unwrap_or_else(|| "C:\\ProgramData".into()))
+            .join("Handy")
+            .join("policy.json");
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let path = PathBuf::from("/etc/handy/policy.json");
+    path
+}
+
+/// The policy file is read once; a changed policy applies after a restart
+static POLICY: Lazy<Option<Policy>> = Lazy::new(|| {
+    let path = policy_path();
+    let contents = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str::<Policy>(&contents) {
+        Ok(policy) => {
+            info!("Using managed policy from {}", path.display());
+            Some(validated(policy))
+        }
+        Err(e) => {
+            warn!("Ignoring invalid policy file {}: {}", path.display(), e);
+            None
+        }
+    }
+});
+
+/// Drops forced settings that aren't settings or don't have a valid value
+fn validated(mut policy: Policy) -> Policy {
+    let defaults = serde_json::to_value(get_default_settings()).unwrap();
+    policy.settings.retain(|key, value| {
+        let mut merged = defaults.clone();
+        let valid = match merged.get_mut(key) {
+            Some(setting) => {
+                *setting = value.clone();
+                serde_json::from_value::<AppSettings>(merged).is_ok()
+            }
+            None => false,
+        };
+        if !valid {
+            warn!("Ignoring invalid policy setting '{}'", key);
+        }
+        valid
+    });
+    policy
+}
+
+fn apply_policy(policy: &Policy, settings: AppSettings) -> AppSettings {
+    if policy.settings.is_empty() {
+        return settings;
+    }
+    let mut value = serde_json::to_value(&settings).unwrap();
+    if let Some(object) = value.as_object_mut() {
+        for (key, forced) in &policy.settings {
+            object.insert(key.clone(), forced.clone());
+        }
+    }
+    serde_json::from_value(value).unwrap_or(settings)
+}
+
+fn strip_policy(policy: &Policy, value: &mut Value, stored: Option<&Value>) {
+    let Some(object) = value.as_object_mut() else {
+        return;
+    };
+    for key in policy.settings.keys() {
+        match stored.and_then(|stored| stored.get(key)) {
+            Some(stored_value) => object.insert(key.clone(), stored_value.clone()),
+            None => object.remove(key),
+        };
+    }
+}
+
+/// Merges the policy's forced settings over `settings`
+pub fn apply(settings: AppSettings) -> AppSettings {
+    match POLICY.as_ref() {
+        Some(policy) => apply_policy(policy, settings),
+        None => settings,
+    }
+}
+
+/// Puts back the stored value of each locked setting, so forced values are
+/// never saved
+pub fn strip_locked(value: &mut Value, stored: Option<&Value>) {
+    if let Some(policy) = POLICY.as_ref() {
+        strip_policy(policy, value, stored);
+    }
+}
+
+pub fn forces_incognito() -> bool {
+    POLICY.as_ref().is_some_and(|policy| policy.force_incognito)
+}
+
+fn keeps_transcripts_local() -> bool {
+    POLICY
+        .as_ref()
+        .is_some_and(|policy| policy.disable_cloud_providers)
+}
+
+/// Whether the policy lets transcripts be sent to `provider`
+pub fn allows_provider(provider: &PostProcessProvider) -> bool {
+    !keeps_transcripts_local() || incognito::is_local_provider(provider)
+}
+
+/// Whether the policy lets transcripts be sent to `url`, or to a bare host
+pub fn allows_destination(url: &str) -> bool {
+    let allowed = !keeps_transcripts_local() || incognito::is_loopback_url(url);
+    if !allowed {
+        warn!(
+            "Not sending to {}, cloud services are disabled by policy",
+            url
+        );
+    }
+    allowed
+}
+
+pub fn status() -> PolicyStatus {
+    let policy = POLICY.as_ref();
+    let mut locked_settings: Vec<String> = policy
+        .map(|policy| policy.settings.keys().cloned().collect())
+        .unwrap_or_default();
+    locked_settings.sort();
+    PolicyStatus {
+        path: policy.map(|_| policy_path().to_string_lossy().to_string()),
+        locked_settings,
+        disable_cloud_providers: policy.is_some_and(|policy| policy.disable_cloud_providers),
+        force_incognito: policy.is_some_and(|policy| policy.force_incognito),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn forced_settings_apply_but_are_not_saved() {
+        let policy: Policy = serde_json::from_value(json!({
+            "settings": {
+                "history_limit": 0,
+                "update_checks_enabled": "no",
+                "not_a_setting": true
+            }
+        }))
+        .unwrap();
+        let policy = validated(policy);
+        assert_eq!(
+            policy.settings.keys().collect::<Vec<_>>(),
+            vec!["history_limit"]
+        );
+
+        let mut settings = get_default_settings();
+        settings.history_limit = 20;
+        let settings = apply_policy(&policy, settings);
+        assert_eq!(settings.history_limit, 0);
+
+        let stored = json!({ "history_limit": 20 });
+        let mut value = serde_json::to_value(&settings).unwrap();
+        strip_policy(&policy, &mut value, Some(&stored));
+        assert_eq!(value["history_limit"], 20);
+    }
+}
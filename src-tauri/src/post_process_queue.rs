@@ -0,0 +1,149 @@
+//! Post-processing put off while a cloud provider is unreachable.
+//!
+//! When the network is down the raw transcript is pasted straight away and
+//! its history entry queued here. Every so often the queue is retried in
+//! order; once the provider answers, the refined text is written to the
+//! entry and announced with a notification. The queue is kept in memory, so
+//! anything still waiting when Handy quits keeps its raw text.
+
+use crate::actions::{
+    post_process_transcription, selected_prompt_text, PostProcessed, ProviderUnreachable,
+};
+use crate::managers::analytics::{self, UsageCategory};
+use crate::managers::history::HistoryManager;
+use crate::managers::transcription::TranscriptionOutput;
+use crate::notifications::{self, notify, NotificationKind};
+use crate::settings::AppSettings;
+use log::{debug, info, warn};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Most entries waiting at once, the oldest are given up on past it
+const MAX_QUEUED: usize = 50;
+
+static QUEUE: Mutex<VecDeque<(i64, QueuedPostProcess)>> = Mutex::new(VecDeque::new());
+
+fn queue() -> MutexGuard<'static, VecDeque<(i64, QueuedPostProcess)>> {
+    QUEUE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A transcript that still needs post-processing
+pub struct QueuedPostProcess {
+    /// What goes to the LLM, the spoken part only
+    pub text: String,
+    /// Verbatim text appended after post-processing
+    pub literal: Option<String>,
+    /// Settings the dictation was made with, per-app overrides included
+    pub settings: AppSettings,
+}
+
+/// Queues post-processing of history entry `entry_id`
+pub fn enqueue(entry_id: i64, job: QueuedPostProcess) {
+    let mut queue = queue();
+    if queue.len() >= MAX_QUEUED {
+        if let Some((dropped, _)) = queue.pop_front() {
+            warn!(
+                "Post-processing queue is full, keeping the raw text of entry {}",
+                dropped
+            );
+        }
+    }
+    queue.push_back((entry_id, job));
+    info!(
+        "Queued post-processing of entry {} until the provider can be reached",
+        entry_id
+    );
+}
+
+/// Retries queued post-processing for as long as Handy runs
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(RETRY_INTERVAL).await;
+            retry_queued(&app).await;
+        }
+    });
+}
+
+/// Works through the queue until it's empty or the provider is still out
+/// of reach
+async fn retry_queued(app: &AppHandle) {
+    loop {
+        let Some((entry_id, job)) = queue().pop_front() else {
+            return;
+        };
+
+        let processed = match post_process_transcription(app, &job.settings, &job.text).await {
+            Ok(PostProcessed {
+                text: Some(processed),
+                ..
+            }) => TranscriptionOutput::join(&processed, job.literal.as_deref()),
+            Ok(PostProcessed { text: None, .. }) => {
+                // Failures other than being offline are reported already
+                debug!("Gave up on queued post-processing of entry {}", entry_id);
+                continue;
+            }
+            Err(ProviderUnreachable) => {
+                queue().push_front((entry_id, job));
+                return;
+            }
+        };
+
+        analytics::track(
+            app,
+            UsageCategory::Provider,
+            &job.settings.post_process_provider_id,
+        );
+        let hm = Arc::clone(&app.state::<Arc<HistoryManager>>());
+        match hm
+            .set_post_processed_text(
+                entry_id,
+                processed.clone(),
+                selected_prompt_text(&job.settings),
+            )
+            .await
+        {
+            Ok(()) => notify(
+                app,
+                NotificationKind::PostProcessRefined,
+                &notifications::preview(&processed),
+                false,
+            ),
+            Err(e) => warn!(
+                "Failed to save queued post-processing of entry {}: {}",
+                entry_id, e
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::get_default_settings;
+
+    fn job(text: &str) -> QueuedPostProcess {
+        QueuedPostProcess {
+            text: text.to_string(),
+            literal: None,
+            settings: get_default_settings(),
+        }
+    }
+
+    #[test]
+    fn full_queue_gives_up_on_the_oldest() {
+        for entry_id in 0..=MAX_QUEUED as i64 {
+            enqueue(entry_id, job("hello"));
+        }
+
+        let queue = queue();
+        assert_eq!(queue.len(), MAX_QUEUED);
+        assert_eq!(queue.front().map(|(id, _)| *id), Some(1));
+        assert_eq!(queue.back().map(|(id, _)| *id), Some(MAX_QUEUED as i64));
+    }
+}
@@ -0,0 +1,166 @@
+//! Preview-and-confirm window shown before pasting.
+//!
+//! When enabled, a finished transcript is held here and shown in a small
+//! editable window instead of being pasted right away. The window calls
+//! `confirm_preview` with the (possibly edited) text, or `cancel_preview`.
+
+use crate::events::{self, ErrorSource};
+use crate::utils;
+use log::{debug, error};
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+const PREVIEW_WINDOW_LABEL: &str = "transcript_preview";
+const PREVIEW_WIDTH: f64 = 480.0;
+const PREVIEW_HEIGHT: f64 = 220.0;
+
+/// Time for focus to return to the previous app after the preview hides
+const FOCUS_RETURN_DELAY: Duration = Duration::from_millis(150);
+
+/// Transcript waiting for confirmation
+static PENDING_TEXT: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+/// Holds `text` for confirmation and shows the preview window. A transcript
+/// that was still pending is replaced.
+pub fn show_preview(app: &AppHandle, text: String) {
+    hold(text.clone());
+
+    let window = match app.get_webview_window(PREVIEW_WINDOW_LABEL) {
+        Some(window) => window,
+        None => match WebviewWindowBuilder::new(
+            app,
+            PREVIEW_WINDOW_LABEL,
+            WebviewUrl::App("src/preview/index.html".into()),
+        )
+        .title("Confirm Transcript")
+        .inner_size(PREVIEW_WIDTH, PREVIEW_HEIGHT)
+        .resizable(true)
+        .maximizable(false)
+        .minimizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .center()
+        .visible(false)
+        .build()
+        {
+            Ok(window) => window,
+            Err(e) => {
+                error!("Failed to create preview window: {}", e);
+                return;
+            }
+        },
+    };
+
+    // The window also fetches the text via `get_pending_preview` when it
+    // first loads, since it may miss this event
+    let _ = window.emit("preview-text", text);
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+fn hold(text: String) {
+    *PENDING_TEXT.lock().unwrap_or_else(|e| e.into_inner()) = Some(text);
+}
+
+/// Takes the pending transcript, leaving nothing to confirm or cancel
+fn take_pending() -> Option<String> {
+    PENDING_TEXT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+}
+
+fn hide_preview(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(PREVIEW_WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+}
+
+/// Returns the transcript waiting for confirmation, if any.
+#[tauri::command]
+#[specta::specta]
+pub fn get_pending_preview() -> Option<String> {
+    PENDING_TEXT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Stores edits made in the preview so they survive the window reloading.
+#[tauri::command]
+#[specta::specta]
+pub fn update_preview(text: String) -> Result<(), String> {
+    let mut pending = PENDING_TEXT.lock().unwrap_or_else(|e| e.into_inner());
+    match pending.as_mut() {
+        Some(pending) => {
+            *pending = text;
+            Ok(())
+        }
+        None => Err("No transcript is waiting for confirmation".to_string()),
+    }
+}
+
+/// Pastes the confirmed text, which may have been edited in the preview.
+#[tauri::command]
+#[specta::specta]
+pub fn confirm_preview(app: AppHandle, text: String) -> Result<(), String> {
+    if take_pending().is_none() {
+        return Err("No transcript is waiting for confirmation".to_string());
+    }
+    hide_preview(&app);
+
+    std::thread::spawn(move || {
+        // Paste into whatever had focus before the preview appeared
+        std::thread::sleep(FOCUS_RETURN_DELAY);
+        let ah = app.clone();
+        app.run_on_main_thread(move || match utils::paste(text, ah.clone(), false) {
+            Ok(()) => debug!("Pasted confirmed transcript"),
+            Err(e) => {
+                error!("Failed to paste confirmed transcript: {}", e);
+                events::emit_error(&ah, ErrorSource::Paste, e);
+            }
+        })
+        .unwrap_or_else(|e| error!("Failed to run paste on main thread: {:?}", e));
+    });
+
+    Ok(())
+}
+
+/// Discards the pending transcript without pasting.
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_preview(app: AppHandle) {
+    take_pending();
+    hide_preview(&app);
+    debug!("Discarded transcript preview");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One test, since the pending transcript is global
+    #[test]
+    fn confirm_and_cancel_flow() {
+        assert!(update_preview("edit".to_string()).is_err());
+
+        // A newer transcript replaces one still waiting
+        hold("first".to_string());
+        hold("second".to_string());
+        assert_eq!(get_pending_preview().as_deref(), Some("second"));
+
+        // Edits are what gets confirmed, and only once
+        update_preview("second, edited".to_string()).unwrap();
+        assert_eq!(take_pending().as_deref(), Some("second, edited"));
+        assert_eq!(take_pending(), None);
+        assert_eq!(get_pending_preview(), None);
+
+        // Cancelling leaves nothing to confirm or edit
+        hold("third".to_string());
+        take_pending();
+        assert_eq!(get_pending_preview(), None);
+        assert!(update_preview("edit".to_string()).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+//! Named settings profiles.
+//!
+//! A profile holds the bindings, model, prompts and output options, so a
+//! setup like "Work" or "Streaming" can be switched to in one step. The
+//! settings in use belong to the active profile: switching first saves them
+//! back into it, then puts the other profile's settings in use. Creating a
+//! profile snapshots the current settings and makes it the active one.
+
+use crate::dictation;
+use crate::events::{self, AppEvent};
+use crate::managers::transcription::TranscriptionManager;
+use crate::pause;
+use crate::settings::{self, AppSettings, SettingsProfile};
+use crate::shortcut;
+use log::{error, info};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+/// Adds a profile named `name` holding the current settings
+fn add_profile(
+    settings: &mut AppSettings,
+    id: String,
+    name: &str,
+) -> Result<SettingsProfile, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    if settings
+        .profiles
+        .iter()
+        .any(|p| p.name.eq_ignore_ascii_case(name))
+    {
+        return Err(format!("A profile named '{}' already exists", name));
+    }
+
+    let profile = SettingsProfile::capture(id, name.to_string(), settings);
+    settings.active_profile_id = Some(profile.id.clone());
+    settings.profiles.push(profile.clone());
+    Ok(profile)
+}
+
+/// Saves the settings in use into the active profile and puts profile `id`
+/// in use
+fn switch_to(settings: &mut AppSettings, id: &str) -> Result<(), String> {
+    let target = settings
+        .profiles
+        .iter()
+        .find(|p| p.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Profile not found: {}", id))?;
+
+    let active_index = settings
+        .profiles
+        .iter()
+        .position(|p| Some(&p.id) == settings.active_profile_id.as_ref());
+    if let Some(index) = active_index {
+        let active = &settings.profiles[index];
+        settings.profiles[index] =
+            SettingsProfile::capture(active.id.clone(), active.name.clone(), settings);
+    }
+
+    target.apply_to(settings);
+    settings.active_profile_id = Some(target.id);
+    Ok(())
+}
+
+pub fn create_profile(app: &AppHandle, name: &str) -> Result<SettingsProfile, String> {
+    let mut settings = settings::get_settings(app);
+    let id = format!("profile_{}", chrono::Utc::now().timestamp_millis());
+    let profile = add_profile(&mut settings, id, name)?;
+    settings::write_settings(app, settings);
+
+    info!("Created profile '{}'", profile.name);
+    dictation::refresh_tray_menu(app);
+    Ok(profile)
+}
+
+/// Switches to profile `id`. Shortcuts are swapped together so bindings from
+/// both profiles are never registered at once. If the new bindings can't all
+/// be registered, the previous profile stays in use.
+pub fn switch_profile(app: &AppHandle, id: &str) -> Result<(), String> {
+    let previous = settings::get_settings(app);
+    if previous.active_profile_id.as_deref() == Some(id) {
+        return Ok(());
+    }
+    let mut settings = previous.clone();
+    switch_to(&mut settings, id)?;
+    let model_changed = settings.selected_model != previous.selected_model;
+
+    // While paused the new bindings are registered on resume
+    let paused = pause::is_paused();
+    if !paused {
+        shortcut::unregister_all(app);
+    }
+    settings::write_settings(app, settings);
+    if !paused {
+        if let Err(e) = shortcut::try_register_all(app) {
+            error!("Shortcuts of profile {} failed, switching back: {}", id, e);
+            shortcut::unregister_all(app);
+            settings::write_settings(app, previous);
+            shortcut::register_all(app);
+            return Err(format!("Could not register the profile's shortcuts: {}", e));
+        }
+    }
+
+    // An unloaded model is loaded from the new settings when it's next needed
+    let transcription_manager = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+    if model_changed && transcription_manager.is_model_loaded() {
+        let app = app.clone();
+        dictation::when_idle(move || {
+            // Profiles switched again in the meantime load only the last one's
+            let selected_model = settings::get_settings(&app).selected_model;
+            if transcription_manager.get_current_model().as_ref() == Some(&selected_model) {
+                return;
+            }
+            if let Err(e) = transcription_manager.load_model(&selected_model) {
+                error!("Failed to load model {} for profile: {}", selected_model, e);
+            }
+        });
+    }
+
+    info!("Switched to profile {}", id);
+    dictation::refresh_tray_menu(app);
+    events::emit(
+        app,
+        AppEvent::ProfileChanged {
+            profile_id: id.to_string(),
+        },
+    );
+    Ok(())
+}
+
+/// Deletes profile `id`. Deleting the active profile keeps its settings in
+/// use without a profile.
+pub fn delete_profile(app: &AppHandle, id: &str) -> Result<(), String> {
+    let mut settings = settings::get_settings(app);
+    let before = settings.profiles.len();
+    settings.profiles.retain(|p| p.id != id);
+    if settings.profiles.len() == before {
+        return Err(format!("Profile not found: {}", id));
+    }
+    if settings.active_profile_id.as_deref() == Some(id) {
+        settings.active_profile_id = None;
+    }
+    settings::write_settings(app, settings);
+
+    info!("Deleted profile {}", id);
+    dictation::refresh_tray_menu(app);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::get_default_settings;
+
+    #[test]
+    fn switching_saves_the_active_profile() {
+        let mut settings = get_default_settings();
+        add_profile(&mut settings, "work".to_string(), "Work").unwrap();
+        assert!(add_profile(&mut settings, "dup".to_string(), " work ").is_err());
+
+        settings.push_to_talk = false;
+        settings.auto_submit = true;
+        add_profile(&mut settings, "streaming".to_string(), "Streaming").unwrap();
+        assert_eq!(settings.active_profile_id.as_deref(), Some("streaming"));
+
+        // Changes made while a profile is active are kept in it
+        settings.append_trailing_space = true;
+        switch_to(&mut settings, "work").unwrap();
+        assert!(!settings.auto_submit);
+        assert!(!settings.append_trailing_space);
+        // Settings outside profiles stay as they are
+        assert!(!settings.push_to_talk);
+
+        switch_to(&mut settings, "streaming").unwrap();
+        assert!(settings.auto_submit);
+        assert!(settings.append_trailing_space);
+        assert!(switch_to(&mut settings, "missing").is_err());
+    }
+
+    #[test]
+    fn switching_replaces_the_bindings() {
+        let mut settings = get_default_settings();
+        add_profile(&mut settings, "work".to_string(), "Work").unwrap();
+        settings.profiles[0].bindings.remove("transcribe");
+
+        let mut stale = settings.bindings["transcribe"].clone();
+        stale.id = "removed_binding".to_string();
+        settings.bindings.insert(stale.id.clone(), stale);
+        settings
+            .bindings
+            .get_mut("transcribe")
+            .unwrap()
+            .current_binding = "ctrl+alt+x".to_string();
+        add_profile(&mut settings, "home".to_string(), "Home").unwrap();
+
+        switch_to(&mut settings, "work").unwrap();
+        assert!(!settings.bindings.contains_key("removed_binding"));
+        // Missing from the profile, so back at its default
+        let transcribe = &settings.bindings["transcribe"];
+        assert_eq!(transcribe.current_binding, transcribe.default_binding);
+    }
+}
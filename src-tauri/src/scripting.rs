@@ -0,0 +1,141 @@
+//! AppleScript commands, which Shortcuts can run through "Run AppleScript".
+//!
+//! The dictionary is `Handy.sdef` and the command classes are in
+//! `swift/scripting.swift`; they call back into the functions below.
+
+use crate::managers::history::HistoryManager;
+use crate::signal_handle;
+use crate::utils;
+use log::{debug, error};
+use once_cell::sync::OnceCell;
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+const SOURCE: &str = "AppleScript";
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+
+extern "C" {
+    fn handy_scripting_register();
+    fn handy_script_finish(context: *mut c_void, text: *const c_char, error: *const c_char);
+}
+
+pub fn setup(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    unsafe { handy_scripting_register() };
+}
+
+fn to_cstring(value: String) -> CString {
+    CString::new(value.replace('\0', "")).unwrap_or_default()
+}
+
+fn into_raw(value: String) -> *mut c_char {
+    to_cstring(value).into_raw()
+}
+
+unsafe fn read_str(value: *const c_char) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+    Some(CStr::from_ptr(value).to_string_lossy().into_owned())
+}
+
+/// # Safety
+/// `name` must be null or a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn handy_script_command(
+    name: *const c_char,
+    post_process: c_int,
+) -> *mut c_char {
+    let Some(app) = APP.get() else {
+        return into_raw("Handy is still starting".to_string());
+    };
+    let result = match read_str(name).as_deref() {
+        Some("start") => signal_handle::start_recording(app, post_process != 0, SOURCE),
+        Some("stop") => signal_handle::stop_recording(app, SOURCE),
+        Some("cancel") => {
+            utils::cancel_current_operation(app);
+            Ok(())
+        }
+        other => Err(format!("Unknown command {:?}", other)),
+    };
+    match result {
+        Ok(()) => std::ptr::null_mut(),
+        Err(e) => {
+            debug!("AppleScript command failed: {}", e);
+            into_raw(e)
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn handy_script_last_transcript() -> *mut c_char {
+    let entry =
+        APP.get().and_then(
+            |app| match app.state::<Arc<HistoryManager>>().get_latest_entry() {
+                Ok(entry) => entry,
+                Err(e) => {
+                    error!("Failed to read last transcription for AppleScript: {}", e);
+                    None
+                }
+            },
+        );
+    into_raw(
+        entry
+            .map(|entry| {
+                entry
+                    .post_processed_text
+                    .unwrap_or(entry.transcription_text)
+            })
+            .unwrap_or_default(),
+    )
+}
+
+/// The suspended Swift command, resumed once the transcription is done
+struct Context(*mut c_void);
+
+unsafe impl Send for Context {}
+
+impl Context {
+    fn finish(self, result: Result<String, String>) {
+        let (text, error) = match result {
+            Ok(text) => (Some(to_cstring(text)), None),
+            Err(e) => (None, Some(to_cstring(e))),
+        };
+        let as_ptr = |value: &Option<CString>| {
+            value
+                .as_ref()
+                .map_or(std::ptr::null(), |value| value.as_ptr())
+        };
+        unsafe { handy_script_finish(self.0, as_ptr(&text), as_ptr(&error)) };
+    }
+}
+
+/// # Safety
+/// `path` must be null or a valid C string, and `context` the pointer
+/// `handy_script_finish` expects.
+#[no_mangle]
+pub unsafe extern "C" fn handy_script_transcribe(path: *const c_char, context: *mut c_void) {
+    let context = Context(context);
+    let path = read_str(path).map(PathBuf::from);
+    let app = APP.get().cloned();
+    std::thread::spawn(move || {
+        let result = match (app, path) {
+            (Some(app), Some(path)) => signal_handle::transcribe_file(&app, &path),
+            (None, _) => Err("Handy is still starting".to_string()),
+            (_, None) => Err("Expected an audio file".to_string()),
+        };
+        context.finish(result);
+    });
+}
+
+/// # Safety
+/// `value` must be null or a string returned by one of the functions above.
+#[no_mangle]
+pub unsafe extern "C" fn handy_script_free_string(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
@@ -0,0 +1,218 @@
+//! Storage for API keys and other credentials.
+//!
+//! Secrets live in the platform credential store (macOS Keychain, Windows
+//! Credential Manager, Secret Service on Linux) instead of the plaintext
+//! settings file. Lookups are cached for the lifetime of the process since
+//! settings are read frequently and keychain access can be slow.
+//!
+//! Every credential is named by its [`SecretKind`] and an id, e.g. the API key
+//! of a post-processing provider. Plaintext API keys found in older settings
+//! files are moved here when settings are loaded.
+
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+const SERVICE_NAME: &str = "com.pais.handy";
+
+/// What the credential store is known to hold for a name
+#[derive(Debug, Clone, PartialEq)]
+enum Cached {
+    Stored(String),
+    Missing,
+    /// Reading failed, so the value is unknown. It isn't retried, and the
+    /// entry is never deleted on the strength of the failed read.
+    Unreadable,
+}
+
+static CACHE: Lazy<Mutex<HashMap<String, Cached>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn cache() -> MutexGuard<'static, HashMap<String, Cached>> {
+    CACHE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// What a credential is for
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKind {
+    /// API key of a post-processing provider, by provider id
+    PostProcessApiKey,
+    /// API key of a cloud transcription service, by service id
+    TranscriptionApiKey,
+    /// Token sent with webhook requests, by webhook id
+    WebhookToken,
+    /// Token clients of the local HTTP API authenticate with
+    ApiServerToken,
+    /// Password of the obs-websocket server captions are sent to
+    ObsPassword,
+    /// Password for the MQTT broker
+    MqttPassword,
+    /// Integration token of a Notion note target, by target id
+    NotionToken,
+    /// Password for the SMTP server transcripts are emailed through
+    SmtpPassword,
+    /// ICS feed addresses for meeting detection, one per line
+    CalendarFeeds,
+}
+
+impl SecretKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SecretKind::PostProcessApiKey => "post_process_api_key",
+            SecretKind::TranscriptionApiKey => "transcription_api_key",
+            SecretKind::WebhookToken => "webhook_token",
+            SecretKind::ApiServerToken => "api_server_token",
+            SecretKind::ObsPassword => "obs_password",
+            SecretKind::MqttPassword => "mqtt_password",
+            SecretKind::NotionToken => "notion_token",
+            SecretKind::SmtpPassword => "smtp_password",
+            SecretKind::CalendarFeeds => "calendar_feeds",
+        }
+    }
+}
+
+/// Name a credential is stored under, e.g. `post_process_api_key.openai`
+pub fn secret_name(kind: SecretKind, id: &str) -> String {
+    format!("{}.{}", kind.as_str(), id)
+}
+
+/// Secret name used for a post-processing provider's API key
+pub fn post_process_api_key_name(provider_id: &str) -> String {
+    secret_name(SecretKind::PostProcessApiKey, provider_id)
+}
+
+fn entry(name: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE_NAME, name)
+        .map_err(|e| format!("Failed to open credential '{}': {}", name, e))
+}
+
+/// Reads a secret from the credential store and caches the outcome
+fn load(name: &str) -> Cached {
+    let cached = match entry(name).and_then(|e| match e.get_password() {
+        Ok(value) => Ok(Cached::Stored(value)),
+        Err(keyring::Error::NoEntry) => Ok(Cached::Missing),
+        Err(e) => Err(e.to_string()),
+    }) {
+        Ok(cached) => cached,
+        Err(e) => {
+            warn!("Failed to read secret '{}': {}", name, e);
+            Cached::Unreadable
+        }
+    };
+    cache().insert(name.to_string(), cached.clone());
+    cached
+}
+
+/// Returns the stored secret, or `None` if it is missing or the store is unavailable.
+pub fn get_secret(name: &str) -> Option<String> {
+    let cached = cache().get(name).cloned();
+    match cached.unwrap_or_else(|| load(name)) {
+        Cached::Stored(value) => Some(value),
+        Cached::Missing | Cached::Unreadable => None,
+    }
+}
+
+/// Returns the stored secret, `Ok(None)` only when the store confirms there is
+/// no entry, and an error when it can't be read. A previously failed read is
+/// retried.
+pub fn read_secret(name: &str) -> Result<Option<String>, String> {
+    let cached = match cache().get(name).cloned() {
+        Some(Cached::Unreadable) | None => load(name),
+        Some(cached) => cached,
+    };
+    match cached {
+        Cached::Stored(value) => Ok(Some(value)),
+        Cached::Missing => Ok(None),
+        Cached::Unreadable => Err(format!(
+            "Failed to read secret '{}' from the credential store",
+            name
+        )),
+    }
+}
+
+/// Stores a secret. An empty value removes the entry.
+pub fn set_secret(name: &str, value: &str) -> Result<(), String> {
+    let new_value = if value.is_empty() {
+        Cached::Missing
+    } else {
+        Cached::Stored(value.to_string())
+    };
+    if cache().get(name) == Some(&new_value) {
+        return Ok(());
+    }
+
+    let entry = entry(name)?;
+    match &new_value {
+        Cached::Stored(value) => entry
+            .set_password(value)
+            .map_err(|e| format!("Failed to store secret '{}': {}", name, e))?,
+        _ => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(format!("Failed to delete secret '{}': {}", name, e)),
+        },
+    }
+
+    debug!("Updated secret '{}'", name);
+    cache().insert(name.to_string(), new_value);
+    Ok(())
+}
+
+/// Stores a secret that was read back from the settings. Settings hold an
+/// empty value for a key that couldn't be loaded, so an empty value only
+/// removes an entry that was read successfully before.
+pub fn sync_secret(name: &str, value: &str) -> Result<(), String> {
+    let known = matches!(cache().get(name), Some(Cached::Stored(_)));
+    if value.is_empty() && !known {
+        return Ok(());
+    }
+    set_secret(name, value)
+}
+
+pub fn has_secret(name: &str) -> bool {
+    get_secret(name).is_some()
+}
+
+pub fn delete_secret(name: &str) -> Result<(), String> {
+    set_secret(name, "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreadable_secrets_are_not_deleted() {
+        let name = secret_name(SecretKind::PostProcessApiKey, "unreadable");
+        cache().insert(name.clone(), Cached::Unreadable);
+
+        assert_eq!(get_secret(&name), None);
+        sync_secret(&name, "").unwrap();
+        assert_eq!(cache().get(&name), Some(&Cached::Unreadable));
+    }
+
+    #[test]
+    fn read_secret_tells_missing_from_stored() {
+        let missing = secret_name(SecretKind::PostProcessApiKey, "read_missing");
+        cache().insert(missing.clone(), Cached::Missing);
+        assert_eq!(read_secret(&missing), Ok(None));
+
+        let stored = secret_name(SecretKind::PostProcessApiKey, "read_stored");
+        cache().insert(stored.clone(), Cached::Stored("key".to_string()));
+        assert_eq!(read_secret(&stored), Ok(Some("key".to_string())));
+    }
+
+    #[test]
+    fn names_secrets_by_kind_and_id() {
+        assert_eq!(
+            post_process_api_key_name("openai"),
+            "post_process_api_key.openai"
+        );
+        assert_eq!(
+            secret_name(SecretKind::WebhookToken, "slack"),
+            "webhook_token.slack"
+        );
+    }
+}
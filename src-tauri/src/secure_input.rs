@@ -0,0 +1,134 @@
+//! Warns when macOS Secure Input blocks Handy's shortcuts.
+//!
+//! While any app has Secure Input on, usually for a password field or a
+//! terminal's "Secure Keyboard Entry", macOS keeps key presses from every
+//! other app, so shortcuts silently stop working. Handy can't see the
+//! presses it misses, so it watches the Secure Input state instead. Changes
+//! are sent as `secure_input_changed` events, and if it stays on long enough
+//! to be more than a password being typed, a notification names the app
+//! holding it.
+
+use crate::events::{self, AppEvent};
+use crate::notifications::{self, NotificationKind};
+use log::{debug, info};
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long Secure Input has to stay on before it's worth a notification
+const NOTIFY_AFTER: Duration = Duration::from_secs(10);
+
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    fn IsSecureEventInputEnabled() -> u8;
+}
+
+fn is_enabled() -> bool {
+    unsafe { IsSecureEventInputEnabled() != 0 }
+}
+
+/// Pid of the process holding Secure Input, from `ioreg` output
+fn holder_pid(ioreg: &str) -> Option<u32> {
+    ioreg
+        .split("\"kCGSSessionSecureInputPID\"=")
+        .nth(1)
+        .and_then(|rest| {
+            let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().ok()
+        })
+}
+
+/// Pid and name of the process holding Secure Input
+fn holder() -> Option<(u32, String)> {
+    let ioreg = Command::new("ioreg")
+        .args(["-l", "-w", "0", "-d", "1"])
+        .output()
+        .ok()?;
+    let pid = holder_pid(&String::from_utf8_lossy(&ioreg.stdout))?;
+
+    let ps = Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "comm="])
+        .output()
+        .ok()?;
+    let command = String::from_utf8_lossy(&ps.stdout).trim().to_string();
+    let name = Path::new(&command)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .filter(|name| !name.is_empty())?;
+    Some((pid, name))
+}
+
+/// Watches Secure Input for as long as Handy runs
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || {
+        // When Secure Input came on, and whether that's been notified
+        let mut since: Option<(Instant, bool)> = None;
+        let mut holder_name: Option<String> = None;
+
+        loop {
+            let enabled = is_enabled();
+            match (enabled, since) {
+                (true, None) => {
+                    let holder = holder();
+                    // Handy's own password fields turn it on too
+                    let own = holder
+                        .as_ref()
+                        .is_some_and(|(pid, _)| *pid == std::process::id());
+                    holder_name = holder.map(|(_, name)| name);
+                    info!("Secure Input turned on by {:?}", holder_name);
+                    since = Some((Instant::now(), own));
+                    events::emit(
+                        &app,
+                        AppEvent::SecureInputChanged {
+                            enabled: true,
+                            process: holder_name.clone(),
+                        },
+                    );
+                }
+                (true, Some((at, false))) if at.elapsed() >= NOTIFY_AFTER => {
+                    let body = format!(
+                        "{} has Secure Input on, so macOS hides your shortcuts from Handy. \
+                         Close its password field or quit it to dictate again.",
+                        holder_name.as_deref().unwrap_or("An app")
+                    );
+                    notifications::notify(&app, NotificationKind::SecureInput, &body, false);
+                    since = Some((at, true));
+                }
+                (false, Some(_)) => {
+                    debug!("Secure Input turned off");
+                    since = None;
+                    holder_name = None;
+                    events::emit(
+                        &app,
+                        AppEvent::SecureInputChanged {
+                            enabled: false,
+                            process: None,
+                        },
+                    );
+                }
+                _ => {}
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_holder_pid() {
+        let ioreg = r#"    | |   "IOConsoleUsers" = ({"kCGSSessionOnConsoleKey"=Yes,"kCGSSessionSecureInputPID"=4321,"kCGSSessionUserNameKey"="me"})"#;
+        assert_eq!(holder_pid(ioreg), Some(4321));
+        assert_eq!(
+            holder_pid(r#""IOConsoleUsers" = ({"kCGSSessionOnConsoleKey"=Yes})"#),
+            None
+        );
+    }
+}
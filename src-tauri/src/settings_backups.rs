@@ -0,0 +1,220 @@
+//! Rotating backups of the settings file.
+//!
+//! Before the first settings write of each day the file is copied into a
+//! `settings_backups` folder next to it, keeping the most recent
+//! [`MAX_BACKUPS`]. Any of them can be restored, e.g. after a bad edit or a
+//! corrupted write. Restoring first backs up the settings it replaces.
+
+use crate::pause;
+use crate::settings::{self, AppSettings};
+use crate::settings_migration;
+use crate::settings_watcher;
+use crate::shortcut;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const BACKUP_DIR: &str = "settings_backups";
+const BACKUP_PREFIX: &str = "settings-";
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+const MAX_BACKUPS: usize = 10;
+
+/// When the newest backup was made, once known
+static LAST_BACKUP: Lazy<Mutex<Option<DateTime<Utc>>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Type)]
+pub struct SettingsBackup {
+    pub file_name: String,
+    pub created_at: i64,
+    pub size_bytes: u64,
+}
+
+fn backup_file_name(created_at: DateTime<Utc>) -> String {
+    format!(
+        "{}{}.json",
+        BACKUP_PREFIX,
+        created_at.format(BACKUP_TIMESTAMP_FORMAT)
+    )
+}
+
+/// Creation time of a backup, or `None` if `file_name` isn't one
+fn parse_backup_name(file_name: &str) -> Option<DateTime<Utc>> {
+    let timestamp = file_name
+        .strip_prefix(BACKUP_PREFIX)?
+        .strip_suffix(".json")?;
+    NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT)
+        .ok()
+        .map(|created_at| created_at.and_utc())
+}
+
+fn backup_dir(app: &AppHandle) -> Option<PathBuf> {
+    Some(
+        settings::settings_file_path(app)?
+            .parent()?
+            .join(BACKUP_DIR),
+    )
+}
+
+/// Backups in `dir`, newest first
+fn list_in(dir: &Path) -> Vec<SettingsBackup> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<SettingsBackup> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let created_at = parse_backup_name(&file_name)?;
+            Some(SettingsBackup {
+                file_name,
+                created_at: created_at.timestamp(),
+                size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    backups
+}
+
+/// Deletes all but the newest `keep` backups in `dir`
+fn prune(dir: &Path, keep: usize) {
+    for backup in list_in(dir).into_iter().skip(keep) {
+        if let Err(e) = fs::remove_file(dir.join(&backup.file_name)) {
+            warn!(
+                "Failed to remove settings backup {}: {}",
+                backup.file_name, e
+            );
+        }
+    }
+}
+
+fn create_backup_in(source: &Path, dir: &Path, now: DateTime<Utc>) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create backup folder: {}", e))?;
+    let backup_path = dir.join(backup_file_name(now));
+    fs::copy(source, &backup_path).map_err(|e| format!("Failed to back up settings: {}", e))?;
+    prune(dir, MAX_BACKUPS);
+    info!("Backed up settings to {}", backup_path.display());
+    Ok(())
+}
+
+/// Backs up the settings file as it is now
+pub fn create_backup(app: &AppHandle) -> Result<(), String> {
+    let source = settings::settings_file_path(app)
+        .filter(|path| path.exists())
+        .ok_or("There is no settings file to back up")?;
+    let dir = backup_dir(app).ok_or("Settings backup folder unknown")?;
+    let now = Utc::now();
+    create_backup_in(&source, &dir, now)?;
+    *LAST_BACKUP.lock().unwrap_or_else(|e| e.into_inner()) = Some(now);
+    Ok(())
+}
+
+/// Backs up the settings file if the newest backup is from an earlier day.
+/// Called before each settings write.
+pub fn backup_if_due(app: &AppHandle) {
+    let Some(dir) = backup_dir(app) else {
+        return;
+    };
+    let mut last_backup = LAST_BACKUP.lock().unwrap_or_else(|e| e.into_inner());
+    if last_backup.is_none() {
+        *last_backup = list_in(&dir)
+            .first()
+            .and_then(|backup| DateTime::from_timestamp(backup.created_at, 0));
+    }
+    let now = Utc::now();
+    if last_backup.is_some_and(|last| last.date_naive() == now.date_naive()) {
+        return;
+    }
+    let Some(source) = settings::settings_file_path(app).filter(|path| path.exists()) else {
+        return;
+    };
+    match create_backup_in(&source, &dir, now) {
+        Ok(()) => *last_backup = Some(now),
+        Err(e) => warn!("{}", e),
+    }
+}
+
+pub fn list_backups(app: &AppHandle) -> Vec<SettingsBackup> {
+    backup_dir(app).map(|dir| list_in(&dir)).unwrap_or_default()
+}
+
+/// The settings in a backup, brought up to date
+fn read_backup(path: &Path) -> Result<AppSettings, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Failed to read backup: {}", e))?;
+    let file: serde_json::Value =
+        serde_json::from_str(&contents).map_err(|e| format!("Backup is not valid JSON: {}", e))?;
+    let mut value = file
+        .get("settings")
+        .cloned()
+        .ok_or("Backup has no settings")?;
+    settings_migration::migrate(&mut value);
+    serde_json::from_value(value).map_err(|e| format!("Backup has invalid settings: {}", e))
+}
+
+/// Replaces the settings with backup `file_name`. API keys are kept, since
+/// backups don't contain them, as are the other settings that belong to
+/// this machine as it is now, like history encryption and data folders.
+pub fn restore_backup(app: &AppHandle, file_name: &str) -> Result<AppSettings, String> {
+    if parse_backup_name(file_name).is_none() {
+        return Err(format!("Not a settings backup: {}", file_name));
+    }
+    let dir = backup_dir(app).ok_or("Settings backup folder unknown")?;
+    let current = settings::get_settings(app);
+    let mut restored = read_backup(&dir.join(file_name))?;
+    restored.keep_machine_settings(&current);
+
+    // The settings being replaced can be restored in turn
+    if let Err(e) = create_backup(app) {
+        warn!("{}", e);
+    }
+
+    // Shortcuts are swapped together, and on resume while paused
+    let paused = pause::is_paused();
+    if !paused {
+        shortcut::unregister_all(app);
+    }
+    settings::write_settings(app, restored);
+    if !paused {
+        shortcut::register_all(app);
+    }
+
+    let new = settings::get_settings(app);
+    settings_watcher::apply_changes(app, &current, &new);
+    info!("Restored settings from backup {}", file_name);
+    Ok(new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn keeps_the_newest_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("settings_store.json");
+        fs::write(&source, r#"{"settings":{}}"#).unwrap();
+
+        let backups = dir.path().join(BACKUP_DIR);
+        for day in 1..=MAX_BACKUPS as u32 + 2 {
+            let created_at = Utc.with_ymd_and_hms(2026, 1, day, 9, 30, 0).unwrap();
+            create_backup_in(&source, &backups, created_at).unwrap();
+        }
+        fs::write(backups.join("notes.json"), "{}").unwrap();
+
+        let listed = list_in(&backups);
+        assert_eq!(listed.len(), MAX_BACKUPS);
+        assert_eq!(listed[0].file_name, "settings-20260112-093000.json");
+        assert_eq!(
+            parse_backup_name(&listed[0].file_name).map(|t| t.timestamp()),
+            Some(listed[0].created_at)
+        );
+        assert!(parse_backup_name("../settings-20260112-093000.json").is_none());
+    }
+}
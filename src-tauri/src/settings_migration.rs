@@ -0,0 +1,119 @@
+//! Versioned settings migrations.
+//!
+//! The stored settings carry a `version`. When Handy starts with older
+//! settings, the file is backed up and each migration from that version on
+//! runs in order on the raw JSON, before it's parsed into `AppSettings`. A
+//! rename or restructure adds a migration here instead of letting serde
+//! defaults silently replace the old value.
+
+use log::{info, warn};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[n]` takes settings from version `n` to `n + 1`
+const MIGRATIONS: &[Migration] = &[log_level_names, window_titles_opt_in];
+
+/// Version of the settings this build writes
+pub const CURRENT_SETTINGS_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Settings from before versioning have no `version` and count as 0
+pub fn version_of(settings: &Value) -> u32 {
+    settings
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(0, |version| version as u32)
+}
+
+pub fn needs_migration(settings: &Value) -> bool {
+    version_of(settings) < CURRENT_SETTINGS_VERSION
+}
+
+/// Runs the migrations the settings haven't had yet and stamps the new version
+pub fn migrate(settings: &mut Value) {
+    let from = version_of(settings);
+    let Some(object) = settings.as_object_mut() else {
+        return;
+    };
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+        info!(
+            "Migrating settings from version {} to {}",
+            version,
+            version + 1
+        );
+        migration(object);
+    }
+    object.insert("version".to_string(), Value::from(CURRENT_SETTINGS_VERSION));
+}
+
+/// Copies the settings file next to itself before it's changed, e.g.
+/// `settings_store.json` to `settings_store.v0.bak.json`
+pub fn backup(path: &Path, label: &str) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_string_lossy();
+    let backup_path = path.with_file_name(format!("{}.{}.bak.json", stem, label));
+    match fs::copy(path, &backup_path) {
+        Ok(_) => {
+            info!("Backed up settings to {}", backup_path.display());
+            Some(backup_path)
+        }
+        Err(e) => {
+            warn!(
+                "Failed to back up settings to {}: {}",
+                backup_path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// 0 → 1: log levels were stored as numbers from 1 (trace) to 5 (error)
+fn log_level_names(settings: &mut Map<String, Value>) {
+    const NAMES: [&str; 5] = ["trace", "debug", "info", "warn", "error"];
+    if let Some(level) = settings.get("log_level").and_then(Value::as_u64) {
+        if let Some(name) = (level as usize)
+            .checked_sub(1)
+            .and_then(|index| NAMES.get(index))
+        {
+            settings.insert("log_level".to_string(), Value::from(*name));
+        }
+    }
+}
+
+/// 1 → 2: window titles were kept by default and are now opt-in. There was
+/// no way to switch them on, so a stored `true` is the old default.
+fn window_titles_opt_in(settings: &mut Map<String, Value>) {
+    settings.insert("capture_window_titles".to_string(), Value::from(false));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrates_unversioned_settings() {
+        let mut settings = json!({ "log_level": 2, "push_to_talk": true });
+        assert!(needs_migration(&settings));
+
+        migrate(&mut settings);
+        assert_eq!(settings["log_level"], "debug");
+        assert_eq!(settings["push_to_talk"], true);
+        assert_eq!(settings["capture_window_titles"], false);
+        assert_eq!(version_of(&settings), CURRENT_SETTINGS_VERSION);
+        assert!(!needs_migration(&settings));
+    }
+
+    #[test]
+    fn window_titles_become_opt_in() {
+        let mut settings = json!({ "version": 1, "capture_window_titles": true });
+        migrate(&mut settings);
+        assert_eq!(settings["capture_window_titles"], false);
+
+        // Chosen after the migration, so left alone
+        settings["capture_window_titles"] = json!(true);
+        assert!(!needs_migration(&settings));
+    }
+}
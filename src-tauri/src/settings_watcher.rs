@@ -0,0 +1,237 @@
+//! Reloads the settings file when it changes outside Handy, e.g. when it's
+//! edited by hand or synced from another machine.
+//!
+//! The file is polled for a new modification time. Handy's own saves also
+//! change it, so a change only counts once the file differs from the
+//! settings Handy last loaded or wrote. Settings changed in Handy since the
+//! file was last in sync are kept over the file's.
+
+use crate::dictation;
+use crate::events::{self, AppEvent};
+use crate::pause;
+use crate::settings::{self, AppSettings};
+use crate::shortcut;
+use crate::tray;
+use crate::utils;
+use log::{info, warn};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tauri::AppHandle;
+use tauri_plugin_autostart::ManagerExt;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Longer than the store's save debounce, so a save of newer changes made in
+/// Handy lands before the file is compared again
+const SAVE_SETTLE: Duration = Duration::from_millis(500);
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Starts watching the settings file in the background
+pub fn start(app: &AppHandle) {
+    let Some(path) = settings::settings_file_path(app) else {
+        warn!("Settings file location unknown, not watching for changes");
+        return;
+    };
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut last_modified = modified(&path);
+        // The settings as they were when Handy and the file last agreed
+        let mut in_sync = settings::stored_settings_value(&app);
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let current = modified(&path);
+            if current != last_modified {
+                last_modified = current;
+                reload_if_changed(&app, &path, &mut in_sync);
+            }
+        }
+    });
+}
+
+/// The settings in the file, or `None` if they can't be used
+fn read_settings_file(path: &Path) -> Option<serde_json::Value> {
+    let contents = fs::read_to_string(path).ok()?;
+    let value = serde_json::from_str::<serde_json::Value>(&contents)
+        .map_err(|e| warn!("Settings file is not valid JSON, not reloading: {}", e))
+        .ok()?
+        .get("settings")?
+        .clone();
+    serde_json::from_value::<AppSettings>(value.clone())
+        .map_err(|e| warn!("Settings file has invalid settings, not reloading: {}", e))
+        .ok()?;
+    Some(value)
+}
+
+/// The file's settings if they're usable and differ from the ones Handy has.
+/// Otherwise the file and Handy agree, which is noted in `in_sync`.
+fn changed_externally(
+    app: &AppHandle,
+    path: &Path,
+    in_sync: &mut Option<serde_json::Value>,
+) -> Option<serde_json::Value> {
+    let on_disk = read_settings_file(path)?;
+    if settings::stored_settings_value(app).as_ref() == Some(&on_disk) {
+        *in_sync = Some(on_disk);
+        return None;
+    }
+    Some(on_disk)
+}
+
+/// Takes each top-level setting from `theirs` unless only `ours` changed it
+/// since `base`
+fn merge(
+    base: &serde_json::Value,
+    ours: &serde_json::Value,
+    theirs: &serde_json::Value,
+) -> serde_json::Value {
+    let mut merged = theirs.clone();
+    if let (Some(merged), Some(ours)) = (merged.as_object_mut(), ours.as_object()) {
+        for (key, value) in ours {
+            if base.get(key) != Some(value) && theirs.get(key) == base.get(key) {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    merged
+}
+
+fn reload_if_changed(app: &AppHandle, path: &Path, in_sync: &mut Option<serde_json::Value>) {
+    if changed_externally(app, path, in_sync).is_none() {
+        return;
+    }
+    // A change made in Handy may still be waiting to be saved
+    std::thread::sleep(SAVE_SETTLE);
+    let Some(on_disk) = changed_externally(app, path, in_sync) else {
+        return;
+    };
+
+    // Changes made in Handy that haven't reached the file are kept
+    let merged = match (in_sync.as_ref(), settings::stored_settings_value(app)) {
+        (Some(base), Some(ours)) => {
+            let merged = merge(base, &ours, &on_disk);
+            if serde_json::from_value::<AppSettings>(merged.clone()).is_ok() {
+                merged
+            } else {
+                on_disk
+            }
+        }
+        _ => on_disk,
+    };
+
+    let old = settings::get_settings(app);
+    // Bindings are swapped together, and on resume while paused
+    let paused = pause::is_paused();
+    if !paused {
+        shortcut::unregister_all(app);
+    }
+    let reloaded = settings::replace_stored_settings(app, merged.clone());
+    if !paused {
+        shortcut::register_all(app);
+    }
+    if let Err(e) = reloaded {
+        warn!("{}", e);
+        return;
+    }
+    *in_sync = Some(merged);
+
+    let new = settings::get_settings(app);
+    apply_changes(app, &old, &new);
+    settings::emit_changes(app, &old, &new);
+    info!("Reloaded settings changed outside Handy");
+    events::emit(app, AppEvent::SettingsReloaded);
+}
+
+/// Re-applies settings that take effect outside the settings store
+pub fn apply_changes(app: &AppHandle, old: &AppSettings, new: &AppSettings) {
+    if old.autostart_enabled != new.autostart_enabled {
+        let autostart_manager = app.autolaunch();
+        let _ = if new.autostart_enabled {
+            autostart_manager.enable()
+        } else {
+            autostart_manager.disable()
+        };
+    }
+    if old.show_tray_icon != new.show_tray_icon {
+        tray::set_tray_visibility(app, new.show_tray_icon);
+    }
+    if old.api_server != new.api_server {
+        if let Err(e) = crate::api_server::restart(app) {
+            warn!("{}", e);
+        }
+        #[cfg(windows)]
+        crate::named_pipe::restart(app);
+        #[cfg(feature = "grpc")]
+        if let Err(e) = crate::grpc::restart(app) {
+            warn!("{}", e);
+        }
+    }
+    if old.stream_deck != new.stream_deck {
+        if let Err(e) = crate::stream_deck::restart(app) {
+            warn!("{}", e);
+        }
+    }
+    if old.mqtt != new.mqtt {
+        if let Err(e) = crate::mqtt::restart(app) {
+            warn!("{}", e);
+        }
+    }
+    if old.meeting_detection != new.meeting_detection {
+        crate::meeting_detection::restart(app);
+    }
+    if old.log_level != new.log_level {
+        let tauri_log_level: tauri_plugin_log::LogLevel = new.log_level.into();
+        let log_level: log::Level = tauri_log_level.into();
+        crate::FILE_LOG_LEVEL.store(
+            log_level.to_level_filter() as u8,
+            std::sync::atomic::Ordering::Relaxed,
+        );
+    }
+
+    utils::update_overlay_position(app);
+    utils::emit_overlay_appearance(app);
+    dictation::refresh_tray_menu(app);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merge_keeps_changes_made_in_handy() {
+        let base = json!({ "push_to_talk": false, "selected_model": "small", "debug_mode": false });
+        let ours = json!({ "push_to_talk": true, "selected_model": "small", "debug_mode": false });
+        let theirs =
+            json!({ "push_to_talk": false, "selected_model": "large", "debug_mode": false });
+
+        assert_eq!(
+            merge(&base, &ours, &theirs),
+            json!({ "push_to_talk": true, "selected_model": "large", "debug_mode": false })
+        );
+    }
+
+    #[test]
+    fn merge_prefers_the_file_when_both_changed() {
+        let base = json!({ "selected_model": "small" });
+        let ours = json!({ "selected_model": "medium" });
+        let theirs = json!({ "selected_model": "large" });
+        assert_eq!(merge(&base, &ours, &theirs), theirs);
+
+        // Nothing changed in Handy, so the file wins everywhere
+        assert_eq!(merge(&base, &base, &theirs), theirs);
+    }
+
+    #[test]
+    fn merge_keeps_settings_added_in_handy() {
+        let base = json!({ "push_to_talk": false });
+        let ours = json!({ "push_to_talk": false, "new_setting": 3 });
+        let theirs = json!({ "push_to_talk": true });
+        assert_eq!(
+            merge(&base, &ours, &theirs),
+            json!({ "push_to_talk": true, "new_setting": 3 })
+        );
+    }
+}
@@ -8,6 +8,7 @@ use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 
 use crate::actions::ACTION_MAP;
+use crate::managers::analytics::{self, UsageCategory};
 use crate::managers::audio::AudioRecordingManager;
 use crate::settings::get_settings;
 use crate::transcription_coordinator::is_transcribe_binding;
@@ -56,6 +57,7 @@ pub fn handle_shortcut_event(
     if binding_id == "cancel" {
         let audio_manager = app.state::<Arc<AudioRecordingManager>>();
         if audio_manager.is_recording() && is_pressed {
+            analytics::track(app, UsageCategory::Action, binding_id);
             action.start(app, binding_id, hotkey_string);
         }
         return;
@@ -63,6 +65,7 @@ pub fn handle_shortcut_event(
 
     // Remaining bindings (e.g. "test") use simple start/stop on press/release.
     if is_pressed {
+        analytics::track(app, UsageCategory::Action, binding_id);
         action.start(app, binding_id, hotkey_string);
     } else {
         action.stop(app, binding_id, hotkey_string);
@@ -28,7 +28,7 @@
 //! via Tauri's event system.
 
 use handy_keys::{Hotkey, HotkeyId, HotkeyManager, HotkeyState, KeyboardListener};
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use serde::Serialize;
 use specta::Type;
 use std::collections::HashMap;
@@ -38,6 +38,8 @@ use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::commands::permissions;
+use crate::events::{self, AppEvent};
 use crate::settings::{self, get_settings, ShortcutBinding};
 
 use super::handler::handle_shortcut_event;
@@ -421,6 +423,41 @@ pub fn validate_shortcut(raw: &str) -> Result<(), String> {
         .map_err(|e| format!("Invalid shortcut for HandyKeys: {}", e))
 }
 
+fn uses_fn_key(raw: &str) -> bool {
+    raw.split('+')
+        .map(|part| part.trim().to_lowercase())
+        .any(|part| part == "fn" || part == "function")
+}
+
+/// Reports a binding with the fn key that macOS won't deliver for lack of
+/// Accessibility or Input Monitoring
+fn check_fn_permissions(app: &AppHandle, binding: &ShortcutBinding) {
+    if !cfg!(target_os = "macos") || !uses_fn_key(&binding.current_binding) {
+        return;
+    }
+    let app = app.clone();
+    let binding_id = binding.id.clone();
+    tauri::async_runtime::spawn(async move {
+        let missing = permissions::get_permission_status()
+            .await
+            .missing_for_fn_shortcuts();
+        if missing.is_empty() {
+            return;
+        }
+        warn!(
+            "Shortcut '{}' uses fn, which needs permissions Handy doesn't have: {:?}",
+            binding_id, missing
+        );
+        events::emit(
+            &app,
+            AppEvent::ShortcutPermissionsMissing {
+                binding_id,
+                missing,
+            },
+        );
+    });
+}
+
 /// Initialize handy-keys shortcuts
 pub fn init_shortcuts(app: &AppHandle) -> Result<(), String> {
     let state = HandyKeysState::new(app.clone())?;
@@ -430,11 +467,7 @@ pub fn init_shortcuts(app: &AppHandle) -> Result<(), String> {
 
     // Register all bindings except cancel (which is dynamic)
     for (id, default_binding) in default_bindings {
-        if id == "cancel" {
-            continue;
-        }
-        // Skip post-processing shortcut when the feature is disabled
-        if id == "transcribe_with_post_process" && !user_settings.post_process_enabled {
+        if !super::should_register_binding(&user_settings, &id) {
             continue;
         }
 
@@ -444,11 +477,12 @@ pub fn init_shortcuts(app: &AppHandle) -> Result<(), String> {
             .cloned()
             .unwrap_or(default_binding);
 
-        if let Err(e) = state.register(&binding) {
-            error!(
+        match state.register(&binding) {
+            Ok(()) => check_fn_permissions(app, &binding),
+            Err(e) => error!(
                 "Failed to register handy-keys shortcut {} during init: {}",
                 id, e
-            );
+            ),
         }
     }
 
@@ -507,7 +541,9 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
     let state = app
         .try_state::<HandyKeysState>()
         .ok_or("HandyKeysState not initialized")?;
-    state.register(&binding)
+    state.register(&binding)?;
+    check_fn_permissions(app, &binding);
+    Ok(())
 }
 
 /// Unregister a shortcut
@@ -16,12 +16,20 @@ mod tauri_impl;
 use log::{error, info, warn};
 use serde::Serialize;
 use specta::Type;
-use tauri::{AppHandle, Emitter, Manager};
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
 use tauri_plugin_autostart::ManagerExt;
 
+use crate::llm_client::{ModelSearchFilter, PostProcessModelInfo};
+use crate::pause;
+use crate::secrets::{self, SecretKind};
 use crate::settings::{
-    self, get_settings, AutoSubmitKey, ClipboardHandling, KeyboardImplementation, LLMPrompt,
-    OverlayPosition, PasteMethod, ShortcutBinding, SoundTheme, TypingTool,
+    self, get_settings, ApiServerSettings, AppOverride, AppPasteRule, AppSettings, AutoSubmitKey,
+    ClipboardHandling, EmailSettings, HttpAction, KeyInjectionSettings, KeyboardImplementation,
+    LLMPrompt, MarkdownOutputMode, MeetingDetectionSettings, MqttSettings, NoteDestination,
+    NoteTarget, NotificationSettings, ObsCaptionSettings, OpenRouterRouting, OverlayAppearance,
+    OverlayPosition, PasteMethod, PrimarySelectionMode, ShortcutBinding, SoundTheme,
+    StreamDeckSettings, TypingSpeed, TypingTool, WatchdogSettings, Webhook,
     APPLE_INTELLIGENCE_DEFAULT_MODEL_ID, APPLE_INTELLIGENCE_PROVIDER_ID,
 };
 use crate::tray;
@@ -72,8 +80,12 @@ pub fn unregister_cancel_shortcut(app: &AppHandle) {
     }
 }
 
-/// Register a shortcut using the appropriate implementation
+/// Register a shortcut using the appropriate implementation. Skipped while
+/// Handy is paused, since resuming registers every binding again.
 pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), String> {
+    if pause::is_paused() {
+        return Ok(());
+    }
     let settings = get_settings(app);
     match settings.keyboard_implementation {
         KeyboardImplementation::Tauri => tauri_impl::register_shortcut(app, binding),
@@ -81,8 +93,68 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
     }
 }
 
-/// Unregister a shortcut using the appropriate implementation
+/// Whether a binding should be registered with the current settings.
+/// Cancel is registered dynamically while recording; optional features only
+/// register their shortcut once enabled or configured.
+pub fn should_register_binding(settings: &AppSettings, binding_id: &str) -> bool {
+    match binding_id {
+        "cancel" => false,
+        "transcribe_with_post_process" => settings.post_process_enabled,
+        "undo_last_dictation" => settings.undo_dictation_enabled,
+        "paste_last_transcript" => settings.paste_last_enabled,
+        "transcribe_incognito" => settings.incognito_shortcut_enabled,
+        "toggle_obs_captions" => settings.obs_captions.shortcut_enabled,
+        "transcribe_to_file" => settings
+            .bindings
+            .get(binding_id)
+            .and_then(|binding| binding.output_file_path.as_deref())
+            .is_some_and(|path| !path.trim().is_empty()),
+        _ => true,
+    }
+}
+
+/// Registers or unregisters an optional feature's shortcut when its toggle
+/// changes. Called before the toggle is saved, so a shortcut that can't be
+/// registered leaves the feature off instead of enabled without a shortcut.
+fn set_feature_shortcut(
+    app: &AppHandle,
+    settings: &AppSettings,
+    binding_id: &str,
+    was_enabled: bool,
+    enabled: bool,
+) -> Result<(), String> {
+    sync_feature_shortcut(
+        settings.bindings.get(binding_id).cloned(),
+        was_enabled,
+        enabled,
+        |binding, enabled| {
+            if enabled {
+                register_shortcut(app, binding)
+            } else {
+                unregister_shortcut(app, binding)
+            }
+        },
+    )
+}
+
+fn sync_feature_shortcut(
+    binding: Option<ShortcutBinding>,
+    was_enabled: bool,
+    enabled: bool,
+    apply: impl FnOnce(ShortcutBinding, bool) -> Result<(), String>,
+) -> Result<(), String> {
+    match binding {
+        Some(binding) if was_enabled != enabled => apply(binding, enabled),
+        _ => Ok(()),
+    }
+}
+
+/// Unregister a shortcut using the appropriate implementation. Nothing is
+/// registered while Handy is paused.
 pub fn unregister_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), String> {
+    if pause::is_paused() {
+        return Ok(());
+    }
     let settings = get_settings(app);
     match settings.keyboard_implementation {
         KeyboardImplementation::Tauri => tauri_impl::unregister_shortcut(app, binding),
@@ -90,6 +162,28 @@ pub fn unregister_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<
     }
 }
 
+/// Unregister every binding, e.g. while Handy is paused
+pub fn unregister_all(app: &AppHandle) {
+    unregister_all_shortcuts(app, get_settings(app).keyboard_implementation);
+}
+
+/// Register every binding that's enabled in the settings again
+pub fn register_all(app: &AppHandle) {
+    register_all_shortcuts_for_implementation(app, get_settings(app).keyboard_implementation);
+}
+
+/// Like [`register_all`], but fails when any binding couldn't be registered,
+/// e.g. because another app holds the shortcut
+pub fn try_register_all(app: &AppHandle) -> Result<(), String> {
+    let registration =
+        register_all_shortcuts_for_implementation(app, get_settings(app).keyboard_implementation);
+    if registration.failed.is_empty() {
+        Ok(())
+    } else {
+        Err(registration.failed.join("; "))
+    }
+}
+
 // ============================================================================
 // Binding Management Commands
 // ============================================================================
@@ -259,6 +353,11 @@ pub fn change_keyboard_implementation_setting(
     let current_impl = current_settings.keyboard_implementation;
     let new_impl = parse_keyboard_implementation(&implementation);
 
+    // Resuming registers with whichever implementation is set by then
+    if pause::is_paused() && current_impl != new_impl {
+        return Err("Resume Handy before switching the keyboard implementation".to_string());
+    }
+
     // If same implementation, nothing to do
     if current_impl == new_impl {
         return Ok(ImplementationChangeResult {
@@ -292,17 +391,7 @@ pub fn change_keyboard_implementation_setting(
     }
 
     // Register all shortcuts with new implementation, resetting invalid ones
-    let reset_bindings = register_all_shortcuts_for_implementation(&app, new_impl);
-
-    // Emit event to notify frontend of the change
-    let _ = app.emit(
-        "settings-changed",
-        serde_json::json!({
-            "setting": "keyboard_implementation",
-            "value": implementation,
-            "reset_bindings": reset_bindings
-        }),
-    );
+    let reset_bindings = register_all_shortcuts_for_implementation(&app, new_impl).reset;
 
     info!("Keyboard implementation switched to {:?}", new_impl);
 
@@ -328,7 +417,7 @@ pub fn get_keyboard_implementation(app: AppHandle) -> String {
 // ============================================================================
 
 /// Validate a shortcut for a specific implementation
-fn validate_shortcut_for_implementation(
+pub fn validate_shortcut_for_implementation(
     raw: &str,
     implementation: KeyboardImplementation,
 ) -> Result<(), String> {
@@ -377,23 +466,26 @@ fn unregister_all_shortcuts(app: &AppHandle, implementation: KeyboardImplementat
     }
 }
 
+/// Outcome of registering every enabled binding
+struct Registration {
+    /// Bindings reset to their default because they're invalid for the implementation
+    reset: Vec<String>,
+    /// Bindings that couldn't be registered, with the reason
+    failed: Vec<String>,
+}
+
 /// Register all shortcuts for a specific implementation, validating and resetting invalid ones
 fn register_all_shortcuts_for_implementation(
     app: &AppHandle,
     implementation: KeyboardImplementation,
-) -> Vec<String> {
+) -> Registration {
     let mut reset_bindings = Vec::new();
+    let mut failed = Vec::new();
     let default_bindings = settings::get_default_settings().bindings;
     let mut current_settings = settings::get_settings(app);
 
     for (id, default_binding) in &default_bindings {
-        // Skip cancel shortcut as it's dynamically registered
-        if id == "cancel" {
-            continue;
-        }
-
-        // Skip post-processing shortcut when the feature is disabled
-        if id == "transcribe_with_post_process" && !current_settings.post_process_enabled {
+        if !should_register_binding(&current_settings, id) {
             continue;
         }
 
@@ -431,6 +523,7 @@ fn register_all_shortcuts_for_implementation(
                 "Failed to register shortcut '{}' for {:?}: {}",
                 id, implementation, e
             );
+            failed.push(format!("{}: {}", id, e));
         }
     }
 
@@ -439,7 +532,10 @@ fn register_all_shortcuts_for_implementation(
         settings::write_settings(app, current_settings);
     }
 
-    reset_bindings
+    Registration {
+        reset: reset_bindings,
+        failed,
+    }
 }
 
 /// Initialize HandyKeys if not already initialized, with rollback on failure
@@ -540,6 +636,7 @@ pub fn change_overlay_position_setting(app: AppHandle, position: String) -> Resu
         "none" => OverlayPosition::None,
         "top" => OverlayPosition::Top,
         "bottom" => OverlayPosition::Bottom,
+        "caret" => OverlayPosition::Caret,
         other => {
             warn!("Invalid overlay position '{}', defaulting to bottom", other);
             OverlayPosition::Bottom
@@ -554,6 +651,70 @@ pub fn change_overlay_position_setting(app: AppHandle, position: String) -> Resu
     Ok(())
 }
 
+/// Set the overlay's opacity, accent color and theme
+#[tauri::command]
+#[specta::specta]
+pub fn change_overlay_appearance_setting(
+    app: AppHandle,
+    appearance: OverlayAppearance,
+) -> Result<(), String> {
+    let appearance = appearance.validated()?;
+    let mut settings = settings::get_settings(&app);
+    settings.overlay_appearance = appearance;
+    settings::write_settings(&app, settings);
+
+    crate::utils::emit_overlay_appearance(&app);
+
+    Ok(())
+}
+
+/// Set the overlay size relative to its default
+#[tauri::command]
+#[specta::specta]
+pub fn change_overlay_scale_setting(app: AppHandle, scale: f64) -> Result<(), String> {
+    use crate::overlay::{MAX_OVERLAY_SCALE, MIN_OVERLAY_SCALE};
+
+    if !(MIN_OVERLAY_SCALE..=MAX_OVERLAY_SCALE).contains(&scale) {
+        return Err(format!(
+            "Overlay scale must be between {} and {}",
+            MIN_OVERLAY_SCALE, MAX_OVERLAY_SCALE
+        ));
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.overlay_scale = scale;
+    settings::write_settings(&app, settings);
+
+    crate::utils::update_overlay_position(&app);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_result_hud_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.result_hud_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Pin the overlay to a monitor from `get_monitors`, or `None` to show it on
+/// the monitor with the cursor
+#[tauri::command]
+#[specta::specta]
+pub fn change_overlay_monitor_setting(
+    app: AppHandle,
+    monitor: Option<String>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.overlay_monitor = monitor;
+    settings::write_settings(&app, settings);
+
+    crate::utils::update_overlay_position(&app);
+
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_debug_mode_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
@@ -561,15 +722,6 @@ pub fn change_debug_mode_setting(app: AppHandle, enabled: bool) -> Result<(), St
     settings.debug_mode = enabled;
     settings::write_settings(&app, settings);
 
-    // Emit event to notify frontend of debug mode change
-    let _ = app.emit(
-        "settings-changed",
-        serde_json::json!({
-            "setting": "debug_mode",
-            "value": enabled
-        }),
-    );
-
     Ok(())
 }
 
@@ -580,14 +732,25 @@ pub fn change_start_hidden_setting(app: AppHandle, enabled: bool) -> Result<(),
     settings.start_hidden = enabled;
     settings::write_settings(&app, settings);
 
-    // Notify frontend
-    let _ = app.emit(
-        "settings-changed",
-        serde_json::json!({
-            "setting": "start_hidden",
-            "value": enabled
-        }),
-    );
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_menu_bar_only_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.menu_bar_only = enabled;
+    settings::write_settings(&app, settings);
+
+    // Apply right away in case the settings window is already closed
+    #[cfg(target_os = "macos")]
+    {
+        let window_visible = app
+            .get_webview_window("main")
+            .and_then(|window| window.is_visible().ok())
+            .unwrap_or(false);
+        crate::utils::apply_activation_policy(&app, window_visible);
+    }
 
     Ok(())
 }
@@ -607,15 +770,6 @@ pub fn change_autostart_setting(app: AppHandle, enabled: bool) -> Result<(), Str
         let _ = autostart_manager.disable();
     }
 
-    // Notify frontend
-    let _ = app.emit(
-        "settings-changed",
-        serde_json::json!({
-            "setting": "autostart_enabled",
-            "value": enabled
-        }),
-    );
-
     Ok(())
 }
 
@@ -626,14 +780,6 @@ pub fn change_update_checks_setting(app: AppHandle, enabled: bool) -> Result<(),
     settings.update_checks_enabled = enabled;
     settings::write_settings(&app, settings);
 
-    let _ = app.emit(
-        "settings-changed",
-        serde_json::json!({
-            "setting": "update_checks_enabled",
-            "value": enabled
-        }),
-    );
-
     Ok(())
 }
 
@@ -646,6 +792,53 @@ pub fn update_custom_words(app: AppHandle, words: Vec<String>) -> Result<(), Str
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_spoken_commands_enabled_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.spoken_commands_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_spoken_commands(
+    app: AppHandle,
+    commands: HashMap<String, String>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.spoken_commands = commands
+        .into_iter()
+        .map(|(phrase, replacement)| (phrase.trim().to_lowercase(), replacement))
+        .filter(|(phrase, _)| !phrase.is_empty())
+        .collect();
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_literal_mode_enabled_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.literal_mode_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_literal_mode_triggers(app: AppHandle, triggers: Vec<String>) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.literal_mode_triggers = triggers
+        .into_iter()
+        .map(|trigger| trigger.trim().to_lowercase())
+        .filter(|trigger| !trigger.is_empty())
+        .collect();
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_word_correction_threshold_setting(
@@ -658,6 +851,33 @@ pub fn change_word_correction_threshold_setting(
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_transcription_timeout_setting(app: AppHandle, seconds: u32) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.transcription_timeout_secs = seconds;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_preload_model_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.preload_model = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_transcription_workers_setting(app: AppHandle, workers: u32) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.transcription_workers = workers.max(1);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_paste_method_setting(app: AppHandle, method: String) -> Result<(), String> {
@@ -669,6 +889,8 @@ pub fn change_paste_method_setting(app: AppHandle, method: String) -> Result<(),
         "shift_insert" => PasteMethod::ShiftInsert,
         "ctrl_shift_v" => PasteMethod::CtrlShiftV,
         "external_script" => PasteMethod::ExternalScript,
+        "accessibility" => PasteMethod::Accessibility,
+        "copy_only" => PasteMethod::CopyOnly,
         other => {
             warn!("Invalid paste method '{}', defaulting to ctrl_v", other);
             PasteMethod::CtrlV
@@ -692,6 +914,138 @@ pub fn get_available_typing_tools() -> Vec<String> {
     }
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_app_paste_rules_setting(
+    app: AppHandle,
+    rules: Vec<AppPasteRule>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.app_paste_rules = rules
+        .into_iter()
+        .map(|rule| AppPasteRule {
+            app_match: rule.app_match.trim().to_string(),
+            ..rule
+        })
+        .filter(|rule| !rule.app_match.is_empty())
+        .collect();
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_app_overrides_setting(
+    app: AppHandle,
+    overrides: Vec<AppOverride>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.app_overrides = overrides
+        .into_iter()
+        .map(|app_override| AppOverride {
+            app_match: app_override.app_match.trim().to_string(),
+            ..app_override
+        })
+        .filter(|app_override| !app_override.app_match.is_empty())
+        .collect();
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_transcript_pipe_path_setting(
+    app: AppHandle,
+    path: Option<String>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.transcript_pipe_path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_daily_journal_dir_setting(app: AppHandle, dir: Option<String>) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.daily_journal_dir = dir.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Set the template for daily journal entries. An empty template restores
+/// the default.
+#[tauri::command]
+#[specta::specta]
+pub fn change_daily_journal_template_setting(
+    app: AppHandle,
+    template: String,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.daily_journal_template = if template.trim().is_empty() {
+        crate::transcript_output::DEFAULT_JOURNAL_TEMPLATE.to_string()
+    } else {
+        template
+    };
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Set the max recording duration in seconds, 0 for no limit. Applies from
+/// the next recording.
+#[tauri::command]
+#[specta::specta]
+pub fn change_max_recording_duration_setting(app: AppHandle, secs: u32) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.max_recording_duration_secs = secs;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_capture_window_titles_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.capture_window_titles = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_deep_link_actions_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.deep_link_actions = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_usage_analytics_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.usage_analytics_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_typing_speed_setting(
+    app: AppHandle,
+    speed: TypingSpeed,
+    slow: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    if slow {
+        settings.slow_typing_speed = speed;
+    } else {
+        settings.typing_speed = speed;
+    }
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_typing_tool_setting(app: AppHandle, tool: String) -> Result<(), String> {
@@ -747,27 +1101,469 @@ pub fn change_clipboard_handling_setting(app: AppHandle, handling: String) -> Re
 
 #[tauri::command]
 #[specta::specta]
-pub fn change_auto_submit_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+pub fn change_primary_selection_mode_setting(app: AppHandle, mode: String) -> Result<(), String> {
     let mut settings = settings::get_settings(&app);
-    settings.auto_submit = enabled;
+    let parsed = match mode.as_str() {
+        "off" => PrimarySelectionMode::Off,
+        "also" => PrimarySelectionMode::Also,
+        "only" => PrimarySelectionMode::Only,
+        other => {
+            warn!(
+                "Invalid primary selection mode '{}', defaulting to off",
+                other
+            );
+            PrimarySelectionMode::Off
+        }
+    };
+    settings.primary_selection_mode = parsed;
     settings::write_settings(&app, settings);
     Ok(())
 }
 
 #[tauri::command]
 #[specta::specta]
-pub fn change_auto_submit_key_setting(app: AppHandle, key: String) -> Result<(), String> {
+pub fn change_key_injection_setting(
+    app: AppHandle,
+    key_injection: KeyInjectionSettings,
+) -> Result<(), String> {
     let mut settings = settings::get_settings(&app);
-    let parsed = match key.as_str() {
-        "enter" => AutoSubmitKey::Enter,
-        "ctrl_enter" => AutoSubmitKey::CtrlEnter,
-        "cmd_enter" => AutoSubmitKey::CmdEnter,
-        other => {
-            warn!("Invalid auto submit key '{}', defaulting to enter", other);
-            AutoSubmitKey::Enter
-        }
-    };
-    settings.auto_submit_key = parsed;
+    settings.key_injection = key_injection;
+    settings::write_settings(&app, settings);
+
+    // Platform options are applied when the backend is created
+    if let Some(state) = app.try_state::<crate::input::InjectorState>() {
+        state.reconfigure(&key_injection)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_notification_settings(
+    app: AppHandle,
+    notifications: NotificationSettings,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.notifications = notifications;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_api_server_setting(
+    app: AppHandle,
+    api_server: ApiServerSettings,
+) -> Result<(), String> {
+    if api_server.port == 0 {
+        return Err("API server port cannot be 0".to_string());
+    }
+    if api_server.grpc && (api_server.grpc_port == 0 || api_server.grpc_port == api_server.port) {
+        return Err("gRPC port must be set and differ from the API server port".to_string());
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.api_server = api_server;
+    settings::write_settings(&app, settings);
+
+    #[cfg(windows)]
+    crate::named_pipe::restart(&app);
+    // Restart the HTTP API even if gRPC fails, so it doesn't stay on the old port
+    let api_result = crate::api_server::restart(&app);
+    #[cfg(feature = "grpc")]
+    if let Err(grpc_error) = crate::grpc::restart(&app) {
+        return Err(match api_result {
+            Ok(()) => grpc_error,
+            Err(api_error) => format!("{}; {}", api_error, grpc_error),
+        });
+    }
+    api_result
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_stream_deck_setting(
+    app: AppHandle,
+    stream_deck: StreamDeckSettings,
+) -> Result<(), String> {
+    if stream_deck.port == 0 {
+        return Err("Stream Deck port cannot be 0".to_string());
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.stream_deck = stream_deck;
+    settings::write_settings(&app, settings);
+
+    crate::stream_deck::restart(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_obs_captions_setting(
+    app: AppHandle,
+    obs_captions: ObsCaptionSettings,
+) -> Result<(), String> {
+    if obs_captions.port == 0 {
+        return Err("OBS port cannot be 0".to_string());
+    }
+    let mut settings = settings::get_settings(&app);
+    set_feature_shortcut(
+        &app,
+        &settings,
+        "toggle_obs_captions",
+        settings.obs_captions.shortcut_enabled,
+        obs_captions.shortcut_enabled,
+    )?;
+    settings.obs_captions = obs_captions;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_mqtt_setting(app: AppHandle, mqtt: MqttSettings) -> Result<(), String> {
+    if mqtt.enabled && mqtt.host.trim().is_empty() {
+        return Err("MQTT broker host cannot be empty".to_string());
+    }
+    if mqtt.port == 0 {
+        return Err("MQTT port cannot be 0".to_string());
+    }
+    if mqtt.topic_prefix.trim().is_empty() {
+        return Err("MQTT topic prefix cannot be empty".to_string());
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.mqtt = mqtt;
+    settings::write_settings(&app, settings);
+
+    crate::mqtt::restart(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_email_setting(app: AppHandle, email: EmailSettings) -> Result<(), String> {
+    crate::email::validate(&email)?;
+    let mut settings = settings::get_settings(&app);
+    settings.email = email;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_meeting_detection_setting(
+    app: AppHandle,
+    meeting_detection: MeetingDetectionSettings,
+) -> Result<(), String> {
+    for url in &meeting_detection.ics_urls {
+        match tauri::Url::parse(url.trim()) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https" | "webcal") => {}
+            _ => return Err(format!("'{}' is not a calendar URL", url)),
+        }
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.meeting_detection = meeting_detection;
+    settings::write_settings(&app, settings);
+
+    crate::meeting_detection::restart(&app);
+    Ok(())
+}
+
+fn validate_webhook_url(url: &str) -> Result<(), String> {
+    match tauri::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(()),
+        _ => Err(format!("'{}' is not an http(s) URL", url)),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn add_webhook(app: AppHandle, url: String) -> Result<Webhook, String> {
+    validate_webhook_url(&url)?;
+    let mut settings = settings::get_settings(&app);
+
+    let webhook = Webhook {
+        id: format!("webhook_{}", chrono::Utc::now().timestamp_millis()),
+        url,
+        enabled: true,
+    };
+    settings.webhooks.push(webhook.clone());
+    settings::write_settings(&app, settings);
+
+    Ok(webhook)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_webhook(app: AppHandle, webhook: Webhook) -> Result<(), String> {
+    validate_webhook_url(&webhook.url)?;
+    let mut settings = settings::get_settings(&app);
+
+    let existing = settings
+        .webhooks
+        .iter_mut()
+        .find(|w| w.id == webhook.id)
+        .ok_or_else(|| format!("Webhook with id '{}' not found", webhook.id))?;
+    *existing = webhook;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Removes a webhook along with its signing secret
+#[tauri::command]
+#[specta::specta]
+pub fn delete_webhook(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    let original_len = settings.webhooks.len();
+    settings.webhooks.retain(|w| w.id != id);
+    if settings.webhooks.len() == original_len {
+        return Err(format!("Webhook with id '{}' not found", id));
+    }
+    settings::write_settings(&app, settings);
+
+    secrets::delete_secret(&secrets::secret_name(SecretKind::WebhookToken, &id))
+}
+
+/// Adds an HTTP action, see `http_actions` for the template variables
+#[tauri::command]
+#[specta::specta]
+pub fn add_http_action(app: AppHandle, mut action: HttpAction) -> Result<HttpAction, String> {
+    crate::http_actions::validate(&action)?;
+    let mut settings = settings::get_settings(&app);
+
+    action.id = format!("http_action_{}", chrono::Utc::now().timestamp_millis());
+    settings.http_actions.push(action.clone());
+    settings::write_settings(&app, settings);
+
+    Ok(action)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_http_action(app: AppHandle, action: HttpAction) -> Result<(), String> {
+    crate::http_actions::validate(&action)?;
+    let mut settings = settings::get_settings(&app);
+
+    let existing = settings
+        .http_actions
+        .iter_mut()
+        .find(|a| a.id == action.id)
+        .ok_or_else(|| format!("HTTP action with id '{}' not found", action.id))?;
+    *existing = action;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_http_action(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    let original_len = settings.http_actions.len();
+    settings.http_actions.retain(|a| a.id != id);
+    if settings.http_actions.len() == original_len {
+        return Err(format!("HTTP action with id '{}' not found", id));
+    }
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn add_note_target(
+    app: AppHandle,
+    name: String,
+    destination: NoteDestination,
+) -> Result<NoteTarget, String> {
+    let mut settings = settings::get_settings(&app);
+
+    let target = NoteTarget {
+        id: format!("note_target_{}", chrono::Utc::now().timestamp_millis()),
+        name,
+        destination,
+    };
+    settings.note_targets.push(target.clone());
+    settings::write_settings(&app, settings);
+
+    Ok(target)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_note_target(app: AppHandle, target: NoteTarget) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    let existing = settings
+        .note_targets
+        .iter_mut()
+        .find(|t| t.id == target.id)
+        .ok_or_else(|| format!("Note target with id '{}' not found", target.id))?;
+    *existing = target;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Removes a note target along with its token. Bindings that sent to it go
+/// back to pasting.
+#[tauri::command]
+#[specta::specta]
+pub fn delete_note_target(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    let original_len = settings.note_targets.len();
+    settings.note_targets.retain(|t| t.id != id);
+    if settings.note_targets.len() == original_len {
+        return Err(format!("Note target with id '{}' not found", id));
+    }
+    for binding in settings.bindings.values_mut() {
+        if binding.note_target_id.as_deref() == Some(id.as_str()) {
+            binding.note_target_id = None;
+        }
+    }
+    settings::write_settings(&app, settings);
+
+    secrets::delete_secret(&secrets::secret_name(SecretKind::NotionToken, &id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_markdown_output_mode_setting(app: AppHandle, mode: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    let parsed = match mode.as_str() {
+        "keep" => MarkdownOutputMode::Keep,
+        "strip" => MarkdownOutputMode::Strip,
+        "rich_text" => MarkdownOutputMode::RichText,
+        other => {
+            warn!(
+                "Invalid markdown output mode '{}', defaulting to keep",
+                other
+            );
+            MarkdownOutputMode::Keep
+        }
+    };
+    settings.markdown_output_mode = parsed;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_smart_insert_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.smart_insert_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_watchdog_setting(app: AppHandle, watchdog: WatchdogSettings) -> Result<(), String> {
+    if watchdog.max_recording_mins == 0 || watchdog.max_processing_mins == 0 {
+        return Err("Watchdog limits must be at least a minute".to_string());
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.watchdog = watchdog;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_focused_control_detection_setting(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.focused_control_detection = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_clipboard_restore_delay_setting(app: AppHandle, delay_ms: u64) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.clipboard_restore_delay_ms = delay_ms;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_auto_submit_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.auto_submit = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_auto_submit_key_setting(app: AppHandle, key: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    let parsed = match key.as_str() {
+        "enter" => AutoSubmitKey::Enter,
+        "ctrl_enter" => AutoSubmitKey::CtrlEnter,
+        "cmd_enter" => AutoSubmitKey::CmdEnter,
+        other => {
+            warn!("Invalid auto submit key '{}', defaulting to enter", other);
+            AutoSubmitKey::Enter
+        }
+    };
+    settings.auto_submit_key = parsed;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Set the file a binding appends transcripts to. The `transcribe_to_file`
+/// shortcut is only registered while a path is configured.
+#[tauri::command]
+#[specta::specta]
+pub fn change_binding_output_file(
+    app: AppHandle,
+    id: String,
+    path: Option<String>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+
+    let was_registered = should_register_binding(&settings, &id);
+    let binding = settings
+        .bindings
+        .get_mut(&id)
+        .ok_or_else(|| format!("Binding '{}' not found", id))?;
+    binding.output_file_path = path;
+    let binding = binding.clone();
+    settings::write_settings(&app, settings.clone());
+
+    let should_register = should_register_binding(&settings, &id);
+    if should_register && !was_registered {
+        register_shortcut(&app, binding)?;
+    } else if !should_register && was_registered {
+        unregister_shortcut(&app, binding)?;
+    }
+
+    Ok(())
+}
+
+/// Set the note target a binding sends transcripts to, or `None` to paste
+#[tauri::command]
+#[specta::specta]
+pub fn change_binding_note_target(
+    app: AppHandle,
+    id: String,
+    target_id: Option<String>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    if let Some(target_id) = &target_id {
+        if !settings.note_targets.iter().any(|t| &t.id == target_id) {
+            return Err(format!("Note target with id '{}' not found", target_id));
+        }
+    }
+
+    let binding = settings
+        .bindings
+        .get_mut(&id)
+        .ok_or_else(|| format!("Binding '{}' not found", id))?;
+    binding.note_target_id = target_id;
     settings::write_settings(&app, settings);
     Ok(())
 }
@@ -795,6 +1591,66 @@ pub fn change_post_process_enabled_setting(app: AppHandle, enabled: bool) -> Res
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_preview_before_paste_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.preview_before_paste = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_undo_dictation_enabled_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    set_feature_shortcut(
+        &app,
+        &settings,
+        "undo_last_dictation",
+        settings.undo_dictation_enabled,
+        enabled,
+    )?;
+    settings.undo_dictation_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_paste_last_enabled_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    set_feature_shortcut(
+        &app,
+        &settings,
+        "paste_last_transcript",
+        settings.paste_last_enabled,
+        enabled,
+    )?;
+    settings.paste_last_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_incognito_shortcut_enabled_setting(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    set_feature_shortcut(
+        &app,
+        &settings,
+        "transcribe_incognito",
+        settings.incognito_shortcut_enabled,
+        enabled,
+    )?;
+    settings.incognito_shortcut_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_experimental_enabled_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
@@ -892,6 +1748,7 @@ pub fn add_post_process_prompt(
     app: AppHandle,
     name: String,
     prompt: String,
+    system_prompt: Option<String>,
 ) -> Result<LLMPrompt, String> {
     let mut settings = settings::get_settings(&app);
 
@@ -902,6 +1759,10 @@ pub fn add_post_process_prompt(
         id: id.clone(),
         name,
         prompt,
+        system_prompt,
+        temperature: None,
+        max_tokens: None,
+        top_p: None,
     };
 
     settings.post_process_prompts.push(new_prompt.clone());
@@ -917,6 +1778,7 @@ pub fn update_post_process_prompt(
     id: String,
     name: String,
     prompt: String,
+    system_prompt: Option<String>,
 ) -> Result<(), String> {
     let mut settings = settings::get_settings(&app);
 
@@ -927,6 +1789,7 @@ pub fn update_post_process_prompt(
     {
         existing_prompt.name = name;
         existing_prompt.prompt = prompt;
+        existing_prompt.system_prompt = system_prompt;
         settings::write_settings(&app, settings);
         Ok(())
     } else {
@@ -934,6 +1797,46 @@ pub fn update_post_process_prompt(
     }
 }
 
+/// Update the sampling parameter overrides for a prompt.
+/// Passing `None` for a value clears the override so the provider default is used.
+#[tauri::command]
+#[specta::specta]
+pub fn update_post_process_prompt_params(
+    app: AppHandle,
+    id: String,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+    top_p: Option<f32>,
+) -> Result<(), String> {
+    if let Some(t) = temperature {
+        if !(0.0..=2.0).contains(&t) {
+            return Err(format!("Temperature must be between 0 and 2, got {}", t));
+        }
+    }
+    if let Some(p) = top_p {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(format!("top_p must be between 0 and 1, got {}", p));
+        }
+    }
+    if max_tokens == Some(0) {
+        return Err("max_tokens must be greater than 0".to_string());
+    }
+
+    let mut settings = settings::get_settings(&app);
+
+    let existing_prompt = settings
+        .post_process_prompts
+        .iter_mut()
+        .find(|p| p.id == id)
+        .ok_or_else(|| format!("Prompt with id '{}' not found", id))?;
+
+    existing_prompt.temperature = temperature;
+    existing_prompt.max_tokens = max_tokens;
+    existing_prompt.top_p = top_p;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn delete_post_process_prompt(app: AppHandle, id: String) -> Result<(), String> {
@@ -1007,6 +1910,48 @@ pub async fn fetch_post_process_models(
     crate::llm_client::fetch_models(provider, api_key).await
 }
 
+/// Search a provider's model catalog by name, price and context length.
+/// Intended for providers like OpenRouter whose full model list is very large.
+#[tauri::command]
+#[specta::specta]
+pub async fn search_post_process_models(
+    app: AppHandle,
+    provider_id: String,
+    filter: ModelSearchFilter,
+) -> Result<Vec<PostProcessModelInfo>, String> {
+    let settings = settings::get_settings(&app);
+
+    let provider = settings
+        .post_process_providers
+        .iter()
+        .find(|p| p.id == provider_id)
+        .ok_or_else(|| format!("Provider '{}' not found", provider_id))?;
+
+    if provider.id == APPLE_INTELLIGENCE_PROVIDER_ID {
+        return Err("Model search is not supported for Apple Intelligence".to_string());
+    }
+
+    let api_key = settings
+        .post_process_api_keys
+        .get(&provider_id)
+        .cloned()
+        .unwrap_or_default();
+
+    crate::llm_client::search_models(provider, api_key, &filter).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_openrouter_routing_setting(
+    app: AppHandle,
+    routing: OpenRouterRouting,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.openrouter_routing = routing;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn set_post_process_selected_prompt(app: AppHandle, id: String) -> Result<(), String> {
@@ -1022,6 +1967,18 @@ pub fn set_post_process_selected_prompt(app: AppHandle, id: String) -> Result<()
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_post_process_system_prompt_setting(
+    app: AppHandle,
+    system_prompt: String,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.post_process_system_prompt = system_prompt;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_mute_while_recording_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
@@ -1044,11 +2001,11 @@ pub fn change_append_trailing_space_setting(app: AppHandle, enabled: bool) -> Re
 #[specta::specta]
 pub fn change_app_language_setting(app: AppHandle, language: String) -> Result<(), String> {
     let mut settings = settings::get_settings(&app);
-    settings.app_language = language.clone();
+    settings.app_language = language;
     settings::write_settings(&app, settings);
 
     // Refresh the tray menu with the new language
-    tray::update_tray_menu(&app, &tray::TrayIconState::Idle, Some(&language));
+    crate::dictation::refresh_tray_menu(&app);
 
     Ok(())
 }
@@ -1065,3 +2022,71 @@ pub fn change_show_tray_icon_setting(app: AppHandle, enabled: bool) -> Result<()
 
     Ok(())
 }
+
+/// Set how many recent transcripts the tray menu lists, 0 to hide them
+#[tauri::command]
+#[specta::specta]
+pub fn change_tray_recent_transcripts_setting(app: AppHandle, count: usize) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.tray_recent_transcripts = count;
+    settings::write_settings(&app, settings);
+
+    crate::dictation::refresh_tray_menu(&app);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding() -> ShortcutBinding {
+        settings::get_default_settings()
+            .bindings
+            .get("undo_last_dictation")
+            .cloned()
+            .unwrap()
+    }
+
+    #[test]
+    fn feature_shortcut_errors_are_returned() {
+        let result = sync_feature_shortcut(Some(binding()), false, true, |_, _| {
+            Err("Shortcut 'ctrl+z' is already in use".to_string())
+        });
+        assert_eq!(
+            result,
+            Err("Shortcut 'ctrl+z' is already in use".to_string())
+        );
+    }
+
+    #[test]
+    fn feature_shortcut_follows_the_toggle() {
+        let mut calls = Vec::new();
+        sync_feature_shortcut(Some(binding()), false, true, |b, enabled| {
+            calls.push((b.id, enabled));
+            Ok(())
+        })
+        .unwrap();
+        sync_feature_shortcut(Some(binding()), true, false, |b, enabled| {
+            calls.push((b.id, enabled));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(
+            calls,
+            vec![
+                ("undo_last_dictation".to_string(), true),
+                ("undo_last_dictation".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn unchanged_or_missing_feature_shortcut_is_left_alone() {
+        let untouched = |_: ShortcutBinding, _: bool| -> Result<(), String> {
+            panic!("shortcut should not be touched")
+        };
+        assert!(sync_feature_shortcut(Some(binding()), true, true, untouched).is_ok());
+        assert!(sync_feature_shortcut(None, false, true, untouched).is_ok());
+    }
+}
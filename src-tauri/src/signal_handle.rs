@@ -1,5 +1,13 @@
+use crate::audio_toolkit::decode_audio_samples;
+use crate::dictation;
+use crate::events::DictationState;
+use crate::managers::audio::AudioRecordingManager;
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::get_settings;
 use crate::TranscriptionCoordinator;
 use log::{debug, warn};
+use std::path::Path;
+use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 
 #[cfg(unix)]
@@ -19,6 +27,59 @@ pub fn send_transcription_input(app: &AppHandle, binding_id: &str, source: &str)
     }
 }
 
+fn is_recording(app: &AppHandle) -> bool {
+    app.state::<Arc<AudioRecordingManager>>().is_recording()
+}
+
+/// Starts recording for an external trigger. Unlike a toggle, this never
+/// stops a recording that's already running.
+pub fn start_recording(app: &AppHandle, post_process: bool, source: &str) -> Result<(), String> {
+    if is_recording(app) {
+        return Err("Already recording".to_string());
+    }
+    let binding_id = if post_process {
+        "transcribe_with_post_process"
+    } else {
+        "transcribe"
+    };
+    send_transcription_input(app, binding_id, source);
+    Ok(())
+}
+
+/// Stops the recording in progress, whichever binding started it
+pub fn stop_recording(app: &AppHandle, source: &str) -> Result<(), String> {
+    if !is_recording(app) {
+        return Err("Not recording".to_string());
+    }
+    if let Some(c) = app.try_state::<TranscriptionCoordinator>() {
+        c.stop_recording(source);
+    }
+    Ok(())
+}
+
+/// Transcribes an audio file with the selected model. Blocks until it's done.
+pub fn transcribe_file(app: &AppHandle, path: &Path) -> Result<String, String> {
+    let data =
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let samples = decode_audio_samples(data, extension)
+        .map_err(|e| format!("Unsupported audio file: {}", e))?;
+
+    // Shown in the tray and overlay unless a dictation already is
+    let shown = dictation::current() == DictationState::Idle
+        && dictation::transition(app, DictationState::Transcribing);
+    let tm = app.state::<Arc<TranscriptionManager>>();
+    tm.initiate_model_load();
+    let result = tm
+        .transcribe(Arc::new(samples), &get_settings(app))
+        .map(|output| output.full_text())
+        .map_err(|e| format!("Transcription failed: {}", e));
+    if shown {
+        dictation::transition(app, DictationState::Idle);
+    }
+    result
+}
+
 #[cfg(unix)]
 pub fn setup_signal_handler(app_handle: AppHandle, mut signals: Signals) {
     debug!("Signal handlers registered (SIGUSR1, SIGUSR2)");
@@ -0,0 +1,182 @@
+//! Context-aware spacing and capitalization for inserted transcripts.
+//!
+//! Consecutive dictations are transcribed independently, so each one starts
+//! with a capital letter and no leading space. Before inserting, we look at
+//! the text to the left of the caret (via the accessibility API when
+//! available, otherwise our own previous output) and adjust the transcript so
+//! it joins cleanly.
+
+use crate::accessibility;
+use crate::active_app::ActiveApp;
+use once_cell::sync::Lazy;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Number of characters of caret context requested from the accessibility API
+const CONTEXT_CHARS: usize = 16;
+
+/// Previous output is only trusted as context for this long
+const TRACKED_OUTPUT_TIMEOUT: Duration = Duration::from_secs(120);
+
+const SENTENCE_END: &[char] = &['.', '!', '?'];
+const NO_SPACE_AFTER: &[char] = &['(', '[', '{', '"', '\'', '/', '-', '@', '#'];
+const NO_SPACE_BEFORE: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}'];
+
+/// Common words a transcript starts with only because it starts a sentence.
+/// Any other capitalized first word may be a name and is left alone.
+const SENTENCE_STARTERS: &[&str] = &[
+    "a", "also", "an", "and", "are", "as", "at", "because", "but", "can", "could", "do", "for",
+    "from", "he", "her", "here", "his", "how", "if", "in", "is", "it", "its", "it's", "just",
+    "maybe", "my", "no", "not", "of", "on", "or", "our", "she", "so", "that", "the", "their",
+    "then", "there", "these", "they", "this", "those", "to", "was", "we", "were", "what", "when",
+    "where", "which", "while", "who", "why", "will", "with", "would", "yes", "you", "your",
+];
+
+struct TrackedOutput {
+    tail: String,
+    app: Option<ActiveApp>,
+    at: Instant,
+}
+
+static LAST_OUTPUT: Lazy<Mutex<Option<TrackedOutput>>> = Lazy::new(|| Mutex::new(None));
+
+/// Text immediately before the caret. `Some("")` means the caret is at the
+/// start of an empty field; `None` means the context is unknown.
+pub fn preceding_context(active_app: Option<&ActiveApp>) -> Option<String> {
+    if let Some(text) = accessibility::text_before_caret(CONTEXT_CHARS) {
+        return Some(text);
+    }
+
+    let last = LAST_OUTPUT.lock().unwrap_or_else(PoisonError::into_inner);
+    last.as_ref()
+        .filter(|output| output.at.elapsed() < TRACKED_OUTPUT_TIMEOUT)
+        .filter(|output| output.app.as_ref() == active_app)
+        .map(|output| output.tail.clone())
+}
+
+/// Remembers what was inserted so the next dictation can continue from it
+pub fn record_output(text: &str, active_app: Option<&ActiveApp>) {
+    let skip = text.chars().count().saturating_sub(CONTEXT_CHARS);
+    *LAST_OUTPUT.lock().unwrap_or_else(PoisonError::into_inner) = Some(TrackedOutput {
+        tail: text.chars().skip(skip).collect(),
+        app: active_app.cloned(),
+        at: Instant::now(),
+    });
+}
+
+fn capitalize_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Lowercases a capitalized first word that is a common sentence starter
+/// ("And" -> "and"). Names, acronyms and "I" are left alone.
+fn decapitalize_first_word(text: &str) -> String {
+    let first_word = text.split_whitespace().next().unwrap_or_default();
+    let word = first_word
+        .trim_end_matches(|c: char| !c.is_alphanumeric())
+        .replace('\u{2019}', "'");
+    let mut chars = word.chars();
+    let is_title_case = chars.next().is_some_and(char::is_uppercase)
+        && chars.all(|c| !c.is_alphabetic() || c.is_lowercase());
+    if !is_title_case || !SENTENCE_STARTERS.contains(&word.to_lowercase().as_str()) {
+        return text.to_string();
+    }
+
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Adjusts leading spacing and capitalization of `text` so it continues the
+/// text before the caret.
+pub fn adjust_for_context(text: &str, preceding: Option<&str>) -> String {
+    let Some(preceding) = preceding else {
+        return text.to_string();
+    };
+    if text.is_empty() {
+        return String::new();
+    }
+
+    let trimmed = preceding.trim_end();
+    let separated = trimmed.len() < preceding.len();
+    let last = trimmed.chars().last();
+    let at_sentence_start =
+        last.is_none_or(|c| SENTENCE_END.contains(&c)) || preceding.ends_with('\n');
+
+    let cased = if at_sentence_start {
+        capitalize_first(text)
+    } else if last.is_some_and(|c| c.is_alphanumeric() || ",;:".contains(c)) {
+        decapitalize_first_word(text)
+    } else {
+        text.to_string()
+    };
+
+    let needs_space = !separated
+        && last.is_some_and(|c| !NO_SPACE_AFTER.contains(&c))
+        && cased
+            .chars()
+            .next()
+            .is_some_and(|c| !c.is_whitespace() && !NO_SPACE_BEFORE.contains(&c));
+
+    if needs_space {
+        format!(" {}", cased)
+    } else {
+        cased
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_context_leaves_text_unchanged() {
+        assert_eq!(adjust_for_context("Hello world", None), "Hello world");
+    }
+
+    #[test]
+    fn joins_after_sentence_end() {
+        assert_eq!(
+            adjust_for_context("world is big.", Some("Hello.")),
+            " World is big."
+        );
+        assert_eq!(adjust_for_context("Next one.", Some("Done. ")), "Next one.");
+        assert_eq!(adjust_for_context("start here", Some("")), "Start here");
+    }
+
+    #[test]
+    fn continues_mid_sentence() {
+        assert_eq!(
+            adjust_for_context("And then we left.", Some("we ate dinner,")),
+            " and then we left."
+        );
+        assert_eq!(
+            adjust_for_context("I think so.", Some("well")),
+            " I think so."
+        );
+        assert_eq!(
+            adjust_for_context("NASA called.", Some("then")),
+            " NASA called."
+        );
+        assert_eq!(
+            adjust_for_context("Sarah said hi.", Some("and then")),
+            " Sarah said hi."
+        );
+        assert_eq!(
+            adjust_for_context("It's fine.", Some("well,")),
+            " it's fine."
+        );
+    }
+
+    #[test]
+    fn no_space_inside_brackets_or_before_punctuation() {
+        assert_eq!(adjust_for_context("Aside", Some("note (")), "Aside");
+        assert_eq!(adjust_for_context(", right?", Some("okay")), ", right?");
+    }
+}
@@ -0,0 +1,322 @@
+//! WebSocket server for Stream Deck plugins.
+//!
+//! A plugin connects to `ws://127.0.0.1:<port>` with the HTTP API token, as
+//! `Authorization: Bearer <token>` or `?token=`. It sends actions as JSON:
+//!
+//! - `{"action": "start"}`, with `"post_process": true` to post-process
+//! - `{"action": "stop"}`, `{"action": "cancel"}` and `{"action": "toggle"}`
+//! - `{"action": "select_profile", "id": "..."}`
+//! - `{"action": "select_prompt", "id": "..."}`
+//! - `{"action": "get_state"}`
+//!
+//! Handy answers with a `state` message on connect, after every action and
+//! whenever the dictation state, profiles or prompts change, so buttons can
+//! show the right icon and pickers the right choices. Failed actions are
+//! answered with `{"type": "error", "message": "..."}`.
+
+use crate::api_server;
+use crate::events::{AppEvent, DictationState};
+use crate::profiles;
+use crate::settings::get_settings;
+use crate::signal_handle;
+use crate::tray;
+use crate::utils;
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Listener};
+use tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tungstenite::{Message, WebSocket};
+
+/// What plugin actions are logged as
+const SOURCE: &str = "Stream Deck";
+
+/// How often waiting sockets check for events and shutdown
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Set to stop the running server and its connections
+static STOP: Lazy<Mutex<Option<Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum DeckAction {
+    Start {
+        #[serde(default)]
+        post_process: bool,
+    },
+    Stop,
+    Cancel,
+    Toggle,
+    SelectProfile {
+        id: String,
+    },
+    SelectPrompt {
+        id: String,
+    },
+    GetState,
+}
+
+/// A profile or prompt a plugin can offer to select
+#[derive(Serialize, Debug)]
+struct Choice {
+    id: String,
+    name: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename = "state")]
+struct StateMessage {
+    state: DictationState,
+    post_process_enabled: bool,
+    active_profile_id: Option<String>,
+    profiles: Vec<Choice>,
+    selected_prompt_id: Option<String>,
+    prompts: Vec<Choice>,
+}
+
+fn state_message(app: &AppHandle, state: DictationState) -> String {
+    let settings = get_settings(app);
+    let message = StateMessage {
+        state,
+        post_process_enabled: settings.post_process_enabled,
+        active_profile_id: settings.active_profile_id,
+        profiles: settings
+            .profiles
+            .into_iter()
+            .map(|p| Choice {
+                id: p.id,
+                name: p.name,
+            })
+            .collect(),
+        selected_prompt_id: settings.post_process_selected_prompt_id,
+        prompts: settings
+            .post_process_prompts
+            .into_iter()
+            .map(|p| Choice {
+                id: p.id,
+                name: p.name,
+            })
+            .collect(),
+    };
+    serde_json::to_string(&message).unwrap_or_default()
+}
+
+fn error_message(message: &str) -> String {
+    json!({ "type": "error", "message": message }).to_string()
+}
+
+fn run(app: &AppHandle, action: DeckAction) -> Result<(), String> {
+    match action {
+        DeckAction::Start { post_process } => {
+            signal_handle::start_recording(app, post_process, SOURCE)
+        }
+        DeckAction::Stop => signal_handle::stop_recording(app, SOURCE),
+        DeckAction::Cancel => {
+            utils::cancel_current_operation(app);
+            Ok(())
+        }
+        DeckAction::Toggle => {
+            signal_handle::send_transcription_input(app, "transcribe", SOURCE);
+            Ok(())
+        }
+        DeckAction::SelectProfile { id } => profiles::switch_profile(app, &id),
+        DeckAction::SelectPrompt { id } => {
+            if !get_settings(app)
+                .post_process_prompts
+                .iter()
+                .any(|prompt| prompt.id == id)
+            {
+                return Err(format!("Prompt with id '{}' not found", id));
+            }
+            tray::select_prompt(app, id);
+            Ok(())
+        }
+        DeckAction::GetState => Ok(()),
+    }
+}
+
+/// Closes the listening socket and, when the plugin is enabled, opens it again
+/// on the configured port
+pub fn restart(app: &AppHandle) -> Result<(), String> {
+    let mut stop = STOP.lock().unwrap();
+    if let Some(running) = stop.take() {
+        running.store(true, Ordering::Relaxed);
+        info!("Stopped Stream Deck server");
+    }
+
+    let settings = get_settings(app).stream_deck;
+    if !settings.enabled {
+        return Ok(());
+    }
+    api_server::token()?;
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, settings.port));
+    let listener = TcpListener::bind(addr)
+        .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+        .map_err(|e| format!("Failed to start Stream Deck server on {}: {}", addr, e))?;
+    info!("Stream Deck server listening on {}", addr);
+
+    let stopped = Arc::new(AtomicBool::new(false));
+    let app = app.clone();
+    let flag = Arc::clone(&stopped);
+    thread::spawn(move || {
+        while !flag.load(Ordering::Relaxed) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let app = app.clone();
+                    let flag = Arc::clone(&flag);
+                    thread::spawn(move || serve(&app, stream, &flag));
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+                Err(e) => warn!("Stream Deck server failed to accept: {}", e),
+            }
+        }
+        debug!("Stream Deck listener exited");
+    });
+    *stop = Some(stopped);
+    Ok(())
+}
+
+/// Accepts the handshake if it carries the API token
+fn accept(stream: TcpStream) -> Option<WebSocket<TcpStream>> {
+    let token = api_server::stored_token().unwrap_or_default();
+    let check = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        let authorization = request
+            .headers()
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+            .or_else(|| {
+                request
+                    .uri()
+                    .query()
+                    .and_then(|query| {
+                        query
+                            .split('&')
+                            .find_map(|pair| pair.strip_prefix("token="))
+                    })
+                    .map(|token| format!("Bearer {}", token))
+            });
+        if !token.is_empty() && api_server::authorized(authorization.as_deref(), &token) {
+            Ok(response)
+        } else {
+            let mut refused = ErrorResponse::new(Some("Missing or invalid API token".to_string()));
+            *refused.status_mut() = tungstenite::http::StatusCode::UNAUTHORIZED;
+            Err(refused)
+        }
+    };
+
+    stream.set_nonblocking(false).ok()?;
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT)).ok()?;
+    match tungstenite::accept_hdr(stream, check) {
+        Ok(socket) => Some(socket),
+        Err(e) => {
+            debug!("Refused Stream Deck connection: {}", e);
+            None
+        }
+    }
+}
+
+fn serve(app: &AppHandle, stream: TcpStream, stopped: &AtomicBool) {
+    let Some(mut socket) = accept(stream) else {
+        return;
+    };
+    if let Err(e) = socket.get_ref().set_read_timeout(Some(POLL_INTERVAL)) {
+        warn!("Failed to set up Stream Deck connection: {}", e);
+        return;
+    }
+
+    let (tx, rx) = mpsc::channel::<AppEvent>();
+    let listener = app.listen_any("app-event", move |event| {
+        if let Ok(event) = serde_json::from_str::<AppEvent>(event.payload()) {
+            let _ = tx.send(event);
+        }
+    });
+    debug!("Stream Deck plugin connected");
+
+    let mut state = DictationState::current();
+    let mut outgoing = vec![state_message(app, state)];
+
+    while !stopped.load(Ordering::Relaxed) {
+        for message in outgoing.drain(..) {
+            if let Err(e) = socket.send(Message::text(message)) {
+                debug!("Stream Deck plugin disconnected: {}", e);
+                app.unlisten(listener);
+                return;
+            }
+        }
+
+        match socket.read() {
+            Ok(Message::Text(text)) => match serde_json::from_str::<DeckAction>(&text) {
+                Ok(action) => {
+                    debug!("Stream Deck action: {:?}", action);
+                    match run(app, action) {
+                        Ok(()) => outgoing.push(state_message(app, state)),
+                        Err(e) => outgoing.push(error_message(&e)),
+                    }
+                }
+                Err(e) => outgoing.push(error_message(&format!("Invalid action: {}", e))),
+            },
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => {
+                debug!("Stream Deck plugin disconnected: {}", e);
+                break;
+            }
+        }
+
+        // Settings changes may rename or switch profiles and prompts
+        let mut changed = false;
+        while let Ok(event) = rx.try_recv() {
+            if let Some(next) = DictationState::after(&event) {
+                changed |= next != state;
+                state = next;
+            } else if matches!(event, AppEvent::SettingsChanged(_)) {
+                changed = true;
+            }
+        }
+        if changed {
+            outgoing.push(state_message(app, state));
+        }
+    }
+    app.unlisten(listener);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_actions() {
+        assert_eq!(
+            serde_json::from_str::<DeckAction>(r#"{"action": "start", "post_process": true}"#)
+                .unwrap(),
+            DeckAction::Start { post_process: true }
+        );
+        assert_eq!(
+            serde_json::from_str::<DeckAction>(r#"{"action": "start"}"#).unwrap(),
+            DeckAction::Start {
+                post_process: false
+            }
+        );
+        assert_eq!(
+            serde_json::from_str::<DeckAction>(r#"{"action": "select_prompt", "id": "p1"}"#)
+                .unwrap(),
+            DeckAction::SelectPrompt {
+                id: "p1".to_string()
+            }
+        );
+        assert!(serde_json::from_str::<DeckAction>(r#"{"action": "select_profile"}"#).is_err());
+    }
+}
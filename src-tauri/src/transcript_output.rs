@@ -0,0 +1,238 @@
+//! Output targets for transcripts other than pasting into the focused app.
+
+use chrono::{DateTime, Local};
+use log::info;
+use once_cell::sync::Lazy;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Serializes appends from overlapping transcriptions within this process
+static FILE_APPEND_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Journal entry template used when none is configured. Supports `{date}`,
+/// `{time}`, `{app}` and `{text}`.
+pub const DEFAULT_JOURNAL_TEMPLATE: &str = "\n### {time} · {app}\n\n{text}\n";
+
+/// Formats a transcript as a Markdown entry with a timestamp header
+fn format_file_entry(text: &str, timestamp: DateTime<Local>) -> String {
+    format!(
+        "\n### {}\n\n{}\n",
+        timestamp.format("%Y-%m-%d %H:%M"),
+        text.trim()
+    )
+}
+
+/// Fills in a journal template. The transcript goes in last so braces in
+/// dictated text are never treated as placeholders.
+fn render_journal_entry(
+    template: &str,
+    text: &str,
+    app: Option<&str>,
+    timestamp: DateTime<Local>,
+) -> String {
+    template
+        .replace("{date}", &timestamp.format("%Y-%m-%d").to_string())
+        .replace("{time}", &timestamp.format("%H:%M").to_string())
+        .replace("{app}", app.unwrap_or("Unknown app"))
+        .replace("{text}", text.trim())
+}
+
+/// Appends a timestamped transcript entry to the given file, creating it if needed.
+/// The entry is written with a single append so concurrent writers (including
+/// other apps like note editors) never see a partially interleaved entry.
+pub fn append_to_file(path: &str, text: &str) -> Result<(), String> {
+    let path = Path::new(path.trim());
+    if path.as_os_str().is_empty() {
+        return Err("Output file path is not configured".to_string());
+    }
+
+    let entry = format_file_entry(text, Local::now());
+    let _guard = FILE_APPEND_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    append_entry(path, &entry)?;
+
+    info!("Appended transcript to {}", path.display());
+    Ok(())
+}
+
+/// Appends a transcript to today's file in the journal directory, named
+/// `YYYY-MM-DD.md` and started with a heading for the day.
+pub fn append_to_journal(
+    dir: &str,
+    template: &str,
+    text: &str,
+    app: Option<&str>,
+) -> Result<PathBuf, String> {
+    let dir = Path::new(dir.trim());
+    if dir.as_os_str().is_empty() {
+        return Err("Journal directory is not configured".to_string());
+    }
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create '{}': {}", dir.display(), e))?;
+
+    let now = Local::now();
+    let path = dir.join(format!("{}.md", now.format("%Y-%m-%d")));
+    let template = if template.trim().is_empty() {
+        DEFAULT_JOURNAL_TEMPLATE
+    } else {
+        template
+    };
+    let mut entry = render_journal_entry(template, text, app, now);
+
+    let _guard = FILE_APPEND_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if !path.exists() {
+        entry.insert_str(0, &format!("# {}\n", now.format("%A, %B %-d, %Y")));
+    }
+    append_entry(&path, &entry)?;
+
+    Ok(path)
+}
+
+/// Writes `entry` to the end of `path`. Callers hold [`FILE_APPEND_LOCK`].
+fn append_entry(path: &Path, entry: &str) -> Result<(), String> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open '{}': {}", path.display(), e))?;
+    file.write_all(entry.as_bytes())
+        .and_then(|_| file.flush())
+        .map_err(|e| format!("Failed to write to '{}': {}", path.display(), e))
+}
+
+/// Writes the transcript as a single line to a named pipe or socket so scripts
+/// can consume dictation. On Unix the path may be a FIFO or a Unix domain
+/// socket; on Windows it is a named pipe such as `\\.\pipe\handy`.
+pub fn write_to_pipe(path: &str, text: &str) -> Result<(), String> {
+    let path = Path::new(path.trim());
+    let line = format!("{}\n", text.trim_end_matches('\n'));
+    platform_write_to_pipe(path, line.as_bytes())
+        .map_err(|e| format!("Failed to write to '{}': {}", path.display(), e))
+}
+
+#[cfg(unix)]
+fn platform_write_to_pipe(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    use std::io::{Error, ErrorKind};
+    use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+    use std::os::unix::net::UnixStream;
+
+    let file_type = std::fs::metadata(path)?.file_type();
+    if file_type.is_socket() {
+        let mut stream = UnixStream::connect(path)?;
+        stream.write_all(bytes)
+    } else if file_type.is_fifo() {
+        // Non-blocking open fails right away when nobody is reading instead
+        // of stalling the pipeline until a reader shows up
+        let mut fifo = OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)
+            .map_err(|e| match e.raw_os_error() {
+                Some(libc::ENXIO) => Error::new(ErrorKind::NotConnected, "no reader is attached"),
+                _ => e,
+            })?;
+        fifo.write_all(bytes)
+    } else {
+        Err(Error::new(
+            ErrorKind::InvalidInput,
+            "path is not a named pipe or Unix socket",
+        ))
+    }
+}
+
+#[cfg(not(unix))]
+fn platform_write_to_pipe(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let mut pipe = OpenOptions::new().write(true).open(path)?;
+    pipe.write_all(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn entry_has_timestamp_header() {
+        let timestamp = Local.with_ymd_and_hms(2025, 3, 14, 9, 5, 0).unwrap();
+        assert_eq!(
+            format_file_entry("  hello world ", timestamp),
+            "\n### 2025-03-14 09:05\n\nhello world\n"
+        );
+    }
+
+    #[test]
+    fn renders_journal_template() {
+        let timestamp = Local.with_ymd_and_hms(2025, 3, 14, 9, 5, 0).unwrap();
+        assert_eq!(
+            render_journal_entry(
+                DEFAULT_JOURNAL_TEMPLATE,
+                " say {app} ",
+                Some("Notes"),
+                timestamp
+            ),
+            "\n### 09:05 · Notes\n\nsay {app}\n"
+        );
+        assert_eq!(
+            render_journal_entry("- {date} {app}: {text}\n", "hi", None, timestamp),
+            "- 2025-03-14 Unknown app: hi\n"
+        );
+    }
+
+    #[test]
+    fn journal_starts_each_day_with_a_heading() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal_dir = dir.path().join("journal");
+        let journal_str = journal_dir.to_str().unwrap();
+
+        let path = append_to_journal(journal_str, "", "first", None).unwrap();
+        append_to_journal(journal_str, "", "second", Some("Mail")).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("# "));
+        assert_eq!(contents.matches("\n### ").count(), 2);
+        assert!(contents.ends_with("· Mail\n\nsecond\n"));
+    }
+
+    #[test]
+    fn appends_entries_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daily.md");
+        std::fs::write(&path, "# Notes\n").unwrap();
+
+        let path_str = path.to_str().unwrap();
+        append_to_file(path_str, "first").unwrap();
+        append_to_file(path_str, "second").unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("# Notes\n"));
+        let first = contents.find("first").unwrap();
+        let second = contents.find("second").unwrap();
+        assert!(first < second);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_line_to_unix_socket() {
+        use std::io::Read;
+        use std::os::unix::net::UnixListener;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("handy.sock");
+        let listener = UnixListener::bind(&path).unwrap();
+
+        write_to_pipe(path.to_str().unwrap(), "hello there").unwrap();
+
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut received = String::new();
+        stream.read_to_string(&mut received).unwrap();
+        assert_eq!(received, "hello there\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_regular_files() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        assert!(write_to_pipe(file.path().to_str().unwrap(), "text").is_err());
+    }
+}
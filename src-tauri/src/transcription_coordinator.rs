@@ -1,4 +1,5 @@
 use crate::actions::ACTION_MAP;
+use crate::managers::analytics::{self, UsageCategory};
 use crate::managers::audio::AudioRecordingManager;
 use log::{debug, error, warn};
 use std::sync::mpsc::{self, Sender};
@@ -20,6 +21,10 @@ enum Command {
     Cancel {
         recording_was_active: bool,
     },
+    /// Stop the recording in progress, whichever binding started it
+    Stop {
+        source: String,
+    },
     ProcessingFinished,
 }
 
@@ -38,7 +43,13 @@ pub struct TranscriptionCoordinator {
 }
 
 pub fn is_transcribe_binding(id: &str) -> bool {
-    id == "transcribe" || id == "transcribe_with_post_process"
+    matches!(
+        id,
+        "transcribe"
+            | "transcribe_with_post_process"
+            | "transcribe_to_file"
+            | "transcribe_incognito"
+    )
 }
 
 impl TranscriptionCoordinator {
@@ -101,6 +112,12 @@ impl TranscriptionCoordinator {
                                 stage = Stage::Idle;
                             }
                         }
+                        Command::Stop { source } => {
+                            if let Stage::Recording(id) = &stage {
+                                let binding_id = id.clone();
+                                stop(&app, &mut stage, &binding_id, &source);
+                            }
+                        }
                         Command::ProcessingFinished => {
                             stage = Stage::Idle;
                         }
@@ -151,6 +168,19 @@ impl TranscriptionCoordinator {
         }
     }
 
+    /// Stop the recording in progress, e.g. from the HTTP API
+    pub fn stop_recording(&self, source: &str) {
+        if self
+            .tx
+            .send(Command::Stop {
+                source: source.to_string(),
+            })
+            .is_err()
+        {
+            warn!("Transcription coordinator channel closed");
+        }
+    }
+
     pub fn notify_processing_finished(&self) {
         if self.tx.send(Command::ProcessingFinished).is_err() {
             warn!("Transcription coordinator channel closed");
@@ -168,6 +198,7 @@ fn start(app: &AppHandle, stage: &mut Stage, binding_id: &str, hotkey_string: &s
         .try_state::<Arc<AudioRecordingManager>>()
         .map_or(false, |a| a.is_recording())
     {
+        analytics::track(app, UsageCategory::Action, binding_id);
         *stage = Stage::Recording(binding_id.to_string());
     } else {
         debug!("Start for '{binding_id}' did not begin recording; staying idle");
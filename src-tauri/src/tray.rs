@@ -1,11 +1,19 @@
+use crate::active_app;
+use crate::events::{self, ErrorSource};
+use crate::incognito;
 use crate::managers::history::{HistoryEntry, HistoryManager};
+use crate::managers::model::ModelManager;
 use crate::managers::transcription::TranscriptionManager;
+use crate::pause;
 use crate::settings;
-use crate::tray_i18n::get_tray_translations;
+use crate::tray_i18n::{get_tray_translations, TrayStrings};
+use crate::utils;
 use log::{error, info, warn};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
 use tauri::image::Image;
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::tray::TrayIcon;
 use tauri::{AppHandle, Manager, Theme};
 use tauri_plugin_clipboard_manager::ClipboardExt;
@@ -15,8 +23,15 @@ pub enum TrayIconState {
     Idle,
     Recording,
     Transcribing,
+    Paused,
 }
 
+/// State last shown by `change_tray_icon`
+static CURRENT_STATE: Mutex<TrayIconState> = Mutex::new(TrayIconState::Idle);
+
+/// Set while the transcription model loads
+static WARMING_UP: AtomicBool = AtomicBool::new(false);
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum AppTheme {
     Dark,
@@ -50,18 +65,27 @@ pub fn get_icon_path(theme: AppTheme, state: TrayIconState) -> &'static str {
         (AppTheme::Dark, TrayIconState::Idle) => "resources/tray_idle.png",
         (AppTheme::Dark, TrayIconState::Recording) => "resources/tray_recording.png",
         (AppTheme::Dark, TrayIconState::Transcribing) => "resources/tray_transcribing.png",
+        (AppTheme::Dark, TrayIconState::Paused) => "resources/tray_paused.png",
         // Light theme uses dark icons
         (AppTheme::Light, TrayIconState::Idle) => "resources/tray_idle_dark.png",
         (AppTheme::Light, TrayIconState::Recording) => "resources/tray_recording_dark.png",
         (AppTheme::Light, TrayIconState::Transcribing) => "resources/tray_transcribing_dark.png",
+        (AppTheme::Light, TrayIconState::Paused) => "resources/tray_paused_dark.png",
         // Colored theme uses pink icons (for Linux)
         (AppTheme::Colored, TrayIconState::Idle) => "resources/handy.png",
         (AppTheme::Colored, TrayIconState::Recording) => "resources/recording.png",
         (AppTheme::Colored, TrayIconState::Transcribing) => "resources/transcribing.png",
+        (AppTheme::Colored, TrayIconState::Paused) => "resources/paused.png",
     }
 }
 
 pub fn change_tray_icon(app: &AppHandle, icon: TrayIconState) {
+    // Going idle while paused keeps the paused icon
+    let icon = if icon == TrayIconState::Idle && pause::is_paused() {
+        TrayIconState::Paused
+    } else {
+        icon
+    };
     let tray = app.state::<TrayIcon>();
     let theme = get_current_theme(app);
 
@@ -76,10 +100,244 @@ pub fn change_tray_icon(app: &AppHandle, icon: TrayIconState) {
         .expect("failed to set icon"),
     ));
 
+    // Held while the status is set so a late timer tick can't overwrite it
+    let mut current_state = CURRENT_STATE.lock().unwrap_or_else(PoisonError::into_inner);
+    *current_state = icon.clone();
+    set_tray_status(app, &icon, None);
+    drop(current_state);
+
     // Update menu based on state
     update_tray_menu(app, &icon, None);
 }
 
+/// Status text for the tray tooltip, or `None` while idle. A model warming
+/// up shows instead of idle or transcribing, a dictation waits on it then.
+fn tray_status_text(
+    strings: &TrayStrings,
+    state: &TrayIconState,
+    elapsed: Option<Duration>,
+    warming_up: bool,
+) -> Option<String> {
+    match state {
+        TrayIconState::Idle | TrayIconState::Transcribing if warming_up => {
+            Some(strings.status_warming_up.clone())
+        }
+        TrayIconState::Idle => None,
+        TrayIconState::Recording => {
+            let secs = elapsed.unwrap_or_default().as_secs();
+            Some(format!(
+                "{} {}:{:02}",
+                strings.status_recording,
+                secs / 60,
+                secs % 60
+            ))
+        }
+        TrayIconState::Transcribing => Some(strings.status_transcribing.clone()),
+        TrayIconState::Paused => Some(strings.status_paused.clone()),
+    }
+}
+
+/// Updates the elapsed time in the tray status, unless the tray has already
+/// moved on from recording
+pub fn set_recording_elapsed(app: &AppHandle, elapsed: Duration) {
+    let current_state = CURRENT_STATE.lock().unwrap_or_else(PoisonError::into_inner);
+    if *current_state == TrayIconState::Recording {
+        set_tray_status(app, &TrayIconState::Recording, Some(elapsed));
+    }
+}
+
+/// Shows in the tray whether the transcription model is loading
+pub fn set_model_warming_up(app: &AppHandle, warming_up: bool) {
+    WARMING_UP.store(warming_up, Ordering::Relaxed);
+    let current_state = CURRENT_STATE.lock().unwrap_or_else(PoisonError::into_inner);
+    // Recording keeps its timer, which ticks the status again anyway
+    if *current_state != TrayIconState::Recording && app.try_state::<TrayIcon>().is_some() {
+        set_tray_status(app, &current_state, None);
+    }
+}
+
+/// Shows the state in the tray tooltip, and next to the icon in the macOS
+/// menu bar. While recording, `elapsed` is the time so far.
+fn set_tray_status(app: &AppHandle, state: &TrayIconState, elapsed: Option<Duration>) {
+    let settings = settings::get_settings(app);
+    let strings = get_tray_translations(Some(settings.app_language));
+    let status = tray_status_text(&strings, state, elapsed, WARMING_UP.load(Ordering::Relaxed));
+
+    let tray = app.state::<TrayIcon>();
+    let tooltip = status.as_ref().map_or_else(
+        || "Handy".to_string(),
+        |status| format!("Handy · {}", status),
+    );
+    let _ = tray.set_tooltip(Some(tooltip));
+    #[cfg(target_os = "macos")]
+    let _ = tray.set_title(status);
+}
+
+/// Menu ids of recent transcripts are this prefix followed by the entry id
+pub const RECENT_TRANSCRIPT_PREFIX: &str = "recent_transcript:";
+
+/// Longest recent transcript label before it's cut off
+const RECENT_TRANSCRIPT_LABEL_CHARS: usize = 40;
+
+fn recent_transcript_label(text: &str) -> String {
+    let first_line = text.trim().lines().next().unwrap_or_default();
+    if first_line.chars().count() > RECENT_TRANSCRIPT_LABEL_CHARS {
+        let cut: String = first_line
+            .chars()
+            .take(RECENT_TRANSCRIPT_LABEL_CHARS)
+            .collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        first_line.to_string()
+    }
+}
+
+/// Submenu with the newest transcripts, or `None` when it's turned off
+fn recent_transcripts_submenu(
+    app: &AppHandle,
+    title: &str,
+    count: usize,
+) -> Option<Submenu<tauri::Wry>> {
+    if count == 0 {
+        return None;
+    }
+    let entries = app
+        .state::<Arc<HistoryManager>>()
+        .get_recent_entries(count)
+        .unwrap_or_else(|e| {
+            error!("Failed to fetch recent transcripts for tray: {}", e);
+            Vec::new()
+        });
+
+    let items: Vec<MenuItem<tauri::Wry>> = entries
+        .iter()
+        .filter_map(|entry| {
+            MenuItem::with_id(
+                app,
+                format!("{}{}", RECENT_TRANSCRIPT_PREFIX, entry.id),
+                recent_transcript_label(last_transcript_text(entry)),
+                true,
+                None::<&str>,
+            )
+            .ok()
+        })
+        .collect();
+    let items: Vec<&dyn IsMenuItem<tauri::Wry>> = items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<tauri::Wry>)
+        .collect();
+
+    Submenu::with_id_and_items(app, "recent_transcripts", title, !items.is_empty(), &items).ok()
+}
+
+/// Menu ids of the model and prompt choices are these prefixes followed by
+/// the model or prompt id
+pub const SELECT_MODEL_PREFIX: &str = "select_model:";
+pub const SELECT_PROMPT_PREFIX: &str = "select_prompt:";
+pub const SELECT_PROFILE_PREFIX: &str = "select_profile:";
+
+/// Submenu of `(id, label, checked)` choices, or `None` when there are none
+fn choice_submenu(
+    app: &AppHandle,
+    id: &str,
+    title: &str,
+    choices: Vec<(String, String, bool)>,
+) -> Option<Submenu<tauri::Wry>> {
+    if choices.is_empty() {
+        return None;
+    }
+    let items: Vec<CheckMenuItem<tauri::Wry>> = choices
+        .into_iter()
+        .filter_map(|(id, label, checked)| {
+            CheckMenuItem::with_id(app, id, label, true, checked, None::<&str>).ok()
+        })
+        .collect();
+    let items: Vec<&dyn IsMenuItem<tauri::Wry>> = items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<tauri::Wry>)
+        .collect();
+
+    Submenu::with_id_and_items(app, id, title, true, &items).ok()
+}
+
+/// Downloaded models, with the selected one checked
+fn model_submenu(app: &AppHandle, title: &str, selected: &str) -> Option<Submenu<tauri::Wry>> {
+    let mut models: Vec<_> = app
+        .state::<Arc<ModelManager>>()
+        .get_available_models()
+        .into_iter()
+        .filter(|model| model.is_downloaded)
+        .collect();
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let choices = models
+        .into_iter()
+        .map(|model| {
+            let checked = model.id == selected;
+            (
+                format!("{}{}", SELECT_MODEL_PREFIX, model.id),
+                model.name,
+                checked,
+            )
+        })
+        .collect();
+    choice_submenu(app, "models", title, choices)
+}
+
+/// Post-processing prompts, with the selected one checked. Hidden while
+/// post-processing is off.
+fn prompt_submenu(
+    app: &AppHandle,
+    title: &str,
+    settings: &settings::AppSettings,
+) -> Option<Submenu<tauri::Wry>> {
+    if !settings.post_process_enabled {
+        return None;
+    }
+    let choices = settings
+        .post_process_prompts
+        .iter()
+        .map(|prompt| {
+            (
+                format!("{}{}", SELECT_PROMPT_PREFIX, prompt.id),
+                prompt.name.clone(),
+                settings.post_process_selected_prompt_id.as_deref() == Some(prompt.id.as_str()),
+            )
+        })
+        .collect();
+    choice_submenu(app, "prompts", title, choices)
+}
+
+/// Saved profiles, with the active one checked. Hidden until a profile is created.
+fn profile_submenu(
+    app: &AppHandle,
+    title: &str,
+    settings: &settings::AppSettings,
+) -> Option<Submenu<tauri::Wry>> {
+    let choices = settings
+        .profiles
+        .iter()
+        .map(|profile| {
+            (
+                format!("{}{}", SELECT_PROFILE_PREFIX, profile.id),
+                profile.name.clone(),
+                settings.active_profile_id.as_deref() == Some(profile.id.as_str()),
+            )
+        })
+        .collect();
+    choice_submenu(app, "profiles", title, choices)
+}
+
+/// Inserts `item` right after the item with id `after`, or first if it's missing
+fn insert_after(menu: &Menu<tauri::Wry>, after: &str, item: &dyn IsMenuItem<tauri::Wry>) {
+    let position = menu
+        .items()
+        .ok()
+        .and_then(|items| items.iter().position(|item| item.id().as_ref() == after))
+        .map_or(0, |index| index + 1);
+    let _ = menu.insert(item, position);
+}
+
 pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState, locale: Option<&str>) {
     let settings = settings::get_settings(app);
 
@@ -124,6 +382,43 @@ pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState, locale: Option<&
         None::<&str>,
     )
     .expect("failed to create copy last transcript item");
+    let paste_last_transcript_i = MenuItem::with_id(
+        app,
+        "paste_last_transcript",
+        &strings.paste_last_transcript,
+        true,
+        None::<&str>,
+    )
+    .expect("failed to create paste last transcript item");
+    let target_i = match active_app::locked_target() {
+        Some(_) => MenuItem::with_id(
+            app,
+            "unlock_target",
+            &strings.unlock_target,
+            true,
+            None::<&str>,
+        ),
+        None => MenuItem::with_id(app, "lock_target", &strings.lock_target, true, None::<&str>),
+    }
+    .expect("failed to create target lock item");
+    let incognito_i = CheckMenuItem::with_id(
+        app,
+        "incognito",
+        &strings.incognito,
+        true,
+        incognito::is_session_enabled(),
+        None::<&str>,
+    )
+    .expect("failed to create incognito item");
+    let pause_i = CheckMenuItem::with_id(
+        app,
+        "pause",
+        &strings.pause,
+        true,
+        pause::is_paused(),
+        None::<&str>,
+    )
+    .expect("failed to create pause item");
     let model_loaded = app.state::<Arc<TranscriptionManager>>().is_model_loaded();
     let unload_model_i = MenuItem::with_id(
         app,
@@ -149,6 +444,10 @@ pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState, locale: Option<&
                     &cancel_i,
                     &separator(),
                     &copy_last_transcript_i,
+                    &paste_last_transcript_i,
+                    &target_i,
+                    &incognito_i,
+                    &pause_i,
                     &separator(),
                     &settings_i,
                     &check_updates_i,
@@ -158,12 +457,16 @@ pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState, locale: Option<&
             )
             .expect("failed to create menu")
         }
-        TrayIconState::Idle => Menu::with_items(
+        TrayIconState::Idle | TrayIconState::Paused => Menu::with_items(
             app,
             &[
                 &version_i,
                 &separator(),
                 &copy_last_transcript_i,
+                &paste_last_transcript_i,
+                &target_i,
+                &incognito_i,
+                &pause_i,
                 &unload_model_i,
                 &separator(),
                 &settings_i,
@@ -175,6 +478,27 @@ pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState, locale: Option<&
         .expect("failed to create menu"),
     };
 
+    // Listed right after the last transcript items
+    if let Some(recent_transcripts_i) = recent_transcripts_submenu(
+        app,
+        &strings.recent_transcripts,
+        settings.tray_recent_transcripts,
+    ) {
+        insert_after(&menu, "paste_last_transcript", &recent_transcripts_i);
+    }
+
+    // Profile, model and prompt switchers follow the dictation toggles
+    let prompts_i = prompt_submenu(app, &strings.prompt, &settings);
+    if let Some(prompts_i) = &prompts_i {
+        insert_after(&menu, "incognito", prompts_i);
+    }
+    if let Some(models_i) = model_submenu(app, &strings.model, &settings.selected_model) {
+        insert_after(&menu, "incognito", &models_i);
+    }
+    if let Some(profiles_i) = profile_submenu(app, &strings.profile, &settings) {
+        insert_after(&menu, "incognito", &profiles_i);
+    }
+
     let tray = app.state::<TrayIcon>();
     let _ = tray.set_menu(Some(menu));
     let _ = tray.set_icon_as_template(true);
@@ -187,6 +511,18 @@ fn last_transcript_text(entry: &HistoryEntry) -> &str {
         .unwrap_or(&entry.transcription_text)
 }
 
+/// Locks dictation to the focused window, or releases the lock, and refreshes the menu
+pub fn set_target_lock(app: &AppHandle, locked: bool) {
+    if locked {
+        if let Err(e) = active_app::lock_target() {
+            warn!("Failed to lock dictation target: {}", e);
+        }
+    } else {
+        active_app::unlock_target();
+    }
+    crate::dictation::refresh_tray_menu(app);
+}
+
 pub fn set_tray_visibility(app: &AppHandle, visible: bool) {
     let tray = app.state::<TrayIcon>();
     if let Err(e) = tray.set_visible(visible) {
@@ -196,6 +532,98 @@ pub fn set_tray_visibility(app: &AppHandle, visible: bool) {
     }
 }
 
+/// Time for focus to leave the tray menu or settings window before pasting
+const REPASTE_FOCUS_DELAY: Duration = Duration::from_millis(150);
+
+/// Pastes text from history into the focused app. The main window is hidden
+/// first when it has focus so the text lands in the app behind it.
+pub fn paste_history_text(app: &AppHandle, text: String) {
+    if let Some(window) = app.get_webview_window("main") {
+        if window.is_focused().unwrap_or(false) {
+            let _ = window.hide();
+        }
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(REPASTE_FOCUS_DELAY);
+        let ah = app.clone();
+        app.run_on_main_thread(move || {
+            if let Err(e) = utils::paste(text, ah.clone(), false) {
+                error!("Failed to paste history entry: {}", e);
+                events::emit_error(&ah, ErrorSource::Paste, e);
+            }
+        })
+        .unwrap_or_else(|e| error!("Failed to run paste on main thread: {:?}", e));
+    });
+}
+
+/// Pastes the most recent transcript again, e.g. after it went to the wrong window
+pub fn paste_last_transcript(app: &AppHandle) -> Result<(), String> {
+    let history_manager = app.state::<Arc<HistoryManager>>();
+    let entry = history_manager
+        .get_latest_entry()
+        .map_err(|e| format!("Failed to fetch last transcription entry: {}", e))?
+        .ok_or("No transcription history entries available")?;
+
+    paste_history_text(app, last_transcript_text(&entry).to_string());
+    info!("Pasting last transcript again.");
+    Ok(())
+}
+
+/// Loads a model picked from the tray and makes it the selected model. A
+/// dictation in progress finishes with the model it started with.
+pub fn select_model(app: &AppHandle, model_id: String) {
+    let app = app.clone();
+    // Loading can take a while, so keep it off the menu event thread
+    crate::dictation::when_idle(move || {
+        let transcription_manager = app.state::<Arc<TranscriptionManager>>();
+        if let Err(e) = transcription_manager.load_model(&model_id) {
+            error!("Failed to switch to model {} via tray: {}", model_id, e);
+        } else {
+            let mut settings = settings::get_settings(&app);
+            settings.selected_model = model_id.clone();
+            settings::write_settings(&app, settings);
+            info!("Switched to model {} via tray.", model_id);
+        }
+        // Also undoes the checkmark the click toggled if loading failed
+        crate::dictation::refresh_tray_menu(&app);
+    });
+}
+
+/// Selects a post-processing prompt picked from the tray
+pub fn select_prompt(app: &AppHandle, prompt_id: String) {
+    let mut settings = settings::get_settings(app);
+    settings.post_process_selected_prompt_id = Some(prompt_id);
+    settings::write_settings(app, settings);
+    crate::dictation::refresh_tray_menu(app);
+}
+
+/// Copies a transcript picked from the tray's recent transcripts
+pub fn copy_history_entry(app: &AppHandle, id: i64) {
+    let history_manager = Arc::clone(&app.state::<Arc<HistoryManager>>());
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let entry = match history_manager.get_entry_by_id(id).await {
+            Ok(Some(entry)) => entry,
+            Ok(None) => {
+                warn!("History entry {} no longer exists", id);
+                return;
+            }
+            Err(err) => {
+                error!("Failed to fetch history entry {}: {}", id, err);
+                return;
+            }
+        };
+
+        if let Err(err) = app.clipboard().write_text(last_transcript_text(&entry)) {
+            error!("Failed to copy transcript to clipboard: {}", err);
+            return;
+        }
+        info!("Copied transcript {} to clipboard via tray.", id);
+    });
+}
+
 pub fn copy_last_transcript(app: &AppHandle) {
     let history_manager = app.state::<Arc<HistoryManager>>();
     let entry = match history_manager.get_latest_entry() {
@@ -220,8 +648,10 @@ pub fn copy_last_transcript(app: &AppHandle) {
 
 #[cfg(test)]
 mod tests {
-    use super::last_transcript_text;
+    use super::{last_transcript_text, recent_transcript_label, tray_status_text, TrayIconState};
     use crate::managers::history::HistoryEntry;
+    use crate::tray_i18n::get_tray_translations;
+    use std::time::Duration;
 
     fn build_entry(transcription: &str, post_processed: Option<&str>) -> HistoryEntry {
         HistoryEntry {
@@ -233,6 +663,10 @@ mod tests {
             transcription_text: transcription.to_string(),
             post_processed_text: post_processed.map(|text| text.to_string()),
             post_process_prompt: None,
+            duration_ms: None,
+            model: None,
+            target_app: None,
+            window_title: None,
         }
     }
 
@@ -247,4 +681,50 @@ mod tests {
         let entry = build_entry("raw", None);
         assert_eq!(last_transcript_text(&entry), "raw");
     }
+
+    #[test]
+    fn recent_transcript_label_is_first_line_cut_to_fit() {
+        assert_eq!(recent_transcript_label("  Buy milk\nand eggs"), "Buy milk");
+        assert_eq!(
+            recent_transcript_label(&"a".repeat(50)),
+            format!("{}…", "a".repeat(40))
+        );
+    }
+
+    #[test]
+    fn status_text_follows_state() {
+        let strings = get_tray_translations(Some("en".to_string()));
+        assert_eq!(
+            tray_status_text(&strings, &TrayIconState::Idle, None, false),
+            None
+        );
+        assert_eq!(
+            tray_status_text(
+                &strings,
+                &TrayIconState::Recording,
+                Some(Duration::from_secs(42)),
+                false
+            )
+            .as_deref(),
+            Some("Recording 0:42")
+        );
+        assert_eq!(
+            tray_status_text(&strings, &TrayIconState::Transcribing, None, false).as_deref(),
+            Some("Transcribing…")
+        );
+        assert_eq!(
+            tray_status_text(&strings, &TrayIconState::Transcribing, None, true).as_deref(),
+            Some("Warming up…")
+        );
+        assert_eq!(
+            tray_status_text(
+                &strings,
+                &TrayIconState::Recording,
+                Some(Duration::from_secs(3)),
+                true
+            )
+            .as_deref(),
+            Some("Recording 0:03")
+        );
+    }
 }
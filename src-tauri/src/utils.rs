@@ -1,3 +1,5 @@
+use crate::dictation;
+use crate::events::DictationState;
 use crate::managers::audio::AudioRecordingManager;
 use crate::managers::transcription::TranscriptionManager;
 use crate::shortcut;
@@ -25,9 +27,8 @@ pub fn cancel_current_operation(app: &AppHandle) {
     let recording_was_active = audio_manager.is_recording();
     audio_manager.cancel_recording();
 
-    // Update tray icon and hide overlay
-    change_tray_icon(app, crate::tray::TrayIconState::Idle);
-    hide_recording_overlay(app);
+    // Back to idle in the tray and overlay
+    dictation::transition(app, DictationState::Idle);
 
     // Unload model if immediate unload is enabled
     let tm = app.state::<Arc<TranscriptionManager>>();
@@ -41,6 +42,20 @@ pub fn cancel_current_operation(app: &AppHandle) {
     info!("Operation cancellation completed - returned to idle state");
 }
 
+/// Shows the Dock icon while the settings window is visible, and otherwise
+/// only when menu bar only mode is off.
+#[cfg(target_os = "macos")]
+pub fn apply_activation_policy(app: &AppHandle, window_visible: bool) {
+    let policy = if window_visible || !crate::settings::get_settings(app).menu_bar_only {
+        tauri::ActivationPolicy::Regular
+    } else {
+        tauri::ActivationPolicy::Accessory
+    };
+    if let Err(e) = app.set_activation_policy(policy) {
+        log::error!("Failed to set activation policy: {}", e);
+    }
+}
+
 /// Check if using the Wayland display server protocol
 #[cfg(target_os = "linux")]
 pub fn is_wayland() -> bool {
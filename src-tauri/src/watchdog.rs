@@ -0,0 +1,98 @@
+//! Cancels dictations stuck in a state they can't really be in.
+//!
+//! A lost key release or a hung model can leave Handy recording or
+//! transcribing for good, or the overlay up after everything has ended.
+//! Every few seconds the watchdog checks how long each has lasted against the
+//! `watchdog` limits and, past them, cancels like the cancel shortcut does
+//! and sends a `stuck_state_recovered` event saying what it fixed.
+
+use crate::dictation;
+use crate::events::{self, AppEvent, DictationState, StuckState};
+use crate::managers::audio::AudioRecordingManager;
+use crate::settings::get_settings;
+use crate::utils;
+use log::warn;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How long the overlay may stay up with nothing going on, well past the
+/// result HUD
+const OVERLAY_GRACE: Duration = Duration::from_secs(60);
+
+fn minutes(mins: u32) -> Duration {
+    Duration::from_secs(u64::from(mins) * 60)
+}
+
+fn overlay_visible(app: &AppHandle) -> bool {
+    app.get_webview_window("recording_overlay")
+        .and_then(|window| window.is_visible().ok())
+        .unwrap_or(false)
+}
+
+/// Watches for stuck dictations for as long as Handy runs
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    thread::spawn(move || {
+        let mut recording_since: Option<Instant> = None;
+        let mut overlay_idle_since: Option<Instant> = None;
+
+        loop {
+            thread::sleep(CHECK_INTERVAL);
+            let (current, entered) = dictation::current_since();
+            let recording = app.state::<Arc<AudioRecordingManager>>().is_recording();
+            let idle_overlay =
+                !recording && current == DictationState::Idle && overlay_visible(&app);
+            recording_since = recording.then(|| recording_since.unwrap_or_else(Instant::now));
+            overlay_idle_since =
+                idle_overlay.then(|| overlay_idle_since.unwrap_or_else(Instant::now));
+
+            let limits = get_settings(&app).watchdog;
+            if !limits.enabled {
+                continue;
+            }
+
+            let processing = matches!(
+                current,
+                DictationState::Transcribing | DictationState::PostProcessing
+            );
+            let stuck = if recording_since
+                .is_some_and(|since| since.elapsed() > minutes(limits.max_recording_mins))
+            {
+                Some((
+                    StuckState::Recording,
+                    format!(
+                        "Cancelled a recording that ran for over {} minutes",
+                        limits.max_recording_mins
+                    ),
+                ))
+            } else if processing && entered.elapsed() > minutes(limits.max_processing_mins) {
+                Some((
+                    StuckState::Processing,
+                    format!(
+                        "Cancelled a transcription that ran for over {} minutes",
+                        limits.max_processing_mins
+                    ),
+                ))
+            } else if overlay_idle_since.is_some_and(|since| since.elapsed() > OVERLAY_GRACE) {
+                Some((
+                    StuckState::Overlay,
+                    "Hid the overlay, which stayed up after the dictation ended".to_string(),
+                ))
+            } else {
+                None
+            };
+
+            if let Some((state, message)) = stuck {
+                warn!("Watchdog: {}", message);
+                utils::cancel_current_operation(&app);
+                recording_since = None;
+                overlay_idle_since = None;
+                events::emit(&app, AppEvent::StuckStateRecovered { state, message });
+            }
+        }
+    });
+}
@@ -0,0 +1,147 @@
+//! Outgoing webhooks for finished transcripts.
+//!
+//! Every enabled hook gets a JSON `POST` after each dictation that is saved to
+//! history, so incognito dictations are never sent. When the hook has a
+//! `webhook_token` secret, the body is signed with HMAC-SHA256 and the
+//! signature sent as `X-Handy-Signature-256: sha256=<hex>`. Failed deliveries
+//! are retried a few times with a growing delay.
+
+use crate::policy;
+use crate::secrets::{self, SecretKind};
+use crate::settings::Webhook;
+use hmac::{Hmac, Mac};
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use serde::Serialize;
+use sha2::Sha256;
+use std::time::Duration;
+
+/// Attempts per delivery, including the first
+const MAX_ATTEMPTS: u32 = 4;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const SIGNATURE_HEADER: &str = "X-Handy-Signature-256";
+
+static CLIENT: Lazy<Client> = Lazy::new(|| {
+    Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+/// Body of the `transcription_completed` webhook
+#[derive(Serialize, Debug, Clone)]
+pub struct TranscriptionPayload {
+    pub event: &'static str,
+    /// Final text, post-processed if it was
+    pub text: String,
+    pub transcription_text: String,
+    pub post_processed_text: Option<String>,
+    pub post_process_prompt: Option<String>,
+    /// Unix time in seconds
+    pub timestamp: i64,
+    pub duration_ms: i64,
+    pub model: Option<String>,
+    /// App the text was dictated into
+    pub app_name: Option<String>,
+    /// Only set when window titles are captured
+    pub window_title: Option<String>,
+    /// Post-processing requests retried after rate limits, server errors
+    /// or timeouts
+    pub post_process_retries: u32,
+}
+
+impl TranscriptionPayload {
+    pub const EVENT: &'static str = "transcription_completed";
+}
+
+fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    let hex: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("sha256={}", hex)
+}
+
+/// Posts the transcript to every enabled hook in the background
+pub fn send_transcription(webhooks: &[Webhook], payload: &TranscriptionPayload) {
+    let hooks: Vec<Webhook> = webhooks
+        .iter()
+        .filter(|hook| hook.enabled && policy::allows_destination(&hook.url))
+        .cloned()
+        .collect();
+    if hooks.is_empty() {
+        return;
+    }
+    let body = match serde_json::to_vec(payload) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Failed to serialize webhook payload: {}", e);
+            return;
+        }
+    };
+    for hook in hooks {
+        let body = body.clone();
+        tauri::async_runtime::spawn(async move { deliver(&hook, body).await });
+    }
+}
+
+async fn deliver(hook: &Webhook, body: Vec<u8>) {
+    let signature = secrets::get_secret(&secrets::secret_name(SecretKind::WebhookToken, &hook.id))
+        .filter(|secret| !secret.is_empty())
+        .map(|secret| signature(&secret, &body));
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut request = CLIENT
+            .post(&hook.url)
+            .header("Content-Type", "application/json")
+            .body(body.clone());
+        if let Some(signature) = &signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+
+        let error = match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                debug!("Delivered webhook {}", hook.id);
+                return;
+            }
+            // Client errors won't go away by trying again
+            Ok(response) if response.status().is_client_error() => {
+                warn!("Webhook {} was refused with {}", hook.id, response.status());
+                return;
+            }
+            Ok(response) => response.status().to_string(),
+            Err(e) => e.to_string(),
+        };
+        if attempt == MAX_ATTEMPTS {
+            warn!(
+                "Giving up on webhook {} after {} attempts: {}",
+                hook.id, attempt, error
+            );
+        } else {
+            debug!("Webhook {} failed ({}), retrying", hook.id, error);
+            tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_like_rfc_4231() {
+        // Test case 2 of RFC 4231
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+import EventKit
+import Foundation
+
+// Reads the system calendar for meeting detection (src/meeting_detection.rs).
+// Strings returned here are allocated with strdup and freed with
+// handy_calendar_free_string.
+
+private let store = EKEventStore()
+
+private func hasAccess(_ status: EKAuthorizationStatus) -> Bool {
+    if #available(macOS 14.0, *) {
+        return status == .fullAccess
+    }
+    return status == .authorized
+}
+
+/// Asks for calendar access the first time. Returns whether Handy has it.
+@_cdecl("handy_calendar_authorize")
+public func handyCalendarAuthorize() -> Bool {
+    let status = EKEventStore.authorizationStatus(for: .event)
+    if status != .notDetermined {
+        return hasAccess(status)
+    }
+
+    let semaphore = DispatchSemaphore(value: 0)
+    var granted = false
+    let completion: (Bool, Error?) -> Void = { allowed, _ in
+        granted = allowed
+        semaphore.signal()
+    }
+    if #available(macOS 14.0, *) {
+        store.requestFullAccessToEvents(completion: completion)
+    } else {
+        store.requestAccess(to: .event, completion: completion)
+    }
+    semaphore.wait()
+    return granted
+}
+
+private func toJSON(_ value: Any) -> UnsafeMutablePointer<CChar>? {
+    guard let data = try? JSONSerialization.data(withJSONObject: value),
+          let json = String(data: data, encoding: .utf8)
+    else { return nil }
+    return strdup(json)
+}
+
+/// Timed events overlapping `from`...`to` (Unix seconds) as a JSON array of
+/// `{"title", "calendar", "start", "end"}`, or NULL without access
+@_cdecl("handy_calendar_events")
+public func handyCalendarEvents(_ from: Double, _ to: Double) -> UnsafeMutablePointer<CChar>? {
+    guard hasAccess(EKEventStore.authorizationStatus(for: .event)) else { return nil }
+    let predicate = store.predicateForEvents(
+        withStart: Date(timeIntervalSince1970: from),
+        end: Date(timeIntervalSince1970: to),
+        calendars: nil
+    )
+    let events: [[String: Any]] = store.events(matching: predicate)
+        .filter { !$0.isAllDay }
+        .map { event in
+            [
+                "title": event.title ?? "",
+                "calendar": event.calendar?.title ?? "",
+                "start": event.startDate.timeIntervalSince1970,
+                "end": event.endDate.timeIntervalSince1970,
+            ]
+        }
+    return toJSON(events)
+}
+
+/// Names of the event calendars as a JSON array, or NULL without access
+@_cdecl("handy_calendar_names")
+public func handyCalendarNames() -> UnsafeMutablePointer<CChar>? {
+    guard hasAccess(EKEventStore.authorizationStatus(for: .event)) else { return nil }
+    return toJSON(store.calendars(for: .event).map { $0.title })
+}
+
+@_cdecl("handy_calendar_free_string")
+public func handyCalendarFreeString(_ value: UnsafeMutablePointer<CChar>?) {
+    free(value)
+}
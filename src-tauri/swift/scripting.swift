@@ -0,0 +1,93 @@
+import Cocoa
+
+// AppleScript commands from Handy.sdef. Each one forwards to Rust through
+// scripting_bridge.h; Cocoa finds the classes by their Objective-C names.
+
+private func takeString(_ pointer: UnsafeMutablePointer<CChar>?) -> String? {
+    guard let pointer = pointer else { return nil }
+    defer { handy_script_free_string(pointer) }
+    return String(cString: pointer)
+}
+
+private func runCommand(_ command: NSScriptCommand, _ name: String, postProcess: Bool = false) {
+    if let error = takeString(handy_script_command(name, postProcess ? 1 : 0)) {
+        command.scriptErrorNumber = -1
+        command.scriptErrorString = error
+    }
+}
+
+@objc(HandyStartCommand)
+class HandyStartCommand: NSScriptCommand {
+    override func performDefaultImplementation() -> Any? {
+        let postProcess = (evaluatedArguments?["postProcess"] as? Bool) ?? false
+        runCommand(self, "start", postProcess: postProcess)
+        return nil
+    }
+}
+
+@objc(HandyStopCommand)
+class HandyStopCommand: NSScriptCommand {
+    override func performDefaultImplementation() -> Any? {
+        runCommand(self, "stop")
+        return nil
+    }
+}
+
+@objc(HandyCancelCommand)
+class HandyCancelCommand: NSScriptCommand {
+    override func performDefaultImplementation() -> Any? {
+        runCommand(self, "cancel")
+        return nil
+    }
+}
+
+@objc(HandyLastTranscriptCommand)
+class HandyLastTranscriptCommand: NSScriptCommand {
+    override func performDefaultImplementation() -> Any? {
+        return takeString(handy_script_last_transcript()) ?? ""
+    }
+}
+
+@objc(HandyTranscribeCommand)
+class HandyTranscribeCommand: NSScriptCommand {
+    override func performDefaultImplementation() -> Any? {
+        guard let url = directParameter as? URL else {
+            scriptErrorNumber = -1
+            scriptErrorString = "Expected an audio file."
+            return nil
+        }
+        // Transcription takes a while, so the script waits without blocking
+        // the main thread
+        suspendExecution()
+        let context = Unmanaged.passRetained(self).toOpaque()
+        handy_script_transcribe(url.path, context)
+        return nil
+    }
+}
+
+@_cdecl("handy_script_finish")
+public func handyScriptFinish(
+    _ context: UnsafeMutableRawPointer,
+    _ text: UnsafePointer<CChar>?,
+    _ error: UnsafePointer<CChar>?
+) {
+    let command = Unmanaged<HandyTranscribeCommand>.fromOpaque(context).takeRetainedValue()
+    let text = text.map { String(cString: $0) }
+    let error = error.map { String(cString: $0) }
+    DispatchQueue.main.async {
+        if let error = error {
+            command.scriptErrorNumber = -1
+            command.scriptErrorString = error
+        }
+        command.resumeExecution(withResult: text)
+    }
+}
+
+// Referenced from Rust so the linker keeps the command classes
+@_cdecl("handy_scripting_register")
+public func handyScriptingRegister() {
+    _ = [
+        HandyStartCommand.self, HandyStopCommand.self, HandyCancelCommand.self,
+        HandyLastTranscriptCommand.self, HandyTranscribeCommand.self,
+    ]
+}
@@ -0,0 +1,28 @@
+#ifndef scripting_bridge_h
+#define scripting_bridge_h
+
+// AppleScript commands call into these functions, implemented in Rust
+// (src/scripting.rs)
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Run "start", "stop" or "cancel". Returns an error message, or NULL on success.
+char* handy_script_command(const char* name, int post_process);
+
+// Text of the most recent transcript, empty when there is none
+char* handy_script_last_transcript(void);
+
+// Transcribe an audio file in the background, then call
+// handy_script_finish(context, text, error)
+void handy_script_transcribe(const char* path, void* context);
+
+// Free a string returned by the functions above
+void handy_script_free_string(char* value);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* scripting_bridge_h */
@@ -61,6 +61,14 @@ async changeStartHiddenSetting(enabled: boolean) : Promise<Result<null, string>>
     else return { status: "error", error: e  as any };
 }
 },
+async changeMenuBarOnlySetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_menu_bar_only_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeAutostartSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_autostart_setting", { enabled }) };
@@ -93,6 +101,60 @@ async changeOverlayPositionSetting(position: string) : Promise<Result<null, stri
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Set the overlay size relative to its default
+ */
+async changeOverlayScaleSetting(scale: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_overlay_scale_setting", { scale }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeResultHudSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_result_hud_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the overlay's opacity, accent color and theme
+ */
+async changeOverlayAppearanceSetting(appearance: OverlayAppearance) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_overlay_appearance_setting", { appearance }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Pin the overlay to a monitor from `get_monitors`, or `None` to show it on
+ * the monitor with the cursor
+ */
+async changeOverlayMonitorSetting(monitor: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_overlay_monitor_setting", { monitor }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set the max recording duration in seconds, 0 for no limit. Applies from
+ * the next recording.
+ */
+async changeMaxRecordingDurationSetting(secs: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_max_recording_duration_setting", { secs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeDebugModeSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_debug_mode_setting", { enabled }) };
@@ -109,6 +171,30 @@ async changeWordCorrectionThresholdSetting(threshold: number) : Promise<Result<n
     else return { status: "error", error: e  as any };
 }
 },
+async changeTranscriptionTimeoutSetting(seconds: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_transcription_timeout_setting", { seconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeTranscriptionWorkersSetting(workers: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_transcription_workers_setting", { workers }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changePreloadModelSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_preload_model_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changePasteMethodSetting(method: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_paste_method_setting", { method }) };
@@ -128,645 +214,2647 @@ async changeTypingToolSetting(tool: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
-async changeExternalScriptPathSetting(path: string | null) : Promise<Result<null, string>> {
+async changeAppPasteRulesSetting(rules: AppPasteRule[]) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_external_script_path_setting", { path }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_app_paste_rules_setting", { rules }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async changeClipboardHandlingSetting(handling: string) : Promise<Result<null, string>> {
+async changeAppOverridesSetting(overrides: AppOverride[]) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_clipboard_handling_setting", { handling }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_app_overrides_setting", { overrides }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async changeAutoSubmitSetting(enabled: boolean) : Promise<Result<null, string>> {
+async changeTypingSpeedSetting(speed: TypingSpeed, slow: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_auto_submit_setting", { enabled }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_typing_speed_setting", { speed, slow }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async changeAutoSubmitKeySetting(key: string) : Promise<Result<null, string>> {
+async changeTranscriptPipePathSetting(path: string | null) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_auto_submit_key_setting", { key }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_transcript_pipe_path_setting", { path }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async changePostProcessEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
+async changeDailyJournalDirSetting(dir: string | null) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_post_process_enabled_setting", { enabled }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_daily_journal_dir_setting", { dir }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async changeExperimentalEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
+/**
+ * Set the template for daily journal entries. An empty template restores
+ * the default.
+ */
+async changeDailyJournalTemplateSetting(template: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_experimental_enabled_setting", { enabled }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_daily_journal_template_setting", { template }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async changePostProcessBaseUrlSetting(providerId: string, baseUrl: string) : Promise<Result<null, string>> {
+async changeCaptureWindowTitlesSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_post_process_base_url_setting", { providerId, baseUrl }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_capture_window_titles_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async changePostProcessApiKeySetting(providerId: string, apiKey: string) : Promise<Result<null, string>> {
+async changeDeepLinkActionsSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_post_process_api_key_setting", { providerId, apiKey }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_deep_link_actions_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async changePostProcessModelSetting(providerId: string, model: string) : Promise<Result<null, string>> {
+async changeUsageAnalyticsSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_post_process_model_setting", { providerId, model }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_usage_analytics_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async setPostProcessProvider(providerId: string) : Promise<Result<null, string>> {
+async changeExternalScriptPathSetting(path: string | null) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("set_post_process_provider", { providerId }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_external_script_path_setting", { path }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async fetchPostProcessModels(providerId: string) : Promise<Result<string[], string>> {
+async changeClipboardHandlingSetting(handling: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("fetch_post_process_models", { providerId }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_clipboard_handling_setting", { handling }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async addPostProcessPrompt(name: string, prompt: string) : Promise<Result<LLMPrompt, string>> {
+async changePrimarySelectionModeSetting(mode: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("add_post_process_prompt", { name, prompt }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_primary_selection_mode_setting", { mode }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async updatePostProcessPrompt(id: string, name: string, prompt: string) : Promise<Result<null, string>> {
+async changeMarkdownOutputModeSetting(mode: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_post_process_prompt", { id, name, prompt }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_markdown_output_mode_setting", { mode }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async deletePostProcessPrompt(id: string) : Promise<Result<null, string>> {
+async changeClipboardRestoreDelaySetting(delayMs: number) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("delete_post_process_prompt", { id }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_clipboard_restore_delay_setting", { delayMs }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async setPostProcessSelectedPrompt(id: string) : Promise<Result<null, string>> {
+async changeSmartInsertSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("set_post_process_selected_prompt", { id }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_smart_insert_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async updateCustomWords(words: string[]) : Promise<Result<null, string>> {
+async changeFocusedControlDetectionSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_custom_words", { words }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_focused_control_detection_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-/**
- * Temporarily unregister a binding while the user is editing it in the UI.
- * This avoids firing the action while keys are being recorded.
- */
-async suspendBinding(id: string) : Promise<Result<null, string>> {
+async changeUndoDictationEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("suspend_binding", { id }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_undo_dictation_enabled_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-/**
- * Re-register the binding after the user has finished editing.
- */
-async resumeBinding(id: string) : Promise<Result<null, string>> {
+async changePasteLastEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("resume_binding", { id }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_paste_last_enabled_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async changeMuteWhileRecordingSetting(enabled: boolean) : Promise<Result<null, string>> {
+async changeIncognitoShortcutEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_mute_while_recording_setting", { enabled }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_incognito_shortcut_enabled_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async changeAppendTrailingSpaceSetting(enabled: boolean) : Promise<Result<null, string>> {
+async changePreviewBeforePasteSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_append_trailing_space_setting", { enabled }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_preview_before_paste_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async changeAppLanguageSetting(language: string) : Promise<Result<null, string>> {
+/**
+ * Returns the transcript waiting for confirmation, if any.
+ */
+async getPendingPreview() : Promise<string | null> {
+    return await TAURI_INVOKE("get_pending_preview");
+},
+/**
+ * Stores edits made in the preview so they survive the window reloading.
+ */
+async updatePreview(text: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_app_language_setting", { language }) };
+    return { status: "ok", data: await TAURI_INVOKE("update_preview", { text }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async changeUpdateChecksSetting(enabled: boolean) : Promise<Result<null, string>> {
+/**
+ * Pastes the confirmed text, which may have been edited in the preview.
+ */
+async confirmPreview(text: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_update_checks_setting", { enabled }) };
+    return { status: "ok", data: await TAURI_INVOKE("confirm_preview", { text }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Change the keyboard implementation with runtime switching.
- * This will unregister all shortcuts from the old implementation,
- * validate shortcuts for the new implementation (resetting invalid ones to defaults),
- * and register them with the new implementation.
+ * Discards the pending transcript without pasting.
  */
-async changeKeyboardImplementationSetting(implementation: string) : Promise<Result<ImplementationChangeResult, string>> {
+async cancelPreview() : Promise<void> {
+    await TAURI_INVOKE("cancel_preview");
+},
+/**
+ * Error reports kept on this machine, newest first
+ */
+async listErrorReports() : Promise<Result<ErrorReport[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_keyboard_implementation_setting", { implementation }) };
+    return { status: "ok", data: await TAURI_INVOKE("list_error_reports") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get the current keyboard implementation
+ * The last `lines` records in the log file at `level` or above
  */
-async getKeyboardImplementation() : Promise<string> {
-    return await TAURI_INVOKE("get_keyboard_implementation");
-},
-async changeShowTrayIconSetting(enabled: boolean) : Promise<Result<null, string>> {
+async tailLog(lines: number, level: LogLevel) : Promise<Result<LogLine[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("change_show_tray_icon_setting", { enabled }) };
+    return { status: "ok", data: await TAURI_INVOKE("tail_log", { lines, level }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Start key recording mode
+ * Sends records at `level` or above as `log-line` events until
+ * `stop_log_stream`. Calling it again changes the level.
  */
-async startHandyKeysRecording(bindingId: string) : Promise<Result<null, string>> {
+async startLogStream(level: LogLevel) : Promise<void> {
+    await TAURI_INVOKE("start_log_stream", { level });
+},
+async stopLogStream() : Promise<void> {
+    await TAURI_INVOKE("stop_log_stream");
+},
+async changeKeyInjectionSetting(keyInjection: KeyInjectionSettings) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("start_handy_keys_recording", { bindingId }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_key_injection_setting", { keyInjection }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-/**
- * Stop key recording mode
- */
-async stopHandyKeysRecording() : Promise<Result<null, string>> {
+async changeNotificationSettings(notifications: NotificationSettings) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("stop_handy_keys_recording") };
+    return { status: "ok", data: await TAURI_INVOKE("change_notification_settings", { notifications }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async triggerUpdateCheck() : Promise<Result<null, string>> {
+async changeApiServerSetting(apiServer: ApiServerSettings) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("trigger_update_check") };
+    return { status: "ok", data: await TAURI_INVOKE("change_api_server_setting", { apiServer }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async cancelOperation() : Promise<void> {
-    await TAURI_INVOKE("cancel_operation");
-},
-async getAppDirPath() : Promise<Result<string, string>> {
+async changeStreamDeckSetting(streamDeck: StreamDeckSettings) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_app_dir_path") };
+    return { status: "ok", data: await TAURI_INVOKE("change_stream_deck_setting", { streamDeck }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getAppSettings() : Promise<Result<AppSettings, string>> {
+async changeMqttSetting(mqtt: MqttSettings) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_app_settings") };
+    return { status: "ok", data: await TAURI_INVOKE("change_mqtt_setting", { mqtt }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getDefaultSettings() : Promise<Result<AppSettings, string>> {
+async changeMeetingDetectionSetting(meetingDetection: MeetingDetectionSettings) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_default_settings") };
+    return { status: "ok", data: await TAURI_INVOKE("change_meeting_detection_setting", { meetingDetection }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getLogDirPath() : Promise<Result<string, string>> {
+async changeEmailSetting(email: EmailSettings) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_log_dir_path") };
+    return { status: "ok", data: await TAURI_INVOKE("change_email_setting", { email }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async setLogLevel(level: LogLevel) : Promise<Result<null, string>> {
+async changeWatchdogSetting(watchdog: WatchdogSettings) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("set_log_level", { level }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_watchdog_setting", { watchdog }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async openRecordingsFolder() : Promise<Result<null, string>> {
+async changeObsCaptionsSetting(obsCaptions: ObsCaptionSettings) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("open_recordings_folder") };
+    return { status: "ok", data: await TAURI_INVOKE("change_obs_captions_setting", { obsCaptions }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async openLogDir() : Promise<Result<null, string>> {
+async addWebhook(url: string) : Promise<Result<Webhook, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("open_log_dir") };
+    return { status: "ok", data: await TAURI_INVOKE("add_webhook", { url }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async openAppDataDir() : Promise<Result<null, string>> {
+async updateWebhook(webhook: Webhook) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("open_app_data_dir") };
+    return { status: "ok", data: await TAURI_INVOKE("update_webhook", { webhook }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Check if Apple Intelligence is available on this device.
- * Called by the frontend when the user selects Apple Intelligence provider.
- */
-async checkAppleIntelligenceAvailable() : Promise<boolean> {
-    return await TAURI_INVOKE("check_apple_intelligence_available");
-},
-/**
- * Try to initialize Enigo (keyboard/mouse simulation).
- * On macOS, this will return an error if accessibility permissions are not granted.
+ * Removes a webhook along with its signing secret
  */
-async initializeEnigo() : Promise<Result<null, string>> {
+async deleteWebhook(id: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("initialize_enigo") };
+    return { status: "ok", data: await TAURI_INVOKE("delete_webhook", { id }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Initialize keyboard shortcuts.
- * On macOS, this should be called after accessibility permissions are granted.
- * This is idempotent - calling it multiple times is safe.
+ * Adds an HTTP action, see `http_actions` for the template variables
  */
-async initializeShortcuts() : Promise<Result<null, string>> {
+async addHttpAction(action: HttpAction) : Promise<Result<HttpAction, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("initialize_shortcuts") };
+    return { status: "ok", data: await TAURI_INVOKE("add_http_action", { action }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getAvailableModels() : Promise<Result<ModelInfo[], string>> {
+async updateHttpAction(action: HttpAction) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_available_models") };
+    return { status: "ok", data: await TAURI_INVOKE("update_http_action", { action }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getModelInfo(modelId: string) : Promise<Result<ModelInfo | null, string>> {
+async deleteHttpAction(id: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_model_info", { modelId }) };
+    return { status: "ok", data: await TAURI_INVOKE("delete_http_action", { id }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async downloadModel(modelId: string) : Promise<Result<null, string>> {
+async addNoteTarget(name: string, destination: NoteDestination) : Promise<Result<NoteTarget, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("download_model", { modelId }) };
+    return { status: "ok", data: await TAURI_INVOKE("add_note_target", { name, destination }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async deleteModel(modelId: string) : Promise<Result<null, string>> {
+async updateNoteTarget(target: NoteTarget) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("delete_model", { modelId }) };
+    return { status: "ok", data: await TAURI_INVOKE("update_note_target", { target }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async cancelDownload(modelId: string) : Promise<Result<null, string>> {
+/**
+ * Removes a note target along with its token. Bindings that sent to it go
+ * back to pasting.
+ */
+async deleteNoteTarget(id: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("cancel_download", { modelId }) };
+    return { status: "ok", data: await TAURI_INVOKE("delete_note_target", { id }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async setActiveModel(modelId: string) : Promise<Result<null, string>> {
+/**
+ * Set the note target a binding sends transcripts to, or `None` to paste
+ */
+async changeBindingNoteTarget(id: string, targetId: string | null) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("set_active_model", { modelId }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_binding_note_target", { id, targetId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getCurrentModel() : Promise<Result<string, string>> {
+async changeAutoSubmitSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_current_model") };
+    return { status: "ok", data: await TAURI_INVOKE("change_auto_submit_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getTranscriptionModelStatus() : Promise<Result<string | null, string>> {
+async changeAutoSubmitKeySetting(key: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_transcription_model_status") };
+    return { status: "ok", data: await TAURI_INVOKE("change_auto_submit_key_setting", { key }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async isModelLoading() : Promise<Result<boolean, string>> {
+async changePostProcessEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("is_model_loading") };
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_enabled_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async hasAnyModelsAvailable() : Promise<Result<boolean, string>> {
+/**
+ * Set the file a binding appends transcripts to. The `transcribe_to_file`
+ * shortcut is only registered while a path is configured.
+ */
+async changeBindingOutputFile(id: string, path: string | null) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("has_any_models_available") };
+    return { status: "ok", data: await TAURI_INVOKE("change_binding_output_file", { id, path }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async hasAnyModelsOrDownloads() : Promise<Result<boolean, string>> {
+async changeExperimentalEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("has_any_models_or_downloads") };
+    return { status: "ok", data: await TAURI_INVOKE("change_experimental_enabled_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async updateMicrophoneMode(alwaysOn: boolean) : Promise<Result<null, string>> {
+async changePostProcessBaseUrlSetting(providerId: string, baseUrl: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_microphone_mode", { alwaysOn }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_base_url_setting", { providerId, baseUrl }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getMicrophoneMode() : Promise<Result<boolean, string>> {
+async changePostProcessApiKeySetting(providerId: string, apiKey: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_microphone_mode") };
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_api_key_setting", { providerId, apiKey }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getAvailableMicrophones() : Promise<Result<AudioDevice[], string>> {
+async changePostProcessModelSetting(providerId: string, model: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_available_microphones") };
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_model_setting", { providerId, model }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async setSelectedMicrophone(deviceName: string) : Promise<Result<null, string>> {
+async setPostProcessProvider(providerId: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("set_selected_microphone", { deviceName }) };
+    return { status: "ok", data: await TAURI_INVOKE("set_post_process_provider", { providerId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getSelectedMicrophone() : Promise<Result<string, string>> {
+async fetchPostProcessModels(providerId: string) : Promise<Result<string[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_selected_microphone") };
+    return { status: "ok", data: await TAURI_INVOKE("fetch_post_process_models", { providerId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getAvailableOutputDevices() : Promise<Result<AudioDevice[], string>> {
+/**
+ * Search a provider's model catalog by name, price and context length.
+ * Intended for providers like OpenRouter whose full model list is very large.
+ */
+async searchPostProcessModels(providerId: string, filter: ModelSearchFilter) : Promise<Result<PostProcessModelInfo[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_available_output_devices") };
+    return { status: "ok", data: await TAURI_INVOKE("search_post_process_models", { providerId, filter }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async setSelectedOutputDevice(deviceName: string) : Promise<Result<null, string>> {
+async changeOpenrouterRoutingSetting(routing: OpenRouterRouting) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("set_selected_output_device", { deviceName }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_openrouter_routing_setting", { routing }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getSelectedOutputDevice() : Promise<Result<string, string>> {
+async addPostProcessPrompt(name: string, prompt: string, systemPrompt: string | null) : Promise<Result<LLMPrompt, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_selected_output_device") };
+    return { status: "ok", data: await TAURI_INVOKE("add_post_process_prompt", { name, prompt, systemPrompt }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async playTestSound(soundType: string) : Promise<void> {
-    await TAURI_INVOKE("play_test_sound", { soundType });
-},
-async checkCustomSounds() : Promise<CustomSounds> {
-    return await TAURI_INVOKE("check_custom_sounds");
-},
-async setClamshellMicrophone(deviceName: string) : Promise<Result<null, string>> {
+async updatePostProcessPrompt(id: string, name: string, prompt: string, systemPrompt: string | null) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("set_clamshell_microphone", { deviceName }) };
+    return { status: "ok", data: await TAURI_INVOKE("update_post_process_prompt", { id, name, prompt, systemPrompt }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getClamshellMicrophone() : Promise<Result<string, string>> {
+/**
+ * Update the sampling parameter overrides for a prompt.
+ * Passing `None` for a value clears the override so the provider default is used.
+ */
+async updatePostProcessPromptParams(id: string, temperature: number | null, maxTokens: number | null, topP: number | null) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_clamshell_microphone") };
+    return { status: "ok", data: await TAURI_INVOKE("update_post_process_prompt_params", { id, temperature, maxTokens, topP }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async isRecording() : Promise<boolean> {
-    return await TAURI_INVOKE("is_recording");
+async deletePostProcessPrompt(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_post_process_prompt", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 },
-async setModelUnloadTimeout(timeout: ModelUnloadTimeout) : Promise<void> {
-    await TAURI_INVOKE("set_model_unload_timeout", { timeout });
+async setPostProcessSelectedPrompt(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_post_process_selected_prompt", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 },
-async getModelLoadStatus() : Promise<Result<ModelLoadStatus, string>> {
+async changePostProcessSystemPromptSetting(systemPrompt: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_model_load_status") };
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_system_prompt_setting", { systemPrompt }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async unloadModelManually() : Promise<Result<null, string>> {
+async updateCustomWords(words: string[]) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("unload_model_manually") };
+    return { status: "ok", data: await TAURI_INVOKE("update_custom_words", { words }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getHistoryEntries() : Promise<Result<HistoryEntry[], string>> {
+async changeSpokenCommandsEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_history_entries") };
+    return { status: "ok", data: await TAURI_INVOKE("change_spoken_commands_enabled_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async toggleHistoryEntrySaved(id: number) : Promise<Result<null, string>> {
+async updateSpokenCommands(commands: Partial<{ [key in string]: string }>) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("toggle_history_entry_saved", { id }) };
+    return { status: "ok", data: await TAURI_INVOKE("update_spoken_commands", { commands }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async getAudioFilePath(fileName: string) : Promise<Result<string, string>> {
+async changeLiteralModeEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_audio_file_path", { fileName }) };
+    return { status: "ok", data: await TAURI_INVOKE("change_literal_mode_enabled_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async deleteHistoryEntry(id: number) : Promise<Result<null, string>> {
+async updateLiteralModeTriggers(triggers: string[]) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("delete_history_entry", { id }) };
+    return { status: "ok", data: await TAURI_INVOKE("update_literal_mode_triggers", { triggers }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async updateHistoryLimit(limit: number) : Promise<Result<null, string>> {
+/**
+ * Temporarily unregister a binding while the user is editing it in the UI.
+ * This avoids firing the action while keys are being recorded.
+ */
+async suspendBinding(id: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_history_limit", { limit }) };
+    return { status: "ok", data: await TAURI_INVOKE("suspend_binding", { id }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async updateRecordingRetentionPeriod(period: string) : Promise<Result<null, string>> {
+/**
+ * Re-register the binding after the user has finished editing.
+ */
+async resumeBinding(id: string) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("update_recording_retention_period", { period }) };
+    return { status: "ok", data: await TAURI_INVOKE("resume_binding", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeMuteWhileRecordingSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_mute_while_recording_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeAppendTrailingSpaceSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_append_trailing_space_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeAppLanguageSetting(language: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_app_language_setting", { language }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeUpdateChecksSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_update_checks_setting", { enabled }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Checks if the Mac is a laptop by detecting battery presence
- * 
- * This uses pmset to check for battery information.
- * Returns true if a battery is detected (laptop), false otherwise (desktop)
+ * Change the keyboard implementation with runtime switching.
+ * This will unregister all shortcuts from the old implementation,
+ * validate shortcuts for the new implementation (resetting invalid ones to defaults),
+ * and register them with the new implementation.
  */
-async isLaptop() : Promise<Result<boolean, string>> {
+async changeKeyboardImplementationSetting(implementation: string) : Promise<Result<ImplementationChangeResult, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("is_laptop") };
+    return { status: "ok", data: await TAURI_INVOKE("change_keyboard_implementation_setting", { implementation }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
+},
+/**
+ * Get the current keyboard implementation
+ */
+async getKeyboardImplementation() : Promise<string> {
+    return await TAURI_INVOKE("get_keyboard_implementation");
+},
+async changeShowTrayIconSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_show_tray_icon_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
 }
+},
+/**
+ * Set how many recent transcripts the tray menu lists, 0 to hide them
+ */
+async changeTrayRecentTranscriptsSetting(count: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_tray_recent_transcripts_setting", { count }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
 }
-
-/** user-defined events **/
-
-
-
-/** user-defined constants **/
-
-
-
-/** user-defined types **/
-
-export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; selected_microphone?: string | null; clamshell_microphone?: string | null; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; auto_submit?: boolean; auto_submit_key?: AutoSubmitKey; post_process_enabled?: boolean; post_process_provider_id?: string; post_process_providers?: PostProcessProvider[]; post_process_api_keys?: Partial<{ [key in string]: string }>; post_process_models?: Partial<{ [key in string]: string }>; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; mute_while_recording?: boolean; append_trailing_space?: boolean; app_language?: string; experimental_enabled?: boolean; keyboard_implementation?: KeyboardImplementation; show_tray_icon?: boolean; paste_delay_ms?: number; typing_tool?: TypingTool; external_script_path: string | null }
-export type AudioDevice = { index: string; name: string; is_default: boolean }
-export type AutoSubmitKey = "enter" | "ctrl_enter" | "cmd_enter"
-export type BindingResponse = { success: boolean; binding: ShortcutBinding | null; error: string | null }
-export type ClipboardHandling = "dont_modify" | "copy_to_clipboard"
-export type CustomSounds = { start: boolean; stop: boolean }
-export type EngineType = "Whisper" | "Parakeet" | "Moonshine" | "MoonshineStreaming" | "SenseVoice"
-export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null }
+},
 /**
- * Result of changing keyboard implementation
+ * Start key recording mode
  */
-export type ImplementationChangeResult = { success: boolean; 
+async startHandyKeysRecording(bindingId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_handy_keys_recording", { bindingId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * List of binding IDs that were reset to defaults due to incompatibility
+ * Stop key recording mode
  */
-reset_bindings: string[] }
-export type KeyboardImplementation = "tauri" | "handy_keys"
-export type LLMPrompt = { id: string; name: string; prompt: string }
-export type LogLevel = "trace" | "debug" | "info" | "warn" | "error"
-export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number; supports_translation: boolean; is_recommended: boolean; supported_languages: string[]; is_custom: boolean }
-export type ModelLoadStatus = { is_loaded: boolean; current_model: string | null }
-export type ModelUnloadTimeout = "never" | "immediately" | "min_2" | "min_5" | "min_10" | "min_15" | "hour_1" | "sec_5"
-export type OverlayPosition = "none" | "top" | "bottom"
-export type PasteMethod = "ctrl_v" | "direct" | "none" | "shift_insert" | "ctrl_shift_v" | "external_script"
-export type PostProcessProvider = { id: string; label: string; base_url: string; allow_base_url_edit?: boolean; models_endpoint?: string | null; supports_structured_output?: boolean }
-export type RecordingRetentionPeriod = "never" | "preserve_limit" | "days_3" | "weeks_2" | "months_3"
-export type ShortcutBinding = { id: string; name: string; description: string; default_binding: string; current_binding: string }
-export type SoundTheme = "marimba" | "pop" | "custom"
-export type TypingTool = "auto" | "wtype" | "kwtype" | "dotool" | "ydotool" | "xdotool"
+async stopHandyKeysRecording() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_handy_keys_recording") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async triggerUpdateCheck() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("trigger_update_check") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async cancelOperation() : Promise<void> {
+    await TAURI_INVOKE("cancel_operation");
+},
+/**
+ * Stops the current recording and transcribes it, the same as pressing its
+ * shortcut again. Used by the overlay's stop control.
+ */
+async stopCurrentRecording() : Promise<void> {
+    await TAURI_INVOKE("stop_current_recording");
+},
+async getAppDirPath() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_app_dir_path") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAppSettings() : Promise<Result<AppSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_app_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * What the managed policy enforces, including which settings it locks
+ */
+async getSettingsPolicy() : Promise<PolicyStatus> {
+    return await TAURI_INVOKE("get_settings_policy");
+},
+async getDefaultSettings() : Promise<Result<AppSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_default_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getLogDirPath() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_log_dir_path") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setLogLevel(level: LogLevel) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_log_level", { level }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async openRecordingsFolder() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_recordings_folder") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async openLogDir() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_log_dir") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async openAppDataDir() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_app_data_dir") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check if Apple Intelligence is available on this device.
+ * Called by the frontend when the user selects Apple Intelligence provider.
+ */
+async checkAppleIntelligenceAvailable() : Promise<boolean> {
+    return await TAURI_INVOKE("check_apple_intelligence_available");
+},
+/**
+ * Try to initialize Enigo (keyboard/mouse simulation).
+ * On macOS, this will return an error if accessibility permissions are not granted.
+ */
+async initializeEnigo() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("initialize_enigo") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Name of the app dictation is locked to, if any.
+ */
+async getDictationTarget() : Promise<string | null> {
+    return await TAURI_INVOKE("get_dictation_target");
+},
+/**
+ * Releases the dictation target lock.
+ */
+async unlockDictationTarget() : Promise<void> {
+    await TAURI_INVOKE("unlock_dictation_target");
+},
+/**
+ * Whether session incognito dictation is on.
+ */
+async getIncognitoMode() : Promise<boolean> {
+    return await TAURI_INVOKE("get_incognito_mode");
+},
+/**
+ * Turns session incognito dictation on or off.
+ */
+async setIncognitoMode(enabled: boolean) : Promise<void> {
+    await TAURI_INVOKE("set_incognito_mode", { enabled });
+},
+/**
+ * Whether Handy is paused, with every shortcut unregistered.
+ */
+async getPaused() : Promise<boolean> {
+    return await TAURI_INVOKE("get_paused");
+},
+/**
+ * Pauses or resumes Handy's shortcuts.
+ */
+async setPaused(paused: boolean) : Promise<void> {
+    await TAURI_INVOKE("set_paused", { paused });
+},
+/**
+ * Connected monitors the overlay can be pinned to.
+ */
+async getMonitors() : Promise<MonitorInfo[]> {
+    return await TAURI_INVOKE("get_monitors");
+},
+/**
+ * Initialize keyboard shortcuts.
+ * On macOS, this should be called after accessibility permissions are granted.
+ * This is idempotent - calling it multiple times is safe.
+ */
+async initializeShortcuts() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("initialize_shortcuts") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAvailableModels() : Promise<Result<ModelInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_available_models") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getModelInfo(modelId: string) : Promise<Result<ModelInfo | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_model_info", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async downloadModel(modelId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("download_model", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Downloads a corrupted model again, see the `model_corrupted` event
+ */
+async repairModel(modelId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("repair_model", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteModel(modelId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_model", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async cancelDownload(modelId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_download", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setActiveModel(modelId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_active_model", { modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getCurrentModel() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_current_model") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getTranscriptionModelStatus() : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_transcription_model_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async isModelLoading() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_model_loading") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async hasAnyModelsAvailable() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("has_any_models_available") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async hasAnyModelsOrDownloads() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("has_any_models_or_downloads") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateMicrophoneMode(alwaysOn: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_microphone_mode", { alwaysOn }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getMicrophoneMode() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_microphone_mode") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAvailableMicrophones() : Promise<Result<AudioDevice[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_available_microphones") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setSelectedMicrophone(deviceName: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_selected_microphone", { deviceName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSelectedMicrophone() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_selected_microphone") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAvailableOutputDevices() : Promise<Result<AudioDevice[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_available_output_devices") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setSelectedOutputDevice(deviceName: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_selected_output_device", { deviceName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSelectedOutputDevice() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_selected_output_device") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async playTestSound(soundType: string) : Promise<void> {
+    await TAURI_INVOKE("play_test_sound", { soundType });
+},
+async checkCustomSounds() : Promise<CustomSounds> {
+    return await TAURI_INVOKE("check_custom_sounds");
+},
+async setClamshellMicrophone(deviceName: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_clamshell_microphone", { deviceName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getClamshellMicrophone() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_clamshell_microphone") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async isRecording() : Promise<boolean> {
+    return await TAURI_INVOKE("is_recording");
+},
+async setModelUnloadTimeout(timeout: ModelUnloadTimeout) : Promise<void> {
+    await TAURI_INVOKE("set_model_unload_timeout", { timeout });
+},
+async getModelLoadStatus() : Promise<Result<ModelLoadStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_model_load_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async unloadModelManually() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unload_model_manually") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistoryEntries() : Promise<Result<HistoryEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_entries") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns one page of history, newest first, optionally filtered by a
+ * search over the raw and post-processed text.
+ */
+async getHistoryPage(offset: number, limit: number, search: string | null, app: string | null) : Promise<Result<HistoryPage, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_page", { offset, limit, search, app }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistoryApps() : Promise<Result<HistoryAppCount[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_apps") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async searchHistory(query: string, limit: number) : Promise<Result<HistorySearchResult[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_history", { query, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns dictation statistics for the last `days` days, or all time.
+ */
+async getDictationStats(days: number | null) : Promise<Result<DictationStats, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_dictation_stats", { days }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns local usage analytics for the last `days` days, or all time.
+ */
+async getUsageReport(days: number | null) : Promise<Result<UsageReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_report", { days }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Writes usage analytics with the app version and platform to `path` as JSON
+ * so it can be shared with a bug report.
+ */
+async exportUsageReport(path: string, days: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_usage_report", { path, days }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async clearUsageAnalytics() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_usage_analytics") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Writes history between the optional `start` and `end` Unix timestamps to
+ * `path` as "csv", "json" or "markdown". Returns the number of entries written.
+ */
+async exportHistory(format: string, path: string, start: number | null, end: number | null) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_history", { format, path, start, end }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets or clears the folder used to sync history between machines, then syncs.
+ */
+async setHistorySyncDir(path: string | null) : Promise<Result<SyncResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_history_sync_dir", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async syncHistoryNow() : Promise<Result<SyncResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_history_now") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns this machine's history key, for importing on the other machines
+ * that sync the same folder so they can merge its encrypted entries.
+ */
+async exportHistoryKey() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_history_key") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remembers another machine's history key, then syncs to merge the entries
+ * that were waiting for it.
+ */
+async importHistoryKey(key: string) : Promise<Result<SyncResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_history_key", { key }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Pastes a history entry into the focused app, preferring its post-processed text.
+ */
+async pasteHistoryEntry(id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("paste_history_entry", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Pastes the most recent transcript again into the focused app.
+ */
+async pasteLastTranscript() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("paste_last_transcript") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async toggleHistoryEntrySaved(id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_history_entry_saved", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Pins (saves) or unpins a history entry. Pinned entries are kept regardless
+ * of the retention settings.
+ */
+async setHistoryEntrySaved(id: number, saved: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_history_entry_saved", { id, saved }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSavedHistoryEntries() : Promise<Result<HistoryEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_saved_history_entries") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAudioFilePath(fileName: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_audio_file_path", { fileName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteHistoryEntry(id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_history_entry", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateHistoryLimit(limit: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_history_limit", { limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeKeepRecordingsSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_keep_recordings_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateRecordingsSizeLimit(limitMb: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_recordings_size_limit", { limitMb }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Plays back the audio kept for a history entry.
+ */
+async playHistoryRecording(id: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("play_history_recording", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Saves the audio kept for a history entry to `path` as a WAV file.
+ */
+async exportHistoryRecording(id: number, path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_history_recording", { id, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Encrypts or decrypts the history database and recordings, and saves the setting.
+ */
+async changeHistoryEncryptionSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_history_encryption_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateRecordingRetentionPeriod(period: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_recording_retention_period", { period }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export all post-processing prompts and spoken command rules to a JSON file.
+ */
+async exportPromptLibrary(path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_prompt_library", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import prompts and spoken command rules from a JSON file, merging them
+ * into the current settings using the given conflict strategy.
+ */
+async importPromptLibrary(path: string, strategy: ImportConflictStrategy) : Promise<Result<PromptImportResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_prompt_library", { path, strategy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export all settings, bindings, prompts and replacement rules, without API
+ * keys or calendar feeds, to a JSON file.
+ */
+async exportSettings(path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_settings", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replace the settings with a bundle from `export_settings`. Nothing is
+ * changed unless the whole bundle is valid.
+ */
+async importSettings(path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_settings", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Automatic backups of the settings file, newest first
+ */
+async listSettingsBackups() : Promise<SettingsBackup[]> {
+    return await TAURI_INVOKE("list_settings_backups");
+},
+/**
+ * Replaces the settings with a backup from `list_settings_backups` and
+ * returns the settings now in use
+ */
+async restoreSettingsBackup(fileName: string) : Promise<Result<AppSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_settings_backup", { fileName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Starts a full reset. The returned token confirms it in `reset_settings`
+ * within a minute, and can only be used once.
+ */
+async requestFullSettingsReset() : Promise<string> {
+    return await TAURI_INVOKE("request_full_settings_reset");
+},
+/**
+ * Resets one category of settings to its defaults, or all of them with a
+ * confirmation token, and returns the settings now in use.
+ */
+async resetSettings(category: SettingsCategory, confirmation: string | null) : Promise<Result<AppSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reset_settings", { category, confirmation }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Checks the settings for problems that would stop them from working, such
+ * as shortcuts that can't be registered or a model that isn't downloaded.
+ */
+async validateSettings() : Promise<Result<SettingsIssue[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Runs every stage of the pipeline: opening the microphone, loading the
+ * model, transcribing a sample clip, reaching the post-processing provider
+ * and pasting
+ */
+async runHealthCheck() : Promise<Result<HealthCheckResult[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_health_check") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Writes a zip for bug reports to `path`: recent logs, the settings without
+ * secrets, the model and GPUs, and permission states
+ */
+async exportDiagnostics(path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_diagnostics", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The folder models, recordings or the history database are kept in
+ */
+async getDataDirectory(kind: DataKind) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_data_directory", { kind }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Moves models, recordings or the history database to `path`, or back to
+ * the default folder when `path` is empty, then restarts Handy.
+ */
+async moveDataDirectory(kind: DataKind, path: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("move_data_directory", { kind, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Token clients of the local HTTP API send as `Authorization: Bearer <token>`
+ */
+async getApiServerToken() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_api_server_token") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replaces the HTTP API token and returns the new one
+ */
+async regenerateApiServerToken() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("regenerate_api_server_token") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Calendars meeting detection can be limited to
+ */
+async getMeetingCalendars() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_meeting_calendars") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Starts recording the meeting offered by a `meeting_started` event
+ */
+async startMeetingTranscription() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_meeting_transcription") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getPermissionStatus() : Promise<PermissionStatus> {
+    return await TAURI_INVOKE("get_permission_status");
+},
+/**
+ * Asks for microphone access, or opens the privacy settings where it's
+ * turned back on
+ */
+async requestMicrophonePermission() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("request_microphone_permission") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Shows the system prompt that leads to the Accessibility settings
+ */
+async requestAccessibilityPermission() : Promise<void> {
+    await TAURI_INVOKE("request_accessibility_permission");
+},
+/**
+ * Shows the system prompt that leads to the Input Monitoring settings
+ */
+async requestInputMonitoringPermission() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("request_input_monitoring_permission") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Permissions Handy lacks for shortcuts with the fn key, empty when they
+ * will work
+ */
+async missingFnShortcutPermissions() : Promise<SystemPermission[]> {
+    return await TAURI_INVOKE("missing_fn_shortcut_permissions");
+},
+/**
+ * Opens the privacy settings pane where `permission` is turned on
+ */
+async openPermissionSettings(permission: SystemPermission) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_permission_settings", { permission }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stores a credential. Values are never sent back to the frontend; use
+ * `has_secret` to check whether one is set.
+ */
+async setSecret(kind: SecretKind, id: string, value: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_secret", { kind, id, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async hasSecret(kind: SecretKind, id: string) : Promise<boolean> {
+    return await TAURI_INVOKE("has_secret", { kind, id });
+},
+async deleteSecret(kind: SecretKind, id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_secret", { kind, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getProfiles() : Promise<SettingsProfile[]> {
+    return await TAURI_INVOKE("get_profiles");
+},
+/**
+ * Saves the current bindings, model, prompts and output options as a new
+ * profile, which becomes the active one.
+ */
+async createProfile(name: string) : Promise<Result<SettingsProfile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_profile", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async switchProfile(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("switch_profile", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteProfile(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_profile", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Checks if the Mac is a laptop by detecting battery presence
+ * 
+ * This uses pmset to check for battery information.
+ * Returns true if a battery is detected (laptop), false otherwise (desktop)
+ */
+async isLaptop() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_laptop") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+export const events = __makeEvents__<{
+appEvent: AppEvent
+}>({
+appEvent: "app-event"
+})
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+/**
+ * The local HTTP API, see `api_server`
+ */
+export type ApiServerSettings = { enabled?: boolean; 
+/**
+ * Port on 127.0.0.1 the API listens on
+ */
+port?: number; 
+/**
+ * Windows only: take the same commands on a named pipe, see `named_pipe`
+ */
+named_pipe?: boolean; 
+/**
+ * Serve the gRPC API too, in builds with the `grpc` feature
+ */
+grpc?: boolean; 
+/**
+ * Port on 127.0.0.1 the gRPC API listens on
+ */
+grpc_port?: number; 
+/**
+ * Let `/v1/result/copy` and the pipe's `copy` overwrite the clipboard
+ */
+allow_clipboard?: boolean }
+export type AppEvent = { type: "recording_started" } | 
+/**
+ * Smoothed microphone levels, sent steadily while recording
+ */
+({ type: "mic_level" } & MicLevel) | ({ type: "recording_timer" } & RecordingTimer) | { type: "transcription_started" } | 
+/**
+ * Text from the model, before post-processing and other changes. Not
+ * sent for incognito dictations, like `TranscriptionCompleted`.
+ */
+{ type: "transcribed"; text: string } | { type: "post_processing_started" } | 
+/**
+ * The text delivered to the target app
+ */
+{ type: "transcription_completed"; text: string } | 
+/**
+ * The dictation finished, was cancelled or failed
+ */
+{ type: "dictation_ended" } | { type: "error"; source: ErrorSource; message: string } | ({ type: "settings_changed" } & SettingsChanged) | 
+/**
+ * A calendar meeting started while Handy was idle, see
+ * `meeting_detection`
+ */
+{ type: "meeting_started"; title: string; calendar: string } | 
+/**
+ * macOS Secure Input turned on or off, see `secure_input`. `process`
+ * is the app holding it, when known.
+ */
+{ type: "secure_input_changed"; enabled: boolean; process: string | null } | 
+/**
+ * A model failed to load and may be corrupted. `verified` is set when
+ * it no longer matches its checksum, otherwise corruption is only the
+ * likely cause. `repair_model` downloads it again.
+ */
+{ type: "model_corrupted"; model_id: string; name: string; verified: boolean } | 
+/**
+ * A model is loading, sent as it starts, periodically while it runs and
+ * once it's ready or failed
+ */
+({ type: "model_loading" } & ModelLoadingEvent) | 
+/**
+ * The watchdog cancelled a stuck dictation, see `watchdog`
+ */
+{ type: "stuck_state_recovered"; state: StuckState; message: string } | 
+/**
+ * A shortcut with the fn key was registered, but won't fire without
+ * the `missing` permissions. `open_permission_settings` opens each.
+ */
+{ type: "shortcut_permissions_missing"; binding_id: string; missing: SystemPermission[] } | 
+/**
+ * The dictation moved between states, see `dictation`. Sent after the
+ * tray and overlay show `to`.
+ */
+{ type: "dictation_state_changed"; from: DictationState; to: DictationState } | 
+/**
+ * Handy was paused or resumed, see `pause`
+ */
+{ type: "pause_changed"; paused: boolean } | 
+/**
+ * Another profile was switched to, see `profiles`
+ */
+{ type: "profile_changed"; profile_id: string } | 
+/**
+ * The settings file was changed outside Handy and reloaded, see
+ * `settings_watcher`
+ */
+{ type: "settings_reloaded" } | 
+/**
+ * Session incognito was switched on or off, see `incognito`
+ */
+{ type: "incognito_changed"; enabled: boolean } | 
+/**
+ * Whether the dictation the overlay shows is incognito
+ */
+{ type: "overlay_incognito"; active: boolean }
+/**
+ * Overrides settings while dictating into a matching application. Fields
+ * left as `None` keep the global setting.
+ */
+export type AppOverride = { 
+/**
+ * Case-insensitive substring matched against the app name or identifier
+ */
+app_match: string; language?: string | null; 
+/**
+ * Post-processing prompt to use instead of the selected one
+ */
+prompt_id?: string | null; paste_method?: PasteMethod | null; 
+/**
+ * Turns post-processing on or off regardless of the shortcut used
+ */
+post_process?: boolean | null }
+/**
+ * Overrides the paste method when the frontmost application matches
+ */
+export type AppPasteRule = { 
+/**
+ * Case-insensitive substring matched against the app name or identifier
+ * (bundle id on macOS, executable path on Windows, window class on Linux)
+ */
+app_match: string; paste_method: PasteMethod; 
+/**
+ * Use `slow_typing_speed` instead of `typing_speed` for direct typing
+ */
+slow_typing?: boolean; 
+/**
+ * Overrides `markdown_output_mode` for this app
+ */
+markdown_mode?: MarkdownOutputMode | null }
+export type AppSettings = { 
+/**
+ * Schema version, see `settings_migration`
+ */
+version?: number; bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; 
+/**
+ * macOS: hide the Dock icon whenever the settings window is closed
+ */
+menu_bar_only?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; selected_microphone?: string | null; clamshell_microphone?: string | null; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; overlay_appearance?: OverlayAppearance; 
+/**
+ * Size of the overlay relative to its default, from 0.75 to 2
+ */
+overlay_scale?: number; 
+/**
+ * Shows copy-only results in the overlay instead of a notification. Like
+ * the notification, it needs `notifications.completions`.
+ */
+result_hud_enabled?: boolean; 
+/**
+ * Monitor the overlay is always shown on, see `overlay::list_monitors`.
+ * `None` follows the cursor.
+ */
+overlay_monitor?: string | null; 
+/**
+ * Recordings stop and are transcribed after this many seconds. 0 means
+ * no limit.
+ */
+max_recording_duration_secs?: number; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; spoken_commands_enabled?: boolean; spoken_commands?: Partial<{ [key in string]: string }>; literal_mode_enabled?: boolean; literal_mode_triggers?: string[]; model_unload_timeout?: ModelUnloadTimeout; 
+/**
+ * Load the model in the background after launch instead of on the
+ * first dictation
+ */
+preload_model?: boolean; word_correction_threshold?: number; 
+/**
+ * Longest a local transcription may take before the text decoded so
+ * far is used instead, 0 waits for as long as it takes. Recordings of up
+ * to a minute are decoded in one piece, so for those a timeout is an
+ * error rather than partial text.
+ */
+transcription_timeout_secs?: number; 
+/**
+ * Chunks of a long recording decoded at once, at most one for every
+ * few cores. Each worker past the first loads its own copy of the
+ * model, in the background while the first one starts decoding.
+ */
+transcription_workers?: number; history_limit?: number; 
+/**
+ * Newest transcripts listed in the tray menu. 0 hides the submenu.
+ */
+tray_recent_transcripts?: number; recording_retention_period?: RecordingRetentionPeriod; 
+/**
+ * Keep the audio of each history entry. Transcripts are kept either way.
+ */
+keep_recordings?: boolean; 
+/**
+ * Oldest unsaved recordings are deleted once their total size exceeds
+ * this many megabytes. 0 means no limit.
+ */
+recordings_size_limit_mb?: number; data_directories?: DataDirectories; 
+/**
+ * Folder shared between machines by a sync tool. History is exchanged
+ * through per-machine journals in this folder.
+ */
+history_sync_dir?: string | null; 
+/**
+ * Names this machine's journal in the sync folder
+ */
+history_sync_device_id?: string | null; paste_method?: PasteMethod; app_paste_rules?: AppPasteRule[]; app_overrides?: AppOverride[]; 
+/**
+ * Named pipe or socket that also receives every final transcript
+ */
+transcript_pipe_path?: string | null; 
+/**
+ * Directory that gets a Markdown file per day with every transcript
+ */
+daily_journal_dir?: string | null; daily_journal_template?: string; 
+/**
+ * Count feature usage and errors locally, see `managers::analytics`
+ */
+usage_analytics_enabled?: boolean; 
+/**
+ * Store the focused window's title with each history entry. Off by
+ * default since titles often name documents, emails and pages.
+ */
+capture_window_titles?: boolean; 
+/**
+ * Let `handy://` links transcribe files, paste, select prompts and run
+ * actions. Start, stop and cancel links always work.
+ */
+deep_link_actions?: boolean; typing_speed?: TypingSpeed; slow_typing_speed?: TypingSpeed; key_injection?: KeyInjectionSettings; notifications?: NotificationSettings; api_server?: ApiServerSettings; webhooks?: Webhook[]; http_actions?: HttpAction[]; note_targets?: NoteTarget[]; stream_deck?: StreamDeckSettings; obs_captions?: ObsCaptionSettings; mqtt?: MqttSettings; email?: EmailSettings; meeting_detection?: MeetingDetectionSettings; watchdog?: WatchdogSettings; clipboard_handling?: ClipboardHandling; primary_selection_mode?: PrimarySelectionMode; markdown_output_mode?: MarkdownOutputMode; auto_submit?: boolean; auto_submit_key?: AutoSubmitKey; post_process_enabled?: boolean; post_process_provider_id?: string; post_process_providers?: PostProcessProvider[]; post_process_api_keys?: Partial<{ [key in string]: string }>; post_process_models?: Partial<{ [key in string]: string }>; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; post_process_system_prompt?: string; openrouter_routing?: OpenRouterRouting; mute_while_recording?: boolean; append_trailing_space?: boolean; 
+/**
+ * Add a leading space and fix capitalization based on the text before the caret
+ */
+smart_insert_enabled?: boolean; 
+/**
+ * Check what kind of control has focus before pasting: skip password
+ * fields, type into terminals and copy when nothing takes text
+ */
+focused_control_detection?: boolean; 
+/**
+ * Show transcripts in an editable window and only paste once confirmed
+ */
+preview_before_paste?: boolean; 
+/**
+ * Registers the `undo_last_dictation` shortcut
+ */
+undo_dictation_enabled?: boolean; 
+/**
+ * Registers the `paste_last_transcript` shortcut
+ */
+paste_last_enabled?: boolean; 
+/**
+ * Registers the `transcribe_incognito` shortcut
+ */
+incognito_shortcut_enabled?: boolean; 
+/**
+ * Keep the history database and recordings encrypted with a key from
+ * the OS credential store
+ */
+encrypt_history?: boolean; 
+/**
+ * Saved profiles, see `profiles`
+ */
+profiles?: SettingsProfile[]; 
+/**
+ * Profile the settings in use were last switched to or saved from
+ */
+active_profile_id?: string | null; app_language?: string; experimental_enabled?: boolean; keyboard_implementation?: KeyboardImplementation; show_tray_icon?: boolean; paste_delay_ms?: number; 
+/**
+ * How long to wait after pasting before restoring the previous clipboard
+ */
+clipboard_restore_delay_ms?: number; typing_tool?: TypingTool; external_script_path: string | null }
+export type AudioDevice = { index: string; name: string; is_default: boolean }
+export type AutoSubmitKey = "enter" | "ctrl_enter" | "cmd_enter"
+export type BindingResponse = { success: boolean; binding: ShortcutBinding | null; error: string | null }
+export type ClipboardHandling = "dont_modify" | "copy_to_clipboard"
+export type CustomSounds = { start: boolean; stop: boolean }
+/**
+ * Totals for a single local day
+ */
+export type DailyStats = { 
+/**
+ * Local date as `YYYY-MM-DD`
+ */
+day: string; recordings: number; words: number }
+/**
+ * Folders for models, recordings and the history database, see
+ * `data_dirs`. `None` keeps the default in the app data folder.
+ */
+export type DataDirectories = { models?: string | null; recordings?: string | null; history?: string | null }
+export type DataKind = "models" | "recordings" | "history"
+/**
+ * Where a dictation is, for integrations that show it, like a button icon
+ */
+export type DictationState = "idle" | "recording" | "transcribing" | "post_processing"
+export type DictationStats = { total_recordings: number; total_words: number; 
+/**
+ * Total length of all recordings in milliseconds
+ */
+total_audio_ms: number; 
+/**
+ * Mean time from the end of recording to finished transcription
+ */
+average_latency_ms: number | null; 
+/**
+ * Time typing the same words at 40 WPM would have taken, minus the time
+ * spent speaking and waiting for the transcription
+ */
+typing_time_saved_ms: number; 
+/**
+ * Days with at least one recording, oldest first
+ */
+daily: DailyStats[] }
+export type EmailDelivery = 
+/**
+ * Opens a draft in the default mail app
+ */
+"mailto" | 
+/**
+ * Sends through an SMTP server without asking
+ */
+"smtp"
+/**
+ * Where longform transcripts are emailed, see `email`
+ */
+export type EmailSettings = { enabled?: boolean; delivery?: EmailDelivery; to?: string; 
+/**
+ * Sender for SMTP, the `to` address if unset
+ */
+from?: string | null; 
+/**
+ * Subject with the same `{{variables}}` as HTTP actions
+ */
+subject_template?: string; 
+/**
+ * Shorter dictations aren't emailed
+ */
+min_duration_secs?: number; smtp_host?: string; smtp_port?: number; smtp_security?: SmtpSecurity; 
+/**
+ * The password is kept in the credential store as `smtp_password`
+ */
+smtp_username?: string | null }
+export type EngineType = "Whisper" | "Parakeet" | "Moonshine" | "MoonshineStreaming" | "SenseVoice"
+export type ErrorReport = { 
+/**
+ * File name of the report, without `.json`
+ */
+id: string; 
+/**
+ * Unix time in milliseconds
+ */
+timestamp: number; source: ErrorSource; 
+/**
+ * The error with secrets and transcript text redacted
+ */
+message: string; app_version: string; os: string; arch: string; selected_model: string; 
+/**
+ * Post-processing provider, when post-processing is on
+ */
+post_process_provider: string | null }
+/**
+ * What failed
+ */
+export type ErrorSource = "transcription" | "post_process" | "paste" | "undo" | 
+/**
+ * Microphone access is off, so recording didn't start
+ */
+"microphone" | 
+/**
+ * Synced history couldn't be merged
+ */
+"history_sync" | 
+/**
+ * A transcript couldn't be sent to the binding's note target
+ */
+"note_target"
+export type HealthCheckResult = { stage: HealthStage; outcome: HealthOutcome; message: string; duration_ms: number }
+export type HealthOutcome = "passed" | "failed" | 
+/**
+ * Not configured, or couldn't run because an earlier stage failed
+ */
+"skipped"
+export type HealthStage = "audio_device" | "model_load" | "transcription" | "post_process" | "paste"
+/**
+ * Number of history entries delivered to an app
+ */
+export type HistoryAppCount = { app: string; entries: number }
+export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null; 
+/**
+ * Length of the recording in milliseconds
+ */
+duration_ms: number | null; 
+/**
+ * Id of the model that produced the transcription
+ */
+model: string | null; 
+/**
+ * Name of the app that had focus when the text was delivered
+ */
+target_app: string | null; 
+/**
+ * Title of the focused window, unless title capture is turned off
+ */
+window_title: string | null }
+/**
+ * One page of history, newest first
+ */
+export type HistoryPage = { entries: HistoryEntry[]; 
+/**
+ * Number of entries matching the search across all pages
+ */
+total: number }
+export type HistorySearchResult = { entry: HistoryEntry; 
+/**
+ * Excerpt around the best match
+ */
+snippet: string; 
+/**
+ * `[start, end)` ranges of matched terms in `snippet`, in UTF-16 code
+ * units so they can be used directly with JavaScript strings
+ */
+highlights: ([number, number])[]; 
+/**
+ * BM25 score; lower is a better match
+ */
+score: number }
+/**
+ * A request sent after each dictation, with `{{transcript}}` and other
+ * variables filled in, see `http_actions`
+ */
+export type HttpAction = { id: string; name: string; enabled?: boolean; method?: HttpMethod; url: string; headers?: HttpHeader[]; 
+/**
+ * JSON body, sent as `application/json` unless a header says otherwise
+ */
+body?: string | null }
+export type HttpHeader = { name: string; value: string }
+export type HttpMethod = "get" | "post" | "put" | "patch" | "delete"
+/**
+ * Result of changing keyboard implementation
+ */
+export type ImplementationChangeResult = { success: boolean; 
+/**
+ * List of binding IDs that were reset to defaults due to incompatibility
+ */
+reset_bindings: string[] }
+/**
+ * How to handle imported entries that collide with existing ones
+ */
+export type ImportConflictStrategy = 
+/**
+ * Keep the existing entry and ignore the imported one
+ */
+"skip" | 
+/**
+ * Replace the existing entry with the imported one
+ */
+"overwrite" | 
+/**
+ * Keep both, renaming the imported prompt
+ */
+"keep_both"
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+/**
+ * Options for the keyboard injection backend
+ */
+export type KeyInjectionSettings = { 
+/**
+ * Longest time to wait for held modifier keys to be released before
+ * injecting. Used as a fixed delay where key state can't be queried.
+ */
+modifier_settle_ms?: number; 
+/**
+ * Delay after every injected key event
+ */
+inter_key_delay_ms?: number; 
+/**
+ * Send key-up events for all modifiers before injecting
+ */
+release_modifiers?: boolean; 
+/**
+ * macOS: post events independent of the physical modifier state
+ */
+ignore_held_modifiers?: boolean; 
+/**
+ * Windows: mark injected events via `dwExtraInfo` so hooks can skip them
+ */
+tag_injected_events?: boolean }
+export type KeyboardImplementation = "tauri" | "handy_keys"
+export type LLMPrompt = { id: string; name: string; prompt: string; 
+/**
+ * Optional system message sent alongside the prompt. When unset, the
+ * global `post_process_system_prompt` is used instead.
+ */
+system_prompt?: string | null; 
+/**
+ * Sampling temperature override. `None` uses the provider default.
+ */
+temperature?: number | null; 
+/**
+ * Maximum number of tokens to generate. `None` uses the provider default.
+ */
+max_tokens?: number | null; 
+/**
+ * Nucleus sampling override. `None` uses the provider default.
+ */
+top_p?: number | null }
+export type LogLevel = "trace" | "debug" | "info" | "warn" | "error"
+export type LogLine = { timestamp: string; level: LogLevel; 
+/**
+ * Module the record came from
+ */
+target: string; 
+/**
+ * Continuation lines of multi-line records included
+ */
+message: string }
+/**
+ * How Markdown in the transcript (typically from LLM post-processing) is pasted
+ */
+export type MarkdownOutputMode = 
+/**
+ * Paste the Markdown source unchanged
+ */
+"keep" | 
+/**
+ * Remove Markdown syntax and paste plain text
+ */
+"strip" | 
+/**
+ * Put HTML on the clipboard (with a plain text fallback) so rich text
+ * editors keep the formatting. Only applies to clipboard paste methods.
+ */
+"rich_text"
+/**
+ * Calendars watched for meetings to offer transcribing, see
+ * `meeting_detection`
+ */
+export type MeetingDetectionSettings = { enabled?: boolean; 
+/**
+ * Read the system calendar through EventKit (macOS only)
+ */
+system_calendar?: boolean; 
+/**
+ * ICS feeds, such as a calendar's secret address. Kept in the secret
+ * store rather than the settings file.
+ */
+ics_urls?: string[]; 
+/**
+ * Names of the calendars whose meetings count, all of them when empty
+ */
+calendars?: string[] }
+/**
+ * Payload of the `mic_level` app event
+ */
+export type MicLevel = { 
+/**
+ * Smoothed RMS amplitude, 0.0 to 1.0
+ */
+rms: number; 
+/**
+ * Recent peak amplitude, decaying slowly, 0.0 to 1.0
+ */
+peak: number; 
+/**
+ * Smoothed loudness per frequency band from low to high, 0.0 to 1.0
+ */
+bands: number[] }
+export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number; supports_translation: boolean; is_recommended: boolean; supported_languages: string[]; is_custom: boolean; 
+/**
+ * Failed to load and no longer matches its checksum, see `repair_model`
+ */
+is_corrupted?: boolean }
+export type ModelLoadStatus = { is_loaded: boolean; current_model: string | null }
+/**
+ * Payload of the `model_loading` app event, sent while a model loads so a
+ * dictation waiting on it can show the model warming up instead of looking
+ * stuck
+ */
+export type ModelLoadingEvent = { model_id: string; stage: ModelLoadingStage; elapsed_ms: number; 
+/**
+ * Between 0 and 1, estimated from how long the model took to load last
+ * time. `None` for a model not loaded before in this session.
+ */
+progress: number | null }
+/**
+ * How far along a model load is
+ */
+export type ModelLoadingStage = "started" | 
+/**
+ * Still loading, sent every `LOADING_PROGRESS_INTERVAL`
+ */
+"loading" | "ready" | "failed"
+export type ModelSearchFilter = { 
+/**
+ * Case-insensitive substring matched against model id and name
+ */
+query?: string | null; 
+/**
+ * Maximum prompt price in USD per million tokens
+ */
+max_prompt_price?: number | null; min_context_length?: number | null; limit?: number | null }
+export type ModelUnloadTimeout = "never" | "immediately" | "min_2" | "min_5" | "min_10" | "min_15" | "hour_1" | "sec_5"
+/**
+ * A connected monitor as listed in the overlay settings
+ */
+export type MonitorInfo = { 
+/**
+ * Stored in `overlay_monitor` to pin the overlay to this monitor
+ */
+id: string; width: number; height: number; is_primary: boolean }
+/**
+ * MQTT broker dictation state and transcripts are published to, see `mqtt`
+ */
+export type MqttSettings = { enabled?: boolean; host?: string; port?: number; tls?: boolean; 
+/**
+ * PEM file of the CA that signed the broker's certificate. The system's
+ * certificates are used without one.
+ */
+ca_cert_path?: string | null; 
+/**
+ * The password is kept in the credential store as `mqtt_password`
+ */
+username?: string | null; 
+/**
+ * Topics are `<prefix>/state` and `<prefix>/transcript`
+ */
+topic_prefix?: string }
+/**
+ * Where a note target appends transcripts
+ */
+export type NoteDestination = 
+/**
+ * A note in a vault, through the Advanced URI plugin
+ */
+{ kind: "obsidian"; vault: string; file_path: string } | 
+/**
+ * A note in Apple Notes, created if it doesn't exist (macOS only)
+ */
+{ kind: "apple_notes"; note: string; folder?: string | null } | 
+/**
+ * A Notion page, with the integration token stored as the target's
+ * `notion_token` secret
+ */
+{ kind: "notion"; page_id: string }
+/**
+ * A note app bindings can send transcripts to, see `note_targets`
+ */
+export type NoteTarget = { id: string; name: string; destination: NoteDestination }
+/**
+ * Which native notifications are shown, see `notifications`
+ */
+export type NotificationSettings = { transcription_errors?: boolean; post_process_errors?: boolean; 
+/**
+ * Finished transcriptions, while the main window is hidden
+ */
+completions?: boolean; 
+/**
+ * macOS: another app's Secure Input is blocking shortcuts
+ */
+secure_input?: boolean; 
+/**
+ * A transcription was copied instead of pasted, when the result HUD is
+ * off
+ */
+copied?: boolean; 
+/**
+ * The focused control was a password field, or not a text field, so
+ * the transcription wasn't pasted
+ */
+focused_control?: boolean }
+/**
+ * Where OBS captions go
+ */
+export type ObsCaptionOutput = 
+/**
+ * A text file an OBS text source reads from
+ */
+"text_file" | 
+/**
+ * obs-websocket, as stream captions or the text of a source
+ */
+"web_socket"
+/**
+ * Captions for OBS from dictation, see `obs_captions`
+ */
+export type ObsCaptionSettings = { 
+/**
+ * Toggled by the `toggle_obs_captions` shortcut
+ */
+enabled?: boolean; output?: ObsCaptionOutput; file_path?: string | null; host?: string; port?: number; 
+/**
+ * Text source to update over obs-websocket. Without one, captions are
+ * sent as stream captions, which only show while streaming.
+ */
+text_source?: string | null; 
+/**
+ * Seconds until a caption is cleared, 0 to keep it
+ */
+clear_after_secs?: number; 
+/**
+ * Registers the `toggle_obs_captions` shortcut
+ */
+shortcut_enabled?: boolean }
+/**
+ * Provider routing preferences sent with OpenRouter requests
+ */
+export type OpenRouterRouting = { 
+/**
+ * Upstream providers to try first, in order (e.g. "anthropic", "together")
+ */
+provider_order?: string[]; 
+/**
+ * Whether OpenRouter may fall back to providers not listed in `provider_order`
+ */
+allow_fallbacks?: boolean; 
+/**
+ * Use the `:nitro` variant of the selected model for higher throughput
+ */
+nitro?: boolean }
+/**
+ * Look of the recording overlay, sent to it as `overlay-appearance`
+ */
+export type OverlayAppearance = { 
+/**
+ * Background opacity, from 0.2 to 1
+ */
+opacity?: number; 
+/**
+ * `#rrggbb` color of the level bars and timer
+ */
+accent_color?: string; theme?: OverlayTheme }
+export type OverlayPosition = "none" | "top" | "bottom" | 
+/**
+ * Next to the text caret, or at the bottom when it can't be found
+ */
+"caret"
+export type OverlayTheme = 
+/**
+ * Follows the system appearance
+ */
+"auto" | "light" | "dark"
+export type PasteMethod = "ctrl_v" | "direct" | "none" | "shift_insert" | "ctrl_shift_v" | "external_script" | 
+/**
+ * Insert via the platform accessibility API (AT-SPI on Linux, AX on macOS),
+ * falling back to CtrlV when the focused element doesn't support it
+ */
+"accessibility" | 
+/**
+ * Copy to the clipboard and show a notification without sending any
+ * input, for systems where synthetic input is blocked
+ */
+"copy_only"
+export type PermissionState = "granted" | "denied" | 
+/**
+ * This platform doesn't ask for it
+ */
+"not_required"
+export type PermissionStatus = { microphone: PermissionState; accessibility: PermissionState; input_monitoring: PermissionState }
+/**
+ * What the policy enforces, for the settings UI
+ */
+export type PolicyStatus = { 
+/**
+ * Policy file in use, if any
+ */
+path: string | null; locked_settings: string[]; disable_cloud_providers: boolean; force_incognito: boolean }
+/**
+ * Model metadata used for searching large model catalogs such as OpenRouter's
+ */
+export type PostProcessModelInfo = { id: string; name: string; context_length: number | null; 
+/**
+ * Prompt price in USD per million tokens
+ */
+prompt_price: number | null; 
+/**
+ * Completion price in USD per million tokens
+ */
+completion_price: number | null }
+export type PostProcessProvider = { id: string; label: string; base_url: string; allow_base_url_edit?: boolean; models_endpoint?: string | null; supports_structured_output?: boolean }
+/**
+ * Whether transcripts are also placed in the primary selection (middle-click
+ * paste). Only has an effect on Linux.
+ */
+export type PrimarySelectionMode = "off" | 
+/**
+ * Populate the primary selection in addition to the normal paste
+ */
+"also" | 
+/**
+ * Populate the primary selection instead of pasting
+ */
+"only"
+export type PromptImportResult = { added: number; updated: number; skipped: number }
+export type RecordingRetentionPeriod = "never" | "preserve_limit" | "days_3" | "weeks_2" | "months_3"
+/**
+ * Payload of the `recording_timer` app event
+ */
+export type RecordingTimer = { elapsed_ms: number; 
+/**
+ * Max recording duration, `None` when recordings aren't limited
+ */
+limit_ms: number | null; 
+/**
+ * The limit is close and the recording will soon stop on its own
+ */
+warning: boolean }
+/**
+ * What a credential is for
+ */
+export type SecretKind = 
+/**
+ * API key of a post-processing provider, by provider id
+ */
+"post_process_api_key" | 
+/**
+ * API key of a cloud transcription service, by service id
+ */
+"transcription_api_key" | 
+/**
+ * Token sent with webhook requests, by webhook id
+ */
+"webhook_token" | 
+/**
+ * Token clients of the local HTTP API authenticate with
+ */
+"api_server_token" | 
+/**
+ * Password of the obs-websocket server captions are sent to
+ */
+"obs_password" | 
+/**
+ * Password for the MQTT broker
+ */
+"mqtt_password" | 
+/**
+ * Integration token of a Notion note target, by target id
+ */
+"notion_token" | 
+/**
+ * Password for the SMTP server transcripts are emailed through
+ */
+"smtp_password" | 
+/**
+ * ICS feed addresses for meeting detection, one per line
+ */
+"calendar_feeds"
+export type SettingsBackup = { file_name: string; created_at: number; size_bytes: number }
+export type SettingsCategory = "bindings" | "audio" | "post_processing" | "overlay" | 
+/**
+ * Every setting. Needs a token from `request_full_settings_reset`.
+ */
+"all"
+/**
+ * A top-level setting changed. API keys are reported as whether each one is
+ * set, and calendar feeds, webhook and HTTP action URLs are redacted.
+ */
+export type SettingsChanged = { setting: string; old_value: JsonValue; new_value: JsonValue }
+/**
+ * A setting that can't work as configured
+ */
+export type SettingsIssue = { kind: SettingsIssueKind; 
+/**
+ * The setting at fault, e.g. `bindings.transcribe` or `selected_model`
+ */
+setting: string; message: string }
+export type SettingsIssueKind = "invalid_shortcut" | "missing_model" | "unreachable_provider" | "missing_sound"
+/**
+ * A named set of bindings, model, prompts and output options, see `profiles`
+ */
+export type SettingsProfile = { id: string; name: string; bindings: Partial<{ [key in string]: ShortcutBinding }>; selected_model: string; post_process_enabled: boolean; post_process_prompts: LLMPrompt[]; post_process_selected_prompt_id: string | null; paste_method: PasteMethod; clipboard_handling: ClipboardHandling; auto_submit: boolean; auto_submit_key: AutoSubmitKey; append_trailing_space: boolean }
+export type ShortcutBinding = { id: string; name: string; description: string; default_binding: string; current_binding: string; 
+/**
+ * File the transcript is appended to instead of pasting.
+ * Only used by the `transcribe_to_file` binding.
+ */
+output_file_path?: string | null; 
+/**
+ * Note target the transcript is sent to instead of pasting, see
+ * `note_targets`
+ */
+note_target_id?: string | null }
+export type SmtpSecurity = 
+/**
+ * Upgrades a plain connection, usually on port 587
+ */
+"start_tls" | 
+/**
+ * TLS from the start, usually on port 465
+ */
+"tls"
+export type SoundTheme = "marimba" | "pop" | "custom"
+/**
+ * WebSocket server for Stream Deck plugins, see `stream_deck`
+ */
+export type StreamDeckSettings = { enabled?: boolean; 
+/**
+ * Port on 127.0.0.1 plugins connect to
+ */
+port?: number }
+/**
+ * A state the watchdog found a dictation stuck in
+ */
+export type StuckState = 
+/**
+ * Recording for longer than `watchdog.max_recording_mins`
+ */
+"recording" | 
+/**
+ * Transcribing or post-processing for longer than
+ * `watchdog.max_processing_mins`
+ */
+"processing" | 
+/**
+ * The overlay stayed up with nothing going on
+ */
+"overlay"
+export type SyncResult = { 
+/**
+ * Local entries appended to this machine's journal
+ */
+exported: number; 
+/**
+ * Entries merged in from other machines
+ */
+imported: number; 
+/**
+ * Records from other machines left unread because they are sealed with
+ * a key this machine doesn't have yet
+ */
+locked: number }
+/**
+ * A permission with its own pane in the system's privacy settings
+ */
+export type SystemPermission = "microphone" | "accessibility" | "input_monitoring"
+/**
+ * Pacing for `PasteMethod::Direct`. Text is typed in chunks of `chunk_size`
+ * characters with `delay_ms` between them; a chunk size of 1 gives a
+ * per-keystroke delay and 0 types everything at once.
+ */
+export type TypingSpeed = { delay_ms?: number; chunk_size?: number }
+export type TypingTool = "auto" | "wtype" | "kwtype" | "dotool" | "ydotool" | "xdotool"
+export type UsageCategory = 
+/**
+ * A shortcut action was started, by binding id
+ */
+"action" | 
+/**
+ * A transcription finished, by model id
+ */
+"model" | 
+/**
+ * Post-processing produced text, by provider id
+ */
+"provider" | 
+/**
+ * A stage of the pipeline failed, e.g. `transcription` or `paste`
+ */
+"error"
+export type UsageCount = { category: UsageCategory; name: string; count: number }
+export type UsageReport = { 
+/**
+ * First local day included as `YYYY-MM-DD`, or `None` for all time
+ */
+since: string | null; 
+/**
+ * Counts by category, most used first within each category
+ */
+counts: UsageCount[]; 
+/**
+ * Share of transcriptions that failed
+ */
+transcription_error_rate: number | null }
+/**
+ * Limits past which a dictation counts as stuck and is cancelled, see
+ * `watchdog`
+ */
+export type WatchdogSettings = { enabled?: boolean; max_recording_mins?: number; 
+/**
+ * Transcription and post-processing together
+ */
+max_processing_mins?: number }
+/**
+ * A URL finished transcripts are posted to, see `webhooks`. Requests are
+ * signed with the `webhook_token` secret of the same id, if there is one.
+ */
+export type Webhook = { id: string; url: string; enabled?: boolean }
 
 /** tauri-specta globals **/
 
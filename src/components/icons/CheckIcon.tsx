@@ -0,0 +1,36 @@
+import React from "react";
+
+interface CheckIconProps {
+  width?: number;
+  height?: number;
+  color?: string;
+  className?: string;
+}
+
+const CheckIcon: React.FC<CheckIconProps> = ({
+  width = 24,
+  height = 24,
+  color = "#FAA2CA",
+  className = "",
+}) => {
+  return (
+    <svg
+      width={width}
+      height={height}
+      viewBox="0 0 24 24"
+      fill="none"
+      xmlns="http://www.w3.org/2000/svg"
+      className={className}
+    >
+      <g fill={color}>
+        <path d="m15.793 8.79297c.3905-.39052 1.0235-.39052 1.414 0s.3905 1.02354 0 1.41406l-6 6.00007c-.3905.3905-1.0235.3905-1.41404 0l-3-3.0001c-.39052-.3905-.39052-1.0235 0-1.414.39053-.3906 1.02354-.3906 1.41407 0l2.29297 2.2929z" />
+        <path
+          d="m20 12c0-4.41828-3.5817-8-8-8-4.41828 0-8 3.58172-8 8 0 4.4183 3.58172 8 8 8 4.4183 0 8-3.5817 8-8zm2 0c0 5.5228-4.4772 10-10 10-5.52285 0-10-4.4772-10-10 0-5.52285 4.47715-10 10-10 5.5228 0 10 4.47715 10 10z"
+          opacity=".4"
+        />
+      </g>
+    </svg>
+  );
+};
+
+export default CheckIcon;
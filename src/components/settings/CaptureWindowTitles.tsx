@@ -0,0 +1,29 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import { ToggleSwitch } from "../ui/ToggleSwitch";
+import { useSettings } from "../../hooks/useSettings";
+
+interface CaptureWindowTitlesProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const CaptureWindowTitles: React.FC<CaptureWindowTitlesProps> =
+  React.memo(({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+
+    const enabled = getSetting("capture_window_titles") ?? false;
+
+    return (
+      <ToggleSwitch
+        checked={enabled}
+        onChange={(enabled) => updateSetting("capture_window_titles", enabled)}
+        isUpdating={isUpdating("capture_window_titles")}
+        label={t("settings.advanced.captureWindowTitles.label")}
+        description={t("settings.advanced.captureWindowTitles.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+      />
+    );
+  });
@@ -0,0 +1,29 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import { ToggleSwitch } from "../ui/ToggleSwitch";
+import { useSettings } from "../../hooks/useSettings";
+
+interface DeepLinkActionsProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const DeepLinkActions: React.FC<DeepLinkActionsProps> =
+  React.memo(({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+
+    const enabled = getSetting("deep_link_actions") ?? false;
+
+    return (
+      <ToggleSwitch
+        checked={enabled}
+        onChange={(enabled) => updateSetting("deep_link_actions", enabled)}
+        isUpdating={isUpdating("deep_link_actions")}
+        label={t("settings.advanced.deepLinkActions.label")}
+        description={t("settings.advanced.deepLinkActions.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+      />
+    );
+  });
@@ -5,10 +5,8 @@ import { Button } from "../../ui/Button";
 import { Copy, Star, Check, Trash2, FolderOpen } from "lucide-react";
 import { convertFileSrc } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { readFile } from "@tauri-apps/plugin-fs";
 import { commands, type HistoryEntry } from "@/bindings";
 import { formatDateTime } from "@/utils/dateFormat";
-import { useOsType } from "@/hooks/useOsType";
 
 interface OpenRecordingsButtonProps {
   onClick: () => void;
@@ -33,7 +31,6 @@ const OpenRecordingsButton: React.FC<OpenRecordingsButtonProps> = ({
 
 export const HistorySettings: React.FC = () => {
   const { t } = useTranslation();
-  const osType = useOsType();
   const [historyEntries, setHistoryEntries] = useState<HistoryEntry[]>([]);
   const [loading, setLoading] = useState(true);
 
@@ -92,28 +89,20 @@ export const HistorySettings: React.FC = () => {
     }
   };
 
-  const getAudioUrl = useCallback(
-    async (fileName: string) => {
-      try {
-        const result = await commands.getAudioFilePath(fileName);
-        if (result.status === "ok") {
-          if (osType === "linux") {
-            const fileData = await readFile(result.data);
-            const blob = new Blob([fileData], { type: "audio/wav" });
-
-            return URL.createObjectURL(blob);
-          }
-
-          return convertFileSrc(result.data, "asset");
-        }
-        return null;
-      } catch (error) {
-        console.error("Failed to get audio file path:", error);
+  // Encrypted recordings are decrypted in memory by the recording protocol
+  const getAudioUrl = useCallback(async (fileName: string) => {
+    try {
+      const response = await fetch(convertFileSrc(fileName, "recording"));
+      if (!response.ok) {
+        console.error("Failed to load recording:", await response.text());
         return null;
       }
-    },
-    [osType],
-  );
+      return URL.createObjectURL(await response.blob());
+    } catch (error) {
+      console.error("Failed to load recording:", error);
+      return null;
+    }
+  }, []);
 
   const deleteAudioEntry = async (id: number) => {
     try {
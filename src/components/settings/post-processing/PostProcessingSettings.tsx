@@ -150,6 +150,7 @@ const PostProcessingSettingsPromptsComponent: React.FC = () => {
   const [isCreating, setIsCreating] = useState(false);
   const [draftName, setDraftName] = useState("");
   const [draftText, setDraftText] = useState("");
+  const [draftSystemPrompt, setDraftSystemPrompt] = useState("");
 
   const prompts = getSetting("post_process_prompts") || [];
   const selectedPromptId = getSetting("post_process_selected_prompt_id") || "";
@@ -162,15 +163,18 @@ const PostProcessingSettingsPromptsComponent: React.FC = () => {
     if (selectedPrompt) {
       setDraftName(selectedPrompt.name);
       setDraftText(selectedPrompt.prompt);
+      setDraftSystemPrompt(selectedPrompt.system_prompt ?? "");
     } else {
       setDraftName("");
       setDraftText("");
+      setDraftSystemPrompt("");
     }
   }, [
     isCreating,
     selectedPromptId,
     selectedPrompt?.name,
     selectedPrompt?.prompt,
+    selectedPrompt?.system_prompt,
   ]);
 
   const handlePromptSelect = (promptId: string | null) => {
@@ -186,6 +190,7 @@ const PostProcessingSettingsPromptsComponent: React.FC = () => {
       const result = await commands.addPostProcessPrompt(
         draftName.trim(),
         draftText.trim(),
+        draftSystemPrompt.trim() || null,
       );
       if (result.status === "ok") {
         await refreshSettings();
@@ -205,6 +210,7 @@ const PostProcessingSettingsPromptsComponent: React.FC = () => {
         selectedPromptId,
         draftName.trim(),
         draftText.trim(),
+        draftSystemPrompt.trim() || null,
       );
       await refreshSettings();
     } catch (error) {
@@ -229,9 +235,11 @@ const PostProcessingSettingsPromptsComponent: React.FC = () => {
     if (selectedPrompt) {
       setDraftName(selectedPrompt.name);
       setDraftText(selectedPrompt.prompt);
+      setDraftSystemPrompt(selectedPrompt.system_prompt ?? "");
     } else {
       setDraftName("");
       setDraftText("");
+      setDraftSystemPrompt("");
     }
   };
 
@@ -239,13 +247,15 @@ const PostProcessingSettingsPromptsComponent: React.FC = () => {
     setIsCreating(true);
     setDraftName("");
     setDraftText("");
+    setDraftSystemPrompt("");
   };
 
   const hasPrompts = prompts.length > 0;
   const isDirty =
     !!selectedPrompt &&
     (draftName.trim() !== selectedPrompt.name ||
-      draftText.trim() !== selectedPrompt.prompt.trim());
+      draftText.trim() !== selectedPrompt.prompt.trim() ||
+      draftSystemPrompt.trim() !== (selectedPrompt.system_prompt ?? "").trim());
 
   return (
     <SettingContainer
@@ -322,6 +332,19 @@ const PostProcessingSettingsPromptsComponent: React.FC = () => {
               />
             </div>
 
+            <div className="space-y-2 flex flex-col">
+              <label className="text-sm font-semibold">
+                {t("settings.postProcessing.prompts.promptSystemPrompt")}
+              </label>
+              <Textarea
+                value={draftSystemPrompt}
+                onChange={(e) => setDraftSystemPrompt(e.target.value)}
+                placeholder={t(
+                  "settings.postProcessing.prompts.promptSystemPromptPlaceholder",
+                )}
+              />
+            </div>
+
             <div className="flex gap-2 pt-2">
               <Button
                 onClick={handleUpdatePrompt}
@@ -389,6 +412,19 @@ const PostProcessingSettingsPromptsComponent: React.FC = () => {
               />
             </div>
 
+            <div className="space-y-2 flex flex-col">
+              <label className="text-sm font-semibold">
+                {t("settings.postProcessing.prompts.promptSystemPrompt")}
+              </label>
+              <Textarea
+                value={draftSystemPrompt}
+                onChange={(e) => setDraftSystemPrompt(e.target.value)}
+                placeholder={t(
+                  "settings.postProcessing.prompts.promptSystemPromptPlaceholder",
+                )}
+              />
+            </div>
+
             <div className="flex gap-2 pt-2">
               <Button
                 onClick={handleCreatePrompt}
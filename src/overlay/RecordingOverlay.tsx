@@ -5,57 +5,132 @@ import {
   MicrophoneIcon,
   TranscriptionIcon,
   CancelIcon,
+  CheckIcon,
 } from "../components/icons";
 import "./RecordingOverlay.css";
-import { commands } from "@/bindings";
+import { commands, events } from "@/bindings";
+import type { AppEvent, RecordingTimer } from "@/bindings";
 import i18n, { syncLanguageFromSettings } from "@/i18n";
 import { getLanguageDirection } from "@/lib/utils/rtl";
 
-type OverlayState = "recording" | "transcribing" | "processing";
+type OverlayState = "recording" | "transcribing" | "processing" | "result";
+
+const STARTED_STATES: Partial<Record<AppEvent["type"], OverlayState>> = {
+  recording_started: "recording",
+  transcription_started: "transcribing",
+  post_processing_started: "processing",
+};
+
+interface OverlayAppearance {
+  opacity: number;
+  accent_color: string;
+  theme: "auto" | "light" | "dark";
+}
+
+const appearanceStyle = (
+  appearance: OverlayAppearance | null,
+): React.CSSProperties => {
+  if (!appearance) {
+    return {};
+  }
+  const dark =
+    appearance.theme === "dark" ||
+    (appearance.theme === "auto" &&
+      window.matchMedia("(prefers-color-scheme: dark)").matches);
+  const base = dark ? "0, 0, 0" : "255, 255, 255";
+  return {
+    "--overlay-background": `rgba(${base}, ${appearance.opacity})`,
+    "--overlay-accent": appearance.accent_color,
+    "--overlay-text": dark ? "#ffffff" : "#1f1f1f",
+  } as React.CSSProperties;
+};
+
+const formatElapsed = (ms: number) => {
+  const secs = Math.floor(ms / 1000);
+  return `${Math.floor(secs / 60)}:${String(secs % 60).padStart(2, "0")}`;
+};
 
 const RecordingOverlay: React.FC = () => {
   const { t } = useTranslation();
   const [isVisible, setIsVisible] = useState(false);
   const [state, setState] = useState<OverlayState>("recording");
-  const [levels, setLevels] = useState<number[]>(Array(16).fill(0));
-  const smoothedLevelsRef = useRef<number[]>(Array(16).fill(0));
+  const [levels, setLevels] = useState<number[]>(Array(9).fill(0));
+  const [timer, setTimer] = useState<RecordingTimer | null>(null);
+  const [result, setResult] = useState("");
+  // Read by listeners, which are set up once
+  const stateRef = useRef<OverlayState>("recording");
+  const [appearance, setAppearance] = useState<OverlayAppearance | null>(
+    null,
+  );
   const direction = getLanguageDirection(i18n.language);
 
   useEffect(() => {
     const setupEventListeners = async () => {
-      // Listen for show-overlay event from Rust
-      const unlistenShow = await listen("show-overlay", async (event) => {
-        // Sync language from settings each time overlay is shown
-        await syncLanguageFromSettings();
-        const overlayState = event.payload as OverlayState;
-        setState(overlayState);
-        setIsVisible(true);
+      // Follow the dictation lifecycle from Rust
+      const unlistenApp = await events.appEvent.listen(async (event) => {
+        const payload = event.payload;
+        const overlayState = STARTED_STATES[payload.type];
+        if (overlayState) {
+          // Sync language from settings each time overlay is shown
+          await syncLanguageFromSettings();
+          stateRef.current = overlayState;
+          setState(overlayState);
+          if (overlayState === "recording") {
+            setTimer(null);
+          }
+          setIsVisible(true);
+          return;
+        }
+        switch (payload.type) {
+          case "dictation_ended":
+            // The result HUD outlives the dictation and hides itself
+            if (stateRef.current !== "result") {
+              setIsVisible(false);
+            }
+            break;
+          case "mic_level":
+            // Already smoothed by the backend
+            setLevels(payload.bands.slice(0, 9));
+            break;
+          case "recording_timer":
+            setTimer({
+              elapsed_ms: payload.elapsed_ms,
+              limit_ms: payload.limit_ms,
+              warning: payload.warning,
+            });
+            break;
+        }
       });
 
-      // Listen for hide-overlay event from Rust
-      const unlistenHide = await listen("hide-overlay", () => {
-        setIsVisible(false);
-      });
-
-      // Listen for mic-level updates
-      const unlistenLevel = await listen<number[]>("mic-level", (event) => {
-        const newLevels = event.payload as number[];
-
-        // Apply smoothing to reduce jitter
-        const smoothed = smoothedLevelsRef.current.map((prev, i) => {
-          const target = newLevels[i] || 0;
-          return prev * 0.7 + target * 0.3; // Smooth transition
-        });
+      // Listen for appearance settings, sent on change and before each show
+      const unlistenAppearance = await listen<OverlayAppearance>(
+        "overlay-appearance",
+        (event) => {
+          setAppearance(event.payload);
+        },
+      );
 
-        smoothedLevelsRef.current = smoothed;
-        setLevels(smoothed.slice(0, 9));
-      });
+      // Copy-only results, then null when it's time to fade out
+      const unlistenResult = await listen<string | null>(
+        "result-hud",
+        async (event) => {
+          if (event.payload === null) {
+            setIsVisible(false);
+            return;
+          }
+          await syncLanguageFromSettings();
+          stateRef.current = "result";
+          setState("result");
+          setResult(event.payload);
+          setIsVisible(true);
+        },
+      );
 
       // Cleanup function
       return () => {
-        unlistenShow();
-        unlistenHide();
-        unlistenLevel();
+        unlistenApp();
+        unlistenAppearance();
+        unlistenResult();
       };
     };
 
@@ -65,6 +140,8 @@ const RecordingOverlay: React.FC = () => {
   const getIcon = () => {
     if (state === "recording") {
       return <MicrophoneIcon />;
+    } else if (state === "result") {
+      return <CheckIcon />;
     } else {
       return <TranscriptionIcon />;
     }
@@ -73,9 +150,26 @@ const RecordingOverlay: React.FC = () => {
   return (
     <div
       dir={direction}
-      className={`recording-overlay ${isVisible ? "fade-in" : ""}`}
+      style={appearanceStyle(appearance)}
+      className={`recording-overlay ${isVisible ? "fade-in" : ""} ${
+        state === "recording" && timer?.warning ? "duration-warning" : ""
+      } ${state === "result" ? "result-hud" : ""}`}
     >
-      <div className="overlay-left">{getIcon()}</div>
+      <div className="overlay-left">
+        {state === "recording" ? (
+          <div
+            className="stop-button"
+            title={t("overlay.stop")}
+            onClick={() => {
+              commands.stopCurrentRecording();
+            }}
+          >
+            {getIcon()}
+          </div>
+        ) : (
+          getIcon()
+        )}
+      </div>
 
       <div className="overlay-middle">
         {state === "recording" && (
@@ -93,18 +187,25 @@ const RecordingOverlay: React.FC = () => {
             ))}
           </div>
         )}
+        {state === "recording" && timer && (
+          <div className="recording-timer">
+            {formatElapsed(timer.elapsed_ms)}
+          </div>
+        )}
         {state === "transcribing" && (
           <div className="transcribing-text">{t("overlay.transcribing")}</div>
         )}
         {state === "processing" && (
           <div className="transcribing-text">{t("overlay.processing")}</div>
         )}
+        {state === "result" && <div className="result-text">{result}</div>}
       </div>
 
       <div className="overlay-right">
         {state === "recording" && (
           <div
             className="cancel-button"
+            title={t("overlay.cancel")}
             onClick={() => {
               commands.cancelOperation();
             }}
@@ -112,6 +213,9 @@ const RecordingOverlay: React.FC = () => {
             <CancelIcon />
           </div>
         )}
+        {state === "result" && (
+          <div className="copied-label">{t("overlay.copied")}</div>
+        )}
       </div>
     </div>
   );
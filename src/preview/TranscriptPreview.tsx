@@ -0,0 +1,93 @@
+import { listen } from "@tauri-apps/api/event";
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { commands } from "@/bindings";
+import { Button } from "@/components/ui/Button";
+import { Textarea } from "@/components/ui/Textarea";
+import i18n, { syncLanguageFromSettings } from "@/i18n";
+import { getLanguageDirection } from "@/lib/utils/rtl";
+
+const TranscriptPreview: React.FC = () => {
+  const { t } = useTranslation();
+  const [text, setText] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const direction = getLanguageDirection(i18n.language);
+
+  useEffect(() => {
+    const showText = async (pending: string) => {
+      await syncLanguageFromSettings();
+      setError(null);
+      setText(pending);
+    };
+
+    // The window may load after the first transcript was sent
+    commands.getPendingPreview().then((pending) => {
+      if (pending !== null) {
+        showText(pending);
+      }
+    });
+
+    const unlisten = listen<string>("preview-text", (event) => {
+      showText(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const handleChange = (value: string) => {
+    setText(value);
+    commands.updatePreview(value);
+  };
+
+  const confirm = async () => {
+    const result = await commands.confirmPreview(text);
+    if (result.status === "error") {
+      setError(result.error);
+    }
+  };
+
+  const cancel = () => {
+    commands.cancelPreview();
+  };
+
+  const handleKeyDown = (e: React.KeyboardEvent) => {
+    if (e.key === "Enter" && (e.metaKey || e.ctrlKey)) {
+      e.preventDefault();
+      confirm();
+    } else if (e.key === "Escape") {
+      e.preventDefault();
+      cancel();
+    }
+  };
+
+  return (
+    <div
+      dir={direction}
+      className="flex flex-col gap-3 h-screen p-4 box-border"
+      onKeyDown={handleKeyDown}
+    >
+      <Textarea
+        autoFocus
+        value={text}
+        onChange={(e) => handleChange(e.target.value)}
+        aria-label={t("preview.title")}
+        className="flex-1 resize-none"
+      />
+      {error && <p className="text-xs text-red-400">{error}</p>}
+      <div className="flex items-center justify-between gap-2">
+        <span className="text-xs text-mid-gray">{t("preview.hint")}</span>
+        <div className="flex gap-2">
+          <Button variant="secondary" onClick={cancel}>
+            {t("preview.discard")}
+          </Button>
+          <Button variant="primary" onClick={confirm}>
+            {t("preview.paste")}
+          </Button>
+        </div>
+      </div>
+    </div>
+  );
+};
+
+export default TranscriptPreview;
@@ -0,0 +1,11 @@
+import React from "react";
+import ReactDOM from "react-dom/client";
+import TranscriptPreview from "./TranscriptPreview";
+import "@/App.css";
+import "@/i18n";
+
+ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
+  <React.StrictMode>
+    <TranscriptPreview />
+  </React.StrictMode>,
+);
@@ -121,6 +121,10 @@ const settingUpdaters: {
   auto_submit_key: (value) =>
     commands.changeAutoSubmitKeySetting(value as string),
   history_limit: (value) => commands.updateHistoryLimit(value as number),
+  capture_window_titles: (value) =>
+    commands.changeCaptureWindowTitlesSetting(value as boolean),
+  deep_link_actions: (value) =>
+    commands.changeDeepLinkActionsSetting(value as boolean),
   post_process_enabled: (value) =>
     commands.changePostProcessEnabledSetting(value as boolean),
   post_process_selected_prompt_id: (value) =>